@@ -8,11 +8,15 @@
 //! 提供数据库迁移的命令行界面
 
 use clap::{Parser, Subcommand};
-use dbnexus::migration::{DatabaseType as MigrationDatabaseType, MigrationExecutor, MigrationFileParser};
-use dbnexus::{config::DbError, DbPool, DbResult};
+use dbnexus::migration::{
+    DatabaseType as MigrationDatabaseType, Migration, MigrationDirection, MigrationExecutor, MigrationFileParser,
+    MigrationLock, MigrationPlan, MigrationProgress, SchemaDiffer, SchemaIntrospector, TableChange,
+    parse_migration_filename,
+};
+use dbnexus::{DbPool, DbResult, config::DbError};
 use std::fs;
 use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// CLI 配置
 #[derive(Parser)]
@@ -31,6 +35,10 @@ struct Cli {
     #[arg(short, long, default_value = "./migrations")]
     migrations_dir: PathBuf,
 
+    /// 等待迁移锁的超时时间（秒），为 0 时锁被占用立即失败
+    #[arg(long, default_value = "30")]
+    lock_timeout: u64,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -52,14 +60,18 @@ enum Commands {
     Up {
         /// 目标版本号（可选，默认为所有待应用迁移）
         #[arg(long)]
-        version: Option<u32>,
+        version: Option<u64>,
+
+        /// 在事务外执行迁移（用于无法在事务中运行的 DDL）
+        #[arg(long, default_value = "false")]
+        no_transaction: bool,
     },
 
     /// 回滚迁移
     Down {
         /// 目标版本号（可选，默认为回滚上一版本）
         #[arg(long)]
-        version: Option<u32>,
+        version: Option<u64>,
 
         /// 回滚所有迁移
         #[arg(long, default_value = "false")]
@@ -69,6 +81,9 @@ enum Commands {
     /// 查看迁移状态
     Status,
 
+    /// 校验线上 Schema 与已应用迁移是否一致
+    Verify,
+
     /// 测试数据库连接
     TestConnection,
 
@@ -109,15 +124,35 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Commands::Create { description, directory } => {
             create_migration(description, directory).await?;
         }
-        Commands::Up { version } => {
-            run_migrations_up(&cli.database_url, &cli.migrations_dir, *version).await?;
+        Commands::Up {
+            version,
+            no_transaction,
+        } => {
+            run_migrations_up(
+                &cli.database_url,
+                &cli.migrations_dir,
+                *version,
+                *no_transaction,
+                Duration::from_secs(cli.lock_timeout),
+            )
+            .await?;
         }
         Commands::Down { version, all } => {
-            run_migrations_down(&cli.database_url, *version, *all).await?;
+            run_migrations_down(
+                &cli.database_url,
+                &cli.migrations_dir,
+                *version,
+                *all,
+                Duration::from_secs(cli.lock_timeout),
+            )
+            .await?;
         }
         Commands::Status => {
             show_status(&cli.database_url, &cli.migrations_dir).await?;
         }
+        Commands::Verify => {
+            run_verify(&cli.database_url, &cli.migrations_dir).await?;
+        }
         Commands::TestConnection => {
             test_connection(&cli.database_url).await?;
         }
@@ -191,7 +226,7 @@ async fn show_status(database_url: &str, migrations_dir: &PathBuf) -> DbResult<(
     };
 
     // 获取数据库类型
-    let db_type = detect_database_type(database_url);
+    let db_type = pool.database_type();
     println!("\n📊 数据库类型: {}", db_type);
     println!("📁 迁移目录: {}", migrations_dir.display());
 
@@ -253,7 +288,7 @@ async fn show_status(database_url: &str, migrations_dir: &PathBuf) -> DbResult<(
 
     if !local_migrations.is_empty() {
         // 显示待应用的迁移
-        let applied_versions: std::collections::HashSet<u32> =
+        let applied_versions: std::collections::HashSet<u64> =
             executor.history.applied_migrations.iter().map(|m| m.version).collect();
 
         let pending: Vec<_> = local_migrations
@@ -285,6 +320,157 @@ async fn show_status(database_url: &str, migrations_dir: &PathBuf) -> DbResult<(
     Ok(())
 }
 
+/// 校验线上 Schema 与已应用迁移是否一致
+async fn run_verify(database_url: &str, migrations_dir: &PathBuf) -> DbResult<()> {
+    println!("\n╔══════════════════════════════════════════════════════════════╗");
+    println!("║                    Schema 校验                               ║");
+    println!("╚══════════════════════════════════════════════════════════════╝");
+
+    let pool = DbPool::new(database_url).await?;
+    let db_type = pool.database_type();
+
+    println!("\n📊 数据库类型: {}", db_type);
+    println!("📁 迁移目录: {}", migrations_dir.display());
+
+    let local_migrations = scan_migration_files(migrations_dir)?;
+
+    let mut session = pool.get_session("admin").await?;
+    let connection = session.connection()?.clone();
+    let mut executor = MigrationExecutor::new(connection, db_type);
+
+    let discrepancies = verify_schema(&mut executor, &local_migrations, db_type).await?;
+
+    if discrepancies.is_empty() {
+        println!("\n✅ 线上 Schema 与已应用迁移一致");
+        println!("\n{}", "─".repeat(60));
+        return Ok(());
+    }
+
+    println!("\n❌ 发现 {} 处不一致:", discrepancies.len());
+    for discrepancy in &discrepancies {
+        println!("   - {}", discrepancy);
+    }
+    println!("\n{}", "─".repeat(60));
+
+    Err(DbError::migration(
+        0,
+        "verify",
+        format!("Schema 与已应用迁移不一致（{} 处）", discrepancies.len()),
+    ))
+}
+
+/// 对比线上 Schema 与已应用迁移推导出的 Schema，返回不一致项描述
+///
+/// 期望 Schema 通过在内存 SQLite 中按版本顺序重放已应用迁移的 UP SQL 得到，
+/// 因此目前仅支持 SQLite
+async fn verify_schema(
+    executor: &mut MigrationExecutor,
+    local_migrations: &[MigrationInfo],
+    db_type: MigrationDatabaseType,
+) -> DbResult<Vec<String>> {
+    use dbnexus::orm::{ConnectOptions, ConnectionTrait, Database};
+
+    if db_type != MigrationDatabaseType::Sqlite {
+        return Err(DbError::migration(
+            0,
+            "verify",
+            format!("verify 目前仅支持 SQLite，当前数据库: {}", db_type),
+        ));
+    }
+
+    executor.load_history().await?;
+
+    // 内存数据库只能使用单连接，否则每个连接都是独立的数据库
+    let mut options = ConnectOptions::new("sqlite::memory:");
+    options.max_connections(1).min_connections(1);
+    let scratch = Database::connect(options).await.map_err(DbError::Connection)?;
+
+    let mut discrepancies = Vec::new();
+    for applied in &executor.history.applied_migrations {
+        let Some(migration) = local_migrations.iter().find(|m| m.version == applied.version) else {
+            discrepancies.push(format!("已应用的迁移 v{} 缺少本地文件", applied.version));
+            continue;
+        };
+
+        let content = fs::read_to_string(&migration.file_path).map_err(|e| {
+            DbError::migration(
+                migration.version,
+                "load",
+                format!("无法读取迁移文件 {}: {}", migration.file_path.display(), e),
+            )
+        })?;
+        let up_sql = extract_sql_section(&content, "UP")?;
+        if !up_sql.trim().is_empty() {
+            scratch.execute_unprepared(&up_sql).await.map_err(DbError::Connection)?;
+        }
+    }
+
+    let expected = SchemaIntrospector::introspect(&scratch, db_type).await?;
+    let actual = SchemaIntrospector::introspect(&executor.connection, db_type).await?;
+
+    for migration in SchemaDiffer::new(expected, actual).diff() {
+        discrepancies.extend(migration.table_changes.iter().flat_map(describe_table_change));
+    }
+
+    Ok(discrepancies)
+}
+
+/// 将线上相对期望 Schema 的变更转换为可读描述
+fn describe_table_change(change: &TableChange) -> Vec<String> {
+    match change {
+        TableChange::CreateTable(table) => vec![format!("线上存在未记录的表 {}", table.name)],
+        TableChange::DropTable { table_name } => vec![format!("线上缺少表 {}", table_name)],
+        TableChange::AlterTable {
+            table_name,
+            column_changes,
+            added_columns,
+            removed_columns,
+            added_indexes,
+            removed_indexes,
+            added_foreign_keys,
+            removed_foreign_keys,
+        } => {
+            let mut descriptions = Vec::new();
+            descriptions.extend(
+                added_columns
+                    .iter()
+                    .map(|c| format!("表 {} 存在未记录的列 {}", table_name, c.name)),
+            );
+            descriptions.extend(
+                removed_columns
+                    .iter()
+                    .map(|c| format!("表 {} 缺少列 {}", table_name, c)),
+            );
+            descriptions.extend(
+                column_changes
+                    .iter()
+                    .map(|c| format!("表 {} 的列定义不一致: {:?}", table_name, c)),
+            );
+            descriptions.extend(
+                added_indexes
+                    .iter()
+                    .map(|i| format!("表 {} 存在未记录的索引 {}", table_name, i.name)),
+            );
+            descriptions.extend(
+                removed_indexes
+                    .iter()
+                    .map(|i| format!("表 {} 缺少索引 {}", table_name, i)),
+            );
+            descriptions.extend(
+                added_foreign_keys
+                    .iter()
+                    .map(|fk| format!("表 {} 存在未记录的外键 {}", table_name, fk.name)),
+            );
+            descriptions.extend(
+                removed_foreign_keys
+                    .iter()
+                    .map(|fk| format!("表 {} 缺少外键 {}", table_name, fk)),
+            );
+            descriptions
+        }
+    }
+}
+
 /// 测试数据库连接
 async fn test_connection(database_url: &str) -> DbResult<()> {
     println!("\n╔══════════════════════════════════════════════════════════════╗");
@@ -311,7 +497,7 @@ async fn test_connection(database_url: &str) -> DbResult<()> {
             let _conn = session.connection()?.clone();
             drop(session);
 
-            let db_type = detect_database_type(database_url);
+            let db_type = pool.database_type();
 
             println!("\n✅ 连接成功!");
             println!("\n   数据库类型: {}", db_type);
@@ -336,13 +522,19 @@ async fn test_connection(database_url: &str) -> DbResult<()> {
 }
 
 /// 运行向上的迁移（应用迁移）
-async fn run_migrations_up(database_url: &str, migrations_dir: &PathBuf, target_version: Option<u32>) -> DbResult<()> {
+async fn run_migrations_up(
+    database_url: &str,
+    migrations_dir: &PathBuf,
+    target_version: Option<u64>,
+    no_transaction: bool,
+    lock_timeout: Duration,
+) -> DbResult<()> {
     println!("\n╔══════════════════════════════════════════════════════════════╗");
     println!("║                    应用迁移                                  ║");
     println!("╚══════════════════════════════════════════════════════════════╝");
 
     let pool = DbPool::new(database_url).await?;
-    let db_type = detect_database_type(database_url);
+    let db_type = pool.database_type();
 
     println!("\n📊 数据库类型: {}", db_type);
     println!("📁 迁移目录: {}", migrations_dir.display());
@@ -360,11 +552,37 @@ async fn run_migrations_up(database_url: &str, migrations_dir: &PathBuf, target_
     let connection = session.connection()?.clone();
     let mut executor = MigrationExecutor::new(connection, db_type);
 
+    // 获取迁移锁，阻止其他迁移进程并发执行
+    let lock = executor.acquire_lock(lock_timeout).await?;
+    let result = apply_pending_migrations(&mut executor, &migrations, target_version, no_transaction).await;
+    release_lock(lock, result).await
+}
+
+/// 释放迁移锁后返回迁移结果
+///
+/// 释放失败时打印警告；迁移本身已失败时返回迁移错误，不被释放错误覆盖
+async fn release_lock(lock: MigrationLock, result: DbResult<()>) -> DbResult<()> {
+    match lock.release().await {
+        Ok(()) => result,
+        Err(e) => {
+            println!("\n⚠️  释放迁移锁失败: {}", e);
+            result.and(Err(e))
+        }
+    }
+}
+
+/// 应用待执行的迁移（调用方需持有迁移锁）
+async fn apply_pending_migrations(
+    executor: &mut MigrationExecutor,
+    migrations: &[MigrationInfo],
+    target_version: Option<u64>,
+    no_transaction: bool,
+) -> DbResult<()> {
     // 加载迁移历史
     executor.load_history().await?;
 
     // 筛选待应用的迁移
-    let applied_versions: std::collections::HashSet<u32> =
+    let applied_versions: std::collections::HashSet<u64> =
         executor.history.applied_migrations.iter().map(|m| m.version).collect();
 
     let mut to_apply: Vec<_> = migrations
@@ -392,52 +610,113 @@ async fn run_migrations_up(database_url: &str, migrations_dir: &PathBuf, target_
         println!("   目标版本: {}", target);
     }
 
-    // 应用迁移
-    println!("\n🚀 开始应用迁移...");
-    let mut success_count = 0;
-
+    // 读取迁移文件
+    let mut contents = Vec::with_capacity(to_apply.len());
     for migration in &to_apply {
-        print!("   正在应用 v{} - {} ... ", migration.version, migration.description);
+        let content = fs::read_to_string(&migration.file_path).map_err(|e| {
+            DbError::migration(
+                migration.version,
+                "load",
+                format!("无法读取迁移文件 {}: {}", migration.file_path.display(), e),
+            )
+        })?;
+        contents.push(content);
+    }
 
-        match std::fs::read_to_string(&migration.file_path) {
-            Ok(content) => match parse_and_apply_migration(&mut executor, &content, migration.version, db_type).await {
-                Ok(_) => {
-                    println!("✓");
-                    success_count += 1;
-                }
-                Err(e) => {
-                    println!("❌ 失败: {}", e);
-                    return Err(e);
-                }
-            },
-            Err(e) => {
-                println!("❌ 无法读取文件: {}", e);
+    println!("\n🚀 开始应用迁移...");
+
+    // 存在事务外执行的迁移时无法把整个计划放进一个事务，逐个应用
+    if no_transaction || contents.iter().any(|c| MigrationFileParser::requires_no_transaction(c)) {
+        for (migration, content) in to_apply.iter().zip(&contents) {
+            print!("   正在应用 v{} - {} ... ", migration.version, migration.description);
+            if let Err(e) = parse_and_apply_migration(executor, content, migration.version, no_transaction).await {
+                println!("❌ 失败: {}", e);
+                return Err(e);
             }
+            println!("✓");
         }
+    } else {
+        let mut plan_migrations = Vec::with_capacity(to_apply.len());
+        for (migration, content) in to_apply.iter().zip(&contents) {
+            plan_migrations.push(migration_from_file(migration, content, "UP")?);
+        }
+        let plan = MigrationPlan {
+            migrations: plan_migrations,
+            direction: MigrationDirection::Up,
+        };
+        executor.execute_plan(&plan, print_progress).await.inspect_err(|e| {
+            println!("   ❌ 失败: {}", e);
+        })?;
     }
 
-    println!("\n✅ 成功应用 {} / {} 个迁移", success_count, to_apply.len());
+    println!("\n✅ 成功应用 {} 个迁移", to_apply.len());
     println!("\n{}", "─".repeat(60));
 
     Ok(())
 }
 
+/// 由迁移文件构造计划中的一步，`section` 为 `UP` 或 `DOWN`
+fn migration_from_file(info: &MigrationInfo, content: &str, section: &str) -> DbResult<Migration> {
+    let description = MigrationFileParser::parse_migration_file(content)
+        .map(|(description, _)| description)
+        .unwrap_or_else(|_| "Migration".to_string());
+
+    let mut migration = Migration::new(info.version, description);
+    migration.sql = Some(extract_sql_section(content, section)?);
+    Ok(migration)
+}
+
+/// 打印迁移计划的执行进度
+fn print_progress(progress: MigrationProgress) {
+    let action = match progress.direction {
+        MigrationDirection::Up => "应用",
+        MigrationDirection::Down => "回滚",
+    };
+    println!(
+        "   [{}/{}] {} v{} - {} ✓",
+        progress.step, progress.total, action, progress.version, progress.description
+    );
+}
+
 /// 运行向下的迁移（回滚迁移）
-async fn run_migrations_down(database_url: &str, target_version: Option<u32>, rollback_all: bool) -> DbResult<()> {
+async fn run_migrations_down(
+    database_url: &str,
+    migrations_dir: &PathBuf,
+    target_version: Option<u64>,
+    rollback_all: bool,
+    lock_timeout: Duration,
+) -> DbResult<()> {
     println!("\n╔══════════════════════════════════════════════════════════════╗");
     println!("║                    回滚迁移                                  ║");
     println!("╚══════════════════════════════════════════════════════════════╝");
 
     let pool = DbPool::new(database_url).await?;
-    let db_type = detect_database_type(database_url);
+    let db_type = pool.database_type();
 
     println!("\n📊 数据库类型: {}", db_type);
+    println!("📁 迁移目录: {}", migrations_dir.display());
+
+    // 扫描本地迁移文件，用于查找 DOWN SQL
+    let local_migrations = scan_migration_files(migrations_dir)?;
 
     // 创建迁移执行器
     let mut session = pool.get_session("admin").await?;
     let connection = session.connection()?.clone();
     let mut executor = MigrationExecutor::new(connection, db_type);
 
+    // 获取迁移锁，阻止其他迁移进程并发执行
+    let lock = executor.acquire_lock(lock_timeout).await?;
+    let result = rollback_applied_migrations(&mut executor, &local_migrations, target_version, rollback_all).await;
+    release_lock(lock, result).await
+}
+
+/// 回滚已应用的迁移（调用方需持有迁移锁）
+async fn rollback_applied_migrations(
+    executor: &mut MigrationExecutor,
+    local_migrations: &[MigrationInfo],
+    target_version: Option<u64>,
+    rollback_all: bool,
+) -> DbResult<()> {
     // 加载迁移历史
     executor.load_history().await?;
 
@@ -449,7 +728,7 @@ async fn run_migrations_down(database_url: &str, target_version: Option<u32>, ro
     }
 
     // 确定要回滚的版本
-    let versions_to_rollback: Vec<u32> = if rollback_all {
+    let versions_to_rollback: Vec<u64> = if rollback_all {
         applied_migrations.iter().map(|m| m.version).collect()
     } else if let Some(target) = target_version {
         applied_migrations
@@ -476,71 +755,48 @@ async fn run_migrations_down(database_url: &str, target_version: Option<u32>, ro
         println!("   模式: 回滚上一个版本");
     }
 
-    // 执行回滚
     println!("\n🔄 开始回滚迁移...");
-    let mut success_count = 0;
-
-    // 收集需要回滚的迁移信息，避免在循环中借用
-    let rollback_info: Vec<(u32, String)> = versions_to_rollback
-        .iter()
-        .filter_map(|version| {
-            applied_migrations
-                .iter()
-                .find(|m| m.version == *version)
-                .map(|info| (info.version, info.description.clone()))
-        })
-        .collect();
-
-    for (version, description) in &rollback_info {
-        print!("   正在回滚 v{} - {} ... ", version, description);
+    let rolled_back = rollback_versions(executor, &versions_to_rollback, local_migrations).await?;
 
-        match rollback_migration(&mut executor, *version, db_type).await {
-            Ok(_) => {
-                println!("✓");
-                success_count += 1;
-            }
-            Err(e) => {
-                println!("❌ 失败: {}", e);
-                // 继续尝试回滚其他迁移
-            }
-        }
-    }
-
-    println!(
-        "\n✅ 成功回滚 {} / {} 个迁移",
-        success_count,
-        versions_to_rollback.len()
-    );
+    println!("\n✅ 成功回滚 {} 个迁移", rolled_back);
     println!("\n{}", "─".repeat(60));
 
     Ok(())
 }
 
-/// 回滚单个迁移
-async fn rollback_migration(
+/// 按给定顺序回滚迁移
+///
+/// 所有版本的 DOWN SQL 与历史记录删除在同一事务中执行；
+/// 任一版本找不到本地迁移文件或执行失败时不修改历史，避免 schema 与历史不一致
+async fn rollback_versions(
     executor: &mut MigrationExecutor,
-    version: u32,
-    db_type: MigrationDatabaseType,
-) -> DbResult<()> {
-    use dbnexus::orm::{ConnectionTrait, TransactionTrait};
+    versions: &[u64],
+    local_migrations: &[MigrationInfo],
+) -> DbResult<usize> {
+    let mut plan_migrations = Vec::with_capacity(versions.len());
+    for version in versions {
+        let info = local_migrations
+            .iter()
+            .find(|m| m.version == *version)
+            .ok_or_else(|| DbError::migration(*version, "rollback", "找不到对应的迁移文件"))?;
+
+        let content = fs::read_to_string(&info.file_path).map_err(|e| {
+            DbError::migration(
+                *version,
+                "rollback",
+                format!("无法读取迁移文件 {}: {}", info.file_path.display(), e),
+            )
+        })?;
+        plan_migrations.push(migration_from_file(info, &content, "DOWN")?);
+    }
 
-    // 删除迁移历史记录
-    let delete_sql = match db_type {
-        MigrationDatabaseType::Postgres | MigrationDatabaseType::MySql => {
-            format!("DELETE FROM dbnexus_migrations WHERE version = {};", version)
-        }
-        MigrationDatabaseType::Sqlite => {
-            format!("DELETE FROM dbnexus_migrations WHERE version = {};", version)
-        }
+    let plan = MigrationPlan {
+        migrations: plan_migrations,
+        direction: MigrationDirection::Down,
     };
-
-    let txn = executor.connection.begin().await.map_err(DbError::Connection)?;
-
-    txn.execute_unprepared(&delete_sql).await.map_err(DbError::Connection)?;
-
-    txn.commit().await.map_err(DbError::Connection)?;
-
-    Ok(())
+    executor.execute_plan(&plan, print_progress).await.inspect_err(|e| {
+        println!("   ❌ 失败: {}", e);
+    })
 }
 
 /// 扫描迁移目录中的文件
@@ -576,24 +832,10 @@ fn scan_migration_files(dir: &PathBuf) -> Result<Vec<MigrationInfo>, DbError> {
     Ok(migrations)
 }
 
-/// 解析迁移文件名
-fn parse_migration_filename(filename: &str) -> Option<(u32, String)> {
-    // 格式: {version}_{description}.sql
-    let parts: Vec<&str> = filename.split('_').collect();
-    if parts.len() < 2 {
-        return None;
-    }
-
-    let version = parts[0].parse::<u32>().ok()?;
-    let description = parts[1..].join("_").replace(".sql", "");
-
-    Some((version, description))
-}
-
 /// 迁移文件信息
 #[derive(Debug, Clone)]
 struct MigrationInfo {
-    version: u32,
+    version: u64,
     description: String,
     file_path: PathBuf,
 }
@@ -710,11 +952,14 @@ fn generate_schema_diff_sql(_from_content: &str, _to_content: &str) -> Result<Di
 }
 
 /// 解析并应用迁移
+///
+/// 文件包含 `-- dbnexus:no-transaction` 指令或指定 `no_transaction` 时，
+/// UP SQL 在事务外执行，成功后再记录迁移历史
 async fn parse_and_apply_migration(
     executor: &mut MigrationExecutor,
     content: &str,
-    version: u32,
-    db_type: MigrationDatabaseType,
+    version: u64,
+    no_transaction: bool,
 ) -> DbResult<()> {
     use dbnexus::orm::{ConnectionTrait, TransactionTrait};
 
@@ -724,6 +969,24 @@ async fn parse_and_apply_migration(
 
     // 提取 UP SQL（-- UP 到 -- DOWN 之间）
     let up_sql = extract_sql_section(content, "UP")?;
+    let file_path = format!("migration_v{}.sql", version);
+    let applied_at = time::OffsetDateTime::now_utc();
+
+    if no_transaction || MigrationFileParser::requires_no_transaction(content) {
+        if !up_sql.trim().is_empty() {
+            executor
+                .connection
+                .execute_unprepared(&up_sql)
+                .await
+                .map_err(DbError::Connection)?;
+        }
+
+        executor
+            .record_applied(&executor.connection, version, &description, applied_at, &file_path)
+            .await?;
+
+        return Ok(());
+    }
 
     // 开始事务
     let txn = executor.connection.begin().await.map_err(DbError::Connection)?;
@@ -734,30 +997,9 @@ async fn parse_and_apply_migration(
     }
 
     // 记录迁移历史
-    let insert_sql = match db_type {
-        MigrationDatabaseType::Postgres | MigrationDatabaseType::MySql => {
-            format!(
-                "INSERT INTO dbnexus_migrations (version, description, applied_at, file_path) \
-                 VALUES ({}, '{}', '{}', 'migration_v{}.sql');",
-                version,
-                description.replace('\'', "''"),
-                chrono::Utc::now().to_rfc3339(),
-                version
-            )
-        }
-        MigrationDatabaseType::Sqlite => {
-            format!(
-                "INSERT INTO dbnexus_migrations (version, description, applied_at, file_path) \
-                 VALUES ({}, '{}', '{}', 'migration_v{}.sql');",
-                version,
-                description.replace('\'', "''"),
-                chrono::Utc::now().to_rfc3339(),
-                version
-            )
-        }
-    };
-
-    txn.execute_unprepared(&insert_sql).await.map_err(DbError::Connection)?;
+    executor
+        .record_applied(&txn, version, &description, applied_at, &file_path)
+        .await?;
 
     txn.commit().await.map_err(DbError::Connection)?;
 
@@ -769,18 +1011,22 @@ fn extract_sql_section(content: &str, section: &str) -> Result<String, DbError>
     let section_start = format!("-- {}", section);
     let section_end = format!("-- {}", if section == "UP" { "DOWN" } else { "UP" });
 
-    let start_idx = content.find(&section_start).map(|i| i + section_start.len());
-    let end_idx = content.find(&section_end);
+    let Some(start) = content.find(&section_start).map(|i| i + section_start.len()) else {
+        return Ok(String::new());
+    };
 
-    if let Some(start) = start_idx {
-        if let Some(end) = end_idx {
-            Ok(content[start..end].trim().to_string())
-        } else {
-            Ok(content[start..].trim().to_string())
-        }
-    } else {
-        Ok(String::new())
-    }
+    // 跳过标记行的剩余部分（如 "-- UP: Apply migration"）
+    let body_start = content[start..]
+        .find('\n')
+        .map(|i| start + i + 1)
+        .unwrap_or(content.len());
+    // 结束标记只在当前部分之后查找（DOWN 部分位于 UP 之后）
+    let end = content[body_start..]
+        .find(&section_end)
+        .map(|i| body_start + i)
+        .unwrap_or(content.len());
+
+    Ok(content[body_start..end].trim().to_string())
 }
 
 /// 列出所有迁移文件
@@ -814,17 +1060,6 @@ fn list_migrations(migrations_dir: &PathBuf) -> Result<(), DbError> {
     Ok(())
 }
 
-/// 检测数据库类型
-fn detect_database_type(database_url: &str) -> MigrationDatabaseType {
-    if database_url.starts_with("postgres") {
-        MigrationDatabaseType::Postgres
-    } else if database_url.starts_with("mysql") {
-        MigrationDatabaseType::MySql
-    } else {
-        MigrationDatabaseType::Sqlite
-    }
-}
-
 /// 隐藏数据库 URL 中的敏感信息
 fn mask_database_url(url: &str) -> String {
     // 隐藏密码
@@ -839,3 +1074,220 @@ fn mask_database_url(url: &str) -> String {
 
     masked
 }
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use dbnexus::orm::ConnectionTrait;
+
+    /// 创建基于临时 SQLite 文件的迁移执行器
+    async fn sqlite_executor(name: &str) -> (MigrationExecutor, PathBuf) {
+        let db_path = std::env::temp_dir().join(format!("dbnexus_cli_{}_{}.db", name, std::process::id()));
+        let _ = fs::remove_file(&db_path);
+        fs::File::create(&db_path).expect("Failed to create database file");
+
+        let pool = DbPool::new(&format!("sqlite:///{}", db_path.display()))
+            .await
+            .expect("Failed to create pool");
+        let mut session = pool.get_session("admin").await.expect("Failed to get session");
+        let connection = session.connection().expect("Failed to get connection").clone();
+
+        let mut executor = MigrationExecutor::new(connection, MigrationDatabaseType::Sqlite);
+        executor.load_history().await.expect("Failed to load history");
+
+        (executor, db_path)
+    }
+
+    async fn history_contains(executor: &MigrationExecutor, version: u64) -> bool {
+        let stmt = dbnexus::orm::Statement::from_string(
+            executor.connection.get_database_backend(),
+            format!("SELECT version FROM dbnexus_migrations WHERE version = {}", version),
+        );
+        executor
+            .connection
+            .query_one_raw(stmt)
+            .await
+            .expect("Failed to query history")
+            .is_some()
+    }
+
+    /// TEST-CLI-016: 默认在事务中应用迁移并记录历史
+    #[tokio::test]
+    async fn test_apply_migration_in_transaction() {
+        let (mut executor, db_path) = sqlite_executor("txn").await;
+        let content = "-- Migration: create_items\n\n-- UP\nCREATE TABLE items (id INTEGER PRIMARY KEY);\n\n-- DOWN\nDROP TABLE items;\n";
+
+        parse_and_apply_migration(&mut executor, content, 1, false)
+            .await
+            .expect("Transactional migration should succeed");
+        assert!(history_contains(&executor, 1).await);
+
+        // VACUUM 无法在事务中执行，失败后不应记录历史
+        let vacuum = "-- Migration: vacuum\n\n-- UP\nVACUUM;\n\n-- DOWN\n";
+        assert!(
+            parse_and_apply_migration(&mut executor, vacuum, 2, false)
+                .await
+                .is_err()
+        );
+        assert!(!history_contains(&executor, 2).await);
+
+        let _ = fs::remove_file(db_path);
+    }
+
+    /// TEST-CLI-017: no-transaction 指令与参数使迁移在事务外执行
+    #[tokio::test]
+    async fn test_apply_migration_without_transaction() {
+        let (mut executor, db_path) = sqlite_executor("no_txn").await;
+
+        let directive = "-- Migration: vacuum\n-- dbnexus:no-transaction\n\n-- UP\nVACUUM;\n\n-- DOWN\n";
+        parse_and_apply_migration(&mut executor, directive, 1, false)
+            .await
+            .expect("Directive migration should run outside a transaction");
+        assert!(history_contains(&executor, 1).await);
+
+        let plain = "-- Migration: vacuum_again\n\n-- UP\nVACUUM;\n\n-- DOWN\n";
+        parse_and_apply_migration(&mut executor, plain, 2, true)
+            .await
+            .expect("--no-transaction migration should run outside a transaction");
+        assert!(history_contains(&executor, 2).await);
+
+        let _ = fs::remove_file(db_path);
+    }
+
+    /// TEST-CLI-018: 应用后回滚会执行 DOWN SQL 并删除历史
+    #[tokio::test]
+    async fn test_up_then_down_drops_table() {
+        let (mut executor, db_path) = sqlite_executor("down").await;
+        let file_path = db_path.with_extension("sql");
+        let content = "-- Migration: create_items\n\n-- UP: Apply migration\nCREATE TABLE items (id INTEGER PRIMARY KEY);\n\n-- DOWN: Rollback migration\nDROP TABLE items;\n";
+        fs::write(&file_path, content).expect("Failed to write migration file");
+        let local_migrations = vec![MigrationInfo {
+            version: 1,
+            description: "create_items".to_string(),
+            file_path: file_path.clone(),
+        }];
+
+        parse_and_apply_migration(&mut executor, content, 1, false)
+            .await
+            .expect("Migration should apply");
+        executor.load_history().await.expect("Failed to reload history");
+        assert!(executor.history.is_version_applied(1));
+
+        // 没有本地文件时不修改历史
+        assert!(rollback_versions(&mut executor, &[1], &[]).await.is_err());
+        assert!(history_contains(&executor, 1).await);
+
+        rollback_versions(&mut executor, &[1], &local_migrations)
+            .await
+            .expect("Rollback should succeed");
+        assert!(!history_contains(&executor, 1).await);
+
+        let stmt = dbnexus::orm::Statement::from_string(
+            executor.connection.get_database_backend(),
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'items'".to_string(),
+        );
+        let table = executor
+            .connection
+            .query_one_raw(stmt)
+            .await
+            .expect("Failed to query schema");
+        assert!(table.is_none(), "DOWN SQL should drop the table");
+
+        let _ = fs::remove_file(file_path);
+        let _ = fs::remove_file(db_path);
+    }
+
+    /// TEST-CLI-019: verify 检测手动添加的列
+    #[tokio::test]
+    async fn test_verify_detects_manual_column() {
+        let (mut executor, db_path) = sqlite_executor("verify").await;
+        let file_path = db_path.with_extension("sql");
+        let content = "-- Migration: create_items\n\n-- UP\nCREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT NOT NULL);\n\n-- DOWN\nDROP TABLE items;\n";
+        fs::write(&file_path, content).expect("Failed to write migration file");
+        let local_migrations = vec![MigrationInfo {
+            version: 1,
+            description: "create_items".to_string(),
+            file_path: file_path.clone(),
+        }];
+
+        parse_and_apply_migration(&mut executor, content, 1, false)
+            .await
+            .expect("Migration should apply");
+
+        let report = verify_schema(&mut executor, &local_migrations, MigrationDatabaseType::Sqlite)
+            .await
+            .expect("Verify should run");
+        assert!(report.is_empty(), "Unexpected discrepancies: {:?}", report);
+
+        executor
+            .connection
+            .execute_unprepared("ALTER TABLE items ADD COLUMN note TEXT")
+            .await
+            .expect("Failed to alter table");
+
+        let report = verify_schema(&mut executor, &local_migrations, MigrationDatabaseType::Sqlite)
+            .await
+            .expect("Verify should run");
+        assert_eq!(report, vec!["表 items 存在未记录的列 note".to_string()]);
+
+        let _ = fs::remove_file(file_path);
+        let _ = fs::remove_file(db_path);
+    }
+
+    /// TEST-CLI-020: 超出 u32 范围的时间戳版本可以应用并读回历史
+    #[tokio::test]
+    async fn test_large_timestamp_version() {
+        let (mut executor, db_path) = sqlite_executor("large_version").await;
+        let (version, description) =
+            parse_migration_filename("1735689600000_create_items.sql").expect("Filename should parse");
+        assert_eq!(description, "create_items");
+
+        let content = "-- Migration: create_items\n\n-- UP\nCREATE TABLE items (id INTEGER PRIMARY KEY);\n\n-- DOWN\nDROP TABLE items;\n";
+        parse_and_apply_migration(&mut executor, content, version, false)
+            .await
+            .expect("Migration should apply");
+
+        executor.load_history().await.expect("Failed to reload history");
+        assert_eq!(executor.history.get_latest_version(), Some(1_735_689_600_000));
+
+        let _ = fs::remove_file(db_path);
+    }
+
+    /// TEST-CLI-021: 释放迁移锁失败时不覆盖迁移错误，迁移成功时返回释放错误
+    #[tokio::test]
+    async fn test_release_lock_keeps_migration_error() {
+        let (executor, db_path) = sqlite_executor("release_lock").await;
+        let break_lock_table = || async {
+            executor
+                .connection
+                .execute_unprepared("DROP TABLE dbnexus_migrations_lock")
+                .await
+                .expect("Failed to drop lock table");
+        };
+
+        let lock = executor
+            .acquire_lock(Duration::from_secs(1))
+            .await
+            .expect("Failed to acquire lock");
+        break_lock_table().await;
+        let migration_error = DbError::migration(2, "up", "boom".to_string());
+        let err = release_lock(lock, Err(migration_error)).await.unwrap_err();
+        assert!(err.to_string().contains("boom"), "{}", err);
+
+        let lock = executor
+            .acquire_lock(Duration::from_secs(1))
+            .await
+            .expect("Failed to acquire lock");
+        break_lock_table().await;
+        let err = release_lock(lock, Ok(())).await.unwrap_err();
+        assert!(matches!(err, DbError::Connection(_)), "{}", err);
+
+        let lock = executor
+            .acquire_lock(Duration::from_secs(1))
+            .await
+            .expect("Failed to acquire lock");
+        assert!(release_lock(lock, Ok(())).await.is_ok());
+
+        let _ = fs::remove_file(db_path);
+    }
+}
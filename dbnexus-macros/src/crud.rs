@@ -0,0 +1,679 @@
+// Copyright (c) 2025 Kirky.X
+//
+// Licensed under the MIT License
+// See LICENSE file in the project root for full license information.
+
+//! `#[db_crud]` 展开
+//!
+//! 标注在 Sea-ORM 实体模块的 `Model` 上，为同模块的 `Entity` 生成查询和增删改方法，
+//! 并为 `#[sea_orm(unique)]` / `#[sea_orm(indexed)]` 列生成 `find_by_<column>`；
+//! 生成代码引用同模块的 `Entity`、`ActiveModel` 与 `Column`。
+//!
+//! 开启 `cache` 选项（或同时标注 `#[db_cache]`）时，`create` / `modify` / `remove` 额外接收
+//! `&CacheManager<Model>`，成功后使 `CacheKey::new(表名, 主键)` 失效，并生成 `fetch_cached`。
+//! 开启 `audit` 选项（或同时标注 `#[db_audit]`）时，改为调用 Session 的 `*_audited` 方法，
+//! 在同一连接（或事务）中写入 `audit_log` 记录。
+//! 指定 `deny_select`（或同时标注 `#[db_permission(deny_select = [...])]`）时生成 `column_policy`，
+//! 完整行查询 `fetch` 对非豁免角色报错，另外生成只返回可见列的 `fetch_visible` / `list_visible`
+//! 以及按指定列查询的 `fetch_columns`。
+
+use crate::entity::EntityModel;
+use crate::options::CrudOptions;
+use crate::permission::{operation_check, role_methods};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{ItemStruct, LitStr};
+
+/// 生成代码引用的实体类型
+struct EntityPaths {
+    /// `EntityTrait` 类型
+    entity: TokenStream,
+    /// `ActiveModel` 类型
+    active_model: TokenStream,
+    /// `Column` 类型
+    column: TokenStream,
+}
+
+impl EntityPaths {
+    fn new(model: &EntityModel) -> Self {
+        if model.plain {
+            let module = model.entity_module();
+            Self {
+                entity: quote!(#module::Entity),
+                active_model: quote!(#module::ActiveModel),
+                column: quote!(#module::Column),
+            }
+        } else {
+            Self {
+                entity: quote!(Self),
+                active_model: quote!(ActiveModel),
+                column: quote!(Column),
+            }
+        }
+    }
+}
+
+/// 展开 `#[db_crud]`
+pub(crate) fn expand(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let mut item: ItemStruct = syn::parse2(item)?;
+    let options = CrudOptions::parse(attr, &mut item)?;
+    let model = EntityModel::parse(&item)?;
+    let paths = EntityPaths::new(&model);
+    let mut methods = if model.plain {
+        plain_methods(&model, &options, &paths)?
+    } else {
+        crud_methods(&model, &options)?
+    };
+    methods.extend(permission_methods(&model, &options, &paths)?);
+    methods.extend(finder_methods(&model, &paths));
+    let roles = role_methods(&model, &options)?;
+
+    let target = if model.plain {
+        let ident = &model.ident;
+        quote!(#ident)
+    } else {
+        quote!(Entity)
+    };
+
+    Ok(quote! {
+        #item
+
+        impl #target {
+            #methods
+        }
+
+        #roles
+    })
+}
+
+/// 有 `deny_select` 时，完整行查询先检查当前角色能否查询所有列
+fn column_guard(options: &CrudOptions, paths: &EntityPaths) -> TokenStream {
+    if options.deny_select.is_empty() {
+        return TokenStream::new();
+    }
+    let column = &paths.column;
+    quote! {
+        let columns: ::std::vec::Vec<&str> = <#column as ::dbnexus::orm::Iterable>::iter()
+            .map(|column| ::dbnexus::orm::IdenStatic::as_str(&column))
+            .collect();
+        session.check_column_access(&Self::column_policy(), &columns)?;
+    }
+}
+
+/// Session 写操作使用的方法名，`audit` 选项下使用写入审计记录的版本
+fn write_methods(options: &CrudOptions) -> (TokenStream, TokenStream, TokenStream) {
+    if options.audit {
+        (
+            quote!(insert_model_audited),
+            quote!(update_model_audited),
+            quote!(delete_model_audited),
+        )
+    } else {
+        (quote!(insert_model), quote!(update_model), quote!(delete_model))
+    }
+}
+
+/// 为普通结构体生成与 dbnexus 0.1.0 一致的 `insert` / `find_by_id` / `update` / `delete` /
+/// `find_all` / `delete_many` / `count`，通过 `#[derive(DbEntity)]` 生成的 `<snake>_entity` 模块访问数据库
+fn plain_methods(model: &EntityModel, options: &CrudOptions, paths: &EntityPaths) -> syn::Result<TokenStream> {
+    if options.cache {
+        return Err(syn::Error::new_spanned(
+            &model.ident,
+            "#[db_crud(cache)] requires a Sea-ORM `Model` with #[sea_orm(table_name = \"...\")]",
+        ));
+    }
+
+    let vis = &model.vis;
+    let key = model.single_primary_key("db_crud")?;
+    let key_ty = &key.ty;
+    let key_ident = &key.ident;
+    let (insert, update, delete) = write_methods(options);
+    let EntityPaths {
+        entity, active_model, ..
+    } = paths;
+    let guard = column_guard(options, paths);
+    let check = |operation| operation_check(model, options, operation);
+    let (select, insert_check, update_check, delete_check) =
+        (check("Select"), check("Insert"), check("Update"), check("Delete"));
+    let into_active_model = quote!(<Self as ::dbnexus::orm::IntoActiveModel<#active_model>>::into_active_model);
+
+    Ok(quote! {
+        #[doc = " 插入一行并返回插入后的实体（带权限检查）"]
+        #vis async fn insert(session: &mut ::dbnexus::Session, entity: Self) -> ::dbnexus::DbResult<Self> {
+            #insert_check
+            session.#insert(#into_active_model(entity)).await.map(Self::from)
+        }
+
+        #[doc = " 按主键查询（带权限检查）"]
+        #vis async fn find_by_id(
+            session: &::dbnexus::Session,
+            id: #key_ty,
+        ) -> ::dbnexus::DbResult<::std::option::Option<Self>> {
+            #select
+            #guard
+            Ok(session.find_model::<#entity>(id).await?.map(Self::from))
+        }
+
+        #[doc = " 按实体的主键更新整行，返回更新后的实体；没有匹配的行时返回错误（带权限检查）"]
+        #vis async fn update(session: &mut ::dbnexus::Session, entity: Self) -> ::dbnexus::DbResult<Self> {
+            #update_check
+            let id = ::std::clone::Clone::clone(&entity.#key_ident);
+            match session.#update(id, #into_active_model(entity)).await? {
+                ::std::option::Option::Some(updated) => Ok(Self::from(updated)),
+                ::std::option::Option::None => Err(::dbnexus::orm::DbErr::RecordNotUpdated.into()),
+            }
+        }
+
+        #[doc = " 按主键删除，返回删除的行数（带权限检查）"]
+        #vis async fn delete(session: &mut ::dbnexus::Session, id: #key_ty) -> ::dbnexus::DbResult<u64> {
+            #delete_check
+            session.#delete::<#entity>(id).await
+        }
+
+        #[doc = " 查询全部行（带权限检查）"]
+        #vis async fn find_all(session: &::dbnexus::Session) -> ::dbnexus::DbResult<::std::vec::Vec<Self>> {
+            #select
+            #guard
+            let models = session.find_models::<#entity>(::dbnexus::Condition::all()).await?;
+            Ok(models.into_iter().map(Self::from).collect())
+        }
+
+        #[doc = " 删除满足 `filter` 的全部行，返回删除的行数（带权限检查）"]
+        #vis async fn delete_many(
+            session: &mut ::dbnexus::Session,
+            filter: ::dbnexus::Condition,
+        ) -> ::dbnexus::DbResult<u64> {
+            #delete_check
+            session.delete_models::<#entity>(filter).await
+        }
+
+        #[doc = " 统计表的总行数（带权限检查）"]
+        #vis async fn count(session: &::dbnexus::Session) -> ::dbnexus::DbResult<u64> {
+            #select
+            session.count_models::<#entity>().await
+        }
+    })
+}
+
+/// 为 Sea-ORM `Entity` 生成分页、计数和基于 Session 的增删改查方法
+fn crud_methods(model: &EntityModel, options: &CrudOptions) -> syn::Result<TokenStream> {
+    let vis = &model.vis;
+    let model_ident = &model.ident;
+    let key = model.single_primary_key("db_crud")?;
+    let key_ty = &key.ty;
+    let key_ident = &key.ident;
+    let (insert, update, delete) = write_methods(options);
+    let check = |operation| operation_check(model, options, operation);
+    let (select, insert_check, update_check, delete_check) =
+        (check("Select"), check("Insert"), check("Update"), check("Delete"));
+
+    // 完整行包含受保护的列，非豁免角色查询时报错
+    let fetch_doc = if options.deny_select.is_empty() {
+        " 按主键查询（带权限检查）"
+    } else {
+        " 按主键查询完整的行（带权限检查），角色无权查询受保护的列时返回错误"
+    };
+    let guard = column_guard(options, &EntityPaths::new(model));
+
+    let mut methods = quote! {
+        #[doc = " 分页查询，按主键升序返回第 `page` 页（从 0 开始）及总行数"]
+        #vis async fn list_paginated<C>(
+            conn: &C,
+            page: u64,
+            per_page: u64,
+        ) -> ::dbnexus::DbResult<(::std::vec::Vec<#model_ident>, u64)>
+        where
+            C: ::dbnexus::orm::ConnectionTrait,
+        {
+            ::dbnexus::entity::list_paginated::<Self, C>(conn, page, per_page).await
+        }
+
+        #[doc = " 统计表的总行数"]
+        #vis async fn count<C>(conn: &C) -> ::dbnexus::DbResult<u64>
+        where
+            C: ::dbnexus::orm::ConnectionTrait,
+        {
+            ::dbnexus::entity::count::<Self, C>(conn).await
+        }
+
+        #[doc = #fetch_doc]
+        #vis async fn fetch(
+            session: &::dbnexus::Session,
+            id: #key_ty,
+        ) -> ::dbnexus::DbResult<::std::option::Option<#model_ident>> {
+            #select
+            #guard
+            session.find_model::<Self>(id).await
+        }
+    };
+
+    if !options.cache {
+        methods.extend(quote! {
+            #[doc = " 插入一行并返回插入后的 Model（带权限检查）"]
+            #vis async fn create(session: &mut ::dbnexus::Session, model: ActiveModel) -> ::dbnexus::DbResult<#model_ident> {
+                #insert_check
+                session.#insert(model).await
+            }
+
+            #[doc = " 更新主键为 `id` 的行，没有匹配的行时返回 `None`（带权限检查）"]
+            #vis async fn modify(
+                session: &mut ::dbnexus::Session,
+                id: #key_ty,
+                model: ActiveModel,
+            ) -> ::dbnexus::DbResult<::std::option::Option<#model_ident>> {
+                #update_check
+                session.#update(id, model).await
+            }
+
+            #[doc = " 按主键删除，返回删除的行数（带权限检查）"]
+            #vis async fn remove(session: &mut ::dbnexus::Session, id: #key_ty) -> ::dbnexus::DbResult<u64> {
+                #delete_check
+                session.#delete::<Self>(id).await
+            }
+        });
+        return Ok(methods);
+    }
+
+    let cache_ty = quote!(::dbnexus::cache::CacheManager<#model_ident>);
+    let table = quote!(::dbnexus::orm::EntityName::table_name(&Self::default()));
+    methods.extend(quote! {
+        #[doc = " 按主键查询，优先读取缓存（带权限检查）"]
+        #vis async fn fetch_cached(
+            session: &::dbnexus::Session,
+            cache: &#cache_ty,
+            id: #key_ty,
+            ttl: ::std::time::Duration,
+        ) -> ::dbnexus::DbResult<::std::option::Option<#model_ident>> {
+            #select
+            #guard
+            session.find_cached::<Self>(id, cache, ttl).await
+        }
+
+        #[doc = " 插入一行并返回插入后的 Model，成功后清除该主键的缓存（包括空值占位）"]
+        #vis async fn create(
+            session: &mut ::dbnexus::Session,
+            cache: &#cache_ty,
+            model: ActiveModel,
+        ) -> ::dbnexus::DbResult<#model_ident> {
+            #insert_check
+            let created = session.#insert(model).await?;
+            cache
+                .invalidate_entity(#table, &::std::string::ToString::to_string(&created.#key_ident))
+                .await;
+            Ok(created)
+        }
+
+        #[doc = " 更新主键为 `id` 的行，没有匹配的行时返回 `None`；成功后清除新旧主键的缓存"]
+        #vis async fn modify(
+            session: &mut ::dbnexus::Session,
+            cache: &#cache_ty,
+            id: #key_ty,
+            model: ActiveModel,
+        ) -> ::dbnexus::DbResult<::std::option::Option<#model_ident>> {
+            #update_check
+            let old_id = ::std::string::ToString::to_string(&id);
+            let updated = session.#update(id, model).await?;
+            match &updated {
+                ::std::option::Option::Some(updated) => {
+                    let new_id = ::std::string::ToString::to_string(&updated.#key_ident);
+                    cache.invalidate_entity_update(#table, &old_id, &new_id).await;
+                }
+                ::std::option::Option::None => cache.invalidate_entity(#table, &old_id).await,
+            }
+            Ok(updated)
+        }
+
+        #[doc = " 按主键删除，返回删除的行数；成功后清除该主键的缓存"]
+        #vis async fn remove(
+            session: &mut ::dbnexus::Session,
+            cache: &#cache_ty,
+            id: #key_ty,
+        ) -> ::dbnexus::DbResult<u64> {
+            #delete_check
+            let key = ::std::string::ToString::to_string(&id);
+            let deleted = session.#delete::<Self>(id).await?;
+            cache.invalidate_entity(#table, &key).await;
+            Ok(deleted)
+        }
+    });
+
+    Ok(methods)
+}
+
+/// 生成列级权限策略及按可见列投影的查询方法
+fn permission_methods(model: &EntityModel, options: &CrudOptions, paths: &EntityPaths) -> syn::Result<TokenStream> {
+    if options.deny_select.is_empty() {
+        return Ok(TokenStream::new());
+    }
+
+    let vis = &model.vis;
+    let key_ty = &model.single_primary_key("db_crud")?.ty;
+    let columns: Vec<String> = model.fields.iter().map(|field| field.column_name()).collect();
+    for denied in &options.deny_select {
+        if !columns
+            .iter()
+            .any(|column| column.eq_ignore_ascii_case(&denied.value()))
+        {
+            return Err(syn::Error::new(
+                denied.span(),
+                format!("unknown column `{}` in deny_select", denied.value()),
+            ));
+        }
+    }
+
+    let deny: &[LitStr] = &options.deny_select;
+    let exempt = options
+        .exempt_roles
+        .as_ref()
+        .map(|roles| quote!(.with_exempt_roles(&[#(#roles),*])));
+    let json = quote!(::dbnexus::orm::JsonValue);
+    let EntityPaths { entity, column, .. } = paths;
+    let select = operation_check(model, options, "Select");
+
+    Ok(quote! {
+        #[doc = " 列级权限策略：非豁免角色不能查询受保护的列"]
+        #vis fn column_policy() -> ::dbnexus::ColumnPolicy {
+            ::dbnexus::ColumnPolicy::new(
+                ::dbnexus::orm::EntityName::table_name(&<#entity as ::std::default::Default>::default()),
+                &[#(#deny),*],
+            )
+            #exempt
+        }
+
+        #[doc = " 按主键查询当前角色可见的列（省略受保护的列），结果为 `{\"列名\": 值}` 的 JSON 对象"]
+        #vis async fn fetch_visible(
+            session: &::dbnexus::Session,
+            id: #key_ty,
+        ) -> ::dbnexus::DbResult<::std::option::Option<#json>> {
+            #select
+            session.find_visible::<#entity>(id, &Self::column_policy()).await
+        }
+
+        #[doc = " 分页查询当前角色可见的列，按主键升序返回第 `page` 页（从 0 开始）及总行数"]
+        #vis async fn list_visible(
+            session: &::dbnexus::Session,
+            page: u64,
+            per_page: u64,
+        ) -> ::dbnexus::DbResult<(::std::vec::Vec<#json>, u64)> {
+            #select
+            session.list_visible::<#entity>(page, per_page, &Self::column_policy()).await
+        }
+
+        #[doc = " 按主键查询指定的列，请求了角色无权查询的列时返回错误"]
+        #vis async fn fetch_columns(
+            session: &::dbnexus::Session,
+            id: #key_ty,
+            columns: &[#column],
+        ) -> ::dbnexus::DbResult<::std::option::Option<#json>> {
+            #select
+            session.find_columns::<#entity>(id, columns, &Self::column_policy()).await
+        }
+    })
+}
+
+/// 为唯一列和索引列生成 `find_by_<column>`
+fn finder_methods(model: &EntityModel, paths: &EntityPaths) -> TokenStream {
+    let vis = &model.vis;
+    let EntityPaths { entity, column, .. } = paths;
+    let (output, map) = if model.plain {
+        (quote!(Self), quote!(.map(|found| found.map(Self::from))))
+    } else {
+        let model_ident = &model.ident;
+        (quote!(#model_ident), TokenStream::new())
+    };
+    model
+        .fields
+        .iter()
+        .filter(|field| field.indexed && !field.primary_key)
+        .map(|field| {
+            let name = format_ident!("find_by_{}", field.name(), span = field.ident.span());
+            let variant = field.column_variant();
+            let ty = &field.ty;
+            let doc = format!(" 按 `{}` 列等值查找，存在多行时返回第一行", field.name());
+            quote! {
+                #[doc = #doc]
+                #vis async fn #name<C>(
+                    conn: &C,
+                    value: impl ::std::convert::Into<#ty>,
+                ) -> ::dbnexus::DbResult<::std::option::Option<#output>>
+                where
+                    C: ::dbnexus::orm::ConnectionTrait,
+                {
+                    ::dbnexus::entity::find_by::<#entity, C, #ty>(conn, #column::#variant, value.into()).await #map
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 展开结果中 `impl Entity` 的方法名
+    fn entity_methods(tokens: TokenStream) -> Vec<String> {
+        let file: syn::File = syn::parse2(tokens).expect("expansion should be valid Rust");
+        file.items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Impl(imp) if matches!(&*imp.self_ty, syn::Type::Path(ty) if ty.path.is_ident("Entity")) => {
+                    Some(imp)
+                }
+                _ => None,
+            })
+            .flat_map(|imp| imp.items.iter())
+            .filter_map(|item| match item {
+                syn::ImplItem::Fn(f) => Some(f.sig.ident.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn article() -> TokenStream {
+        quote! {
+            #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+            #[sea_orm(table_name = "articles")]
+            pub struct Model {
+                #[sea_orm(primary_key, auto_increment = false)]
+                pub id: i32,
+                pub title: String,
+            }
+        }
+    }
+
+    /// TEST-U-100: db_crud 为 Entity 生成分页、计数和增删改查方法，并保留原结构体
+    #[test]
+    fn test_expand_generates_crud_methods() {
+        let expanded = expand(TokenStream::new(), article()).unwrap();
+        assert_eq!(
+            entity_methods(expanded.clone()),
+            vec!["list_paginated", "count", "fetch", "create", "modify", "remove"]
+        );
+
+        let file: syn::File = syn::parse2(expanded).unwrap();
+        assert!(matches!(&file.items[0], syn::Item::Struct(s) if s.ident == "Model"));
+    }
+
+    /// TEST-U-101: 主键类型取自主键字段，缺少或存在多个主键时报错
+    #[test]
+    fn test_expand_primary_key_handling() {
+        let expanded = expand(TokenStream::new(), article()).unwrap().to_string();
+        assert!(expanded.contains("id : i32"), "{expanded}");
+
+        let no_key = quote! { pub struct Model { pub id: i32 } };
+        let err = expand(TokenStream::new(), no_key).unwrap_err();
+        assert!(err.to_string().contains("requires a field marked"));
+
+        let composite = quote! {
+            pub struct Model {
+                #[primary_key]
+                pub a: i32,
+                #[sea_orm(primary_key)]
+                pub b: i32,
+            }
+        };
+        let err = expand(TokenStream::new(), composite).unwrap_err();
+        assert!(err.to_string().contains("single-column primary keys"));
+
+        let err = expand(quote!(cached), article()).unwrap_err();
+        assert!(err.to_string().contains("unsupported #[db_crud] option"));
+    }
+
+    /// TEST-U-104: cache 选项为增删改方法增加 CacheManager 参数，并在成功后使新旧主键失效
+    #[test]
+    fn test_expand_cache_option_invalidates_keys() {
+        let expanded = expand(quote!(cache), article()).unwrap();
+        assert_eq!(
+            entity_methods(expanded.clone()),
+            vec![
+                "list_paginated",
+                "count",
+                "fetch",
+                "fetch_cached",
+                "create",
+                "modify",
+                "remove"
+            ]
+        );
+
+        let expanded = expanded.to_string();
+        assert!(
+            expanded.contains("cache : & :: dbnexus :: cache :: CacheManager < Model >"),
+            "{expanded}"
+        );
+        assert!(expanded.contains("invalidate_entity_update"), "{expanded}");
+        assert!(expanded.contains("updated . id"), "{expanded}");
+    }
+
+    /// TEST-U-105: audit 选项使增删改方法调用 Session 的审计版本，且可与 cache 组合
+    #[test]
+    fn test_expand_audit_option_calls_audited_methods() {
+        let plain = expand(TokenStream::new(), article()).unwrap().to_string();
+        assert!(!plain.contains("_audited"), "{plain}");
+
+        let expanded = expand(quote!(audit, cache), article()).unwrap().to_string();
+        for method in ["insert_model_audited", "update_model_audited", "delete_model_audited"] {
+            assert!(expanded.contains(method), "{expanded}");
+        }
+        assert!(expanded.contains("invalidate_entity_update"), "{expanded}");
+    }
+
+    /// TEST-U-107: deny_select 生成列级策略和可见列查询，完整行查询先检查列权限
+    #[test]
+    fn test_expand_permission_generates_guarded_projection() {
+        let item = quote! {
+            pub struct Model {
+                #[sea_orm(primary_key)]
+                pub id: i32,
+                pub email: String,
+                #[sea_orm(column_name = "pw_hash")]
+                pub password_hash: String,
+            }
+        };
+        let expanded = expand(
+            quote!(deny_select = ["pw_hash"], exempt_roles = ["auditor"]),
+            item.clone(),
+        )
+        .unwrap();
+        let methods = entity_methods(expanded.clone());
+        for method in ["column_policy", "fetch_visible", "list_visible", "fetch_columns"] {
+            assert!(methods.contains(&method.to_string()), "{methods:?}");
+        }
+
+        let expanded = expanded.to_string();
+        assert!(expanded.contains("& [\"pw_hash\"]"), "{expanded}");
+        assert!(expanded.contains("with_exempt_roles (& [\"auditor\"])"), "{expanded}");
+        assert!(expanded.contains("check_column_access"), "{expanded}");
+
+        let plain = expand(TokenStream::new(), article()).unwrap();
+        assert!(!entity_methods(plain.clone()).contains(&"column_policy".to_string()));
+        assert!(!plain.to_string().contains("check_column_access"));
+
+        let err = expand(quote!(deny_select = ["password_hash"]), item).unwrap_err();
+        assert!(err.to_string().contains("unknown column `password_hash`"));
+    }
+
+    /// TEST-U-102: 仅为非主键的唯一列和索引列生成 find_by_<column>，并使用对应的 Column 成员
+    #[test]
+    fn test_expand_generates_finders_for_indexed_columns() {
+        let item = quote! {
+            pub struct Model {
+                #[sea_orm(primary_key, unique)]
+                pub id: i32,
+                #[sea_orm(unique)]
+                pub email_address: String,
+                #[sea_orm(indexed, column_type = "Text")]
+                pub r#type: Option<String>,
+                pub title: String,
+            }
+        };
+        let expanded = expand(TokenStream::new(), item).unwrap();
+        let methods = entity_methods(expanded.clone());
+        assert_eq!(&methods[6..], ["find_by_email_address", "find_by_type"]);
+
+        let expanded = expanded.to_string();
+        assert!(expanded.contains("Column :: EmailAddress"), "{expanded}");
+        assert!(expanded.contains("Column :: Type"), "{expanded}");
+        assert!(expanded.contains("Into < Option < String > >"), "{expanded}");
+    }
+
+    /// TEST-U-114: 普通结构体上生成 0.1.0 的 Session 方法，声明 roles 时先检查操作权限，不支持 cache
+    #[test]
+    fn test_expand_plain_struct_methods() {
+        let user = quote! {
+            #[table_name = "users"]
+            #[db_permission(roles = ["admin"])]
+            pub struct User {
+                #[primary_key]
+                pub id: i64,
+                #[sea_orm(unique)]
+                pub email: String,
+            }
+        };
+        let expanded = expand(TokenStream::new(), user.clone()).unwrap();
+        let file: syn::File = syn::parse2(expanded.clone()).unwrap();
+        let methods: Vec<String> = file
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Impl(imp) => Some(imp),
+                _ => None,
+            })
+            .flat_map(|imp| imp.items.iter())
+            .filter_map(|item| match item {
+                syn::ImplItem::Fn(f) => Some(f.sig.ident.to_string()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            methods,
+            [
+                "insert",
+                "find_by_id",
+                "update",
+                "delete",
+                "find_all",
+                "delete_many",
+                "count",
+                "find_by_email",
+                "check_permission",
+                "check_operation"
+            ]
+        );
+
+        let expanded = expanded.to_string();
+        assert!(
+            expanded.contains("find_model :: < user_entity :: Entity >"),
+            "{expanded}"
+        );
+        assert!(
+            expanded.contains(
+                "User :: check_operation (session . permission_ctx () , & :: dbnexus :: PermissionAction :: Insert)"
+            ),
+            "{expanded}"
+        );
+
+        let err = expand(quote!(cache), user).unwrap_err();
+        assert!(err.to_string().contains("requires a Sea-ORM `Model`"));
+    }
+}
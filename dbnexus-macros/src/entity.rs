@@ -0,0 +1,214 @@
+// Copyright (c) 2025 Kirky.X
+//
+// Licensed under the MIT License
+// See LICENSE file in the project root for full license information.
+
+//! 实体结构体解析
+//!
+//! 主键同时识别 dbnexus 的 `#[primary_key]` 和 Sea-ORM 的 `#[sea_orm(primary_key)]`；
+//! 唯一列和索引列取自 `#[sea_orm(unique)]` / `#[sea_orm(indexed)]`；
+//! 表名取自 `#[table_name = "..."]` 或 `#[sea_orm(table_name = "...")]`。
+//!
+//! 使用 `#[table_name = "..."]` 的普通结构体（dbnexus 0.1.0 的写法）由 `#[derive(DbEntity)]`
+//! 映射为 Sea-ORM 实体，`#[db_crud]` 在结构体本身上生成方法；使用 `#[sea_orm(table_name = "...")]`
+//! 的是 Sea-ORM 实体模块中的 `Model`，`#[db_crud]` 在同模块的 `Entity` 上生成方法
+
+use heck::{ToSnakeCase, ToUpperCamelCase};
+use syn::ext::IdentExt;
+use syn::meta::ParseNestedMeta;
+use syn::{Attribute, Fields, Ident, ItemStruct, Token, Type, Visibility};
+
+/// 解析后的实体 Model
+pub(crate) struct EntityModel {
+    /// 结构体名（通常为 `Model`）
+    pub(crate) ident: Ident,
+    /// 结构体可见性，生成的方法沿用
+    pub(crate) vis: Visibility,
+    /// 表名
+    pub(crate) table_name: Option<String>,
+    /// 表名是否取自 `#[table_name = "..."]`（普通结构体）
+    pub(crate) plain: bool,
+    /// `#[db_entity(migration_only)]`：只生成迁移表结构，不映射为 Sea-ORM 实体
+    pub(crate) migration_only: bool,
+    /// 字段
+    pub(crate) fields: Vec<EntityField>,
+}
+
+/// 解析后的实体字段
+pub(crate) struct EntityField {
+    /// 字段名
+    pub(crate) ident: Ident,
+    /// 字段类型
+    pub(crate) ty: Type,
+    /// 是否为主键
+    pub(crate) primary_key: bool,
+    /// 主键是否由 `#[sea_orm(primary_key)]` 声明
+    pub(crate) sea_orm_primary_key: bool,
+    /// 是否为唯一列或索引列
+    pub(crate) indexed: bool,
+    /// `#[sea_orm(column_name = "...")]` 指定的列名
+    column_name: Option<String>,
+    /// 字段上的 `#[sea_orm(...)]` 属性，映射为 Sea-ORM 实体时原样保留
+    pub(crate) sea_orm_attrs: Vec<Attribute>,
+}
+
+impl EntityModel {
+    /// 从结构体定义解析实体
+    pub(crate) fn parse(item: &ItemStruct) -> syn::Result<Self> {
+        let Fields::Named(named) = &item.fields else {
+            return Err(syn::Error::new_spanned(
+                &item.ident,
+                "entity must be a struct with named fields",
+            ));
+        };
+
+        let mut table_name = None;
+        let mut plain = false;
+        let mut migration_only = false;
+        for attr in &item.attrs {
+            if attr.path().is_ident("db_entity") && !matches!(attr.meta, syn::Meta::Path(_)) {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("migration_only") {
+                        migration_only = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("unsupported #[db_entity] option, expected `migration_only`"))
+                    }
+                })?;
+            } else if attr.path().is_ident("table_name") {
+                let value = &attr.meta.require_name_value()?.value;
+                table_name = Some(syn::parse2::<syn::LitStr>(quote::quote!(#value))?.value());
+                plain = true;
+            } else if attr.path().is_ident("sea_orm") {
+                if let Some(name) = SeaOrmAttrs::parse(attr)?.table_name {
+                    table_name = Some(name);
+                    plain = false;
+                }
+            }
+        }
+
+        let mut fields = Vec::with_capacity(named.named.len());
+        for field in &named.named {
+            let ident = field.ident.clone().expect("named field has an ident");
+            let mut primary_key = false;
+            let mut sea_orm_primary_key = false;
+            let mut indexed = false;
+            let mut column_name = None;
+            let mut sea_orm_attrs = Vec::new();
+            for attr in &field.attrs {
+                if attr.path().is_ident("primary_key") {
+                    attr.meta.require_path_only()?;
+                    primary_key = true;
+                } else if attr.path().is_ident("sea_orm") {
+                    let sea_orm = SeaOrmAttrs::parse(attr)?;
+                    primary_key |= sea_orm.primary_key;
+                    sea_orm_primary_key |= sea_orm.primary_key;
+                    indexed |= sea_orm.unique || sea_orm.indexed;
+                    column_name = sea_orm.column_name.or(column_name);
+                    sea_orm_attrs.push(attr.clone());
+                }
+            }
+            fields.push(EntityField {
+                ident,
+                ty: field.ty.clone(),
+                primary_key,
+                sea_orm_primary_key,
+                indexed,
+                column_name,
+                sea_orm_attrs,
+            });
+        }
+
+        Ok(Self {
+            ident: item.ident.clone(),
+            vis: item.vis.clone(),
+            table_name,
+            plain,
+            migration_only,
+            fields,
+        })
+    }
+
+    /// 普通结构体映射出的 Sea-ORM 实体模块名（`User` -> `user_entity`）
+    pub(crate) fn entity_module(&self) -> Ident {
+        let name = self.ident.unraw().to_string().to_snake_case();
+        Ident::new(&format!("{name}_entity"), self.ident.span())
+    }
+
+    /// 返回唯一的主键字段
+    pub(crate) fn single_primary_key(&self, macro_name: &str) -> syn::Result<&EntityField> {
+        let mut keys = self.fields.iter().filter(|field| field.primary_key);
+        match (keys.next(), keys.next()) {
+            (Some(key), None) => Ok(key),
+            (None, _) => Err(syn::Error::new(
+                self.ident.span(),
+                format!("#[{macro_name}] requires a field marked #[primary_key] or #[sea_orm(primary_key)]"),
+            )),
+            (Some(_), Some(second)) => Err(syn::Error::new(
+                second.ident.span(),
+                format!("#[{macro_name}] supports single-column primary keys only"),
+            )),
+        }
+    }
+}
+
+impl EntityField {
+    /// 字段名（去掉 `r#` 前缀）
+    pub(crate) fn name(&self) -> String {
+        self.ident.unraw().to_string()
+    }
+
+    /// 数据库列名
+    pub(crate) fn column_name(&self) -> String {
+        self.column_name.clone().unwrap_or_else(|| self.name())
+    }
+
+    /// 对应的 Sea-ORM `Column` 枚举成员，与 `DeriveEntityModel` 的命名规则一致
+    pub(crate) fn column_variant(&self) -> Ident {
+        Ident::new(&self.name().to_upper_camel_case(), self.ident.span())
+    }
+}
+
+/// `#[sea_orm(...)]` 中与 dbnexus 相关的键，其余键忽略
+#[derive(Default)]
+struct SeaOrmAttrs {
+    primary_key: bool,
+    unique: bool,
+    indexed: bool,
+    column_name: Option<String>,
+    table_name: Option<String>,
+}
+
+impl SeaOrmAttrs {
+    fn parse(attr: &Attribute) -> syn::Result<Self> {
+        let mut attrs = Self::default();
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("primary_key") {
+                attrs.primary_key = true;
+            } else if meta.path.is_ident("unique") {
+                attrs.unique = true;
+            } else if meta.path.is_ident("indexed") {
+                attrs.indexed = true;
+            } else if meta.path.is_ident("column_name") {
+                attrs.column_name = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else if meta.path.is_ident("table_name") {
+                attrs.table_name = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else {
+                skip_meta(&meta)?;
+            }
+            Ok(())
+        })?;
+        Ok(attrs)
+    }
+}
+
+/// 跳过不关心的键及其取值
+fn skip_meta(meta: &ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<syn::Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        let _content;
+        syn::parenthesized!(_content in meta.input);
+    }
+    Ok(())
+}
@@ -0,0 +1,130 @@
+// Copyright (c) 2025 Kirky.X
+//
+// Licensed under the MIT License
+// See LICENSE file in the project root for full license information.
+
+//! DB Nexus 过程宏
+//!
+//! 由 `dbnexus` 重新导出，标注在 Sea-ORM 实体模块的 `Model` 上使用：
+//!
+//! ```rust,ignore
+//! mod user {
+//!     use dbnexus::orm::entity::prelude::*;
+//!
+//!     #[dbnexus::db_crud]
+//!     #[derive(Clone, Debug, PartialEq, DeriveEntityModel, dbnexus::DbEntity)]
+//!     #[sea_orm(table_name = "users")]
+//!     pub struct Model {
+//!         #[sea_orm(primary_key)]
+//!         pub id: i64,
+//!         pub name: String,
+//!     }
+//!
+//!     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+//!     pub enum Relation {}
+//!
+//!     impl ActiveModelBehavior for ActiveModel {}
+//! }
+//!
+//! let (page, total) = user::Entity::list_paginated(&conn, 0, 20).await?;
+//! ```
+//!
+//! 也兼容 dbnexus 0.1.0 的普通结构体写法，`#[derive(DbEntity)]` 负责映射为 Sea-ORM 实体，
+//! `#[db_crud]` 在结构体本身上生成方法：
+//!
+//! ```rust,ignore
+//! #[derive(dbnexus::DbEntity)]
+//! #[db_entity]
+//! #[table_name = "users"]
+//! #[dbnexus::db_crud]
+//! #[dbnexus::db_permission(roles = ["admin", "user"])]
+//! struct User {
+//!     #[primary_key]
+//!     id: i64,
+//!     name: String,
+//! }
+//!
+//! let user = User::insert(&mut session, User { id: 1, name: "Alice".into() }).await?;
+//! let found = User::find_by_id(&session, 1).await?;
+//! ```
+
+mod crud;
+mod entity;
+mod mapping;
+mod migration;
+mod options;
+mod permission;
+
+use proc_macro::TokenStream;
+
+/// 实体派生宏，实现 `dbnexus::migration::ToMigrationTable`
+///
+/// 表名取自 `#[table_name = "..."]` 或 `#[sea_orm(table_name = "...")]`，主键取自
+/// `#[primary_key]` 或 `#[sea_orm(primary_key)]`，列类型由字段类型映射；`#[db_entity]` 为可选标记。
+/// 未开启 dbnexus 的 `migration` 特性时不生成实现。
+///
+/// 使用 `#[table_name = "..."]` 的普通结构体另外映射为 Sea-ORM 实体：生成 `<snake>_entity` 模块
+/// （`Entity`、`Model`、`ActiveModel`、`Column`），以及结构体与 `Model` 的相互转换和 `IntoActiveModel`；
+/// `#[db_entity(migration_only)]` 只生成迁移表结构。
+#[proc_macro_derive(DbEntity, attributes(db_entity, table_name, primary_key, sea_orm))]
+pub fn derive_db_entity(input: TokenStream) -> TokenStream {
+    let input = proc_macro2::TokenStream::from(input);
+    migration::expand(input.clone())
+        .and_then(|mut expanded| {
+            expanded.extend(mapping::expand(input)?);
+            Ok(expanded)
+        })
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// 为实体生成 `list_paginated`、`count` 以及基于 Session 的 `fetch` / `create` / `modify` / `remove`
+///
+/// 唯一列和索引列（`#[sea_orm(unique)]` / `#[sea_orm(indexed)]`）另外生成 `find_by_<column>`。
+/// 标注在使用 `#[table_name = "..."]` 的普通结构体上时，改为在结构体上生成基于 Session 的
+/// `insert` / `find_by_id` / `update` / `delete` / `find_all` / `delete_many` / `count`。
+#[proc_macro_attribute]
+pub fn db_crud(attr: TokenStream, item: TokenStream) -> TokenStream {
+    crud::expand(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// 为 `#[db_crud]` 生成的增删改方法接入缓存失效，等价于 `#[db_crud(cache)]`
+///
+/// `create` / `modify` / `remove` 额外接收 `&dbnexus::cache::CacheManager<Model>`，成功后清除
+/// `CacheKey::new(表名, 主键)`；更新改变主键时新旧两个键都会清除。另外生成读取缓存的 `fetch_cached`。
+#[proc_macro_attribute]
+pub fn db_cache(attr: TokenStream, item: TokenStream) -> TokenStream {
+    options::expand_companion(options::Companion::Cache, attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// 为 `#[db_crud]` 生成的增删改方法写入审计记录，等价于 `#[db_crud(audit)]`
+///
+/// `create` / `modify` / `remove` 成功后向 `audit_log` 表写入操作、表名、主键、执行角色、时间，
+/// 以及变更列的新旧值 JSON；需要开启 dbnexus 的 `audit` 特性并预先创建 `audit_log` 表。
+#[proc_macro_attribute]
+pub fn db_audit(attr: TokenStream, item: TokenStream) -> TokenStream {
+    options::expand_companion(options::Companion::Audit, attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// 声明允许访问实体的角色和操作，以及 `#[db_crud]` 实体的列级查询限制
+///
+/// `#[db_permission(roles = ["admin", "user"], operations = ["SELECT", "INSERT"])]`：生成 `ALLOWED_ROLES`、
+/// `ALLOWED_OPERATIONS` 常量和 `check_permission(ctx)` / `check_operation(ctx, operation)`，
+/// `operations` 可省略（允许全部操作）；与 `#[db_crud]` 一起使用时，生成的 Session 方法先调用 `check_operation`。
+///
+/// `#[db_permission(deny_select = ["password_hash"], exempt_roles = ["admin"])]` 需要与 `#[db_crud]` 一起使用，
+/// 等价于 `#[db_crud(deny_select = [...])]`：`exempt_roles` 可省略，默认只有 `admin` 不受限制。
+/// 生成 `column_policy()`；非豁免角色调用完整行查询 `fetch` 时返回错误，
+/// `fetch_visible` / `list_visible` 省略受保护的列，`fetch_columns` 显式请求受保护的列时返回错误。
+#[proc_macro_attribute]
+pub fn db_permission(attr: TokenStream, item: TokenStream) -> TokenStream {
+    options::expand_companion(options::Companion::Permission, attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
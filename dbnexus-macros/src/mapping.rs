@@ -0,0 +1,173 @@
+// Copyright (c) 2025 Kirky.X
+//
+// Licensed under the MIT License
+// See LICENSE file in the project root for full license information.
+
+//! `#[derive(DbEntity)]` 普通结构体映射
+//!
+//! 为使用 `#[table_name = "..."]` 的普通结构体生成 `<snake>_entity` 模块（`User` -> `user_entity`），
+//! 其中是由 Sea-ORM `DeriveEntityModel` 派生的 `Entity`、`Model`、`ActiveModel` 与 `Column`；
+//! 同时实现结构体与 `Model` 的相互转换，以及所有字段均为 `Set` 的 `IntoActiveModel`。
+//! 字段上的 `#[sea_orm(...)]` 原样保留，`#[primary_key]` 转换为 `#[sea_orm(primary_key)]`。
+//! 实体模块通过 `use super::*` 引用字段类型，因此结构体应定义在模块层级（而不是函数体内）；
+//! 只需要迁移表结构时使用 `#[db_entity(migration_only)]` 跳过映射。
+
+use crate::entity::EntityModel;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ItemStruct, Type};
+
+/// 展开普通结构体的实体映射，Sea-ORM `Model` 与 `#[db_entity(migration_only)]` 不需要映射，返回空
+pub(crate) fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let item: ItemStruct = syn::parse2(input)?;
+    let model = EntityModel::parse(&item)?;
+    if !model.plain || model.migration_only {
+        return Ok(TokenStream::new());
+    }
+    if !item.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item.generics,
+            "#[derive(DbEntity)] does not support generic structs",
+        ));
+    }
+    model.single_primary_key("derive(DbEntity)")?;
+
+    let ident = &model.ident;
+    let vis = &model.vis;
+    let module = model.entity_module();
+    let table_name = model.table_name.as_deref().unwrap_or_default();
+    let names: Vec<_> = model.fields.iter().map(|field| &field.ident).collect();
+    let columns = model.fields.iter().map(|field| {
+        let attrs = &field.sea_orm_attrs;
+        let key = (field.primary_key && !field.sea_orm_primary_key).then(|| {
+            if is_integer(&field.ty) {
+                quote!(#[sea_orm(primary_key)])
+            } else {
+                quote!(#[sea_orm(primary_key, auto_increment = false)])
+            }
+        });
+        let name = &field.ident;
+        let ty = &field.ty;
+        quote! {
+            #key
+            #(#attrs)*
+            pub #name: #ty,
+        }
+    });
+    let doc = format!(" `{ident}` 映射的 Sea-ORM 实体（表 `{table_name}`）");
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[allow(missing_docs)]
+        #vis mod #module {
+            use super::*;
+            use ::dbnexus::orm as sea_orm;
+            use ::dbnexus::orm::entity::prelude::*;
+
+            #[derive(Clone, Debug, sea_orm::DeriveEntityModel)]
+            #[sea_orm(table_name = #table_name)]
+            pub struct Model {
+                #(#columns)*
+            }
+
+            #[derive(Copy, Clone, Debug, sea_orm::EnumIter, sea_orm::DeriveRelation)]
+            pub enum Relation {}
+
+            impl sea_orm::ActiveModelBehavior for ActiveModel {}
+        }
+
+        impl ::std::convert::From<#module::Model> for #ident {
+            fn from(model: #module::Model) -> Self {
+                Self { #(#names: model.#names),* }
+            }
+        }
+
+        impl ::std::convert::From<#ident> for #module::Model {
+            fn from(entity: #ident) -> Self {
+                Self { #(#names: entity.#names),* }
+            }
+        }
+
+        impl ::dbnexus::orm::IntoActiveModel<#module::ActiveModel> for #ident {
+            fn into_active_model(self) -> #module::ActiveModel {
+                #module::ActiveModel {
+                    #(#names: ::dbnexus::orm::ActiveValue::Set(self.#names)),*
+                }
+            }
+        }
+    })
+}
+
+/// 整数主键保留 Sea-ORM 默认的自增，其他类型的主键由调用方提供
+fn is_integer(ty: &Type) -> bool {
+    const INTEGERS: [&str; 10] = ["i8", "i16", "i32", "i64", "i128", "u8", "u16", "u32", "u64", "u128"];
+    matches!(ty, Type::Path(path) if path.qself.is_none()
+        && path.path.get_ident().is_some_and(|ident| INTEGERS.iter().any(|int| ident == int)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// TEST-U-112: 普通结构体映射为 `<snake>_entity` 模块，主键转换为 sea_orm 属性，Sea-ORM Model 不映射
+    #[test]
+    fn test_expand_plain_struct_entity() {
+        let input = quote! {
+            #[derive(DbEntity)]
+            #[table_name = "user_accounts"]
+            pub struct UserAccount {
+                #[primary_key]
+                pub id: i64,
+                #[sea_orm(unique)]
+                pub email: String,
+            }
+        };
+        let file: syn::File = syn::parse2(expand(input).unwrap()).unwrap();
+        let syn::Item::Mod(module) = &file.items[0] else {
+            panic!("expected the entity module first");
+        };
+        assert_eq!(module.ident, "user_account_entity");
+        let body = quote!(#module).to_string();
+        assert!(body.contains("table_name = \"user_accounts\""), "{body}");
+        assert!(body.contains("# [sea_orm (primary_key)] pub id : i64"), "{body}");
+        assert!(body.contains("# [sea_orm (unique)] pub email : String"), "{body}");
+        assert_eq!(file.items.len(), 4);
+
+        let string_key = quote! {
+            #[table_name = "tags"]
+            struct Tag {
+                #[primary_key]
+                name: String,
+            }
+        };
+        let expanded = expand(string_key).unwrap().to_string();
+        assert!(expanded.contains("primary_key , auto_increment = false"), "{expanded}");
+
+        let model = quote! {
+            #[sea_orm(table_name = "posts")]
+            pub struct Model {
+                #[sea_orm(primary_key)]
+                pub id: i32,
+            }
+        };
+        assert!(expand(model).unwrap().is_empty());
+        let migration_only = quote! {
+            #[db_entity(migration_only)]
+            #[table_name = "tags"]
+            struct Tag {
+                #[primary_key]
+                name: String,
+            }
+        };
+        assert!(expand(migration_only).unwrap().is_empty());
+
+        let err = expand(quote! {
+            #[table_name = "tags"]
+            struct Tag {
+                name: String,
+            }
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("requires a field marked"));
+    }
+}
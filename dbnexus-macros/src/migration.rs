@@ -0,0 +1,242 @@
+// Copyright (c) 2025 Kirky.X
+//
+// Licensed under the MIT License
+// See LICENSE file in the project root for full license information.
+
+//! `#[derive(DbEntity)]` 展开
+//!
+//! 为结构体实现 `dbnexus::migration::ToMigrationTable`。实现通过 dbnexus 导出的
+//! `__impl_migration_table!` 生成，未开启 dbnexus 的 `migration` 特性时该宏展开为空。
+//! 列类型和可空性在展开时按字段类型解析，以 `ColumnType` 表达式传给该宏。
+
+use crate::entity::EntityModel;
+use proc_macro2::TokenStream;
+use quote::{ToTokens, quote};
+use syn::{GenericArgument, ItemStruct, PathArguments, Type};
+
+/// 展开 `#[derive(DbEntity)]`
+pub(crate) fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let item: ItemStruct = syn::parse2(input.clone())
+        .map_err(|_| syn::Error::new_spanned(&input, "#[derive(DbEntity)] supports structs only"))?;
+    let model = EntityModel::parse(&item)?;
+    let Some(table_name) = &model.table_name else {
+        return Err(syn::Error::new_spanned(
+            &item.ident,
+            "#[derive(DbEntity)] requires #[table_name = \"...\"] or #[sea_orm(table_name = \"...\")]",
+        ));
+    };
+
+    let ident = &model.ident;
+    let columns = model.fields.iter().map(|field| {
+        let name = field.column_name();
+        let (column_type, nullable) = match option_inner(&field.ty) {
+            Some(inner) => (column_type(inner), true),
+            None => (column_type(&field.ty), false),
+        };
+        let primary_key = field.primary_key;
+        quote!((#name, #column_type, #nullable, #primary_key))
+    });
+
+    Ok(quote! {
+        ::dbnexus::__impl_migration_table!(#ident, #table_name, [#(#columns),*]);
+    })
+}
+
+/// 字段类型为 `Option<T>`（允许 `std::option::` / `core::option::` 路径前缀）时返回 `T`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    if path.qself.is_some() {
+        return None;
+    }
+    let segments: Vec<String> = path.path.segments.iter().map(|s| s.ident.to_string()).collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    if !matches!(
+        segments.as_slice(),
+        ["Option"] | ["std", "option", "Option"] | ["core", "option", "Option"]
+    ) {
+        return None;
+    }
+    let single_type_argument = match &path.path.segments.last()?.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => args.args.first(),
+        _ => None,
+    };
+    match single_type_argument {
+        Some(GenericArgument::Type(inner)) => Some(inner),
+        _ => None,
+    }
+}
+
+/// 按字段类型的最后一个路径段（及其类型参数）解析列类型
+///
+/// 映射与 `ColumnType::from_rust_type` 一致，无法识别的类型生成 `ColumnType::Custom`
+fn column_type(ty: &Type) -> TokenStream {
+    let variant = match ty {
+        Type::Reference(reference) if last_segment(&reference.elem).is_some_and(|(ident, _)| ident == "str") => {
+            Some(quote!(String(::std::option::Option::Some(255))))
+        }
+        _ => last_segment(ty).and_then(|(ident, argument)| {
+            let argument = argument.and_then(last_segment).map(|(ident, _)| ident);
+            let variant = match (ident.as_str(), argument.as_deref()) {
+                ("i8" | "i16" | "i32" | "u8" | "u16" | "u32", None) => quote!(Integer),
+                ("i64" | "u64", None) => quote!(BigInteger),
+                ("String", None) => quote!(String(::std::option::Option::Some(255))),
+                ("bool", None) => quote!(Boolean),
+                ("f32", None) => quote!(Float),
+                ("f64", None) => quote!(Double),
+                ("Decimal" | "BigDecimal", None) => quote!(Custom(::std::string::String::from("DECIMAL"))),
+                ("NaiveDate" | "Date", None) => quote!(Date),
+                ("NaiveTime" | "Time", None) => quote!(Time),
+                ("NaiveDateTime" | "DateTime" | "PrimitiveDateTime", None) => quote!(DateTime),
+                ("DateTime", Some("Utc" | "FixedOffset"))
+                | ("DateTimeUtc" | "DateTimeWithTimeZone" | "OffsetDateTime", None) => quote!(Timestamp),
+                ("Json" | "JsonValue" | "Value", None) => quote!(Json),
+                ("Vec", Some("u8")) => quote!(Binary),
+                _ => return None,
+            };
+            Some(variant)
+        }),
+    };
+    let variant = variant.unwrap_or_else(|| {
+        let custom: String = ty
+            .to_token_stream()
+            .to_string()
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        quote!(Custom(::std::string::String::from(#custom)))
+    });
+    quote!(::dbnexus::migration::ColumnType::#variant)
+}
+
+/// 路径类型的最后一个路径段名及其第一个类型参数
+fn last_segment(ty: &Type) -> Option<(String, Option<&Type>)> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let argument = match &segment.arguments {
+        PathArguments::None => None,
+        PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(GenericArgument::Type(argument)) => Some(argument),
+            _ => return None,
+        },
+        PathArguments::Parenthesized(_) => return None,
+    };
+    Some((segment.ident.to_string(), argument))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// TEST-U-108: DbEntity 生成迁移表结构，表名、列名和主键取自属性
+    #[test]
+    fn test_expand_migration_table() {
+        let input = quote! {
+            #[db_entity]
+            #[table_name = "users"]
+            struct User {
+                #[primary_key]
+                id: i64,
+                #[sea_orm(column_name = "mail")]
+                email: String,
+                nickname: Option<String>,
+            }
+        };
+        let expanded = expand(input).unwrap().to_string();
+        assert_eq!(
+            expanded,
+            quote! {
+                ::dbnexus::__impl_migration_table!(
+                    User,
+                    "users",
+                    [
+                        ("id", ::dbnexus::migration::ColumnType::BigInteger, false, true),
+                        (
+                            "mail",
+                            ::dbnexus::migration::ColumnType::String(::std::option::Option::Some(255)),
+                            false,
+                            false
+                        ),
+                        (
+                            "nickname",
+                            ::dbnexus::migration::ColumnType::String(::std::option::Option::Some(255)),
+                            true,
+                            false
+                        )
+                    ]
+                );
+            }
+            .to_string()
+        );
+
+        let sea_orm = quote! {
+            #[sea_orm(table_name = "posts")]
+            pub struct Model {
+                #[sea_orm(primary_key)]
+                pub id: i32,
+            }
+        };
+        assert!(expand(sea_orm).unwrap().to_string().contains("\"posts\""));
+
+        let err = expand(quote!(
+            struct User {
+                id: i64,
+            }
+        ))
+        .unwrap_err();
+        assert!(err.to_string().contains("requires #[table_name"));
+        let err = expand(quote!(
+            enum User {
+                A,
+            }
+        ))
+        .unwrap_err();
+        assert!(err.to_string().contains("supports structs only"));
+    }
+
+    /// TEST-U-115: 带完整路径的 Option 字段可为空，按内部类型解析列类型
+    #[test]
+    fn test_qualified_option_is_nullable() {
+        let resolve = |ty: Type| {
+            let nullable = option_inner(&ty).is_some();
+            let column_type = column_type(option_inner(&ty).unwrap_or(&ty)).to_string();
+            (column_type, nullable)
+        };
+        let column_type = |variant: TokenStream| quote!(::dbnexus::migration::ColumnType::#variant).to_string();
+
+        assert_eq!(
+            resolve(syn::parse_quote!(std::option::Option<i64>)),
+            (column_type(quote!(BigInteger)), true)
+        );
+        assert_eq!(
+            resolve(syn::parse_quote!(::core::option::Option<chrono::DateTime<chrono::Utc>>)),
+            (column_type(quote!(Timestamp)), true)
+        );
+        assert_eq!(
+            resolve(syn::parse_quote!(chrono::NaiveDateTime)),
+            (column_type(quote!(DateTime)), false)
+        );
+        assert_eq!(
+            resolve(syn::parse_quote!(Vec<u8>)),
+            (column_type(quote!(Binary)), false)
+        );
+        // 非标准库的 Option 不视为可空
+        assert_eq!(
+            resolve(syn::parse_quote!(my::Option<i64>)),
+            (
+                column_type(quote!(Custom(::std::string::String::from("my::Option<i64>")))),
+                false
+            )
+        );
+        assert_eq!(
+            resolve(syn::parse_quote!(uuid::Uuid)),
+            (
+                column_type(quote!(Custom(::std::string::String::from("uuid::Uuid")))),
+                false
+            )
+        );
+    }
+}
@@ -0,0 +1,330 @@
+// Copyright (c) 2025 Kirky.X
+//
+// Licensed under the MIT License
+// See LICENSE file in the project root for full license information.
+
+//! `#[db_crud]` 选项与配套属性
+//!
+//! 配套属性（`#[db_cache]` 等）必须与 `#[db_crud]` 标注在同一个 `Model` 上，顺序不限：
+//!
+//! - 配套属性在 `#[db_crud]` 之后时，由 `#[db_crud]` 读取并移除；
+//! - 配套属性在 `#[db_crud]` 之前时，改写为 `#[db_crud]` 的参数，例如
+//!   `#[db_cache] #[db_crud]` 等价于 `#[db_crud(cache)]`。
+//!
+//! 只声明 `roles` / `operations` 的 `#[db_permission]` 也可以单独使用，只生成角色检查。
+
+use crate::entity::EntityModel;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, ItemStruct, LitStr, Meta, Token};
+
+/// 配套属性
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Companion {
+    /// `#[db_cache]`
+    Cache,
+    /// `#[db_audit]`
+    Audit,
+    /// `#[db_permission(...)]`
+    Permission,
+}
+
+impl Companion {
+    const ALL: [Companion; 3] = [Companion::Cache, Companion::Audit, Companion::Permission];
+
+    /// 属性名
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Companion::Cache => "db_cache",
+            Companion::Audit => "db_audit",
+            Companion::Permission => "db_permission",
+        }
+    }
+
+    /// 按属性路径的最后一段识别配套属性
+    fn of(attr: &Attribute) -> Option<Self> {
+        let last = attr.path().segments.last()?;
+        Self::ALL.into_iter().find(|companion| last.ident == companion.name())
+    }
+
+    /// 转换为 `#[db_crud]` 参数
+    fn to_crud_args(self, attr: &TokenStream) -> syn::Result<TokenStream> {
+        match self {
+            Companion::Permission if attr.is_empty() => Err(syn::Error::new(
+                Span::call_site(),
+                "#[db_permission] requires `roles = [\"role\", ...]` or `deny_select = [\"column\", ...]`",
+            )),
+            Companion::Permission => Ok(attr.clone()),
+            _ if !attr.is_empty() => Err(syn::Error::new_spanned(
+                attr,
+                format!("#[{}] takes no arguments", self.name()),
+            )),
+            Companion::Cache => Ok(quote!(cache)),
+            Companion::Audit => Ok(quote!(audit)),
+        }
+    }
+}
+
+/// `#[db_crud]` 选项
+#[derive(Default)]
+pub(crate) struct CrudOptions {
+    /// 生成的增删改方法接收 `CacheManager` 并在成功后使实体缓存失效
+    pub(crate) cache: bool,
+    /// 生成的增删改方法在成功后写入 `audit_log` 审计记录
+    pub(crate) audit: bool,
+    /// 非豁免角色不能查询的列
+    pub(crate) deny_select: Vec<LitStr>,
+    /// 不受列级限制的角色，未指定时使用 `ColumnPolicy` 的默认值（`admin`）
+    pub(crate) exempt_roles: Option<Vec<LitStr>>,
+    /// 允许访问实体的角色，未指定时不做实体级角色检查
+    pub(crate) roles: Option<Vec<LitStr>>,
+    /// 允许的操作（`SELECT` / `INSERT` / `UPDATE` / `DELETE`），未指定时允许全部操作
+    pub(crate) operations: Option<Vec<LitStr>>,
+}
+
+impl CrudOptions {
+    /// 解析 `#[db_crud(...)]` 参数，并读取、移除结构体上的配套属性
+    pub(crate) fn parse(attr: TokenStream, item: &mut ItemStruct) -> syn::Result<Self> {
+        let mut options = Self::default();
+        syn::meta::parser(|meta| options.parse_meta(&meta)).parse2(attr)?;
+
+        let mut result = Ok(());
+        item.attrs.retain(|attr| match Companion::of(attr) {
+            Some(companion) => {
+                let args = match &attr.meta {
+                    Meta::Path(_) => Ok(TokenStream::new()),
+                    _ => attr.meta.require_list().map(|list| list.tokens.clone()),
+                };
+                let parsed = args
+                    .and_then(|args| companion.to_crud_args(&args))
+                    .and_then(|args| syn::meta::parser(|meta| options.parse_meta(&meta)).parse2(args));
+                if let Err(e) = parsed {
+                    combine(&mut result, e);
+                }
+                false
+            }
+            None => true,
+        });
+        result?;
+        options.validate()?;
+        Ok(options)
+    }
+
+    /// 检查选项之间的依赖
+    fn validate(&self) -> syn::Result<()> {
+        if self.deny_select.is_empty() && self.exempt_roles.is_some() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`exempt_roles` requires `deny_select`",
+            ));
+        }
+        if self.roles.is_none() && self.operations.is_some() {
+            return Err(syn::Error::new(Span::call_site(), "`operations` requires `roles`"));
+        }
+        Ok(())
+    }
+
+    fn parse_meta(&mut self, meta: &ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("cache") {
+            self.cache = true;
+            Ok(())
+        } else if meta.path.is_ident("audit") {
+            self.audit = true;
+            Ok(())
+        } else if meta.path.is_ident("deny_select") {
+            self.deny_select.extend(parse_str_list(meta)?);
+            Ok(())
+        } else if meta.path.is_ident("exempt_roles") {
+            self.exempt_roles
+                .get_or_insert_with(Vec::new)
+                .extend(parse_str_list(meta)?);
+            Ok(())
+        } else if meta.path.is_ident("roles") {
+            self.roles.get_or_insert_with(Vec::new).extend(parse_str_list(meta)?);
+            Ok(())
+        } else if meta.path.is_ident("operations") {
+            self.operations
+                .get_or_insert_with(Vec::new)
+                .extend(parse_str_list(meta)?);
+            Ok(())
+        } else {
+            Err(meta.error(
+                "unsupported #[db_crud] option, expected `cache`, `audit`, `roles`, `operations`, `deny_select` \
+                 or `exempt_roles`",
+            ))
+        }
+    }
+}
+
+/// 展开位于 `#[db_crud]` 之前的配套属性：将其合并进 `#[db_crud]` 的参数
+pub(crate) fn expand_companion(companion: Companion, attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let args = companion.to_crud_args(&attr)?;
+    let mut item: ItemStruct = syn::parse2(item)?;
+
+    let Some(crud) = item.attrs.iter_mut().find(|attr| {
+        attr.path()
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "db_crud")
+    }) else {
+        if companion == Companion::Permission {
+            return expand_standalone_permission(args, item);
+        }
+        return Err(syn::Error::new_spanned(
+            &item.ident,
+            format!("#[{}] must be used together with #[db_crud]", companion.name()),
+        ));
+    };
+
+    let path = crud.path().clone();
+    let merged = match &crud.meta {
+        Meta::Path(_) => args,
+        meta => {
+            let existing = &meta.require_list()?.tokens;
+            quote!(#existing, #args)
+        }
+    };
+    crud.meta = syn::parse_quote!(#path(#merged));
+
+    Ok(quote!(#item))
+}
+
+/// 展开不带 `#[db_crud]` 的 `#[db_permission(roles = [...])]`：只生成角色常量和检查方法
+fn expand_standalone_permission(args: TokenStream, item: ItemStruct) -> syn::Result<TokenStream> {
+    let mut options = CrudOptions::default();
+    syn::meta::parser(|meta| options.parse_meta(&meta)).parse2(args)?;
+    options.validate()?;
+    if !options.deny_select.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item.ident,
+            "#[db_permission(deny_select = [...])] must be used together with #[db_crud]",
+        ));
+    }
+    if options.cache || options.audit {
+        return Err(syn::Error::new_spanned(
+            &item.ident,
+            "#[db_permission] accepts `roles`, `operations`, `deny_select` and `exempt_roles` only",
+        ));
+    }
+
+    let model = EntityModel::parse(&item)?;
+    let methods = crate::permission::role_methods(&model, &options)?;
+    Ok(quote! {
+        #item
+        #methods
+    })
+}
+
+/// 解析 `key = ["a", "b"]`
+fn parse_str_list(meta: &ParseNestedMeta) -> syn::Result<Vec<LitStr>> {
+    let value = meta.value()?;
+    let content;
+    syn::bracketed!(content in value);
+    let items = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+    Ok(items.into_iter().collect())
+}
+
+fn combine(result: &mut syn::Result<()>, error: syn::Error) {
+    match result {
+        Ok(()) => *result = Err(error),
+        Err(existing) => existing.combine(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(attrs: TokenStream) -> ItemStruct {
+        syn::parse2(quote! {
+            #attrs
+            pub struct Model {
+                #[sea_orm(primary_key)]
+                pub id: i32,
+            }
+        })
+        .unwrap()
+    }
+
+    /// TEST-U-103: db_crud 读取并移除其后的配套属性，配套属性在前时改写为 db_crud 参数
+    #[test]
+    fn test_companion_attributes_in_either_order() {
+        let mut item = model(quote! {
+            #[dbnexus::db_cache]
+            #[derive(Clone)]
+            #[db_audit]
+        });
+        let options = CrudOptions::parse(TokenStream::new(), &mut item).unwrap();
+        assert!(options.cache && options.audit);
+        assert_eq!(item.attrs.len(), 1);
+        assert!(item.attrs[0].path().is_ident("derive"));
+
+        let rewritten = expand_companion(
+            Companion::Cache,
+            TokenStream::new(),
+            quote!(
+                #[dbnexus::db_crud]
+                pub struct Model {
+                    pub id: i32,
+                }
+            ),
+        )
+        .unwrap();
+        let item: ItemStruct = syn::parse2(rewritten).unwrap();
+        assert_eq!(item.attrs[0].meta.require_list().unwrap().tokens.to_string(), "cache");
+        let rewritten = expand_companion(Companion::Audit, TokenStream::new(), quote!(#item)).unwrap();
+        let item: ItemStruct = syn::parse2(rewritten).unwrap();
+        assert_eq!(
+            item.attrs[0].meta.require_list().unwrap().tokens.to_string(),
+            "cache , audit"
+        );
+
+        let err = expand_companion(
+            Companion::Cache,
+            TokenStream::new(),
+            quote!(
+                pub struct Model {
+                    pub id: i32,
+                }
+            ),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("must be used together with #[db_crud]"));
+
+        let mut item = model(quote!(#[db_cache(ttl = 5)]));
+        let err = CrudOptions::parse(TokenStream::new(), &mut item).err().unwrap();
+        assert!(err.to_string().contains("takes no arguments"));
+    }
+
+    /// TEST-U-106: db_permission 的 deny_select / exempt_roles 作为 db_crud 选项解析
+    #[test]
+    fn test_permission_options() {
+        let mut item = model(quote! {
+            #[db_permission(deny_select = ["password_hash", "salt"], exempt_roles = ["admin", "auditor"])]
+        });
+        let options = CrudOptions::parse(TokenStream::new(), &mut item).unwrap();
+        let values = |lits: &[LitStr]| lits.iter().map(LitStr::value).collect::<Vec<_>>();
+        assert_eq!(values(&options.deny_select), ["password_hash", "salt"]);
+        assert_eq!(values(options.exempt_roles.as_deref().unwrap()), ["admin", "auditor"]);
+        assert!(item.attrs.is_empty());
+
+        let mut item = model(TokenStream::new());
+        let options = CrudOptions::parse(quote!(deny_select = ["password_hash"]), &mut item).unwrap();
+        assert!(options.exempt_roles.is_none());
+
+        let mut item = model(quote!(#[db_permission]));
+        let err = CrudOptions::parse(TokenStream::new(), &mut item).err().unwrap();
+        assert!(
+            err.to_string()
+                .contains("requires `roles = [\"role\", ...]` or `deny_select")
+        );
+
+        let err = CrudOptions::parse(quote!(exempt_roles = ["admin"]), &mut model(TokenStream::new()))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("requires `deny_select`"));
+    }
+}
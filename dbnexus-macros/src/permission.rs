@@ -0,0 +1,146 @@
+// Copyright (c) 2025 Kirky.X
+//
+// Licensed under the MIT License
+// See LICENSE file in the project root for full license information.
+
+//! `#[db_permission(roles = [...], operations = [...])]` 实体级角色检查
+//!
+//! 在标注的结构体上生成 `ALLOWED_ROLES`、`ALLOWED_OPERATIONS` 常量以及
+//! `check_permission(ctx)` / `check_operation(ctx, operation)`；与 `#[db_crud]` 一起使用时，
+//! 生成的 Session 方法在访问数据库前先调用 `check_operation`。
+
+use crate::entity::EntityModel;
+use crate::options::CrudOptions;
+use heck::ToSnakeCase;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+
+/// 生成角色常量和检查方法，未声明 `roles` 时返回空
+pub(crate) fn role_methods(model: &EntityModel, options: &CrudOptions) -> syn::Result<TokenStream> {
+    let Some(roles) = &options.roles else {
+        return Ok(TokenStream::new());
+    };
+
+    let operations = match &options.operations {
+        Some(operations) => operations
+            .iter()
+            .map(|operation| match operation.value().to_ascii_uppercase().as_str() {
+                "SELECT" => Ok(quote!(Select)),
+                "INSERT" => Ok(quote!(Insert)),
+                "UPDATE" => Ok(quote!(Update)),
+                "DELETE" => Ok(quote!(Delete)),
+                other => Err(syn::Error::new(
+                    operation.span(),
+                    format!("unknown operation `{other}`, expected SELECT, INSERT, UPDATE or DELETE"),
+                )),
+            })
+            .collect::<syn::Result<Vec<_>>>()?,
+        None => vec![quote!(Select), quote!(Insert), quote!(Update), quote!(Delete)],
+    };
+
+    let ident = &model.ident;
+    let vis = &model.vis;
+    let table = table_name(model);
+    let action = quote!(::dbnexus::PermissionAction);
+    Ok(quote! {
+        impl #ident {
+            #[doc = " 允许访问该实体的角色"]
+            #vis const ALLOWED_ROLES: &'static [&'static str] = &[#(#roles),*];
+
+            #[doc = " 允许的操作"]
+            #vis const ALLOWED_OPERATIONS: &'static [#action] = &[#(#action::#operations),*];
+
+            #[doc = " 检查 Session 角色是否在 `ALLOWED_ROLES` 中"]
+            #vis fn check_permission(ctx: &::dbnexus::PermissionContext) -> ::dbnexus::DbResult<()> {
+                ::dbnexus::entity::check_entity_role(ctx, #table, Self::ALLOWED_ROLES)
+            }
+
+            #[doc = " 检查 Session 角色能否对该实体执行 `operation`"]
+            #vis fn check_operation(
+                ctx: &::dbnexus::PermissionContext,
+                operation: &#action,
+            ) -> ::dbnexus::DbResult<()> {
+                ::dbnexus::entity::check_entity_operation(
+                    ctx,
+                    #table,
+                    Self::ALLOWED_ROLES,
+                    Self::ALLOWED_OPERATIONS,
+                    operation,
+                )
+            }
+        }
+    })
+}
+
+/// 生成的 Session 方法开头调用的 `check_operation`，未声明 `roles` 时返回空
+pub(crate) fn operation_check(model: &EntityModel, options: &CrudOptions, operation: &str) -> TokenStream {
+    if options.roles.is_none() {
+        return TokenStream::new();
+    }
+    let ident = &model.ident;
+    let operation = syn::Ident::new(operation, proc_macro2::Span::call_site());
+    quote! {
+        #ident::check_operation(session.permission_ctx(), &::dbnexus::PermissionAction::#operation)?;
+    }
+}
+
+/// 错误信息中的表名，未声明表名时使用结构体名的 snake_case 形式
+fn table_name(model: &EntityModel) -> String {
+    model
+        .table_name
+        .clone()
+        .unwrap_or_else(|| model.ident.unraw().to_string().to_snake_case())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::options::{Companion, expand_companion};
+    use proc_macro2::TokenStream;
+    use quote::quote;
+
+    fn user() -> TokenStream {
+        quote! {
+            #[table_name = "users"]
+            pub struct User {
+                #[primary_key]
+                pub id: i64,
+            }
+        }
+    }
+
+    /// TEST-U-113: 单独使用的 db_permission 生成角色常量和检查方法，未声明 operations 时允许全部操作
+    #[test]
+    fn test_standalone_role_permission() {
+        let expanded = expand_companion(
+            Companion::Permission,
+            quote!(roles = ["admin", "user"], operations = ["select", "UPDATE"]),
+            user(),
+        )
+        .unwrap()
+        .to_string();
+        assert!(expanded.contains("ALLOWED_ROLES : & 'static [& 'static str] = & [\"admin\" , \"user\"]"));
+        assert!(expanded.contains("PermissionAction :: Select , :: dbnexus :: PermissionAction :: Update"));
+        assert!(
+            expanded.contains("check_entity_operation (ctx , \"users\""),
+            "{expanded}"
+        );
+
+        let all = expand_companion(Companion::Permission, quote!(roles = ["admin"]), user())
+            .unwrap()
+            .to_string();
+        assert!(all.contains("PermissionAction :: Delete"), "{all}");
+
+        let err = expand_companion(
+            Companion::Permission,
+            quote!(roles = ["admin"], operations = ["drop"]),
+            user(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("unknown operation `DROP`"));
+        let err = expand_companion(Companion::Permission, quote!(operations = ["SELECT"]), user()).unwrap_err();
+        assert!(err.to_string().contains("`operations` requires `roles`"));
+        let err = expand_companion(Companion::Permission, quote!(deny_select = ["id"]), user()).unwrap_err();
+        assert!(err.to_string().contains("must be used together with #[db_crud]"));
+    }
+}
@@ -6,13 +6,115 @@
 //! Build script for compile-time permissions validation
 //!
 //! This script reads the permissions.yaml file and generates:
-//! 1. A Rust module for the main crate with the list of defined roles
+//! 1. A Rust module for the main crate with the list of defined roles,
+//!    `is_known_role` and (for small role sets) a typed `Role` enum
 //! 2. A file that can be included in procedural macros for validation
 
 use std::env;
 use std::fs;
 use std::path::Path;
 
+/// 超过该数量的角色不生成 `Role` 枚举
+const MAX_ENUM_ROLES: usize = 64;
+
+/// 将角色名转换为枚举变体名（`read_only` -> `ReadOnly`），无法转换为合法标识符时返回 None
+fn variant_name(role: &str) -> Option<String> {
+    let name: String = role
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+
+    if name.chars().next().is_some_and(|c| c.is_ascii_alphabetic()) {
+        Some(name)
+    } else {
+        None
+    }
+}
+
+/// 生成 `Role` 枚举；角色为空、过多或变体名冲突时不生成
+fn role_enum_code(roles: &[String]) -> String {
+    if roles.is_empty() || roles.len() > MAX_ENUM_ROLES {
+        return String::new();
+    }
+
+    let mut variants: Vec<(String, &String)> = Vec::with_capacity(roles.len());
+    for role in roles {
+        let Some(variant) = variant_name(role) else {
+            return String::new();
+        };
+        if variants.iter().any(|(existing, _)| *existing == variant) {
+            return String::new();
+        }
+        variants.push((variant, role));
+    }
+
+    let declarations: String = variants
+        .iter()
+        .map(|(variant, role)| format!("    /// Role {:?}\n    {},\n", role, variant))
+        .collect();
+    let all: String = variants
+        .iter()
+        .map(|(variant, _)| format!("Role::{}, ", variant))
+        .collect();
+    let as_str_arms: String = variants
+        .iter()
+        .map(|(variant, role)| format!("            Role::{} => {:?},\n", variant, role))
+        .collect();
+    let from_str_arms: String = variants
+        .iter()
+        .map(|(variant, role)| format!("            {:?} => Ok(Role::{}),\n", role, variant))
+        .collect();
+
+    format!(
+        r#"
+/// Typed role defined in permissions.yaml
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Role {{
+{declarations}}}
+
+impl Role {{
+    /// All roles in declaration order
+    pub const ALL: &'static [Role] = &[{all}];
+
+    /// Role name as written in permissions.yaml
+    pub fn as_str(&self) -> &'static str {{
+        match self {{
+{as_str_arms}        }}
+    }}
+}}
+
+impl AsRef<str> for Role {{
+    fn as_ref(&self) -> &str {{
+        self.as_str()
+    }}
+}}
+
+impl std::fmt::Display for Role {{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{
+        f.write_str(self.as_str())
+    }}
+}}
+
+impl std::str::FromStr for Role {{
+    type Err = String;
+
+    fn from_str(role: &str) -> Result<Self, Self::Err> {{
+        match role {{
+{from_str_arms}            _ => Err(format!("Unknown role '{{}}'", role)),
+        }}
+    }}
+}}
+"#
+    )
+}
+
 fn main() {
     // 获取 permissions.yaml 文件路径
     let out_dir = env::var("OUT_DIR").unwrap();
@@ -96,7 +198,7 @@ fn main() {
     } else {
         roles
             .iter()
-            .map(|r| format!("    \"{}\"", r))
+            .map(|r| format!("    {:?}", r))
             .collect::<Vec<_>>()
             .join(",\n")
     };
@@ -120,8 +222,17 @@ pub fn is_role_defined(role: &str) -> bool {{
 pub fn get_defined_roles() -> &'static [&'static str] {{
     DEFINED_ROLES
 }}
-"#,
-        roles_array_code
+
+/// Roles defined in permissions.yaml (same as `DEFINED_ROLES`)
+pub const ROLES: &[&str] = DEFINED_ROLES;
+
+/// Check if a role is one of `ROLES`
+pub fn is_known_role(role: &str) -> bool {{
+    ROLES.contains(&role)
+}}
+{}"#,
+        roles_array_code,
+        role_enum_code(&roles)
     );
 
     fs::write(&dest_path, generated_code).unwrap();
@@ -22,6 +22,7 @@
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use sea_orm::ConnectionTrait;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::sync::Arc;
@@ -459,6 +460,259 @@ impl AuditStorage for MemoryAuditStorage {
     }
 }
 
+/// 数据库审计表名
+pub const AUDIT_LOG_TABLE: &str = "audit_log";
+
+/// 数据库审计存储
+///
+/// 将审计事件写入 `audit_log` 表，表结构：
+///
+/// | 列 | 说明 |
+/// |----|------|
+/// | `id` | 事件 ID（UUID） |
+/// | `operation` | 操作类型（`CREATE` / `UPDATE` / `DELETE` 等） |
+/// | `table_name` | 实体表名 |
+/// | `record_id` | 记录主键 |
+/// | `actor` | 执行操作的角色（Session 角色） |
+/// | `user_id` | 用户 ID |
+/// | `created_at` | RFC3339 时间戳 |
+/// | `changes` | 变更列的 JSON：`{"列名": {"old": 旧值, "new": 新值}}` |
+///
+/// 标注 `#[db_audit]` 的实体通过 [`write_audit_log`] 在 Session 的连接或事务中写入同一张表。
+pub struct DbAuditStorage {
+    conn: sea_orm::DatabaseConnection,
+}
+
+impl DbAuditStorage {
+    /// 创建数据库审计存储
+    pub fn new(conn: sea_orm::DatabaseConnection) -> Self {
+        Self { conn }
+    }
+
+    /// 创建 `audit_log` 表（已存在时跳过）
+    pub async fn ensure_table(&self) -> Result<(), sea_orm::DbErr> {
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {} (\
+             id VARCHAR(36) PRIMARY KEY, \
+             operation VARCHAR(64) NOT NULL, \
+             table_name VARCHAR(255) NOT NULL, \
+             record_id VARCHAR(255) NOT NULL, \
+             actor VARCHAR(255) NOT NULL, \
+             user_id VARCHAR(255) NOT NULL, \
+             created_at VARCHAR(64) NOT NULL, \
+             changes TEXT NULL)",
+            AUDIT_LOG_TABLE
+        );
+        self.conn.execute_unprepared(&sql).await.map(|_| ())
+    }
+
+    /// 生成第 `index` 个（从 1 开始）参数占位符
+    fn placeholder(&self, index: usize) -> String {
+        placeholder(self.conn.get_database_backend(), index)
+    }
+
+    /// 将审计表行还原为审计事件，变更 JSON 放入 `extra`
+    fn to_event(row: &sea_orm::QueryResult) -> Result<AuditEvent, sea_orm::DbErr> {
+        let created_at: String = row.try_get("", "created_at")?;
+        let operation: String = row.try_get("", "operation")?;
+        let table_name: String = row.try_get("", "table_name")?;
+        let record_id: String = row.try_get("", "record_id")?;
+        let actor: String = row.try_get("", "actor")?;
+        let user_id: String = row.try_get("", "user_id")?;
+
+        let mut event = AuditEvent::new(
+            parse_operation(&operation),
+            &table_name,
+            &record_id,
+            &user_id,
+            &actor,
+            "",
+        );
+        event.id = row.try_get("", "id")?;
+        event.timestamp = DateTime::parse_from_rfc3339(&created_at)
+            .map(|timestamp| timestamp.with_timezone(&Utc))
+            .map_err(|e| sea_orm::DbErr::Type(e.to_string()))?;
+        event.extra = row.try_get("", "changes")?;
+        Ok(event)
+    }
+}
+
+/// 通过任意连接（包括事务）向 `audit_log` 表写入一条审计事件
+///
+/// `changes` 列由 `before_value` 与 `after_value` 经 [`column_diff`] 计算得到，表结构见 [`DbAuditStorage`]
+///
+/// # Errors
+///
+/// 写入失败时返回数据库错误
+pub async fn write_audit_log<C>(conn: &C, event: &AuditEvent) -> Result<(), sea_orm::DbErr>
+where
+    C: ConnectionTrait,
+{
+    let parse = |value: &Option<String>| value.as_deref().and_then(|v| serde_json::from_str(v).ok());
+    let changes = column_diff(parse(&event.before_value).as_ref(), parse(&event.after_value).as_ref())
+        .map(|diff| diff.to_string());
+
+    let backend = conn.get_database_backend();
+    let sql = format!(
+        "INSERT INTO {} (id, operation, table_name, record_id, actor, user_id, created_at, changes) \
+         VALUES ({})",
+        AUDIT_LOG_TABLE,
+        (1..=8).map(|i| placeholder(backend, i)).collect::<Vec<_>>().join(", ")
+    );
+    let values: Vec<sea_orm::Value> = vec![
+        event.id.clone().into(),
+        event.operation.to_string().into(),
+        event.entity_type.clone().into(),
+        event.entity_id.clone().into(),
+        event.user_role.clone().into(),
+        event.user_id.clone().into(),
+        format_timestamp(&event.timestamp).into(),
+        changes.into(),
+    ];
+
+    conn.execute_raw(sea_orm::Statement::from_sql_and_values(backend, sql, values))
+        .await
+        .map(|_| ())
+}
+
+/// 生成第 `index` 个（从 1 开始）参数占位符
+fn placeholder(backend: sea_orm::DatabaseBackend, index: usize) -> String {
+    match backend {
+        sea_orm::DatabaseBackend::Postgres => format!("${}", index),
+        _ => "?".to_string(),
+    }
+}
+
+#[async_trait]
+impl AuditStorage for DbAuditStorage {
+    async fn store(&self, event: &AuditEvent) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        write_audit_log(&self.conn, event).await?;
+        Ok(())
+    }
+
+    async fn query(
+        &self,
+        filters: &AuditQueryFilters,
+    ) -> Result<Vec<AuditEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut conditions = Vec::new();
+        let mut values: Vec<sea_orm::Value> = Vec::new();
+        let mut push = |column: &str, op: &str, value: String| {
+            values.push(value.into());
+            conditions.push(format!("{} {} {}", column, op, self.placeholder(values.len())));
+        };
+
+        if let Some(user_id) = &filters.user_id {
+            push("user_id", "=", user_id.clone());
+        }
+        if let Some(entity_type) = &filters.entity_type {
+            push("table_name", "=", entity_type.clone());
+        }
+        if let Some(operation) = &filters.operation {
+            push("operation", "=", operation.to_string());
+        }
+        if let Some(start_time) = &filters.start_time {
+            push("created_at", ">=", format_timestamp(start_time));
+        }
+        if let Some(end_time) = &filters.end_time {
+            push("created_at", "<=", format_timestamp(end_time));
+        }
+
+        let mut sql = format!(
+            "SELECT id, operation, table_name, record_id, actor, user_id, created_at, changes FROM {}",
+            AUDIT_LOG_TABLE
+        );
+        if !conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        sql.push_str(" ORDER BY created_at, id");
+
+        let backend = self.conn.get_database_backend();
+        let rows = self
+            .conn
+            .query_all_raw(sea_orm::Statement::from_sql_and_values(backend, sql, values))
+            .await?;
+
+        // 审计表不保存严重级别和结果，对应过滤条件只匹配默认值
+        let mut events = rows.iter().map(Self::to_event).collect::<Result<Vec<_>, _>>()?;
+        if let Some(severity) = &filters.severity {
+            events.retain(|e| e.severity == *severity);
+        }
+        if let Some(result) = &filters.result {
+            events.retain(|e| e.result == *result);
+        }
+
+        Ok(events)
+    }
+
+    async fn cleanup(&self, before: &DateTime<Utc>) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let sql = format!(
+            "DELETE FROM {} WHERE created_at < {}",
+            AUDIT_LOG_TABLE,
+            self.placeholder(1)
+        );
+        let backend = self.conn.get_database_backend();
+        let result = self
+            .conn
+            .execute_raw(sea_orm::Statement::from_sql_and_values(
+                backend,
+                sql,
+                [sea_orm::Value::from(format_timestamp(before))],
+            ))
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+/// 计算两个 JSON 对象之间变更的列
+///
+/// 返回 `{"列名": {"old": 旧值, "new": 新值}}`，缺失的一侧记为 `null`；
+/// 两侧都不是 JSON 对象或没有任何变化时返回 `None`。
+pub fn column_diff(before: Option<&serde_json::Value>, after: Option<&serde_json::Value>) -> Option<serde_json::Value> {
+    let empty = serde_json::Map::new();
+    let before = before.and_then(|v| v.as_object());
+    let after = after.and_then(|v| v.as_object());
+    if before.is_none() && after.is_none() {
+        return None;
+    }
+    let (before, after) = (before.unwrap_or(&empty), after.unwrap_or(&empty));
+
+    let mut columns: Vec<&String> = before.keys().chain(after.keys()).collect();
+    columns.sort();
+    columns.dedup();
+
+    let diff: serde_json::Map<String, serde_json::Value> = columns
+        .into_iter()
+        .filter_map(|column| {
+            let old = before.get(column).cloned().unwrap_or(serde_json::Value::Null);
+            let new = after.get(column).cloned().unwrap_or(serde_json::Value::Null);
+            (old != new).then(|| (column.clone(), serde_json::json!({ "old": old, "new": new })))
+        })
+        .collect();
+
+    (!diff.is_empty()).then_some(serde_json::Value::Object(diff))
+}
+
+/// 以固定精度格式化时间戳，保证字符串顺序与时间顺序一致
+fn format_timestamp(timestamp: &DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(chrono::SecondsFormat::Micros, true)
+}
+
+/// 从 `Display` 输出还原操作类型
+fn parse_operation(value: &str) -> AuditOperation {
+    match value {
+        "CREATE" => AuditOperation::Create,
+        "READ" => AuditOperation::Read,
+        "UPDATE" => AuditOperation::Update,
+        "DELETE" => AuditOperation::Delete,
+        "LOGIN" => AuditOperation::Login,
+        "LOGOUT" => AuditOperation::Logout,
+        "PERMISSION_CHANGE" => AuditOperation::PermissionChange,
+        "CONFIG_CHANGE" => AuditOperation::ConfigChange,
+        other => AuditOperation::Other(other.to_string()),
+    }
+}
+
 /// 审计告警回调类型
 type AuditAlertCallback = Arc<dyn Fn(&AuditEvent) + Send + Sync>;
 
@@ -671,6 +925,160 @@ impl AuditContext {
     }
 }
 
+/// 语句级审计记录中 SQL 摘要的最大字符数
+const AUDIT_STATEMENT_SUMMARY_LEN: usize = 500;
+
+/// 语句级审计记录
+///
+/// 安装了 [`AuditSink`] 的连接池中，Session 每执行一条写语句（INSERT / UPDATE / DELETE / DDL）
+/// 生成一条记录，与 `db_audit` 宏无关。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatementAuditEntry {
+    /// 执行完成时间
+    pub timestamp: DateTime<Utc>,
+    /// 执行语句的 Session 角色
+    pub role: String,
+    /// 操作的表（DDL 或无法解析时为 `None`）
+    pub table: Option<String>,
+    /// 操作类型
+    pub operation: AuditOperation,
+    /// SQL 摘要（合并空白并截断）
+    pub statement: String,
+    /// 受影响行数（执行失败时为 `None`）
+    pub rows_affected: Option<u64>,
+    /// 执行结果
+    pub result: AuditResult,
+    /// 失败原因
+    pub error: Option<String>,
+}
+
+impl StatementAuditEntry {
+    /// 创建审计记录，时间戳取当前时间
+    pub fn new(role: &str, table: Option<&str>, operation: AuditOperation, sql: &str) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            role: role.to_string(),
+            table: table.map(str::to_string),
+            operation,
+            statement: statement_summary(sql),
+            rows_affected: None,
+            result: AuditResult::Unknown,
+            error: None,
+        }
+    }
+
+    /// 记录执行成功及受影响行数
+    pub fn succeeded(mut self, rows_affected: u64) -> Self {
+        self.rows_affected = Some(rows_affected);
+        self.result = AuditResult::Success;
+        self
+    }
+
+    /// 记录执行失败及原因
+    pub fn failed(mut self, error: &str) -> Self {
+        self.result = AuditResult::Failure;
+        self.error = Some(error.to_string());
+        self
+    }
+}
+
+/// 生成语句摘要：合并空白并截断
+fn statement_summary(sql: &str) -> String {
+    sql.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(AUDIT_STATEMENT_SUMMARY_LEN)
+        .collect()
+}
+
+/// 语句级审计输出端
+///
+/// 通过 [`DbPoolBuilder::audit_sink`](crate::DbPoolBuilder::audit_sink) 安装到连接池；
+/// 写入失败只记录警告，不影响语句执行结果。
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    /// 写入一条审计记录
+    async fn record(&self, entry: &StatementAuditEntry) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// 内存审计输出端（用于测试）
+#[derive(Debug, Default)]
+pub struct InMemoryAuditSink {
+    entries: Mutex<Vec<StatementAuditEntry>>,
+}
+
+impl InMemoryAuditSink {
+    /// 创建空的内存审计输出端
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 获取已记录的全部审计记录
+    pub async fn entries(&self) -> Vec<StatementAuditEntry> {
+        self.entries.lock().await.clone()
+    }
+
+    /// 清空已记录的审计记录
+    pub async fn clear(&self) {
+        self.entries.lock().await.clear();
+    }
+}
+
+#[async_trait]
+impl AuditSink for InMemoryAuditSink {
+    async fn record(&self, entry: &StatementAuditEntry) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.entries.lock().await.push(entry.clone());
+        Ok(())
+    }
+}
+
+/// 文件审计输出端
+///
+/// 以 JSON Lines 格式追加写入，每条记录一行。
+#[derive(Debug)]
+pub struct FileAuditSink {
+    path: std::path::PathBuf,
+    file: Mutex<tokio::fs::File>,
+}
+
+impl FileAuditSink {
+    /// 以追加模式打开（不存在时创建）审计文件
+    ///
+    /// # Errors
+    ///
+    /// 文件无法打开时返回 IO 错误
+    pub fn open(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = std::fs::OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self {
+            path,
+            file: Mutex::new(tokio::fs::File::from_std(file)),
+        })
+    }
+
+    /// 审计文件路径
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+}
+
+#[async_trait]
+impl AuditSink for FileAuditSink {
+    async fn record(&self, entry: &StatementAuditEntry) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+
+        // 整行一次写入，并发记录不会交错
+        let mut file = self.file.lock().await;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -734,6 +1142,26 @@ mod tests {
         assert!(after_value.contains("name"));
     }
 
+    #[test]
+    fn test_column_diff() {
+        let before = serde_json::json!({ "id": 1, "name": "old", "email": "a@example.com" });
+        let after = serde_json::json!({ "id": 1, "name": "new", "email": "a@example.com", "age": 30 });
+
+        assert_eq!(
+            column_diff(Some(&before), Some(&after)),
+            Some(serde_json::json!({
+                "age": { "old": null, "new": 30 },
+                "name": { "old": "old", "new": "new" }
+            }))
+        );
+        assert_eq!(column_diff(Some(&before), Some(&before)), None);
+        assert_eq!(
+            column_diff(None, Some(&serde_json::json!({ "id": 1 }))),
+            Some(serde_json::json!({ "id": { "old": null, "new": 1 } }))
+        );
+        assert_eq!(column_diff(None, None), None);
+    }
+
     #[tokio::test]
     async fn test_audit_context() {
         let ctx = AuditContext::new("user123", "admin", "192.168.1.1");
@@ -10,6 +10,8 @@
 //! - TTL (Time-To-Live) 过期机制
 //! - 缓存穿透防护
 //! - 缓存击穿保护
+//! - 基于版本号的乐观并发写入
+//! - 写穿（write-through）与写回（write-behind）写入策略
 //!
 //! # Example
 //!
@@ -22,11 +24,20 @@
 //! ```
 
 use async_trait::async_trait;
+use futures::FutureExt;
 use indexmap::IndexMap;
+use parking_lot::Mutex;
+use rand::Rng;
+use std::collections::HashMap;
+use std::future::Future;
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use tokio::sync::{OnceCell, RwLock, oneshot};
+use twox_hash::XxHash64;
+
+/// `CacheKey::from_value` 使用的固定哈希种子，保证键在不同进程和版本间稳定
+const CACHE_KEY_HASH_SEED: u64 = 0;
 
 /// 缓存配置
 #[derive(Debug, Clone)]
@@ -39,6 +50,25 @@ pub struct CacheConfig {
     pub cleanup_interval: u64,
     /// 是否启用统计
     pub enable_stats: bool,
+    /// 空值（未找到）缓存 TTL（秒），用于缓存穿透防护
+    pub negative_ttl: u64,
+    /// TTL 随机抖动比例（0.0 ~ 1.0），如 0.1 表示实际 TTL 在名义值的 ±10% 内随机取值，
+    /// 避免同时写入的条目同时过期；0 表示按名义 TTL 过期
+    pub ttl_jitter_fraction: f64,
+}
+
+impl CacheConfig {
+    /// 对 TTL 应用随机抖动，抖动比例为 0 时原样返回
+    pub fn jittered_ttl(&self, ttl: Duration) -> Duration {
+        let fraction = self.ttl_jitter_fraction;
+        if fraction.is_nan() || fraction <= 0.0 || ttl.is_zero() {
+            return ttl;
+        }
+
+        let fraction = fraction.min(1.0);
+        let factor = rand::thread_rng().gen_range(1.0 - fraction..=1.0 + fraction);
+        ttl.mul_f64(factor)
+    }
 }
 
 impl Default for CacheConfig {
@@ -48,6 +78,8 @@ impl Default for CacheConfig {
             default_ttl: 300,
             cleanup_interval: 60,
             enable_stats: true,
+            negative_ttl: 30,
+            ttl_jitter_fraction: 0.0,
         }
     }
 }
@@ -55,8 +87,8 @@ impl Default for CacheConfig {
 /// 缓存条目
 #[derive(Debug, Clone)]
 struct CacheEntry<T> {
-    /// 缓存值
-    value: T,
+    /// 缓存值，`None` 表示空值占位（记录不存在）
+    value: Option<T>,
     /// 创建时间
     #[allow(dead_code)]
     created_at: Instant,
@@ -66,10 +98,12 @@ struct CacheEntry<T> {
     access_count: usize,
     /// 最后访问时间
     last_accessed: Instant,
+    /// 写入版本，每次写入递增
+    version: u64,
 }
 
 impl<T> CacheEntry<T> {
-    fn new(value: T, ttl: Duration) -> Self {
+    fn new(value: Option<T>, ttl: Duration, version: u64) -> Self {
         let now = Instant::now();
         Self {
             value,
@@ -77,6 +111,7 @@ impl<T> CacheEntry<T> {
             expires_at: now + ttl,
             access_count: 0,
             last_accessed: now,
+            version,
         }
     }
 
@@ -111,17 +146,24 @@ impl CacheKey {
     }
 
     /// 从任意值创建缓存键
+    ///
+    /// 使用固定种子的 XxHash64，相同输入在不同进程中生成相同的键
     pub fn from_value(table: &str, value: &(impl Hash + ?Sized)) -> Self
     where
         String: std::hash::Hash + std::cmp::Eq,
     {
-        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        let mut hasher = XxHash64::with_seed(CACHE_KEY_HASH_SEED);
         value.hash(&mut hasher);
         let hash = hasher.finish();
         Self {
             key: format!("{}:{:x}", table, hash),
         }
     }
+
+    /// 获取键的字符串表示
+    pub fn as_str(&self) -> &str {
+        &self.key
+    }
 }
 
 impl Hash for CacheKey {
@@ -237,7 +279,9 @@ impl<S: CacheStrategy> CacheStrategy for TtlAwareStrategy<S> {
 }
 
 /// 缓存统计信息
-#[derive(Debug, Default)]
+///
+/// 克隆出的统计与原统计共享同一组计数器，可交给 [`MetricsCollector`](crate::metrics::MetricsCollector) 导出
+#[derive(Debug, Default, Clone)]
 pub struct CacheStats {
     /// 命中次数
     pub hits: Arc<std::sync::atomic::AtomicU64>,
@@ -249,6 +293,8 @@ pub struct CacheStats {
     pub deletes: Arc<std::sync::atomic::AtomicU64>,
     /// 过期清除次数
     pub expirations: Arc<std::sync::atomic::AtomicU64>,
+    /// 当前条目数（每次写入、删除和清理后更新）
+    pub size: Arc<std::sync::atomic::AtomicU64>,
 }
 
 impl CacheStats {
@@ -260,9 +306,29 @@ impl CacheStats {
             sets: Arc::new(std::sync::atomic::AtomicU64::new(0)),
             deletes: Arc::new(std::sync::atomic::AtomicU64::new(0)),
             expirations: Arc::new(std::sync::atomic::AtomicU64::new(0)),
+            size: Arc::new(std::sync::atomic::AtomicU64::new(0)),
         }
     }
 
+    /// 将所有计数器清零（共享计数器的克隆同样被清零）
+    pub fn reset(&self) {
+        for counter in [
+            &self.hits,
+            &self.misses,
+            &self.sets,
+            &self.deletes,
+            &self.expirations,
+            &self.size,
+        ] {
+            counter.store(0, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+
+    /// 获取当前条目数
+    pub fn size(&self) -> u64 {
+        self.size.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
     /// 获取命中率
     pub fn hit_rate(&self) -> f64 {
         let hits = self.hits.load(std::sync::atomic::Ordering::Relaxed);
@@ -297,26 +363,360 @@ impl CacheStats {
     }
 }
 
+/// 后端查找结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheLookup<T> {
+    /// 命中，`None` 表示空值占位（记录不存在）
+    Hit(Option<T>),
+    /// 条目已过期（后端已将其移除）
+    Expired,
+    /// 未命中
+    Miss,
+}
+
+/// 版本化写入失败的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum CasError {
+    /// 当前版本与期望版本不一致，条目已被其他写入方修改
+    #[error("Cache version conflict: expected {expected}, current {current}")]
+    Conflict {
+        /// 调用方期望的版本
+        expected: u64,
+        /// 条目当前的版本（不存在时为 0）
+        current: u64,
+    },
+    /// 存储后端不支持版本化写入
+    #[error("Cache backend does not support versioned writes")]
+    Unsupported,
+}
+
+/// 缓存存储后端
+///
+/// 负责条目的存取、TTL 与容量淘汰；统计信息、缓存策略和单飞加载由 [`CacheManager`] 负责。
+/// 值为 `None` 的条目是空值占位，用于缓存穿透防护。
+#[async_trait]
+pub trait CacheBackend<T>: Send + Sync
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// 获取条目
+    async fn get(&self, key: &CacheKey) -> CacheLookup<T>;
+
+    /// 写入条目
+    async fn set(&self, key: CacheKey, value: Option<T>, ttl: Duration);
+
+    /// 删除条目，返回条目是否存在
+    async fn delete(&self, key: &CacheKey) -> bool;
+
+    /// 清空所有条目
+    async fn clear(&self);
+
+    /// 获取条目数
+    async fn len(&self) -> usize;
+
+    /// 是否没有任何条目
+    async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+
+    /// 批量获取条目，结果与 `keys` 顺序一致
+    async fn get_many(&self, keys: &[CacheKey]) -> Vec<CacheLookup<T>> {
+        let mut results = Vec::with_capacity(keys.len());
+        for key in keys {
+            results.push(self.get(key).await);
+        }
+        results
+    }
+
+    /// 批量写入条目
+    async fn set_many(&self, items: Vec<(CacheKey, Option<T>)>, ttl: Duration) {
+        for (key, value) in items {
+            self.set(key, value, ttl).await;
+        }
+    }
+
+    /// 清理过期条目，返回清理数量；自带过期机制的后端可保持默认实现
+    async fn purge_expired(&self) -> usize {
+        0
+    }
+
+    /// 获取条目及其版本，未命中或已过期时版本为 0
+    ///
+    /// 不支持版本的后端保持默认实现，版本恒为 0
+    async fn get_versioned(&self, key: &CacheKey) -> (CacheLookup<T>, u64) {
+        (self.get(key).await, 0)
+    }
+
+    /// 仅当条目当前版本等于 `expected_version` 时写入，返回写入后的版本
+    ///
+    /// 不存在或已过期的条目版本为 0；版本检查与写入必须是原子的，无法保证时保持默认实现
+    async fn set_if_version(
+        &self,
+        _key: CacheKey,
+        _value: Option<T>,
+        _ttl: Duration,
+        _expected_version: u64,
+    ) -> Result<u64, CasError> {
+        Err(CasError::Unsupported)
+    }
+}
+
+/// 进程内缓存后端
+///
+/// 使用 IndexMap 维护访问顺序实现 O(1) LRU：访问和写入时移动到末尾，淘汰时移除首个条目。
+/// 条目版本取自后端级的递增计数器，删除或淘汰后重新写入的键不会复用旧版本
+#[derive(Debug)]
+pub struct MemoryBackend<T> {
+    /// 内部存储
+    entries: RwLock<IndexMap<CacheKey, CacheEntry<T>>>,
+    /// 最大容量
+    max_capacity: usize,
+    /// 最近一次写入分配的版本
+    last_version: std::sync::atomic::AtomicU64,
+}
+
+impl<T> MemoryBackend<T> {
+    /// 创建进程内缓存后端
+    pub fn new(max_capacity: usize) -> Self {
+        Self {
+            entries: RwLock::new(IndexMap::new()),
+            max_capacity,
+            last_version: std::sync::atomic::AtomicU64::new(0),
+        }
+    }
+
+    fn get_locked(entries: &mut IndexMap<CacheKey, CacheEntry<T>>, key: &CacheKey) -> CacheLookup<T>
+    where
+        T: Clone,
+    {
+        Self::get_versioned_locked(entries, key).0
+    }
+
+    fn get_versioned_locked(entries: &mut IndexMap<CacheKey, CacheEntry<T>>, key: &CacheKey) -> (CacheLookup<T>, u64)
+    where
+        T: Clone,
+    {
+        let Some((index, _, entry)) = entries.get_full_mut(key) else {
+            return (CacheLookup::Miss, 0);
+        };
+
+        if entry.is_expired() {
+            entries.shift_remove_index(index);
+            return (CacheLookup::Expired, 0);
+        }
+
+        // 访问命中 - 记录访问并移动到末尾（最近使用）
+        entry.access();
+        let value = entry.value.clone();
+        let version = entry.version;
+        let last = entries.len() - 1;
+        entries.move_index(index, last);
+        (CacheLookup::Hit(value), version)
+    }
+
+    /// 写入条目并返回新版本
+    fn set_locked(
+        &self,
+        entries: &mut IndexMap<CacheKey, CacheEntry<T>>,
+        key: CacheKey,
+        value: Option<T>,
+        ttl: Duration,
+    ) -> u64 {
+        // 检查容量，必要时淘汰最久未使用的项
+        if entries.len() >= self.max_capacity && !entries.contains_key(&key) {
+            // IndexMap 的 shift_remove_index 会移除第一个键（最久未使用）
+            entries.shift_remove_index(0);
+        }
+
+        let version = self.last_version.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
+        let (index, _) = entries.insert_full(key, CacheEntry::new(value, ttl, version));
+        let last = entries.len() - 1;
+        entries.move_index(index, last);
+        version
+    }
+}
+
+#[async_trait]
+impl<T> CacheBackend<T> for MemoryBackend<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    async fn get(&self, key: &CacheKey) -> CacheLookup<T> {
+        let mut entries = self.entries.write().await;
+        Self::get_locked(&mut entries, key)
+    }
+
+    async fn set(&self, key: CacheKey, value: Option<T>, ttl: Duration) {
+        let mut entries = self.entries.write().await;
+        self.set_locked(&mut entries, key, value, ttl);
+    }
+
+    async fn delete(&self, key: &CacheKey) -> bool {
+        self.entries.write().await.shift_remove(key).is_some()
+    }
+
+    async fn clear(&self) {
+        self.entries.write().await.clear();
+    }
+
+    async fn len(&self) -> usize {
+        self.entries.read().await.len()
+    }
+
+    async fn get_many(&self, keys: &[CacheKey]) -> Vec<CacheLookup<T>> {
+        let mut entries = self.entries.write().await;
+        keys.iter().map(|key| Self::get_locked(&mut entries, key)).collect()
+    }
+
+    async fn set_many(&self, items: Vec<(CacheKey, Option<T>)>, ttl: Duration) {
+        let mut entries = self.entries.write().await;
+        for (key, value) in items {
+            self.set_locked(&mut entries, key, value, ttl);
+        }
+    }
+
+    async fn purge_expired(&self) -> usize {
+        let mut entries = self.entries.write().await;
+        let before = entries.len();
+        entries.retain(|_key, entry| !entry.is_expired());
+        before - entries.len()
+    }
+
+    async fn get_versioned(&self, key: &CacheKey) -> (CacheLookup<T>, u64) {
+        let mut entries = self.entries.write().await;
+        Self::get_versioned_locked(&mut entries, key)
+    }
+
+    async fn set_if_version(
+        &self,
+        key: CacheKey,
+        value: Option<T>,
+        ttl: Duration,
+        expected_version: u64,
+    ) -> Result<u64, CasError> {
+        let mut entries = self.entries.write().await;
+        let current = entries
+            .get(&key)
+            .filter(|entry| !entry.is_expired())
+            .map_or(0, |entry| entry.version);
+        if current != expected_version {
+            return Err(CasError::Conflict {
+                expected: expected_version,
+                current,
+            });
+        }
+
+        Ok(self.set_locked(&mut entries, key, value, ttl))
+    }
+}
+
+/// 持久化失败的错误
+pub type PersistError = Box<dyn std::error::Error + Send + Sync>;
+
+/// 将一批缓存写入持久化到底层存储的函数
+pub type Persister<T> =
+    Arc<dyn Fn(Vec<(CacheKey, T)>) -> futures::future::BoxFuture<'static, Result<(), PersistError>> + Send + Sync>;
+
+/// 缓存写入策略，决定 [`CacheManager::set`] 等写入是否同时持久化到底层存储
+///
+/// 只作用于调用方主动写入的值；`get_or_compute` 等从底层存储加载的值不会被再次持久化
+#[derive(Clone)]
+pub enum WritePolicy<T> {
+    /// 只写缓存，由调用方自行写入底层存储（cache-aside）
+    CacheAside,
+    /// 写入缓存前同步持久化，持久化失败时不写入缓存并移除旧值
+    WriteThrough {
+        /// 持久化函数
+        persister: Persister<T>,
+    },
+    /// 立即写入缓存，写入放入队列后批量持久化
+    ///
+    /// 队列中同一键只保留最新值；队列达到 `max_batch` 条、[`CacheManager::clear`] 时刷新，
+    /// 按 `flush_interval` 定时刷新需要启动 [`CacheManager::spawn_write_behind_task`]
+    WriteBehind {
+        /// 持久化函数
+        persister: Persister<T>,
+        /// 触发刷新的队列长度
+        max_batch: usize,
+        /// 定时刷新间隔
+        flush_interval: Duration,
+    },
+}
+
+impl<T> WritePolicy<T> {
+    /// 写穿策略
+    pub fn write_through<F, Fut>(persister: F) -> Self
+    where
+        F: Fn(Vec<(CacheKey, T)>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), PersistError>> + Send + 'static,
+    {
+        WritePolicy::WriteThrough {
+            persister: Arc::new(move |batch| persister(batch).boxed()),
+        }
+    }
+
+    /// 写回策略
+    pub fn write_behind<F, Fut>(persister: F, max_batch: usize, flush_interval: Duration) -> Self
+    where
+        F: Fn(Vec<(CacheKey, T)>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), PersistError>> + Send + 'static,
+    {
+        WritePolicy::WriteBehind {
+            persister: Arc::new(move |batch| persister(batch).boxed()),
+            max_batch: max_batch.max(1),
+            flush_interval,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for WritePolicy<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WritePolicy::CacheAside => f.write_str("CacheAside"),
+            WritePolicy::WriteThrough { .. } => f.write_str("WriteThrough"),
+            WritePolicy::WriteBehind {
+                max_batch,
+                flush_interval,
+                ..
+            } => f
+                .debug_struct("WriteBehind")
+                .field("max_batch", max_batch)
+                .field("flush_interval", flush_interval)
+                .finish(),
+        }
+    }
+}
+
+/// 正在加载的键及其共享结果，布尔值区分是否允许缓存空值
+type InflightLoads<T> = Mutex<HashMap<(CacheKey, bool), Arc<OnceCell<Option<T>>>>>;
+
 /// 缓存管理器
-#[allow(dead_code)]
-pub struct CacheManager<T>
+///
+/// 默认使用进程内的 [`MemoryBackend`]，可通过 [`with_backend`](Self::with_backend) 替换存储后端
+pub struct CacheManager<T, B = MemoryBackend<T>>
 where
     T: Clone + Send + Sync + 'static,
+    B: CacheBackend<T>,
 {
-    /// 内部存储 - 使用 IndexMap 实现 O(1) LRU
-    /// IndexMap 维护插入顺序，move_to_end 实现访问顺序更新
-    cache: RwLock<IndexMap<CacheKey, CacheEntry<T>>>,
+    /// 存储后端
+    backend: B,
     /// 配置
     config: CacheConfig,
     /// 缓存策略
     strategy: Box<dyn CacheStrategy>,
     /// 统计信息
     stats: CacheStats,
-    /// 最大容量
-    max_capacity: usize,
+    /// 正在加载的键（用于缓存击穿保护），布尔值区分是否允许缓存空值
+    inflight: InflightLoads<T>,
+    /// 写入策略
+    write_policy: WritePolicy<T>,
+    /// 写回策略下待持久化的写入
+    write_queue: Mutex<IndexMap<CacheKey, T>>,
+    /// 串行化刷新，保证同一键的新值不会先于旧值持久化
+    flush_lock: tokio::sync::Mutex<()>,
 }
 
-impl<T> CacheManager<T>
+impl<T> CacheManager<T, MemoryBackend<T>>
 where
     T: Clone + Send + Sync + 'static,
 {
@@ -327,46 +727,88 @@ where
 
     /// 创建带策略的缓存管理器
     pub fn with_strategy(config: CacheConfig, strategy: Box<dyn CacheStrategy>) -> Self {
+        let backend = MemoryBackend::new(config.max_capacity);
+        Self::with_backend(config, strategy, backend)
+    }
+}
+
+impl<T, B> CacheManager<T, B>
+where
+    T: Clone + Send + Sync + 'static,
+    B: CacheBackend<T>,
+{
+    /// 创建使用指定存储后端的缓存管理器
+    pub fn with_backend(config: CacheConfig, strategy: Box<dyn CacheStrategy>, backend: B) -> Self {
         Self {
-            cache: RwLock::new(IndexMap::new()),
-            config: config.clone(),
+            backend,
+            config,
             strategy,
             stats: CacheStats::new(),
-            max_capacity: config.max_capacity,
+            inflight: Mutex::new(HashMap::new()),
+            write_policy: WritePolicy::CacheAside,
+            write_queue: Mutex::new(IndexMap::new()),
+            flush_lock: tokio::sync::Mutex::new(()),
         }
     }
 
+    /// 设置写入策略
+    pub fn with_write_policy(mut self, policy: WritePolicy<T>) -> Self {
+        self.write_policy = policy;
+        self
+    }
+
+    /// 获取写入策略
+    pub fn write_policy(&self) -> &WritePolicy<T> {
+        &self.write_policy
+    }
+
+    /// 获取存储后端
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
     /// 获取缓存值
     pub async fn get(&self, key: &CacheKey) -> Option<T> {
-        let mut cache = self.cache.write().await;
+        self.lookup(key).await.flatten()
+    }
+
+    /// 获取缓存值及其版本，用于之后的 [`set_if_version`](Self::set_if_version)
+    ///
+    /// 未命中时返回 `(None, 0)`；命中空值占位时返回 `(None, 版本)`
+    pub async fn get_versioned(&self, key: &CacheKey) -> (Option<T>, u64) {
+        let (result, version) = self.backend.get_versioned(key).await;
+        match self.record_lookup(key, result).await {
+            Some(value) => (value, version),
+            None => (None, 0),
+        }
+    }
 
-        if let Some(entry) = cache.get_mut(key) {
-            if entry.is_expired() {
-                // 过期，移除
-                cache.shift_remove(key);
+    /// 查找缓存条目，`Some(None)` 表示命中空值占位
+    async fn lookup(&self, key: &CacheKey) -> Option<Option<T>> {
+        let result = self.backend.get(key).await;
+        self.record_lookup(key, result).await
+    }
+
+    /// 根据后端查找结果更新统计信息并通知策略
+    async fn record_lookup(&self, key: &CacheKey, result: CacheLookup<T>) -> Option<Option<T>> {
+        match result {
+            CacheLookup::Hit(value) => {
+                self.stats.record_hit();
+                self.strategy.on_hit(key).await;
+                Some(value)
+            }
+            CacheLookup::Expired => {
+                self.stats.record_expiration();
                 self.stats.record_miss();
+                self.refresh_size().await;
                 self.strategy.on_miss(key).await;
-                return None;
+                None
+            }
+            CacheLookup::Miss => {
+                self.stats.record_miss();
+                self.strategy.on_miss(key).await;
+                None
             }
-
-            // 访问命中 - 更新 LRU 顺序
-            entry.access();
-            // 保存值和 TTL
-            let value = entry.value.clone();
-            let ttl = entry.expires_at.saturating_duration_since(Instant::now());
-
-            // 移除并重新插入到末尾
-            cache.shift_remove(key);
-            cache.insert(key.clone(), CacheEntry::new(value, ttl));
-
-            self.stats.record_hit();
-            self.strategy.on_hit(key).await;
-
-            Some(cache.get(key)?.value.clone())
-        } else {
-            self.stats.record_miss();
-            self.strategy.on_miss(key).await;
-            None
         }
     }
 
@@ -376,50 +818,329 @@ where
     }
 
     /// 设置缓存值（带自定义 TTL）
+    ///
+    /// 按 [`WritePolicy`] 持久化；写穿策略下持久化失败时不写入缓存
     pub async fn set_with_ttl(&self, key: CacheKey, value: T, ttl: Duration) {
-        let mut cache = self.cache.write().await;
+        let items = [(key, value)];
+        if !self.apply_write_policy(&items).await {
+            self.delete(&items[0].0).await;
+            return;
+        }
 
-        // 检查容量，必要时淘汰最久未使用的项
-        if cache.len() >= self.max_capacity && !cache.contains_key(&key) {
-            // IndexMap 的 shift_remove_index 会移除第一个键（最久未使用）
-            cache.shift_remove_index(0);
+        let [(key, value)] = items;
+        self.insert_entry(key, Some(value), ttl).await;
+    }
+
+    /// 按写入策略持久化，返回是否可以写入缓存
+    async fn apply_write_policy(&self, items: &[(CacheKey, T)]) -> bool {
+        match &self.write_policy {
+            WritePolicy::CacheAside => true,
+            WritePolicy::WriteThrough { persister } => match persister(items.to_vec()).await {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::warn!(
+                        count = items.len(),
+                        error = %e,
+                        "Cache write-through persist failed, entries not cached"
+                    );
+                    false
+                }
+            },
+            WritePolicy::WriteBehind { max_batch, .. } => {
+                let queued = {
+                    let mut queue = self.write_queue.lock();
+                    for (key, value) in items {
+                        queue.insert(key.clone(), value.clone());
+                    }
+                    queue.len()
+                };
+                if queued >= *max_batch {
+                    if let Err(e) = self.flush().await {
+                        tracing::warn!(error = %e, "Cache write-behind flush failed, writes kept for retry");
+                    }
+                }
+                true
+            }
         }
+    }
 
-        // 创建新条目
-        let entry = CacheEntry::new(value, ttl);
+    /// 持久化写回队列中的全部写入，返回持久化的条数
+    ///
+    /// 非写回策略或队列为空时直接返回 0
+    ///
+    /// # Errors
+    ///
+    /// 持久化失败时返回错误，本批写入放回队列（期间被再次写入的键保留新值）
+    pub async fn flush(&self) -> Result<usize, PersistError> {
+        let WritePolicy::WriteBehind { persister, .. } = &self.write_policy else {
+            return Ok(0);
+        };
 
-        // 插入或更新条目
-        cache.insert(key.clone(), entry);
+        let _guard = self.flush_lock.lock().await;
+        let batch: Vec<(CacheKey, T)> = self.write_queue.lock().drain(..).collect();
+        if batch.is_empty() {
+            return Ok(0);
+        }
+
+        let count = batch.len();
+        if let Err(e) = persister(batch.clone()).await {
+            let mut queue = self.write_queue.lock();
+            for (key, value) in batch {
+                queue.entry(key).or_insert(value);
+            }
+            return Err(e);
+        }
+        Ok(count)
+    }
+
+    /// 写回队列中待持久化的写入数
+    pub fn pending_writes(&self) -> usize {
+        self.write_queue.lock().len()
+    }
+
+    /// 仅当条目的当前版本等于 `expected_version` 时写入（使用默认 TTL），返回新版本
+    ///
+    /// 期望版本取自 [`get_versioned`](Self::get_versioned)，0 表示仅在条目不存在时写入；
+    /// 任何写入（包括 [`set`](Self::set)）都会使版本递增，此后持有旧版本的写入方得到冲突。
+    /// 版本化写入用于与数据库写入协调，不经过 [`WritePolicy`]
+    ///
+    /// # Errors
+    ///
+    /// 版本不一致时返回 [`CasError::Conflict`]，后端不支持版本化写入时返回 [`CasError::Unsupported`]
+    pub async fn set_if_version(&self, key: CacheKey, value: T, expected_version: u64) -> Result<u64, CasError> {
+        let version = self
+            .backend
+            .set_if_version(
+                key.clone(),
+                Some(value),
+                self.config.jittered_ttl(self.strategy.ttl()),
+                expected_version,
+            )
+            .await?;
+
+        self.stats.record_set();
+        self.refresh_size().await;
+        self.strategy.on_update(&key).await;
+        Ok(version)
+    }
+
+    /// 写入缓存条目
+    async fn insert_entry(&self, key: CacheKey, value: Option<T>, ttl: Duration) {
+        self.backend
+            .set(key.clone(), value, self.config.jittered_ttl(ttl))
+            .await;
 
         self.stats.record_set();
+        self.refresh_size().await;
         self.strategy.on_update(&key).await;
     }
 
+    /// 将后端当前条目数同步到统计信息
+    async fn refresh_size(&self) {
+        let size = self.backend.len().await;
+        self.stats.size.store(size as u64, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// 批量获取缓存值，只返回命中的键
+    ///
+    /// 后端只获取一次锁，每个键的统计信息和 LRU 顺序与 [`get`](Self::get) 一致
+    pub async fn get_many(&self, keys: &[CacheKey]) -> HashMap<CacheKey, T> {
+        let results = self.backend.get_many(keys).await;
+        let mut found = HashMap::with_capacity(keys.len());
+
+        for (key, result) in keys.iter().zip(results) {
+            if let Some(Some(value)) = self.record_lookup(key, result).await {
+                found.insert(key.clone(), value);
+            }
+        }
+
+        found
+    }
+
+    /// 批量设置缓存值（使用默认 TTL），未启用 TTL 抖动时后端只获取一次锁
+    ///
+    /// 按 [`WritePolicy`] 持久化，写穿策略下整批只调用一次持久化函数，失败时整批不写入缓存
+    pub async fn set_many(&self, items: Vec<(CacheKey, T)>) {
+        if !self.apply_write_policy(&items).await {
+            for (key, _) in &items {
+                self.delete(key).await;
+            }
+            return;
+        }
+
+        let keys: Vec<CacheKey> = items.iter().map(|(key, _)| key.clone()).collect();
+        let items = items.into_iter().map(|(key, value)| (key, Some(value))).collect();
+        let ttl = self.strategy.ttl();
+        if self.config.ttl_jitter_fraction > 0.0 {
+            // 每个条目使用独立的抖动 TTL，无法合并为一次批量写入
+            for (key, value) in items {
+                self.backend.set(key, value, self.config.jittered_ttl(ttl)).await;
+            }
+        } else {
+            self.backend.set_many(items, ttl).await;
+        }
+        self.refresh_size().await;
+
+        for key in &keys {
+            self.stats.record_set();
+            self.strategy.on_update(key).await;
+        }
+    }
+
+    /// 获取缓存值，未命中时调用 `loader` 加载并写入缓存
+    ///
+    /// 同一键的并发未命中只会执行一次 `loader`，其余调用等待同一结果（缓存击穿保护）
+    pub async fn get_or_compute<F>(&self, key: CacheKey, ttl: Duration, loader: F) -> T
+    where
+        F: Future<Output = T>,
+    {
+        if let Some(Some(value)) = self.lookup(&key).await {
+            return value;
+        }
+
+        let value = self.load_once(key, false, ttl, async { Some(loader.await) }).await;
+        value.expect("Non-negative cache loads always produce a value")
+    }
+
+    /// 获取缓存值，未命中时调用可能返回 `None` 的 `loader`
+    ///
+    /// `loader` 返回 `None` 时写入空值占位，在 `negative_ttl` 内不再调用 `loader`（缓存穿透防护）
+    pub async fn get_or_compute_optional<F>(&self, key: CacheKey, ttl: Duration, loader: F) -> Option<T>
+    where
+        F: Future<Output = Option<T>>,
+    {
+        if let Some(value) = self.lookup(&key).await {
+            return value;
+        }
+
+        self.load_once(key, true, ttl, loader).await
+    }
+
+    /// 获取缓存值，未命中时调用可能失败的 `loader`
+    ///
+    /// 与 [`get_or_compute_optional`](Self::get_or_compute_optional) 相同地提供击穿与穿透保护；
+    /// `loader` 返回错误时不写入缓存，错误返回给执行加载的调用方，等待中的调用方之一会重新加载
+    ///
+    /// # Errors
+    ///
+    /// 返回 `loader` 产生的错误
+    pub async fn try_get_or_compute_optional<F, E>(
+        &self,
+        key: CacheKey,
+        ttl: Duration,
+        loader: F,
+    ) -> Result<Option<T>, E>
+    where
+        F: Future<Output = Result<Option<T>, E>>,
+    {
+        if let Some(value) = self.lookup(&key).await {
+            return Ok(value);
+        }
+
+        self.try_load_once(key, true, ttl, loader).await
+    }
+
+    /// 单飞加载：同一键同时只执行一次 `loader` 并写入缓存
+    async fn load_once<F>(&self, key: CacheKey, negative: bool, ttl: Duration, loader: F) -> Option<T>
+    where
+        F: Future<Output = Option<T>>,
+    {
+        let result: Result<Option<T>, std::convert::Infallible> =
+            self.try_load_once(key, negative, ttl, async { Ok(loader.await) }).await;
+        match result {
+            Ok(value) => value,
+            Err(never) => match never {},
+        }
+    }
+
+    /// 可失败的单飞加载：失败的结果不写入缓存，已在等待的调用方之一会重新执行加载
+    async fn try_load_once<F, E>(&self, key: CacheKey, negative: bool, ttl: Duration, loader: F) -> Result<Option<T>, E>
+    where
+        F: Future<Output = Result<Option<T>, E>>,
+    {
+        let flight_key = (key, negative);
+        let cell = self
+            .inflight
+            .lock()
+            .entry(flight_key.clone())
+            .or_insert_with(|| Arc::new(OnceCell::new()))
+            .clone();
+
+        let result = cell
+            .get_or_try_init(|| async {
+                let value = loader.await?;
+                let ttl = match value {
+                    Some(_) => ttl,
+                    None => Duration::from_secs(self.config.negative_ttl),
+                };
+                self.insert_entry(flight_key.0.clone(), value.clone(), ttl).await;
+                Ok(value)
+            })
+            .await
+            .cloned();
+
+        // 加载完成后移除在途记录，后续请求直接命中缓存
+        let mut inflight = self.inflight.lock();
+        if inflight
+            .get(&flight_key)
+            .is_some_and(|current| Arc::ptr_eq(current, &cell))
+        {
+            inflight.remove(&flight_key);
+        }
+
+        result
+    }
+
+    /// 获取正在加载的键数量
+    pub fn pending_loads(&self) -> usize {
+        self.inflight.lock().len()
+    }
+
     /// 删除缓存值
     pub async fn delete(&self, key: &CacheKey) {
-        let mut cache = self.cache.write().await;
-
-        if cache.shift_remove(key).is_some() {
+        if self.backend.delete(key).await {
             self.stats.record_delete();
+            self.refresh_size().await;
         }
     }
 
-    /// 清空缓存
-    pub async fn clear(&mut self) {
-        let mut cache = self.cache.write().await;
+    /// 实体删除后使其缓存失效
+    ///
+    /// 开启 `db_cache` 时，`db_crud` 生成的 `create` / `remove` 在成功后调用，键为 `CacheKey::new(table, id)`
+    pub async fn invalidate_entity(&self, table: &str, id: &str) {
+        self.delete(&CacheKey::new(table, id)).await;
+    }
+
+    /// 实体更新后使其缓存失效
+    ///
+    /// 开启 `db_cache` 时由 `db_crud` 生成的 `modify` 在成功后调用；
+    /// 主键在更新中发生变化时，新旧两个键都会被清除
+    pub async fn invalidate_entity_update(&self, table: &str, old_id: &str, new_id: &str) {
+        self.invalidate_entity(table, old_id).await;
+        if new_id != old_id {
+            self.invalidate_entity(table, new_id).await;
+        }
+    }
 
-        cache.clear();
-        self.stats = CacheStats::new();
+    /// 清空缓存并将统计信息清零
+    ///
+    /// 写回策略下先持久化队列中的写入，失败的写入保留在队列中
+    pub async fn clear(&mut self) {
+        if let Err(e) = self.flush().await {
+            tracing::warn!(error = %e, "Cache write-behind flush before clear failed");
+        }
+        self.backend.clear().await;
+        self.stats.reset();
     }
 
     /// 获取缓存条目数
     pub async fn len(&self) -> usize {
-        self.cache.read().await.len()
+        self.backend.len().await
     }
 
     /// 检查缓存是否为空
     pub async fn is_empty(&self) -> bool {
-        self.cache.read().await.is_empty()
+        self.backend.is_empty().await
     }
 
     /// 获取统计信息
@@ -427,20 +1148,129 @@ where
         &self.stats
     }
 
+    /// 将缓存统计注册到指标收集器，以 `dbnexus_cache_*{cache="<name>"}` 导出
+    ///
+    /// 收集器与缓存共享计数器，之后每次导出都反映缓存的最新状态；同名缓存重复注册时替换旧的统计
+    #[cfg(feature = "metrics")]
+    pub fn register_metrics(&self, metrics: &crate::metrics::MetricsCollector, name: &str) {
+        metrics.register_cache(name, self.stats.clone());
+    }
+
     /// 清理过期条目
     pub async fn cleanup(&self) -> usize {
-        let mut cache = self.cache.write().await;
+        let removed = self.backend.purge_expired().await;
+        self.stats
+            .expirations
+            .fetch_add(removed as u64, std::sync::atomic::Ordering::Relaxed);
+        self.refresh_size().await;
+        removed
+    }
 
-        let before = cache.len();
-        cache.retain(|_key, entry| {
-            let not_expired = !entry.is_expired();
-            if !not_expired {
-                self.stats.record_expiration();
+    /// 启动后台清理任务，每 `cleanup_interval` 秒调用一次 [`cleanup`](Self::cleanup)
+    ///
+    /// 任务持有缓存的弱引用，缓存被释放或句柄发出停止信号（含 drop）后任务结束
+    pub fn spawn_cleanup_task(self: Arc<Self>) -> CacheCleanupHandle
+    where
+        B: 'static,
+    {
+        let interval = Duration::from_secs(self.config.cleanup_interval.max(1));
+        self.spawn_periodic(interval, |cache| async move {
+            let removed = cache.cleanup().await;
+            if removed > 0 {
+                tracing::debug!("Cache cleanup removed {} expired entries", removed);
+            }
+        })
+    }
+
+    /// 启动写回刷新任务，每 `flush_interval` 调用一次 [`flush`](Self::flush)
+    ///
+    /// 非写回策略返回 `None`；任务的生命周期与 [`spawn_cleanup_task`](Self::spawn_cleanup_task) 相同，
+    /// 任务结束时不会自动刷新，需要时在释放缓存前调用 `flush`
+    pub fn spawn_write_behind_task(self: Arc<Self>) -> Option<CacheCleanupHandle>
+    where
+        B: 'static,
+    {
+        let WritePolicy::WriteBehind { flush_interval, .. } = &self.write_policy else {
+            return None;
+        };
+        let interval = (*flush_interval).max(Duration::from_millis(1));
+
+        Some(self.spawn_periodic(interval, |cache| async move {
+            match cache.flush().await {
+                Ok(0) => {}
+                Ok(count) => tracing::debug!("Cache write-behind flushed {} entries", count),
+                Err(e) => tracing::warn!(error = %e, "Cache write-behind flush failed, writes kept for retry"),
+            }
+        }))
+    }
+
+    /// 启动周期性后台任务，任务持有缓存的弱引用
+    fn spawn_periodic<F, Fut>(self: Arc<Self>, interval: Duration, mut tick: F) -> CacheCleanupHandle
+    where
+        B: 'static,
+        F: FnMut(Arc<Self>) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        let cache = Arc::downgrade(&self);
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
+
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // 第一次 tick 立即完成，跳过
+            ticker.tick().await;
+
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    _ = ticker.tick() => {
+                        let Some(cache) = cache.upgrade() else {
+                            break;
+                        };
+                        tick(cache).await;
+                    }
+                }
             }
-            not_expired
         });
 
-        before - cache.len()
+        CacheCleanupHandle {
+            task,
+            shutdown: Some(shutdown_tx),
+        }
+    }
+}
+
+/// 缓存后台任务（清理、写回刷新）句柄
+///
+/// 句柄被 drop 时发出停止信号
+#[derive(Debug)]
+pub struct CacheCleanupHandle {
+    /// 后台任务
+    task: tokio::task::JoinHandle<()>,
+    /// 停止信号
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl CacheCleanupHandle {
+    /// 后台任务是否已结束
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// 发出停止信号并等待后台任务结束
+    pub async fn shutdown(mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        let _ = (&mut self.task).await;
+    }
+}
+
+impl Drop for CacheCleanupHandle {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
     }
 }
 
@@ -460,6 +1290,8 @@ mod tests {
             default_ttl: 60,
             cleanup_interval: 10,
             enable_stats: true,
+            negative_ttl: 30,
+            ttl_jitter_fraction: 0.0,
         };
         let cache = CacheManager::<String>::new(config);
 
@@ -487,6 +1319,8 @@ mod tests {
             default_ttl: 1,
             cleanup_interval: 10,
             enable_stats: true,
+            negative_ttl: 30,
+            ttl_jitter_fraction: 0.0,
         };
         let cache = CacheManager::<String>::new(config);
 
@@ -510,6 +1344,8 @@ mod tests {
             default_ttl: 60,
             cleanup_interval: 10,
             enable_stats: true,
+            negative_ttl: 30,
+            ttl_jitter_fraction: 0.0,
         };
         let cache = CacheManager::<String>::new(config);
 
@@ -570,4 +1406,57 @@ mod tests {
         // 命中率
         assert!((cache.stats().hit_rate() - 0.5).abs() < 0.01);
     }
+
+    /// TEST-U-054: CacheKey::from_value 使用稳定哈希
+    #[test]
+    fn test_cache_key_from_value_is_stable() {
+        let key = CacheKey::from_value("users", &"alice@example.com");
+
+        let mut hasher = XxHash64::with_seed(CACHE_KEY_HASH_SEED);
+        "alice@example.com".hash(&mut hasher);
+        assert_eq!(key.as_str(), format!("users:{:x}", hasher.finish()));
+
+        assert_eq!(key, CacheKey::from_value("users", &"alice@example.com"));
+        assert_ne!(key, CacheKey::from_value("orders", &"alice@example.com"));
+    }
+
+    /// TEST-U-099: TTL 抖动使同批写入的条目过期时间分散在 ±比例范围内
+    #[tokio::test]
+    async fn test_ttl_jitter_spreads_expiry() {
+        async fn remaining_ttls(jitter: f64) -> Vec<Duration> {
+            let config = CacheConfig {
+                max_capacity: 1000,
+                default_ttl: 100,
+                ttl_jitter_fraction: jitter,
+                ..CacheConfig::default()
+            };
+            let cache = CacheManager::<u32>::new(config);
+
+            cache
+                .set_many((0..100).map(|i| (CacheKey::new("users", &i.to_string()), i)).collect())
+                .await;
+            for i in 100..200 {
+                cache.set(CacheKey::new("users", &i.to_string()), i).await;
+            }
+
+            let entries = cache.backend.entries.read().await;
+            entries.values().map(|entry| entry.remaining_ttl()).collect()
+        }
+
+        let ttls = remaining_ttls(0.1).await;
+        assert_eq!(ttls.len(), 200);
+        let min = *ttls.iter().min().unwrap();
+        let max = *ttls.iter().max().unwrap();
+        assert!(min >= Duration::from_secs(89), "min ttl {:?}", min);
+        assert!(max <= Duration::from_secs(110), "max ttl {:?}", max);
+        // 200 个均匀分布在 [90s, 110s] 的样本，极差小于 10s 的概率可忽略
+        assert!(max - min > Duration::from_secs(10), "ttl spread {:?}", max - min);
+
+        // 不抖动时所有条目按名义 TTL 过期
+        let ttls = remaining_ttls(0.0).await;
+        let min = *ttls.iter().min().unwrap();
+        let max = *ttls.iter().max().unwrap();
+        assert!(max <= Duration::from_secs(100));
+        assert!(max - min < Duration::from_secs(1), "ttl spread {:?}", max - min);
+    }
 }
@@ -5,10 +5,353 @@
 
 //! 实体转换模块
 //!
-//! 提供实体转换工具和类型重导出
+//! 提供实体转换工具和类型重导出，以及 `#[db_crud]` 生成代码调用的通用查询辅助函数。
+//!
+//! `#[db_crud]` 标注在 Sea-ORM 实体模块的 `Model` 上，为同模块的 `Entity` 生成：
+//!
+//! - `list_paginated(conn, page, per_page)` / `count(conn)`：委托 [`list_paginated`] 和 [`count`]
+//! - `fetch` / `create` / `modify` / `remove`：通过 [`Session`](crate::pool::Session) 执行，带权限检查
+//! - `find_by_<column>(conn, value)`：为 `#[sea_orm(unique)]` / `#[sea_orm(indexed)]` 列生成，委托 [`find_by`]
+//!
+//! 同时标注 `#[db_cache]` 时，`create` / `modify` / `remove` 额外接收
+//! `cache::CacheManager`，成功后使对应主键的缓存失效，并生成 `fetch_cached`。
+//! 同时标注 `#[db_audit]` 时，三者改为调用 Session 的 `*_audited` 方法，在成功后写入 `audit_log` 审计记录。
+//! 同时标注 `#[db_permission(deny_select = [...])]` 时，非豁免角色调用 `fetch` 会被拒绝，
+//! 另外生成只返回可见列的 `fetch_visible` / `list_visible`（见 [`list_columns`]）和 `fetch_columns`。
+//! 以连接为参数的 `list_paginated` / `count` / `find_by_<column>` 不经过 Session，不做权限检查。
+//!
+//! 标注在 `#[derive(DbEntity)]` + `#[table_name = "..."]` 的普通结构体上时（dbnexus 0.1.0 的写法），
+//! `#[db_crud]` 在结构体上生成 `insert` / `find_by_id` / `update` / `delete` / `find_all` / `delete_many` /
+//! `count`；`#[db_permission(roles = [...])]` 生成的 `check_operation` 委托 [`check_entity_operation`]。
+//!
+//! 入站 DTO 实现 [`Validate`] 和 Sea-ORM `IntoActiveModel` 后，可通过
+//! [`Session::insert_validated`](crate::pool::Session::insert_validated) 在插入前完成字段校验；
+//! 出站方向实现 [`FromModel`] 将 Model 转换为普通结构体。
 
 pub use sea_orm::entity::prelude::{
     ActiveModelBehavior, ActiveModelTrait, DeriveActiveModel, DeriveIntoActiveModel, EntityTrait, Iden, RelationTrait,
 };
 
-pub use sea_orm::{Condition, Set};
+pub use sea_orm::{Condition, IntoActiveModel, Set};
+
+use crate::config::{DbError, DbResult};
+use crate::permission::{PermissionAction, PermissionContext};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, IdenStatic, Iterable, ModelTrait, PaginatorTrait, PrimaryKeyToColumn, QueryFilter,
+    QueryOrder, QuerySelect,
+};
+
+/// 分页查询实体，返回当前页数据和总行数
+///
+/// 基于 Sea-ORM `Paginator`，按主键升序，页码从 0 开始；超出范围的页返回空列表。
+///
+/// # Errors
+///
+/// 如果 `per_page` 为 0 或查询失败，返回错误
+pub async fn list_paginated<E, C>(conn: &C, page: u64, per_page: u64) -> DbResult<(Vec<E::Model>, u64)>
+where
+    E: EntityTrait,
+    E::Model: Sync + 'static,
+    C: ConnectionTrait,
+{
+    if per_page == 0 {
+        return Err(DbError::Config("per_page must be greater than 0".to_string()));
+    }
+
+    let mut query = E::find();
+    for key in E::PrimaryKey::iter() {
+        query = query.order_by_asc(key.into_column());
+    }
+
+    let paginator = query.paginate(conn, per_page);
+    let total = paginator.num_items().await?;
+    let items = paginator.fetch_page(page).await?;
+
+    Ok((items, total))
+}
+
+/// 分页查询实体的指定列，每行为 `{"列名": 值}` 的 JSON 对象
+///
+/// 排序和分页规则与 [`list_paginated`] 相同，用于按列级权限投影的查询
+///
+/// # Errors
+///
+/// 如果 `per_page` 为 0、`columns` 为空或查询失败，返回错误
+pub async fn list_columns<E, C>(
+    conn: &C,
+    columns: &[E::Column],
+    page: u64,
+    per_page: u64,
+) -> DbResult<(Vec<serde_json::Value>, u64)>
+where
+    E: EntityTrait,
+    C: ConnectionTrait,
+{
+    if per_page == 0 {
+        return Err(DbError::Config("per_page must be greater than 0".to_string()));
+    }
+    if columns.is_empty() {
+        return Err(DbError::Config("at least one column must be selected".to_string()));
+    }
+
+    let mut query = E::find().select_only().columns(columns.iter().copied());
+    for key in E::PrimaryKey::iter() {
+        query = query.order_by_asc(key.into_column());
+    }
+
+    let paginator = query.into_json().paginate(conn, per_page);
+    let total = paginator.num_items().await?;
+    let items = paginator.fetch_page(page).await?;
+
+    Ok((items, total))
+}
+
+/// 统计实体表的总行数
+///
+/// # Errors
+///
+/// 如果查询失败，返回错误
+pub async fn count<E, C>(conn: &C) -> DbResult<u64>
+where
+    E: EntityTrait,
+    E::Model: Sync + 'static,
+    C: ConnectionTrait,
+{
+    Ok(E::find().count(conn).await?)
+}
+
+/// 按列等值查找单个实体
+///
+/// 用于唯一列或索引列的 `find_by_<column>` 查找；存在多行时返回第一行。
+///
+/// # Errors
+///
+/// 如果查询失败，返回错误
+pub async fn find_by<E, C, V>(conn: &C, column: E::Column, value: V) -> DbResult<Option<E::Model>>
+where
+    E: EntityTrait,
+    C: ConnectionTrait,
+    V: Into<sea_orm::Value>,
+{
+    Ok(E::find().filter(column.eq(value)).one(conn).await?)
+}
+
+/// 检查 Session 角色是否在 `#[db_permission(roles = [...])]` 声明的角色中
+///
+/// 由生成的 `check_permission(ctx)` 调用
+///
+/// # Errors
+///
+/// 角色不在 `roles` 中时返回 `DbError::Forbidden`
+pub fn check_entity_role(ctx: &PermissionContext, table: &str, roles: &[&str]) -> DbResult<()> {
+    if roles.contains(&ctx.role()) {
+        return Ok(());
+    }
+    Err(DbError::Forbidden {
+        role: ctx.role().to_string(),
+        reason: format!("role '{}' is not allowed to access table '{}'", ctx.role(), table),
+    })
+}
+
+/// 检查 Session 角色及操作是否在 `#[db_permission(roles = [...], operations = [...])]` 声明的范围内
+///
+/// 由生成的 `check_operation(ctx, operation)` 调用
+///
+/// # Errors
+///
+/// 角色不在 `roles` 中时返回 `DbError::Forbidden`，操作不在 `operations` 中时返回 `DbError::Permission`
+pub fn check_entity_operation(
+    ctx: &PermissionContext,
+    table: &str,
+    roles: &[&str],
+    operations: &[PermissionAction],
+    operation: &PermissionAction,
+) -> DbResult<()> {
+    check_entity_role(ctx, table, roles)?;
+    if operations.contains(operation) {
+        return Ok(());
+    }
+    Err(DbError::Permission {
+        role: ctx.role().to_string(),
+        table: table.to_string(),
+        operation: operation.clone(),
+    })
+}
+
+/// 将 Model 的各列转换为 JSON 对象 `{"列名": 值}`
+///
+/// 用于审计记录的变更前后快照；非 UTF-8 的二进制列按有损方式转换为字符串
+pub fn model_to_json<M: ModelTrait>(model: &M) -> serde_json::Value {
+    let columns = <M::Entity as EntityTrait>::Column::iter()
+        .map(|column| (column.as_str().to_string(), value_to_json(&model.get(column))))
+        .collect();
+    serde_json::Value::Object(columns)
+}
+
+/// Model 主键的字符串形式，复合主键以逗号连接
+#[cfg(feature = "audit")]
+pub(crate) fn primary_key_string<M: ModelTrait>(model: &M) -> String {
+    model
+        .get_primary_key_value()
+        .into_iter()
+        .map(|value| match value_to_json(&value) {
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn value_to_json(value: &sea_orm::Value) -> serde_json::Value {
+    match value {
+        sea_orm::Value::Bytes(Some(bytes)) => String::from_utf8_lossy(bytes).into_owned().into(),
+        other => sea_orm::sea_query::value::sea_value_to_json_value(other),
+    }
+}
+
+/// 从 Sea-ORM Model 转换为普通结构体（如对外输出的 DTO）
+pub trait FromModel<M>: Sized {
+    /// 由 Model 构造
+    fn from_model(model: M) -> Self;
+}
+
+/// 字段校验
+///
+/// 在 DTO 转换为 ActiveModel 之前调用，通常借助 [`Validator`] 实现：
+///
+/// ```rust,ignore
+/// impl Validate for NewUser {
+///     fn validate(&self) -> Result<(), ValidationErrors> {
+///         Validator::new()
+///             .non_empty("name", &self.name)
+///             .length("email", &self.email, 3, 254)
+///             .range("age", self.age, 0, 150)
+///             .finish()
+///     }
+/// }
+/// ```
+pub trait Validate {
+    /// 校验字段，失败时返回全部字段错误
+    fn validate(&self) -> Result<(), ValidationErrors>;
+}
+
+/// 单个字段的校验错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// 字段名
+    pub field: String,
+    /// 错误描述
+    pub message: String,
+}
+
+/// 校验错误集合
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ValidationErrors {
+    /// 字段错误列表
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    /// 添加字段错误
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: field.to_string(),
+            message: message.into(),
+        });
+    }
+
+    /// 是否没有错误
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl std::fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let messages: Vec<String> = self
+            .errors
+            .iter()
+            .map(|e| format!("{}: {}", e.field, e.message))
+            .collect();
+        write!(f, "{}", messages.join("; "))
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+/// 常用字段校验规则的构建器
+#[derive(Debug, Default)]
+pub struct Validator {
+    errors: ValidationErrors,
+}
+
+impl Validator {
+    /// 创建校验器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 字符串去除首尾空白后不能为空
+    pub fn non_empty(mut self, field: &str, value: &str) -> Self {
+        if value.trim().is_empty() {
+            self.errors.add(field, "must not be empty");
+        }
+        self
+    }
+
+    /// 字符串长度（字符数）必须在 `[min, max]` 范围内
+    pub fn length(mut self, field: &str, value: &str, min: usize, max: usize) -> Self {
+        let len = value.chars().count();
+        if len < min || len > max {
+            self.errors.add(
+                field,
+                format!("length must be between {} and {}, got {}", min, max, len),
+            );
+        }
+        self
+    }
+
+    /// 数值必须在 `[min, max]` 范围内
+    pub fn range<T: PartialOrd + std::fmt::Display>(mut self, field: &str, value: T, min: T, max: T) -> Self {
+        if value < min || value > max {
+            self.errors
+                .add(field, format!("must be between {} and {}, got {}", min, max, value));
+        }
+        self
+    }
+
+    /// 自定义规则：`valid` 为假时记录错误
+    pub fn check(mut self, field: &str, valid: bool, message: impl Into<String>) -> Self {
+        if !valid {
+            self.errors.add(field, message);
+        }
+        self
+    }
+
+    /// 结束校验
+    ///
+    /// # Errors
+    ///
+    /// 存在任意字段错误时返回 [`ValidationErrors`]
+    pub fn finish(self) -> Result<(), ValidationErrors> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors)
+        }
+    }
+}
+
+/// 校验 DTO 并转换为 ActiveModel
+///
+/// # Errors
+///
+/// 校验失败时返回 `DbError::Validation`
+pub fn into_validated_active_model<T, A>(dto: T) -> DbResult<A>
+where
+    T: Validate + IntoActiveModel<A>,
+    A: ActiveModelTrait,
+{
+    dto.validate()?;
+    Ok(dto.into_active_model())
+}
@@ -7,6 +7,12 @@
 //!
 //! 此模块由 build.rs 自动生成，包含 permissions.yaml 中定义的所有角色
 //! 用于 #[db_permission] 宏的编译时验证
+//!
+//! 生成的 API：
+//! - `ROLES` / `DEFINED_ROLES`: 角色名列表（已排序）
+//! - `is_known_role(role)`: 角色是否在列表中
+//! - `Role`: 角色不超过 64 个且名称都能转换为合法标识符时生成的类型化枚举
+//!   （`read_only` -> `Role::ReadOnly`），提供 `as_str`、`ALL`、`Display` 和 `FromStr`
 
 include!(concat!(env!("OUT_DIR"), "/generated_roles.rs"));
 
@@ -22,4 +28,12 @@ mod tests {
         let defined_roles = get_defined_roles();
         assert!(!defined_roles.contains(&"nonexistent_role"));
     }
+
+    /// TEST-U-077: ROLES 与 is_known_role 一致
+    #[test]
+    fn test_known_roles() {
+        assert_eq!(ROLES, DEFINED_ROLES);
+        assert!(ROLES.iter().all(|role| is_known_role(role)));
+        assert!(!is_known_role("nonexistent_role"));
+    }
 }
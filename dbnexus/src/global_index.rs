@@ -8,7 +8,8 @@
 //! 提供跨分片的全局索引功能，支持：
 //! - 异步同步分片数据到全局索引
 //! - 不带时间条件的查询
-//! - binlog/CDC 风格的变更捕获
+//! - 大结果集的分页与流式查询
+//! - binlog/CDC 风格的变更捕获（变更表轮询，PostgreSQL 下可使用 `LISTEN/NOTIFY`）
 //!
 //! # Example
 //!
@@ -21,12 +22,26 @@
 //! ```
 
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures::future;
+use futures::stream::{self, Stream, TryStreamExt};
+use lru::LruCache;
 use sea_orm::entity::prelude::*;
-use sea_orm::{ActiveValue, Database};
-use std::collections::HashMap;
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{ActiveValue, Database, QueryOrder, QueryResult, QuerySelect, Statement};
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+use std::ops::RangeBounds;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+
+use crate::migration::{Column as MigrationColumn, ColumnType, DatabaseType, Index, SqlGenerator, Table};
+#[cfg(feature = "postgres")]
+use crate::retry::RetryPolicy;
+
+/// 全局索引表名
+pub const INDEX_TABLE_NAME: &str = "global_index";
 
 /// 同步状态：待同步
 pub const SYNC_STATUS_PENDING: &str = "pending";
@@ -35,6 +50,13 @@ pub const SYNC_STATUS_SYNCED: &str = "synced";
 /// 同步状态：同步失败
 pub const SYNC_STATUS_FAILED: &str = "failed";
 
+/// 同步操作：插入
+pub const SYNC_OP_INSERT: &str = "insert";
+/// 同步操作：更新
+pub const SYNC_OP_UPDATE: &str = "update";
+/// 同步操作：删除
+pub const SYNC_OP_DELETE: &str = "delete";
+
 /// 全局索引条目实体
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
 #[sea_orm(table_name = "global_index")]
@@ -58,6 +80,8 @@ pub struct Model {
     pub updated_at: String,
     /// 同步状态
     pub sync_status: String,
+    /// 最近一次同步事件的操作（`insert` / `update` / `delete`），用于重建待重试的事件
+    pub sync_op: String,
 }
 
 /// 实体关系枚举
@@ -67,7 +91,7 @@ pub enum Relation {}
 impl ActiveModelBehavior for ActiveModel {}
 
 /// 索引条目结构
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct IndexEntry {
     /// 表名
     pub table_name: String,
@@ -79,6 +103,42 @@ pub struct IndexEntry {
     pub index_key: String,
     /// 索引值
     pub index_value: String,
+    /// 创建时间，从数据库读取时填充，写入时忽略
+    pub created_at: Option<DateTime<Utc>>,
+    /// 更新时间，从数据库读取时填充，写入时忽略
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl IndexEntry {
+    /// 创建索引条目，时间戳由写入时的数据库记录决定
+    pub fn new(table_name: &str, record_id: &str, shard_id: u32, index_key: &str, index_value: &str) -> Self {
+        Self {
+            table_name: table_name.to_string(),
+            record_id: record_id.to_string(),
+            shard_id,
+            index_key: index_key.to_string(),
+            index_value: index_value.to_string(),
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    /// 将索引值解析为数字，非数字值返回 `None`
+    pub fn numeric_value(&self) -> Option<f64> {
+        self.index_value
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite())
+    }
+}
+
+/// 解析存储的时间字符串，兼容 RFC 3339 与数据库 `CURRENT_TIMESTAMP` 的 `YYYY-MM-DD HH:MM:SS` 格式
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|at| at.with_timezone(&Utc))
+        .or_else(|_| chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f").map(|at| at.and_utc()))
+        .ok()
 }
 
 /// 同步事件类型
@@ -129,6 +189,106 @@ pub enum SyncEvent {
     },
 }
 
+impl SyncEvent {
+    /// 事件的操作（[`SYNC_OP_INSERT`] / [`SYNC_OP_UPDATE`] / [`SYNC_OP_DELETE`]）
+    pub fn operation(&self) -> &'static str {
+        match self {
+            SyncEvent::Insert { .. } => SYNC_OP_INSERT,
+            SyncEvent::Update { .. } => SYNC_OP_UPDATE,
+            SyncEvent::Delete { .. } => SYNC_OP_DELETE,
+        }
+    }
+
+    /// 获取事件对应的 `(table_name, record_id)`
+    fn record(&self) -> (&str, &str) {
+        match self {
+            SyncEvent::Insert {
+                table_name, record_id, ..
+            }
+            | SyncEvent::Update {
+                table_name, record_id, ..
+            }
+            | SyncEvent::Delete {
+                table_name, record_id, ..
+            } => (table_name, record_id),
+        }
+    }
+
+    /// 获取事件写入后的索引条目，删除事件返回 `None`
+    fn to_entry(&self) -> Option<IndexEntry> {
+        match self {
+            SyncEvent::Insert {
+                table_name,
+                record_id,
+                shard_id,
+                index_key,
+                index_value,
+            }
+            | SyncEvent::Update {
+                table_name,
+                record_id,
+                shard_id,
+                new_index_key: index_key,
+                new_index_value: index_value,
+                ..
+            } => Some(IndexEntry::new(
+                table_name,
+                record_id,
+                *shard_id,
+                index_key,
+                index_value,
+            )),
+            SyncEvent::Delete { .. } => None,
+        }
+    }
+
+    /// 根据索引行及其记录的 `sync_op` 重建同步事件
+    ///
+    /// 行中保存的是事件写入后的索引键值，更新事件的旧值无从恢复，按新值重建
+    fn from_model(model: &Model) -> Self {
+        let (table_name, record_id) = (model.table_name.clone(), model.record_id.clone());
+        let (shard_id, index_key, index_value) = (
+            model.shard_id as u32,
+            model.index_key.clone(),
+            model.index_value.clone(),
+        );
+        match model.sync_op.as_str() {
+            SYNC_OP_DELETE => SyncEvent::Delete {
+                table_name,
+                record_id,
+                shard_id,
+                index_key,
+                index_value,
+            },
+            SYNC_OP_UPDATE => SyncEvent::Update {
+                table_name,
+                record_id,
+                shard_id,
+                old_index_key: index_key.clone(),
+                old_index_value: index_value.clone(),
+                new_index_key: index_key,
+                new_index_value: index_value,
+            },
+            _ => SyncEvent::Insert {
+                table_name,
+                record_id,
+                shard_id,
+                index_key,
+                index_value,
+            },
+        }
+    }
+}
+
+/// 重试队列中的失败事件
+#[derive(Debug, Clone)]
+struct RetryItem {
+    /// 失败的同步事件
+    event: SyncEvent,
+    /// 已重试次数
+    attempts: u32,
+}
+
 /// 变更捕获配置
 #[derive(Debug, Clone)]
 pub struct ChangeCaptureConfig {
@@ -140,6 +300,17 @@ pub struct ChangeCaptureConfig {
     pub max_retries: u32,
     /// 重试间隔（毫秒）
     pub retry_interval_ms: u64,
+    /// 索引缓存最大条目数（0 表示不缓存）
+    pub cache_max_entries: usize,
+    /// 索引缓存有效期（毫秒）
+    pub cache_ttl_ms: u64,
+}
+
+impl ChangeCaptureConfig {
+    /// 获取索引缓存有效期
+    pub fn cache_ttl(&self) -> Duration {
+        Duration::from_millis(self.cache_ttl_ms)
+    }
 }
 
 impl Default for ChangeCaptureConfig {
@@ -149,12 +320,248 @@ impl Default for ChangeCaptureConfig {
             poll_interval_ms: 1000,
             max_retries: 3,
             retry_interval_ms: 5000,
+            cache_max_entries: 10_000,
+            cache_ttl_ms: 300_000,
+        }
+    }
+}
+
+/// 缓存桶标识：`(table_name, index_key, index_value)`
+type BucketKey = (String, String, String);
+
+/// 单个索引值对应的缓存桶
+#[derive(Debug)]
+struct Bucket {
+    /// 缓存的条目
+    entries: Vec<IndexEntry>,
+    /// 写入缓存的时间
+    cached_at: Instant,
+}
+
+/// 单个索引键的缓存
+#[derive(Debug, Default)]
+struct KeyCache {
+    /// 索引值到缓存桶的映射
+    values: HashMap<String, Bucket>,
+    /// 从数据库完整加载该索引键的时间；`None` 表示缓存不完整
+    complete_at: Option<Instant>,
+}
+
+/// 索引缓存，按 `(table_name, index_key)` 组织
+///
+/// 条目总数超过 `max_entries` 时按 LRU 淘汰缓存桶，超过 `ttl` 的缓存桶在访问时失效。
+/// 淘汰或失效会使所属索引键不再被视为完整加载。
+#[derive(Debug)]
+struct IndexCache {
+    keys: HashMap<(String, String), KeyCache>,
+    /// 缓存桶访问顺序
+    recency: LruCache<BucketKey, ()>,
+    /// 当前缓存的条目总数
+    len: usize,
+    /// 最大缓存条目数
+    max_entries: usize,
+    /// 缓存有效期
+    ttl: Duration,
+}
+
+impl IndexCache {
+    fn new(max_entries: usize, ttl: Duration) -> Self {
+        Self {
+            keys: HashMap::new(),
+            recency: LruCache::unbounded(),
+            len: 0,
+            max_entries,
+            ttl,
+        }
+    }
+
+    /// 更新容量和有效期，并立即按新容量淘汰
+    fn set_limits(&mut self, max_entries: usize, ttl: Duration) {
+        self.max_entries = max_entries;
+        self.ttl = ttl;
+        self.evict();
+    }
+
+    fn is_fresh(&self, at: Instant) -> bool {
+        at.elapsed() < self.ttl
+    }
+
+    /// 按索引值查询；索引键已完整加载时，缺失的值视为空结果
+    fn get(&mut self, table_name: &str, index_key: &str, index_value: &str) -> Option<Vec<IndexEntry>> {
+        let id = (table_name.to_string(), index_key.to_string());
+        let key_cache = self.keys.get(&id)?;
+        let complete = key_cache.complete_at.is_some_and(|at| self.is_fresh(at));
+
+        let hit = match key_cache.values.get(index_value) {
+            Some(bucket) if self.is_fresh(bucket.cached_at) => Some(bucket.entries.clone()),
+            Some(_) => None,
+            None => return complete.then(Vec::new),
+        };
+
+        let bucket_key = (id.0, id.1, index_value.to_string());
+        match hit {
+            Some(entries) => {
+                self.recency.promote(&bucket_key);
+                Some(entries)
+            }
+            None => {
+                self.drop_bucket(&bucket_key);
+                None
+            }
+        }
+    }
+
+    /// 查询索引键在所有分片上的条目，仅在完整加载且未过期时命中
+    fn get_all(&mut self, table_name: &str, index_key: &str) -> Option<Vec<IndexEntry>> {
+        let id = (table_name.to_string(), index_key.to_string());
+        let key_cache = self.keys.get(&id)?;
+        let fresh = key_cache.complete_at.is_some_and(|at| self.is_fresh(at))
+            && key_cache.values.values().all(|bucket| self.is_fresh(bucket.cached_at));
+        if !fresh {
+            self.drop_key(&id);
+            return None;
+        }
+
+        let mut entries: Vec<IndexEntry> = key_cache
+            .values
+            .values()
+            .flat_map(|bucket| bucket.entries.iter().cloned())
+            .collect();
+        let values: Vec<String> = key_cache.values.keys().cloned().collect();
+        for value in values {
+            self.recency.promote(&(id.0.clone(), id.1.clone(), value));
+        }
+
+        entries.sort_by(|a, b| a.record_id.cmp(&b.record_id));
+        Some(entries)
+    }
+
+    /// 写入条目，同一记录的旧条目会被替换
+    fn insert(&mut self, entry: &IndexEntry) {
+        self.remove_record(&entry.table_name, &entry.record_id);
+
+        let bucket_key = (
+            entry.table_name.clone(),
+            entry.index_key.clone(),
+            entry.index_value.clone(),
+        );
+        self.keys
+            .entry((entry.table_name.clone(), entry.index_key.clone()))
+            .or_default()
+            .values
+            .entry(entry.index_value.clone())
+            .or_insert_with(|| Bucket {
+                entries: Vec::new(),
+                cached_at: Instant::now(),
+            })
+            .entries
+            .push(entry.clone());
+        self.len += 1;
+        self.recency.put(bucket_key, ());
+
+        self.evict();
+    }
+
+    /// 用数据库中的全部条目替换索引键缓存，并标记为完整
+    fn fill_key(&mut self, table_name: &str, index_key: &str, entries: &[IndexEntry]) {
+        let id = (table_name.to_string(), index_key.to_string());
+        self.drop_key(&id);
+
+        let now = Instant::now();
+        let mut key_cache = KeyCache {
+            values: HashMap::new(),
+            complete_at: Some(now),
+        };
+        for entry in entries {
+            key_cache
+                .values
+                .entry(entry.index_value.clone())
+                .or_insert_with(|| Bucket {
+                    entries: Vec::new(),
+                    cached_at: now,
+                })
+                .entries
+                .push(entry.clone());
+        }
+
+        for value in key_cache.values.keys() {
+            self.recency.put((id.0.clone(), id.1.clone(), value.clone()), ());
+        }
+        self.len += entries.len();
+        self.keys.insert(id, key_cache);
+
+        self.evict();
+    }
+
+    /// 移除某条记录的所有缓存条目
+    fn remove_record(&mut self, table_name: &str, record_id: &str) {
+        let mut removed = 0;
+        let mut empty_buckets = Vec::new();
+        for ((table, index_key), key_cache) in self.keys.iter_mut() {
+            if table != table_name {
+                continue;
+            }
+            key_cache.values.retain(|value, bucket| {
+                let before = bucket.entries.len();
+                bucket.entries.retain(|e| e.record_id != record_id);
+                removed += before - bucket.entries.len();
+                if bucket.entries.is_empty() {
+                    empty_buckets.push((table.clone(), index_key.clone(), value.clone()));
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+
+        self.len -= removed;
+        for bucket_key in empty_buckets {
+            self.recency.pop(&bucket_key);
+        }
+    }
+
+    /// 按容量淘汰最久未访问的缓存桶
+    fn evict(&mut self) {
+        while self.len > self.max_entries {
+            match self.recency.peek_lru() {
+                Some((bucket_key, _)) => {
+                    let bucket_key = bucket_key.clone();
+                    self.drop_bucket(&bucket_key);
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// 丢弃缓存桶，所属索引键不再完整
+    fn drop_bucket(&mut self, bucket_key: &BucketKey) {
+        self.recency.pop(bucket_key);
+
+        let id = (bucket_key.0.clone(), bucket_key.1.clone());
+        if let Some(key_cache) = self.keys.get_mut(&id) {
+            key_cache.complete_at = None;
+            if let Some(bucket) = key_cache.values.remove(&bucket_key.2) {
+                self.len -= bucket.entries.len();
+            }
+            if key_cache.values.is_empty() {
+                self.keys.remove(&id);
+            }
+        }
+    }
+
+    /// 丢弃整个索引键的缓存
+    fn drop_key(&mut self, id: &(String, String)) {
+        if let Some(key_cache) = self.keys.remove(id) {
+            for (value, bucket) in key_cache.values {
+                self.len -= bucket.entries.len();
+                self.recency.pop(&(id.0.clone(), id.1.clone(), value));
+            }
         }
     }
 }
 
-/// 索引缓存类型
-type IndexCache = HashMap<String, HashMap<String, HashMap<String, Vec<IndexEntry>>>>;
+/// 流式查询每次从数据库读取的条目数
+pub const STREAM_PAGE_SIZE: u64 = 1000;
 
 /// 全局索引管理器
 #[derive(Debug)]
@@ -165,11 +572,18 @@ pub struct GlobalIndex {
     cache: Arc<RwLock<IndexCache>>,
     /// 配置
     config: ChangeCaptureConfig,
+    /// 同步失败的重试队列，按条目 ID 索引
+    retry_queue: Mutex<HashMap<String, RetryItem>>,
 }
 
 impl GlobalIndex {
     /// 创建新的全局索引管理器
     pub async fn new(database_url: &str) -> Result<Self, DbErr> {
+        Self::with_config(database_url, ChangeCaptureConfig::default()).await
+    }
+
+    /// 使用指定配置创建全局索引管理器
+    pub async fn with_config(database_url: &str, config: ChangeCaptureConfig) -> Result<Self, DbErr> {
         let conn = Database::connect(database_url).await?;
 
         // 简单起见，使用 migrations
@@ -177,33 +591,99 @@ impl GlobalIndex {
 
         Ok(Self {
             conn,
-            cache: Arc::new(RwLock::new(HashMap::new())),
-            config: ChangeCaptureConfig::default(),
+            cache: Arc::new(RwLock::new(IndexCache::new(
+                config.cache_max_entries,
+                config.cache_ttl(),
+            ))),
+            config,
+            retry_queue: Mutex::new(HashMap::new()),
         })
     }
 
+    /// 全局索引表的结构定义
+    ///
+    /// 时间列与 [`Model`] 一致按 RFC 3339 字符串存储；表由外部迁移管理时可用于生成建表语句
+    pub fn schema_table() -> Table {
+        let column = |name: &str, column_type: ColumnType| MigrationColumn {
+            name: name.to_string(),
+            column_type,
+            is_primary_key: false,
+            is_nullable: false,
+            has_default: false,
+            default_value: None,
+            is_auto_increment: false,
+            comment: None,
+        };
+        let index = |name: &str, columns: &[&str], is_unique: bool| Index {
+            name: name.to_string(),
+            table_name: INDEX_TABLE_NAME.to_string(),
+            columns: columns.iter().map(|c| String::from(*c)).collect(),
+            is_unique,
+            is_constraint: false,
+            where_clause: None,
+            method: None,
+        };
+
+        let id = MigrationColumn {
+            is_primary_key: true,
+            ..column("id", ColumnType::String(Some(64)))
+        };
+        let sync_status = MigrationColumn {
+            has_default: true,
+            default_value: Some(format!("'{}'", SYNC_STATUS_SYNCED)),
+            ..column("sync_status", ColumnType::String(Some(20)))
+        };
+        let sync_op = MigrationColumn {
+            has_default: true,
+            default_value: Some(format!("'{}'", SYNC_OP_INSERT)),
+            ..column("sync_op", ColumnType::String(Some(16)))
+        };
+
+        let mut table = Table::new(
+            INDEX_TABLE_NAME,
+            vec![
+                id,
+                column("table_name", ColumnType::String(Some(128))),
+                column("record_id", ColumnType::String(Some(128))),
+                column("shard_id", ColumnType::Integer),
+                column("index_key", ColumnType::String(Some(128))),
+                column("index_value", ColumnType::String(Some(512))),
+                column("created_at", ColumnType::String(Some(64))),
+                column("updated_at", ColumnType::String(Some(64))),
+                sync_status,
+                sync_op,
+            ],
+        );
+        table.indexes = vec![
+            index("uk_table_record", &["table_name", "record_id"], true),
+            index(
+                "idx_global_index_key",
+                &["table_name", "index_key", "index_value"],
+                false,
+            ),
+            index("idx_global_index_shard", &["table_name", "shard_id"], false),
+        ];
+        table
+    }
+
     /// 初始化数据库 schema
+    ///
+    /// 索引表已存在（如由外部迁移创建）时不执行任何 DDL，否则按当前数据库类型生成建表语句
     async fn init_schema(conn: &DatabaseConnection) -> Result<(), DbErr> {
-        // 创建全局索引表
-        let create_sql = r#"
-        CREATE TABLE IF NOT EXISTS global_index (
-            id VARCHAR(64) PRIMARY KEY,
-            table_name VARCHAR(128) NOT NULL,
-            record_id VARCHAR(128) NOT NULL,
-            shard_id INTEGER NOT NULL,
-            index_key VARCHAR(128) NOT NULL,
-            index_value VARCHAR(512) NOT NULL,
-            created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
-            updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
-            sync_status VARCHAR(20) DEFAULT 'synced',
-            CONSTRAINT uk_table_record UNIQUE (table_name, record_id)
-        );
-        
-        CREATE INDEX IF NOT EXISTS idx_global_index_key ON global_index (table_name, index_key, index_value);
-        CREATE INDEX IF NOT EXISTS idx_global_index_shard ON global_index (table_name, shard_id);
-        "#;
+        let backend = conn.get_database_backend();
+        let probe = format!("SELECT 1 FROM {} WHERE 1 = 0", INDEX_TABLE_NAME);
+        if conn.query_one_raw(Statement::from_string(backend, probe)).await.is_ok() {
+            return Ok(());
+        }
+
+        let db_type = match backend {
+            sea_orm::DatabaseBackend::Postgres => DatabaseType::Postgres,
+            sea_orm::DatabaseBackend::MySql => DatabaseType::MySql,
+            _ => DatabaseType::Sqlite,
+        };
+        let create_sql = SqlGenerator::new(db_type).generate_create_table_sql(&Self::schema_table());
 
-        conn.execute_unprepared(create_sql).await?;
+        conn.execute_unprepared(&create_sql).await?;
         Ok(())
     }
 
@@ -213,13 +693,24 @@ impl GlobalIndex {
     }
 
     /// 注册索引条目
+    ///
+    /// 同一 `(table_name, record_id)` 已存在时更新分片、索引键值和更新时间
     pub async fn register_entry(&self, entry: IndexEntry) -> Result<(), DbErr> {
+        let written = self.write_entry(&entry, SYNC_STATUS_SYNCED, SYNC_OP_INSERT).await?;
+
+        // 更新缓存
+        self.update_cache(&written).await;
+        Ok(())
+    }
+
+    /// 以指定同步状态和操作写入索引条目（upsert），不更新缓存，返回写入后的数据库行
+    async fn write_entry(&self, entry: &IndexEntry, sync_status: &str, sync_op: &str) -> Result<Vec<Model>, DbErr> {
         let id = Self::generate_id(&entry.table_name, &entry.record_id);
         let now = chrono::Utc::now().to_rfc3339();
         let now_clone = now.clone();
 
         let active = ActiveModel {
-            id: ActiveValue::Set(id),
+            id: ActiveValue::Set(id.clone()),
             table_name: ActiveValue::Set(entry.table_name.clone()),
             record_id: ActiveValue::Set(entry.record_id.clone()),
             shard_id: ActiveValue::Set(entry.shard_id as i32),
@@ -227,50 +718,109 @@ impl GlobalIndex {
             index_value: ActiveValue::Set(entry.index_value.clone()),
             created_at: ActiveValue::Set(now_clone),
             updated_at: ActiveValue::Set(now),
-            sync_status: ActiveValue::Set(SYNC_STATUS_SYNCED.to_string()),
+            sync_status: ActiveValue::Set(sync_status.to_string()),
+            sync_op: ActiveValue::Set(sync_op.to_string()),
         };
 
-        Entity::insert(active).exec(&self.conn).await?;
+        self.upsert(vec![id], vec![active]).await
+    }
+
+    /// upsert 索引行并返回写入后的数据库行
+    ///
+    /// 冲突时保留已有行的创建时间，因此写入后的行从数据库取得：支持 `RETURNING` 的后端直接返回，
+    /// 否则按 ID 读回
+    async fn upsert(&self, ids: Vec<String>, models: Vec<ActiveModel>) -> Result<Vec<Model>, DbErr> {
+        if models.is_empty() {
+            return Ok(Vec::new());
+        }
 
-        // 更新缓存
-        self.update_cache(&entry).await;
+        let insert = Entity::insert_many(models).on_conflict(Self::upsert_on_conflict());
+        if self.conn.support_returning() {
+            return insert.exec_with_returning(&self.conn).await;
+        }
+        insert.exec(&self.conn).await?;
+        Entity::find().filter(Column::Id.is_in(ids)).all(&self.conn).await
+    }
+
+    /// 更新索引条目的同步状态及对应的操作
+    async fn set_sync_status(
+        &self,
+        table_name: &str,
+        record_id: &str,
+        sync_status: &str,
+        sync_op: &str,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::SyncStatus, Expr::value(sync_status))
+            .col_expr(Column::SyncOp, Expr::value(sync_op))
+            .col_expr(Column::UpdatedAt, Expr::value(chrono::Utc::now().to_rfc3339()))
+            .filter(Column::Id.eq(Self::generate_id(table_name, record_id)))
+            .exec(&self.conn)
+            .await?;
         Ok(())
     }
 
+    /// 按同步状态查询索引条目
+    pub async fn query_by_status(&self, sync_status: &str) -> Result<Vec<IndexEntry>, DbErr> {
+        let result = Entity::find()
+            .filter(Column::SyncStatus.eq(sync_status))
+            .order_by_asc(Column::UpdatedAt)
+            .all(&self.conn)
+            .await?;
+
+        Ok(result.iter().map(Self::to_entry).collect())
+    }
+
     /// 批量注册索引条目
     pub async fn register_entries(&self, entries: Vec<IndexEntry>) -> Result<(), DbErr> {
         let now = chrono::Utc::now().to_rfc3339();
         let sync_status = SYNC_STATUS_SYNCED.to_string();
         let now_clone = now.clone();
 
+        let ids: Vec<String> = entries
+            .iter()
+            .map(|entry| Self::generate_id(&entry.table_name, &entry.record_id))
+            .collect();
         let active_models: Vec<ActiveModel> = entries
             .iter()
-            .map(|entry| {
-                let id = Self::generate_id(&entry.table_name, &entry.record_id);
-                ActiveModel {
-                    id: ActiveValue::Set(id),
-                    table_name: ActiveValue::Set(entry.table_name.clone()),
-                    record_id: ActiveValue::Set(entry.record_id.clone()),
-                    shard_id: ActiveValue::Set(entry.shard_id as i32),
-                    index_key: ActiveValue::Set(entry.index_key.clone()),
-                    index_value: ActiveValue::Set(entry.index_value.clone()),
-                    created_at: ActiveValue::Set(now_clone.clone()),
-                    updated_at: ActiveValue::Set(now.clone()),
-                    sync_status: ActiveValue::Set(sync_status.clone()),
-                }
+            .zip(&ids)
+            .map(|(entry, id)| ActiveModel {
+                id: ActiveValue::Set(id.clone()),
+                table_name: ActiveValue::Set(entry.table_name.clone()),
+                record_id: ActiveValue::Set(entry.record_id.clone()),
+                shard_id: ActiveValue::Set(entry.shard_id as i32),
+                index_key: ActiveValue::Set(entry.index_key.clone()),
+                index_value: ActiveValue::Set(entry.index_value.clone()),
+                created_at: ActiveValue::Set(now_clone.clone()),
+                updated_at: ActiveValue::Set(now.clone()),
+                sync_status: ActiveValue::Set(sync_status.clone()),
+                sync_op: ActiveValue::Set(SYNC_OP_INSERT.to_string()),
             })
             .collect();
 
-        Entity::insert_many(active_models).exec(&self.conn).await?;
+        let written = self.upsert(ids, active_models).await?;
 
         // 更新缓存
-        for entry in entries {
-            self.update_cache(&entry).await;
-        }
-
+        self.update_cache(&written).await;
         Ok(())
     }
 
+    /// `(table_name, record_id)` 冲突时的更新策略
+    ///
+    /// MySQL 使用 `ON DUPLICATE KEY UPDATE`，冲突列由唯一约束决定
+    fn upsert_on_conflict() -> OnConflict {
+        OnConflict::columns([Column::TableName, Column::RecordId])
+            .update_columns([
+                Column::ShardId,
+                Column::IndexKey,
+                Column::IndexValue,
+                Column::UpdatedAt,
+                Column::SyncStatus,
+                Column::SyncOp,
+            ])
+            .to_owned()
+    }
+
     /// 根据索引键查询
     pub async fn query_by_index(
         &self,
@@ -279,15 +829,8 @@ impl GlobalIndex {
         index_value: &str,
     ) -> Result<Vec<IndexEntry>, DbErr> {
         // 先查缓存
-        {
-            let cache = self.cache.read().await;
-            if let Some(table_cache) = cache.get(table_name) {
-                if let Some(key_cache) = table_cache.get(index_key) {
-                    if let Some(entries) = key_cache.get(index_value) {
-                        return Ok(entries.clone());
-                    }
-                }
-            }
+        if let Some(entries) = self.cache.write().await.get(table_name, index_key, index_value) {
+            return Ok(entries);
         }
 
         // 缓存未命中，从数据库查询
@@ -298,95 +841,293 @@ impl GlobalIndex {
             .all(&self.conn)
             .await?;
 
-        let entries: Vec<IndexEntry> = result
-            .iter()
-            .map(|m| IndexEntry {
-                table_name: m.table_name.clone(),
-                record_id: m.record_id.clone(),
-                shard_id: m.shard_id as u32,
-                index_key: m.index_key.clone(),
-                index_value: m.index_value.clone(),
-            })
-            .collect();
+        let entries: Vec<IndexEntry> = result.iter().map(Self::to_entry).collect();
 
         // 更新缓存
+        let mut cache = self.cache.write().await;
         for entry in &entries {
-            self.update_cache(entry).await;
+            cache.insert(entry);
         }
 
         Ok(entries)
     }
 
+    /// 按数值范围查询索引键
+    ///
+    /// 索引值以字符串存储，因此逐页读取该索引键的全部条目后解析比较，无法解析为数字的值被跳过；
+    /// 结果按数值升序排列，数值相同时按 `record_id` 排序
+    pub async fn query_by_index_range<R: RangeBounds<f64>>(
+        &self,
+        table_name: &str,
+        index_key: &str,
+        range: R,
+    ) -> Result<Vec<IndexEntry>, DbErr> {
+        let mut matched: Vec<(f64, IndexEntry)> = self
+            .query_all_shards_stream(table_name, index_key)
+            .try_filter_map(|entry| {
+                let value = entry.numeric_value().filter(|value| range.contains(value));
+                future::ready(Ok(value.map(|value| (value, entry))))
+            })
+            .try_collect()
+            .await?;
+
+        // 稳定排序，保留流中的 record_id 顺序
+        matched.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(matched.into_iter().map(|(_, entry)| entry).collect())
+    }
+
     /// 查询所有分片的记录
+    ///
+    /// 与 [`query_by_index`](Self::query_by_index) 共用缓存，首次查询后该索引键的全部条目会被缓存
     pub async fn query_all_shards(&self, table_name: &str, index_key: &str) -> Result<Vec<IndexEntry>, DbErr> {
+        if let Some(entries) = self.cache.write().await.get_all(table_name, index_key) {
+            return Ok(entries);
+        }
+
         let result = Entity::find()
             .filter(Column::TableName.eq(table_name))
             .filter(Column::IndexKey.eq(index_key))
+            .order_by_asc(Column::RecordId)
             .all(&self.conn)
             .await?;
 
-        Ok(result
-            .iter()
-            .map(|m| IndexEntry {
-                table_name: m.table_name.clone(),
-                record_id: m.record_id.clone(),
-                shard_id: m.shard_id as u32,
-                index_key: m.index_key.clone(),
-                index_value: m.index_value.clone(),
-            })
-            .collect())
+        let entries: Vec<IndexEntry> = result.iter().map(Self::to_entry).collect();
+        self.cache.write().await.fill_key(table_name, index_key, &entries);
+
+        Ok(entries)
+    }
+
+    /// 分页查询所有分片的记录
+    ///
+    /// 按 `record_id` 排序，`page` 从 0 开始；直接查询数据库，不读取也不填充缓存
+    pub async fn query_all_shards_paged(
+        &self,
+        table_name: &str,
+        index_key: &str,
+        page: u64,
+        per_page: u64,
+    ) -> Result<Vec<IndexEntry>, DbErr> {
+        let result = Entity::find()
+            .filter(Column::TableName.eq(table_name))
+            .filter(Column::IndexKey.eq(index_key))
+            .order_by_asc(Column::RecordId)
+            .offset(page.saturating_mul(per_page))
+            .limit(per_page)
+            .all(&self.conn)
+            .await?;
+
+        Ok(result.iter().map(Self::to_entry).collect())
+    }
+
+    /// 以流的方式查询所有分片的记录
+    ///
+    /// 按 `record_id` 顺序每次从数据库读取 [`STREAM_PAGE_SIZE`] 条，内存占用与结果总数无关；
+    /// 不读取也不填充缓存，需要缓存时使用 [`query_all_shards_stream_cached`](Self::query_all_shards_stream_cached)
+    pub fn query_all_shards_stream<'a>(
+        &'a self,
+        table_name: &'a str,
+        index_key: &'a str,
+    ) -> impl Stream<Item = Result<IndexEntry, DbErr>> + Send + 'a {
+        self.stream_all_shards(table_name, index_key, false)
+    }
+
+    /// 以流的方式查询所有分片的记录，读完后预热缓存
+    ///
+    /// 条目总数不超过缓存容量时，流结束后按 [`query_all_shards`](Self::query_all_shards) 的方式缓存该索引键；
+    /// 超过容量时停止收集，行为与 [`query_all_shards_stream`](Self::query_all_shards_stream) 相同
+    pub fn query_all_shards_stream_cached<'a>(
+        &'a self,
+        table_name: &'a str,
+        index_key: &'a str,
+    ) -> impl Stream<Item = Result<IndexEntry, DbErr>> + Send + 'a {
+        self.stream_all_shards(table_name, index_key, true)
+    }
+
+    /// 按 `record_id` 做键集分页，避免大偏移量的 OFFSET 扫描
+    fn stream_all_shards<'a>(
+        &'a self,
+        table_name: &'a str,
+        index_key: &'a str,
+        warm_cache: bool,
+    ) -> impl Stream<Item = Result<IndexEntry, DbErr>> + Send + 'a {
+        let capacity = self.config.cache_max_entries;
+        let warm = warm_cache.then(Vec::new);
+
+        stream::try_unfold(
+            (None::<String>, false, warm),
+            move |(after, done, mut warm): (Option<String>, bool, Option<Vec<IndexEntry>>)| async move {
+                if done {
+                    return Ok::<_, DbErr>(None);
+                }
+
+                let mut query = Entity::find()
+                    .filter(Column::TableName.eq(table_name))
+                    .filter(Column::IndexKey.eq(index_key));
+                if let Some(after) = &after {
+                    query = query.filter(Column::RecordId.gt(after.as_str()));
+                }
+                let result = query
+                    .order_by_asc(Column::RecordId)
+                    .limit(STREAM_PAGE_SIZE)
+                    .all(&self.conn)
+                    .await?;
+
+                let entries: Vec<IndexEntry> = result.iter().map(Self::to_entry).collect();
+                let done = (entries.len() as u64) < STREAM_PAGE_SIZE;
+
+                warm = warm.filter(|buffer| buffer.len() + entries.len() <= capacity);
+                if let Some(buffer) = warm.as_mut() {
+                    buffer.extend(entries.iter().cloned());
+                }
+                if done {
+                    if let Some(buffer) = warm.take() {
+                        self.cache.write().await.fill_key(table_name, index_key, &buffer);
+                    }
+                }
+
+                let after = entries.last().map(|entry| entry.record_id.clone()).or(after);
+                Ok(Some((stream::iter(entries.into_iter().map(Ok)), (after, done, warm))))
+            },
+        )
+        .try_flatten()
+    }
+
+    /// 将数据库模型转换为索引条目
+    fn to_entry(model: &Model) -> IndexEntry {
+        IndexEntry {
+            table_name: model.table_name.clone(),
+            record_id: model.record_id.clone(),
+            shard_id: model.shard_id as u32,
+            index_key: model.index_key.clone(),
+            index_value: model.index_value.clone(),
+            created_at: parse_timestamp(&model.created_at),
+            updated_at: parse_timestamp(&model.updated_at),
+        }
     }
 
     /// 处理同步事件
+    ///
+    /// 写入前将条目标记为 `pending` 并记录事件的操作；标记或同步失败时标记为 `failed` 并加入重试队列，
+    /// 不向调用方返回同步错误，可通过 [`retry_failed`](Self::retry_failed) 重试
     pub async fn process_sync_event(&self, event: SyncEvent) -> Result<(), DbErr> {
-        match event {
-            SyncEvent::Insert {
-                table_name,
-                record_id,
-                shard_id,
-                index_key,
-                index_value,
-            } => {
-                let entry = IndexEntry {
-                    table_name,
-                    record_id,
-                    shard_id,
-                    index_key,
-                    index_value,
-                };
-                self.register_entry(entry).await?;
+        let (table_name, record_id) = event.record();
+        let operation = event.operation();
+        let pending = match event.to_entry() {
+            Some(entry) => self.write_entry(&entry, SYNC_STATUS_PENDING, operation).await.map(drop),
+            None => {
+                self.set_sync_status(table_name, record_id, SYNC_STATUS_PENDING, operation)
+                    .await
             }
-            SyncEvent::Update {
-                table_name,
-                record_id,
-                shard_id,
-                old_index_key: _,
-                old_index_value: _,
-                new_index_key,
-                new_index_value,
-            } => {
-                // 删除旧索引
-                self.delete_entry(&table_name, &record_id).await?;
-
-                // 注册新索引
-                let entry = IndexEntry {
-                    table_name,
-                    record_id,
-                    shard_id,
-                    index_key: new_index_key,
-                    index_value: new_index_value,
-                };
-                self.register_entry(entry).await?;
+        };
+
+        let id = Self::generate_id(table_name, record_id);
+        let result = match pending {
+            Ok(()) => self.apply_sync_event(&event).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => {
+                self.retry_queue.lock().await.remove(&id);
             }
-            SyncEvent::Delete {
-                table_name, record_id, ..
-            } => {
-                self.delete_entry(&table_name, &record_id).await?;
+            Err(e) => {
+                tracing::warn!("Global index sync failed for {}/{}: {}", table_name, record_id, e);
+                if let Err(e) = self
+                    .set_sync_status(table_name, record_id, SYNC_STATUS_FAILED, operation)
+                    .await
+                {
+                    tracing::warn!(
+                        "Failed to mark global index entry {}/{} as failed: {}",
+                        table_name,
+                        record_id,
+                        e
+                    );
+                }
+                self.retry_queue
+                    .lock()
+                    .await
+                    .insert(id, RetryItem { event, attempts: 0 });
             }
         }
         Ok(())
     }
 
+    /// 重试同步失败的事件，最多处理 `max` 个，返回重试成功的数量
+    ///
+    /// 每个事件最多重试 `ChangeCaptureConfig::max_retries` 次。先处理标记为 `failed` 的条目，
+    /// 重试队列中没有对应事件（如进程重启后）时按条目记录的 `sync_op` 重建事件；
+    /// 再处理未能写入 `failed` 标记、只在重试队列中的事件
+    pub async fn retry_failed(&self, max: usize) -> Result<usize, DbErr> {
+        let failed = Entity::find()
+            .filter(Column::SyncStatus.eq(SYNC_STATUS_FAILED))
+            .order_by_asc(Column::UpdatedAt)
+            .all(&self.conn)
+            .await?;
+
+        let candidates: Vec<(String, SyncEvent, u32)> = {
+            let queue = self.retry_queue.lock().await;
+            let mut candidates: Vec<_> = failed
+                .iter()
+                .map(|model| match queue.get(&model.id) {
+                    Some(item) => (model.id.clone(), item.event.clone(), item.attempts),
+                    None => (model.id.clone(), SyncEvent::from_model(model), 0),
+                })
+                .collect();
+            candidates.extend(
+                queue
+                    .iter()
+                    .filter(|(id, _)| !failed.iter().any(|model| &model.id == *id))
+                    .map(|(id, item)| (id.clone(), item.event.clone(), item.attempts)),
+            );
+            candidates
+        };
+
+        let mut attempted = 0;
+        let mut synced = 0;
+        for (id, event, attempts) in candidates {
+            if attempted >= max {
+                break;
+            }
+            if attempts >= self.config.max_retries {
+                continue;
+            }
+
+            attempted += 1;
+            match self.apply_sync_event(&event).await {
+                Ok(()) => {
+                    self.retry_queue.lock().await.remove(&id);
+                    synced += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("Global index sync retry {} failed: {}", attempts + 1, e);
+                    self.retry_queue.lock().await.insert(
+                        id,
+                        RetryItem {
+                            event,
+                            attempts: attempts + 1,
+                        },
+                    );
+                }
+            }
+        }
+
+        Ok(synced)
+    }
+
+    /// 将同步事件写入全局索引
+    async fn apply_sync_event(&self, event: &SyncEvent) -> Result<(), DbErr> {
+        match event.to_entry() {
+            Some(entry) => {
+                let written = self.write_entry(&entry, SYNC_STATUS_SYNCED, event.operation()).await?;
+                self.update_cache(&written).await;
+                Ok(())
+            }
+            None => {
+                let (table_name, record_id) = event.record();
+                self.delete_entry(table_name, record_id).await
+            }
+        }
+    }
+
     /// 删除索引条目
     async fn delete_entry(&self, table_name: &str, record_id: &str) -> Result<(), DbErr> {
         let id = Self::generate_id(table_name, record_id);
@@ -394,15 +1135,7 @@ impl GlobalIndex {
         Entity::delete_by_id(id).exec(&self.conn).await?;
 
         // 从缓存中移除
-        let mut cache = self.cache.write().await;
-        if let Some(table_cache) = cache.get_mut(table_name) {
-            for key_cache in table_cache.values_mut() {
-                key_cache.retain(|_key, entries| {
-                    entries.retain(|e| e.record_id != record_id);
-                    !entries.is_empty()
-                });
-            }
-        }
+        self.cache.write().await.remove_record(table_name, record_id);
 
         Ok(())
     }
@@ -415,17 +1148,11 @@ impl GlobalIndex {
         format!("{:x}", hasher.finalize())
     }
 
-    /// 更新缓存
-    async fn update_cache(&self, entry: &IndexEntry) {
+    /// 将刚写入的数据库行放入缓存，缓存条目的时间戳与数据库一致
+    async fn update_cache(&self, written: &[Model]) {
         let mut cache = self.cache.write().await;
-        let table_cache = cache.entry(entry.table_name.clone()).or_insert_with(HashMap::new);
-        let key_cache = table_cache.entry(entry.index_key.clone()).or_insert_with(HashMap::new);
-
-        let entries = key_cache.entry(entry.index_value.clone()).or_insert_with(Vec::new);
-
-        // 检查是否已存在
-        if !entries.iter().any(|e| e.record_id == entry.record_id) {
-            entries.push(entry.clone());
+        for model in written {
+            cache.insert(&Self::to_entry(model));
         }
     }
 
@@ -436,8 +1163,16 @@ impl GlobalIndex {
 
     /// 设置配置
     pub fn set_config(&mut self, config: ChangeCaptureConfig) {
+        if let Ok(mut cache) = self.cache.try_write() {
+            cache.set_limits(config.cache_max_entries, config.cache_ttl());
+        }
         self.config = config;
     }
+
+    /// 获取当前缓存的条目数
+    pub async fn cached_entry_count(&self) -> usize {
+        self.cache.read().await.len
+    }
 }
 
 /// Binlog/CDC 变更捕获 trait
@@ -456,27 +1191,161 @@ pub trait ChangeCapture: Send + Sync {
     fn is_running(&self) -> bool;
 }
 
-/// 简单的轮询变更捕获实现
+/// 默认变更表（outbox）名称
+pub const DEFAULT_OUTBOX_TABLE: &str = "global_index_outbox";
+
+/// 基于变更表（outbox）的轮询变更捕获实现
+///
+/// 变更表需包含列：`id`（整数，自增）、`table_name`、`record_id`、`shard_id`、`op`
+/// （`insert` / `update` / `delete`）、`index_key`、`index_value`、`created_at`
+/// （UTC RFC3339 文本，如 `2024-01-01T00:00:00.000000Z`）以及 `processed`（整数，默认 0）。
+/// 每次轮询读取 `created_at` 不早于 `last_poll` 且未处理的行，转换为 [`SyncEvent`] 后标记为已处理。
 #[derive(Debug)]
 pub struct PollingChangeCapture {
+    /// 数据库连接
+    conn: DatabaseConnection,
+    /// 变更表名称
+    table: String,
     /// 轮询间隔
-    #[allow(dead_code)]
     interval_ms: u64,
+    /// 单次轮询最大行数
+    batch_size: usize,
     /// 运行状态
     running: bool,
     /// 最后轮询时间
     last_poll: RwLock<DateTime<Utc>>,
+    /// 已读取但尚未返回的事件
+    pending: VecDeque<SyncEvent>,
 }
 
 impl PollingChangeCapture {
     /// 创建新的轮询变更捕获
-    pub fn new(interval_ms: u64) -> Self {
+    pub fn new(conn: DatabaseConnection, interval_ms: u64) -> Self {
         Self {
+            conn,
+            table: DEFAULT_OUTBOX_TABLE.to_string(),
             interval_ms,
+            batch_size: ChangeCaptureConfig::default().batch_size,
             running: false,
             last_poll: RwLock::new(Utc::now()),
+            pending: VecDeque::new(),
         }
     }
+
+    /// 设置变更表名称
+    pub fn with_table(mut self, table: &str) -> Self {
+        self.table = table.to_string();
+        self
+    }
+
+    /// 设置单次轮询最大行数
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// 获取轮询间隔
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms)
+    }
+
+    /// 获取最后轮询时间
+    pub async fn last_poll(&self) -> DateTime<Utc> {
+        *self.last_poll.read().await
+    }
+
+    /// 查询变更表中的新行，转换为事件并标记为已处理
+    async fn poll(&mut self) -> Result<(), DbErr> {
+        let backend = self.conn.get_database_backend();
+        let placeholder = match backend {
+            sea_orm::DatabaseBackend::Postgres => "$1",
+            _ => "?",
+        };
+        let last_poll = *self.last_poll.read().await;
+
+        let sql = format!(
+            "SELECT id, table_name, record_id, shard_id, op, index_key, index_value, created_at FROM {} \
+             WHERE processed = 0 AND created_at >= {} ORDER BY created_at, id LIMIT {}",
+            self.table, placeholder, self.batch_size
+        );
+        let statement = Statement::from_sql_and_values(
+            backend,
+            sql,
+            [sea_orm::Value::from(
+                last_poll.to_rfc3339_opts(SecondsFormat::Micros, true),
+            )],
+        );
+        let rows = self.conn.query_all_raw(statement).await?;
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let mut ids = Vec::with_capacity(rows.len());
+        let mut newest = last_poll;
+        for row in &rows {
+            let id: i64 = row.try_get("", "id")?;
+            let created_at: String = row.try_get("", "created_at")?;
+            if let Ok(created_at) = DateTime::parse_from_rfc3339(&created_at) {
+                newest = newest.max(created_at.with_timezone(&Utc));
+            }
+            ids.push(id.to_string());
+
+            match Self::to_event(row)? {
+                Some(event) => self.pending.push_back(event),
+                None => tracing::warn!("Skipping outbox row {} with unknown op", id),
+            }
+        }
+
+        let update_sql = format!(
+            "UPDATE {} SET processed = 1 WHERE id IN ({})",
+            self.table,
+            ids.join(", ")
+        );
+        self.conn.execute_unprepared(&update_sql).await?;
+        *self.last_poll.write().await = newest;
+
+        Ok(())
+    }
+
+    /// 将变更表行转换为同步事件；未知操作类型返回 `None`
+    fn to_event(row: &QueryResult) -> Result<Option<SyncEvent>, DbErr> {
+        let table_name: String = row.try_get("", "table_name")?;
+        let record_id: String = row.try_get("", "record_id")?;
+        let shard_id: i32 = row.try_get("", "shard_id")?;
+        let op: String = row.try_get("", "op")?;
+        let index_key: String = row.try_get("", "index_key")?;
+        let index_value: String = row.try_get("", "index_value")?;
+        let shard_id = shard_id as u32;
+
+        let event = match op.to_lowercase().as_str() {
+            "insert" => SyncEvent::Insert {
+                table_name,
+                record_id,
+                shard_id,
+                index_key,
+                index_value,
+            },
+            // 变更表只记录新值，旧值与新值相同
+            "update" => SyncEvent::Update {
+                table_name,
+                record_id,
+                shard_id,
+                old_index_key: index_key.clone(),
+                old_index_value: index_value.clone(),
+                new_index_key: index_key,
+                new_index_value: index_value,
+            },
+            "delete" => SyncEvent::Delete {
+                table_name,
+                record_id,
+                shard_id,
+                index_key,
+                index_value,
+            },
+            _ => return Ok(None),
+        };
+        Ok(Some(event))
+    }
 }
 
 #[async_trait]
@@ -497,9 +1366,296 @@ impl ChangeCapture for PollingChangeCapture {
             return None;
         }
 
-        // 模拟轮询逻辑
-        // 实际实现中，这里会查询 binlog 或变更追踪表
-        None
+        if self.pending.is_empty() {
+            if let Err(e) = self.poll().await {
+                tracing::warn!("Failed to poll outbox table '{}': {}", self.table, e);
+                return None;
+            }
+        }
+
+        self.pending.pop_front()
+    }
+
+    fn is_running(&self) -> bool {
+        self.running
+    }
+}
+
+/// 默认的变更通知频道名称
+pub const DEFAULT_NOTIFY_CHANNEL: &str = "global_index_changes";
+
+/// 变更通知的 JSON 负载
+///
+/// ```json
+/// {"table": "orders", "record_id": "42", "shard_id": 3, "op": "update",
+///  "index_key": "user_id", "index_value": "user_b",
+///  "old_index_key": "user_id", "old_index_value": "user_a"}
+/// ```
+///
+/// `op` 取值为 `insert` / `update` / `delete`（不区分大小写）；
+/// `old_index_key` / `old_index_value` 仅对 `update` 有意义，缺省时与新值相同
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct NotifyPayload {
+    /// 表名
+    pub table: String,
+    /// 记录ID
+    pub record_id: String,
+    /// 分片ID
+    pub shard_id: u32,
+    /// 操作类型
+    pub op: String,
+    /// 索引键（更新事件为新索引键）
+    pub index_key: String,
+    /// 索引值（更新事件为新索引值）
+    pub index_value: String,
+    /// 旧索引键
+    #[serde(default)]
+    pub old_index_key: Option<String>,
+    /// 旧索引值
+    #[serde(default)]
+    pub old_index_value: Option<String>,
+}
+
+impl NotifyPayload {
+    /// 解析通知负载
+    ///
+    /// # Errors
+    ///
+    /// 负载不是合法 JSON 或缺少必填字段时返回 `DbErr::Json`
+    pub fn parse(payload: &str) -> Result<Self, DbErr> {
+        serde_json::from_str(payload).map_err(|e| DbErr::Json(format!("Invalid change notification payload: {}", e)))
+    }
+
+    /// 转换为同步事件；未知操作类型返回 `None`
+    pub fn into_event(self) -> Option<SyncEvent> {
+        let event = match self.op.to_lowercase().as_str() {
+            "insert" => SyncEvent::Insert {
+                table_name: self.table,
+                record_id: self.record_id,
+                shard_id: self.shard_id,
+                index_key: self.index_key,
+                index_value: self.index_value,
+            },
+            "update" => SyncEvent::Update {
+                table_name: self.table,
+                record_id: self.record_id,
+                shard_id: self.shard_id,
+                old_index_key: self.old_index_key.unwrap_or_else(|| self.index_key.clone()),
+                old_index_value: self.old_index_value.unwrap_or_else(|| self.index_value.clone()),
+                new_index_key: self.index_key,
+                new_index_value: self.index_value,
+            },
+            "delete" => SyncEvent::Delete {
+                table_name: self.table,
+                record_id: self.record_id,
+                shard_id: self.shard_id,
+                index_key: self.index_key,
+                index_value: self.index_value,
+            },
+            _ => return None,
+        };
+        Some(event)
+    }
+}
+
+/// 基于 PostgreSQL `LISTEN/NOTIFY` 的变更捕获实现
+///
+/// 订阅指定频道，将 [`NotifyPayload`] 格式的通知负载转换为 [`SyncEvent`]。
+/// 通知通常由分片表上的触发器发出：
+///
+/// ```sql
+/// PERFORM pg_notify('global_index_changes', json_build_object(
+///     'table', 'orders', 'record_id', NEW.id::text, 'shard_id', 0,
+///     'op', lower(TG_OP), 'index_key', 'user_id', 'index_value', NEW.user_id)::text);
+/// ```
+///
+/// 连接断开后自动重连并重新订阅，重连失败按退避策略等待后在下一次 `next_event` 时重试。
+/// 断线期间发出的通知不会被补发，需要可靠投递时配合 [`PollingChangeCapture`] 使用。
+#[cfg(feature = "postgres")]
+pub struct PostgresNotifyChangeCapture {
+    /// 数据库连接 URL
+    database_url: String,
+    /// 订阅频道
+    channel: String,
+    /// 当前监听连接，断开后为 `None`
+    listener: Option<sea_orm::sqlx::postgres::PgListener>,
+    /// 单次 `next_event` 等待通知的最长时间
+    wait_timeout: Duration,
+    /// 重连退避策略
+    reconnect_policy: RetryPolicy,
+    /// 连续重连失败次数
+    failed_reconnects: u32,
+    /// 累计重连次数
+    reconnects: u64,
+    /// 运行状态
+    running: bool,
+}
+
+#[cfg(feature = "postgres")]
+impl std::fmt::Debug for PostgresNotifyChangeCapture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PostgresNotifyChangeCapture")
+            .field("database_url", &crate::config::mask_url(&self.database_url))
+            .field("channel", &self.channel)
+            .field("connected", &self.listener.is_some())
+            .field("wait_timeout", &self.wait_timeout)
+            .field("reconnects", &self.reconnects)
+            .field("running", &self.running)
+            .finish()
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresNotifyChangeCapture {
+    /// 创建订阅默认频道的变更捕获，`start` 时才建立连接
+    pub fn new(database_url: &str) -> Self {
+        Self {
+            database_url: database_url.to_string(),
+            channel: DEFAULT_NOTIFY_CHANNEL.to_string(),
+            listener: None,
+            wait_timeout: Duration::from_millis(ChangeCaptureConfig::default().poll_interval_ms),
+            reconnect_policy: RetryPolicy::default()
+                .with_base_backoff(Duration::from_millis(100))
+                .with_max_backoff(Duration::from_secs(5)),
+            failed_reconnects: 0,
+            reconnects: 0,
+            running: false,
+        }
+    }
+
+    /// 设置订阅频道
+    pub fn with_channel(mut self, channel: &str) -> Self {
+        self.channel = channel.to_string();
+        self
+    }
+
+    /// 设置单次 `next_event` 等待通知的最长时间
+    pub fn with_wait_timeout(mut self, wait_timeout: Duration) -> Self {
+        self.wait_timeout = wait_timeout;
+        self
+    }
+
+    /// 设置重连退避策略（只使用其中的退避参数）
+    pub fn with_reconnect_policy(mut self, policy: RetryPolicy) -> Self {
+        self.reconnect_policy = policy;
+        self
+    }
+
+    /// 获取订阅频道
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    /// 累计重连次数（包括底层连接的自动重连）
+    pub fn reconnects(&self) -> u64 {
+        self.reconnects
+    }
+
+    /// 建立监听连接并订阅频道
+    async fn connect(&mut self) -> Result<(), DbErr> {
+        let to_db_err = |e: sea_orm::sqlx::Error| DbErr::Conn(sea_orm::RuntimeErr::Internal(e.to_string()));
+
+        let mut listener = sea_orm::sqlx::postgres::PgListener::connect(&self.database_url)
+            .await
+            .map_err(to_db_err)?;
+        listener.listen(&self.channel).await.map_err(to_db_err)?;
+        self.listener = Some(listener);
+        Ok(())
+    }
+
+    /// 监听连接断开后重新连接，失败时按退避策略等待
+    async fn reconnect(&mut self) -> bool {
+        match self.connect().await {
+            Ok(()) => {
+                self.failed_reconnects = 0;
+                self.reconnects += 1;
+                tracing::info!("Reconnected to notification channel '{}'", self.channel);
+                true
+            }
+            Err(e) => {
+                self.failed_reconnects = self.failed_reconnects.saturating_add(1);
+                let backoff = self.reconnect_policy.backoff(self.failed_reconnects);
+                tracing::warn!(
+                    "Failed to reconnect to notification channel '{}' (attempt {}): {}, retrying in {:?}",
+                    self.channel,
+                    self.failed_reconnects,
+                    e,
+                    backoff
+                );
+                tokio::time::sleep(backoff).await;
+                false
+            }
+        }
+    }
+}
+
+#[cfg(feature = "postgres")]
+#[async_trait]
+impl ChangeCapture for PostgresNotifyChangeCapture {
+    async fn start(&mut self) -> Result<(), DbErr> {
+        if self.listener.is_none() {
+            self.connect().await?;
+        }
+        self.failed_reconnects = 0;
+        self.running = true;
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<(), DbErr> {
+        self.running = false;
+        // 丢弃监听连接即取消订阅
+        self.listener = None;
+        Ok(())
+    }
+
+    /// 等待下一条通知，超过等待时间仍无通知时返回 `None`
+    ///
+    /// 无效负载与未知操作类型会被跳过并记录警告
+    async fn next_event(&mut self) -> Option<SyncEvent> {
+        if !self.running {
+            return None;
+        }
+
+        if self.listener.is_none() && !self.reconnect().await {
+            return None;
+        }
+
+        let deadline = Instant::now() + self.wait_timeout;
+        loop {
+            let listener = self.listener.as_mut()?;
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let received = match tokio::time::timeout(remaining, listener.try_recv()).await {
+                Ok(received) => received,
+                Err(_) => return None,
+            };
+
+            match received {
+                Ok(Some(notification)) => {
+                    match NotifyPayload::parse(notification.payload()).map(NotifyPayload::into_event) {
+                        Ok(Some(event)) => return Some(event),
+                        Ok(None) => tracing::warn!(
+                            "Skipping change notification with unknown op on '{}': {}",
+                            self.channel,
+                            notification.payload()
+                        ),
+                        Err(e) => tracing::warn!("Skipping change notification on '{}': {}", self.channel, e),
+                    }
+                }
+                // 连接断开：下一次 try_recv 会自动重连并重新订阅
+                Ok(None) => {
+                    self.reconnects += 1;
+                    tracing::warn!(
+                        "Lost connection to notification channel '{}', reconnecting",
+                        self.channel
+                    );
+                }
+                Err(e) => {
+                    tracing::warn!("Notification channel '{}' failed: {}", self.channel, e);
+                    self.listener = None;
+                    return None;
+                }
+            }
+        }
     }
 
     fn is_running(&self) -> bool {
@@ -527,16 +1683,59 @@ mod tests {
 
     #[test]
     fn test_index_entry() {
-        let entry = IndexEntry {
-            table_name: "orders".to_string(),
-            record_id: "order_123".to_string(),
-            shard_id: 4,
-            index_key: "user_id".to_string(),
-            index_value: "user_456".to_string(),
-        };
+        let entry = IndexEntry::new("orders", "order_123", 4, "user_id", "user_456");
 
         assert_eq!(entry.table_name, "orders");
         assert_eq!(entry.shard_id, 4);
+        assert_eq!(entry.created_at, None);
+        assert_eq!(entry.numeric_value(), None);
+        assert_eq!(
+            IndexEntry::new("orders", "order_1", 0, "amount", " 12.5 ").numeric_value(),
+            Some(12.5)
+        );
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        let rfc3339 = parse_timestamp("2025-01-02T03:04:05.5+08:00").unwrap();
+        assert_eq!(rfc3339.to_rfc3339(), "2025-01-01T19:04:05.500+00:00");
+
+        let sql = parse_timestamp("2025-01-02 03:04:05").unwrap();
+        assert_eq!(sql.to_rfc3339(), "2025-01-02T03:04:05+00:00");
+
+        assert_eq!(parse_timestamp("not a time"), None);
+    }
+
+    #[test]
+    fn test_notify_payload_into_event() {
+        let payload = NotifyPayload::parse(
+            r#"{"table":"orders","record_id":"42","shard_id":3,"op":"UPDATE","index_key":"user_id","index_value":"user_b","old_index_value":"user_a"}"#,
+        )
+        .unwrap();
+        match payload.into_event() {
+            Some(SyncEvent::Update {
+                old_index_key,
+                old_index_value,
+                new_index_value,
+                shard_id,
+                ..
+            }) => {
+                assert_eq!(old_index_key, "user_id");
+                assert_eq!(old_index_value, "user_a");
+                assert_eq!(new_index_value, "user_b");
+                assert_eq!(shard_id, 3);
+            }
+            other => panic!("Expected update event, got {:?}", other),
+        }
+
+        let unknown = NotifyPayload::parse(
+            r#"{"table":"orders","record_id":"1","shard_id":0,"op":"truncate","index_key":"k","index_value":"v"}"#,
+        )
+        .unwrap();
+        assert!(unknown.into_event().is_none());
+
+        assert!(NotifyPayload::parse(r#"{"table":"orders"}"#).is_err());
+        assert!(NotifyPayload::parse("not json").is_err());
     }
 
     #[test]
@@ -591,6 +1790,8 @@ mod tests {
         assert_eq!(config.poll_interval_ms, 1000);
         assert_eq!(config.max_retries, 3);
         assert_eq!(config.retry_interval_ms, 5000);
+        assert_eq!(config.cache_max_entries, 10_000);
+        assert_eq!(config.cache_ttl(), Duration::from_secs(300));
     }
 
     #[test]
@@ -88,7 +88,9 @@ pub mod migration;
 /// 权限控制模块
 pub mod permission;
 
-pub use permission::{PermissionAction, PermissionConfig, PermissionContext, RolePolicy, TablePermission};
+pub use permission::{
+    ColumnPolicy, PermissionAction, PermissionConfig, PermissionContext, PermissionError, RolePolicy, TablePermission,
+};
 /// Operation 是 PermissionAction 的别名，用于简化使用
 pub type Operation = permission::PermissionAction;
 /// 可插拔权限引擎模块
@@ -97,15 +99,21 @@ pub mod permission_engine;
 /// 权限引擎类型导出
 #[cfg(feature = "permission-engine")]
 pub use permission_engine::{
-    PermissionAction as EnginePermissionAction, PermissionContext as PermissionEngineContext, PermissionDecision,
-    PermissionEngine, PermissionEngineConfig, PermissionProvider, PermissionResource, PermissionRule,
-    PermissionSubject, PolicyDecisionPoint, RbacPermissionProvider, Role, YamlPermissionProvider,
+    DecisionLog, DecisionLogEntry, InMemoryDecisionLog, PermissionAction as EnginePermissionAction,
+    PermissionContext as PermissionEngineContext, PermissionDecision, PermissionEngine, PermissionEngineConfig,
+    PermissionEvaluation, PermissionProvider, PermissionResource, PermissionRule, PermissionSubject,
+    PolicyDecisionPoint, RbacPermissionProvider, Role, YamlPermissionProvider,
 };
 /// 连接池管理模块
 pub mod pool;
+/// 瞬时错误重试模块
+pub mod retry;
 /// 分片管理模块
 #[cfg(feature = "sharding")]
 pub mod sharding;
+/// 测试辅助模块（单连接内存连接池）
+#[cfg(all(feature = "test-util", feature = "sqlite"))]
+pub mod test_util;
 /// 分布式追踪模块
 #[cfg(feature = "tracing")]
 pub mod tracing;
@@ -118,7 +126,38 @@ pub use crate::entity::{
 pub use sea_orm as orm;
 
 pub use crate::pool::DbPool;
+pub use crate::pool::DbPoolBuilder;
+pub use crate::pool::DbTransaction;
 pub use crate::pool::Session;
+pub use crate::retry::RetryPolicy;
+
+/// `#[derive(DbEntity)]` 的展开目标：实现 [`migration::ToMigrationTable`]
+///
+/// 列以 `(列名, 列类型, 是否为 Option 字段, 是否主键)` 给出，列类型由派生宏按字段类型解析；
+/// 在 dbnexus 内判断 `migration` 特性是否开启
+#[doc(hidden)]
+#[cfg(feature = "migration")]
+#[macro_export]
+macro_rules! __impl_migration_table {
+    ($entity:ident, $table:expr, [$(($column:expr, $ty:expr, $nullable:expr, $primary_key:expr)),* $(,)?]) => {
+        impl $crate::migration::ToMigrationTable for $entity {
+            fn migration_table() -> $crate::migration::Table {
+                $crate::migration::Table::new(
+                    $table,
+                    ::std::vec![$($crate::migration::Column::typed($column, $ty, $primary_key, $nullable)),*],
+                )
+            }
+        }
+    };
+}
+
+/// 未开启 `migration` 特性时 `#[derive(DbEntity)]` 不生成实现
+#[doc(hidden)]
+#[cfg(not(feature = "migration"))]
+#[macro_export]
+macro_rules! __impl_migration_table {
+    ($($tokens:tt)*) => {};
+}
 
 /// 过程宏重新导出
 pub use dbnexus_macros::DbEntity;
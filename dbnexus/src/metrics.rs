@@ -11,6 +11,8 @@
 //! - **延迟分布**: 直方图统计
 //! - **连接指标**: 连接获取延迟、连接池使用率
 //! - **事务指标**: 事务持续时间、事务成功率
+//! - **错误分类**: 按查询类型和错误类别（超时、约束冲突、连接、其他）计数
+//! - **SLO**: 按查询类型配置延迟与错误率目标，计算达标比例与错误预算消耗速度
 
 use parking_lot::RwLock;
 use std::collections::HashMap;
@@ -18,6 +20,48 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
+/// 默认的延迟直方图桶边界（毫秒）
+pub const DEFAULT_HISTOGRAM_BUCKETS_MS: &[u64] = &[1, 5, 10, 25, 50, 100, 250, 500, 1000, 5000];
+
+/// Metrics 收集器配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricsConfig {
+    /// 查询延迟直方图的默认桶边界（毫秒）
+    pub histogram_buckets: Vec<u64>,
+    /// 按查询类型覆盖的桶边界，键与 [`MetricsCollector::record_query`] 的 `query_type` 一致
+    pub query_histogram_buckets: HashMap<String, Vec<u64>>,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            histogram_buckets: DEFAULT_HISTOGRAM_BUCKETS_MS.to_vec(),
+            query_histogram_buckets: HashMap::new(),
+        }
+    }
+}
+
+impl MetricsConfig {
+    /// 设置默认桶边界
+    pub fn with_histogram_buckets(mut self, buckets: Vec<u64>) -> Self {
+        self.histogram_buckets = buckets;
+        self
+    }
+
+    /// 为查询类型设置单独的桶边界
+    pub fn with_query_histogram_buckets(mut self, query_type: &str, buckets: Vec<u64>) -> Self {
+        self.query_histogram_buckets.insert(query_type.to_string(), buckets);
+        self
+    }
+
+    /// 查询类型使用的桶边界
+    pub fn histogram_buckets_for(&self, query_type: &str) -> &[u64] {
+        self.query_histogram_buckets
+            .get(query_type)
+            .unwrap_or(&self.histogram_buckets)
+    }
+}
+
 /// 延迟百分位数据
 #[derive(Debug, Clone, Default)]
 pub struct LatencyPercentiles {
@@ -99,8 +143,9 @@ impl LatencyHistogram {
     ///
     /// # Arguments
     ///
-    /// * `bucket_boundaries` - 桶边界定义（毫秒），如 [1, 5, 10, 50, 100, 500, 1000]
-    pub fn new(bucket_boundaries: Vec<u64>) -> Self {
+    /// * `bucket_boundaries` - 桶边界定义（毫秒），如 [1, 5, 10, 50, 100, 500, 1000]，未排序时自动排序
+    pub fn new(mut bucket_boundaries: Vec<u64>) -> Self {
+        bucket_boundaries.sort_unstable();
         let counts: Vec<_> = (0..bucket_boundaries.len() + 1).map(|_| AtomicU64::new(0)).collect();
 
         Self {
@@ -113,15 +158,8 @@ impl LatencyHistogram {
     /// 记录一次延迟
     pub fn record(&self, duration: Duration) {
         let latency_ms = duration.as_millis() as u64;
-        let mut bucket_idx = 0;
-
-        for (idx, boundary) in self.buckets.iter().enumerate() {
-            if latency_ms <= *boundary {
-                bucket_idx = idx;
-                break;
-            }
-            bucket_idx = idx + 1;
-        }
+        // 第一个满足 latency_ms <= boundary 的桶，都不满足时落入溢出桶
+        let bucket_idx = self.buckets.partition_point(|boundary| *boundary < latency_ms);
 
         self.counts[bucket_idx].fetch_add(1, Ordering::SeqCst);
         self.total.fetch_add(1, Ordering::SeqCst);
@@ -207,6 +245,10 @@ pub struct ThroughputStats {
     pub avg_qps: f64,
     /// 窗口 QPS
     pub window_qps: f64,
+    /// 成功操作累计传输字节数
+    pub bytes_total: u64,
+    /// 平均每秒传输字节数
+    pub bytes_per_sec: f64,
 }
 
 /// 查询统计信息（增强版）
@@ -255,6 +297,61 @@ pub struct SlowQueryRecord {
     pub timestamp: time::OffsetDateTime,
 }
 
+/// 单个查询类型的服务等级目标（SLO）
+///
+/// 如 "99% 的 SELECT 在 50ms 内完成" 对应 `latency_threshold_ms = 50`、`target = 0.99`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SloConfig {
+    /// 延迟目标（毫秒），不超过该值的请求视为达标
+    ///
+    /// 达标数由直方图按桶统计，阈值应取直方图的桶边界，否则落在阈值所在桶的请求都视为未达标
+    pub latency_threshold_ms: u64,
+    /// 达标比例目标，取值 (0, 1)
+    pub target: f64,
+    /// 允许的最大错误率，`None` 表示不设错误率目标
+    pub max_error_rate: Option<f64>,
+}
+
+impl SloConfig {
+    /// 创建延迟目标
+    pub fn new(latency_threshold_ms: u64, target: f64) -> Self {
+        Self {
+            latency_threshold_ms,
+            target,
+            max_error_rate: None,
+        }
+    }
+
+    /// 设置错误率目标
+    pub fn with_max_error_rate(mut self, max_error_rate: f64) -> Self {
+        self.max_error_rate = Some(max_error_rate);
+        self
+    }
+}
+
+/// SLO 达标状态，统计窗口为收集器启动（或重置）以来的全部请求
+#[derive(Debug, Clone, PartialEq)]
+pub struct SloStatus {
+    /// 查询类型
+    pub query_type: String,
+    /// SLO 配置
+    pub config: SloConfig,
+    /// 请求总数
+    pub total: u64,
+    /// 满足延迟目标的请求数
+    pub good: u64,
+    /// 满足延迟目标的比例，没有请求时为 1.0
+    pub compliance: f64,
+    /// 错误率
+    pub error_rate: f64,
+    /// 错误预算消耗速度：1.0 表示恰好用完预算，大于 1.0 表示预算将提前耗尽
+    ///
+    /// 取延迟目标与错误率目标两者中较大的消耗速度
+    pub burn_rate: f64,
+    /// 是否同时满足延迟与错误率目标
+    pub met: bool,
+}
+
 /// 连接获取统计
 #[derive(Debug, Clone)]
 pub struct ConnectionAcquireStats {
@@ -268,6 +365,10 @@ pub struct ConnectionAcquireStats {
     pub failure_count: u64,
     /// 超时率
     pub timeout_rate: f64,
+    /// 获取延迟百分位
+    pub latency_percentiles: LatencyPercentiles,
+    /// 获取延迟直方图
+    pub histogram: HistogramStats,
 }
 
 /// 事务统计
@@ -307,6 +408,225 @@ impl PoolMetrics {
     }
 }
 
+/// 查询错误类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QueryErrorKind {
+    /// 语句或连接获取超时
+    Timeout,
+    /// 唯一键、外键等约束冲突
+    ConstraintViolation,
+    /// 连接错误
+    Connection,
+    /// 其他错误
+    Other,
+}
+
+impl QueryErrorKind {
+    /// 全部错误类别
+    pub const ALL: [QueryErrorKind; 4] = [
+        QueryErrorKind::Timeout,
+        QueryErrorKind::ConstraintViolation,
+        QueryErrorKind::Connection,
+        QueryErrorKind::Other,
+    ];
+
+    /// Prometheus 标签值
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            QueryErrorKind::Timeout => "timeout",
+            QueryErrorKind::ConstraintViolation => "constraint_violation",
+            QueryErrorKind::Connection => "connection",
+            QueryErrorKind::Other => "other",
+        }
+    }
+
+    /// 根据 Sea-ORM 错误分类
+    pub fn from_db_err(err: &sea_orm::DbErr) -> Self {
+        use sea_orm::{ConnAcquireErr, DbErr};
+
+        match err {
+            DbErr::ConnectionAcquire(ConnAcquireErr::Timeout) => QueryErrorKind::Timeout,
+            DbErr::ConnectionAcquire(_) | DbErr::Conn(_) => QueryErrorKind::Connection,
+            _ if err.sql_err().is_some() => QueryErrorKind::ConstraintViolation,
+            // NOT NULL / CHECK 等约束没有对应的 SqlErr 变体，按错误信息兜底识别
+            DbErr::Exec(_) | DbErr::Query(_) if err.to_string().to_lowercase().contains("constraint") => {
+                QueryErrorKind::ConstraintViolation
+            }
+            _ => QueryErrorKind::Other,
+        }
+    }
+
+    /// 根据 DbNexus 错误分类
+    ///
+    /// 语句超时（`DbError::Transaction("query timeout")`）归为 `Timeout`
+    pub fn from_db_error(err: &crate::config::DbError) -> Self {
+        use crate::config::DbError;
+
+        match err {
+            DbError::Connection(db_err) => Self::from_db_err(db_err),
+            DbError::Transaction(message) if message.contains("timeout") => QueryErrorKind::Timeout,
+            _ => QueryErrorKind::Other,
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            QueryErrorKind::Timeout => 0,
+            QueryErrorKind::ConstraintViolation => 1,
+            QueryErrorKind::Connection => 2,
+            QueryErrorKind::Other => 3,
+        }
+    }
+}
+
+impl std::fmt::Display for QueryErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_label())
+    }
+}
+
+/// 语句类别，用作查询指标的 `query_type`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QueryKind {
+    /// 查询（包括 `WITH ... SELECT`）
+    Select,
+    /// 插入
+    Insert,
+    /// 更新
+    Update,
+    /// 删除
+    Delete,
+    /// 建表、删表、修改表结构等
+    Ddl,
+    /// 其他语句（事务控制、PRAGMA、EXPLAIN 等）
+    Other,
+}
+
+impl QueryKind {
+    /// 作为 `query_type` 使用的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QueryKind::Select => "SELECT",
+            QueryKind::Insert => "INSERT",
+            QueryKind::Update => "UPDATE",
+            QueryKind::Delete => "DELETE",
+            QueryKind::Ddl => "DDL",
+            QueryKind::Other => "OTHER",
+        }
+    }
+}
+
+impl std::fmt::Display for QueryKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 按语句的首个关键字判断类别
+///
+/// 忽略大小写、前导空白与注释（`--`、`/* */`）；`WITH` 开头的语句取 CTE 定义之后的主语句类别，
+/// 如 `WITH x AS (...) SELECT` 为 `Select`，`WITH x AS (...) DELETE` 为 `Delete`
+pub fn classify_statement(sql: &str) -> QueryKind {
+    let mut words = TopLevelWords::new(sql);
+
+    let Some(first) = words.next() else {
+        return QueryKind::Other;
+    };
+
+    if first.eq_ignore_ascii_case("WITH") {
+        // CTE 定义位于括号内，顶层第一个语句关键字即主语句
+        return words
+            .map(keyword_kind)
+            .find(|kind| *kind != QueryKind::Other)
+            .unwrap_or(QueryKind::Other);
+    }
+
+    keyword_kind(first)
+}
+
+fn keyword_kind(word: &str) -> QueryKind {
+    match word.to_ascii_uppercase().as_str() {
+        "SELECT" | "VALUES" => QueryKind::Select,
+        "INSERT" | "REPLACE" => QueryKind::Insert,
+        "UPDATE" => QueryKind::Update,
+        "DELETE" => QueryKind::Delete,
+        "CREATE" | "DROP" | "ALTER" | "TRUNCATE" | "RENAME" => QueryKind::Ddl,
+        _ => QueryKind::Other,
+    }
+}
+
+/// 依次产出 SQL 中位于括号外的单词，跳过注释、字符串和带引号的标识符
+struct TopLevelWords<'a> {
+    sql: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> TopLevelWords<'a> {
+    fn new(sql: &'a str) -> Self {
+        Self { sql, pos: 0, depth: 0 }
+    }
+
+    /// 跳过到 `terminator` 之后（找不到时跳到末尾）
+    fn skip_past(&mut self, terminator: &str) {
+        self.pos = self.sql[self.pos..]
+            .find(terminator)
+            .map_or(self.sql.len(), |offset| self.pos + offset + terminator.len());
+    }
+}
+
+impl<'a> Iterator for TopLevelWords<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let sql = self.sql;
+        let bytes = sql.as_bytes();
+        while self.pos < bytes.len() {
+            let rest = &sql[self.pos..];
+            match bytes[self.pos] {
+                _ if rest.starts_with("--") => self.skip_past("\n"),
+                _ if rest.starts_with("/*") => {
+                    self.pos += 2;
+                    self.skip_past("*/");
+                }
+                quote @ (b'\'' | b'"' | b'`') => {
+                    // 成对的引号表示转义，视为两段相邻的引用即可
+                    let terminator = match quote {
+                        b'\'' => "'",
+                        b'"' => "\"",
+                        _ => "`",
+                    };
+                    self.pos += 1;
+                    self.skip_past(terminator);
+                }
+                b'(' => {
+                    self.depth += 1;
+                    self.pos += 1;
+                }
+                b')' => {
+                    self.depth = self.depth.saturating_sub(1);
+                    self.pos += 1;
+                }
+                byte if byte.is_ascii_alphabetic() || byte == b'_' => {
+                    let start = self.pos;
+                    while self.pos < bytes.len() && (bytes[self.pos].is_ascii_alphanumeric() || bytes[self.pos] == b'_')
+                    {
+                        self.pos += 1;
+                    }
+                    if self.depth == 0 {
+                        return Some(&sql[start..self.pos]);
+                    }
+                }
+                _ => {
+                    // 按字符前进，保证切片始终位于 UTF-8 字符边界
+                    self.pos += rest.chars().next().map_or(1, char::len_utf8);
+                }
+            }
+        }
+        None
+    }
+}
+
 /// 延迟样本存储（使用锁保护）
 #[derive(Debug)]
 struct LatencyStorage {
@@ -392,9 +712,16 @@ pub struct MetricsCollector {
     pub connection_errors: Arc<AtomicU64>,
     /// 查询错误计数
     pub query_errors: Arc<AtomicU64>,
+    /// 可重试错误触发的重试次数
+    pub query_retries: Arc<AtomicU64>,
 
     /// 连接获取指标
     connection_acquire: Arc<RwLock<ConnectionAcquireMetricsInner>>,
+    /// 已注册的缓存统计（名称, 统计）
+    #[cfg(feature = "cache")]
+    caches: Arc<RwLock<Vec<(String, crate::cache::CacheStats)>>>,
+    /// 子收集器，导出时与本收集器的指标合并
+    children: Arc<RwLock<Vec<Arc<MetricsCollector>>>>,
     /// 事务指标
     transaction: Arc<RwLock<TransactionMetricsInner>>,
 
@@ -404,9 +731,17 @@ pub struct MetricsCollector {
     slow_query_config: Arc<RwLock<SlowQueryConfig>>,
     /// 慢查询最大记录数
     max_slow_queries: usize,
+    /// 按查询类型配置的 SLO，与子收集器共享
+    slo_configs: Arc<RwLock<HashMap<String, SloConfig>>>,
 
     /// 启动时间
     start_time: Instant,
+
+    /// 附加到每行导出指标上的全局标签（如租户、数据库名）
+    labels: Arc<HashMap<String, String>>,
+
+    /// 收集器配置（直方图桶边界等），与子收集器共享
+    config: Arc<MetricsConfig>,
 }
 
 struct QueryMetricsInner {
@@ -418,12 +753,15 @@ struct QueryMetricsInner {
     throughput: ThroughputTrackerInner,
     /// 错误计数
     error_count: AtomicU64,
+    /// 按错误类别的计数（下标见 `QueryErrorKind::index`）
+    error_kinds: [AtomicU64; 4],
 }
 
 struct ThroughputTrackerInner {
     success_count: AtomicU64,
     failure_count: AtomicU64,
     bytes_total: AtomicU64,
+    /// 最近一次成功记录的时间（距收集器启动的毫秒数）
     last_record_time: AtomicU64,
 }
 
@@ -437,8 +775,8 @@ impl ThroughputTrackerInner {
         }
     }
 
-    fn record_success(&self, bytes: Option<u64>) {
-        let now = Instant::now().elapsed().as_secs();
+    fn record_success(&self, bytes: Option<u64>, start_time: Instant) {
+        let now = start_time.elapsed().as_millis() as u64;
         self.success_count.fetch_add(1, Ordering::SeqCst);
         self.last_record_time.store(now, Ordering::SeqCst);
         if let Some(b) = bytes {
@@ -454,10 +792,14 @@ impl ThroughputTrackerInner {
         let success = self.success_count.load(Ordering::SeqCst);
         let failure = self.failure_count.load(Ordering::SeqCst);
         let total = success + failure;
-        let avg_qps = if elapsed_secs > 0 {
-            total as f64 / elapsed_secs as f64
+        let bytes_total = self.bytes_total.load(Ordering::SeqCst);
+        let (avg_qps, bytes_per_sec) = if elapsed_secs > 0 {
+            (
+                total as f64 / elapsed_secs as f64,
+                bytes_total as f64 / elapsed_secs as f64,
+            )
         } else {
-            total as f64
+            (total as f64, bytes_total as f64)
         };
 
         ThroughputStats {
@@ -467,6 +809,8 @@ impl ThroughputTrackerInner {
             error_rate: if total > 0 { failure as f64 / total as f64 } else { 0.0 },
             avg_qps,
             window_qps: 0.0,
+            bytes_total,
+            bytes_per_sec,
         }
     }
 
@@ -480,6 +824,8 @@ struct ConnectionAcquireMetricsInner {
     success_count: AtomicU64,
     timeout_count: AtomicU64,
     failure_count: AtomicU64,
+    latency: LatencyStorage,
+    histogram: LatencyHistogram,
 }
 
 impl ConnectionAcquireMetricsInner {
@@ -489,9 +835,16 @@ impl ConnectionAcquireMetricsInner {
             success_count: AtomicU64::new(0),
             timeout_count: AtomicU64::new(0),
             failure_count: AtomicU64::new(0),
+            latency: LatencyStorage::new(),
+            histogram: LatencyHistogram::new(DEFAULT_HISTOGRAM_BUCKETS_MS.to_vec()),
         }
     }
 
+    fn record_latency(&mut self, duration: Duration) {
+        self.latency.record(duration.as_nanos() as u64);
+        self.histogram.record(duration);
+    }
+
     fn record_success(&self) {
         self.total_attempts.fetch_add(1, Ordering::SeqCst);
         self.success_count.fetch_add(1, Ordering::SeqCst);
@@ -519,6 +872,8 @@ impl ConnectionAcquireMetricsInner {
             } else {
                 0.0
             },
+            latency_percentiles: self.latency.percentiles(),
+            histogram: self.histogram.stats(),
         }
     }
 }
@@ -587,7 +942,11 @@ impl MetricsCollector {
             pool_idle: Arc::new(AtomicU64::new(0)),
             connection_errors: Arc::new(AtomicU64::new(0)),
             query_errors: Arc::new(AtomicU64::new(0)),
+            query_retries: Arc::new(AtomicU64::new(0)),
             connection_acquire: Arc::new(RwLock::new(ConnectionAcquireMetricsInner::new())),
+            #[cfg(feature = "cache")]
+            caches: Arc::new(RwLock::new(Vec::new())),
+            children: Arc::new(RwLock::new(Vec::new())),
             transaction: Arc::new(RwLock::new(TransactionMetricsInner::new())),
             slow_queries: Arc::new(RwLock::new(Vec::new())),
             slow_query_config: Arc::new(RwLock::new(SlowQueryConfig {
@@ -595,31 +954,78 @@ impl MetricsCollector {
                 enabled: true,
             })),
             max_slow_queries: 100,
+            slo_configs: Arc::new(RwLock::new(HashMap::new())),
             start_time: Instant::now(),
+            labels: Arc::new(HashMap::new()),
+            config: Arc::new(MetricsConfig::default()),
         }
     }
 
+    /// 设置全局标签，导出时附加到每一行指标
+    ///
+    /// ```rust,ignore
+    /// let collector = MetricsCollector::new()
+    ///     .with_labels(HashMap::from([("tenant".to_string(), "acme".to_string())]));
+    /// // dbnexus_queries_total{type="select",tenant="acme"} 1
+    /// ```
+    pub fn with_labels(mut self, labels: HashMap<String, String>) -> Self {
+        self.labels = Arc::new(labels);
+        self
+    }
+
+    /// 设置收集器配置
+    ///
+    /// 直方图桶边界在查询类型首次被记录时确定，已存在的查询类型不受影响
+    pub fn with_config(mut self, config: MetricsConfig) -> Self {
+        self.config = Arc::new(config);
+        self
+    }
+
+    /// 获取收集器配置
+    pub fn config(&self) -> &MetricsConfig {
+        &self.config
+    }
+
+    /// 获取全局标签
+    pub fn labels(&self) -> &HashMap<String, String> {
+        &self.labels
+    }
+
+    /// 创建子收集器
+    ///
+    /// 子收集器独立计数，全局标签为本收集器的全局标签加上 `labels`（同名键以 `labels` 为准）。
+    /// 本收集器导出时会合并所有子收集器的指标，如分片连接池为每个分片创建一个带 `shard` 标签的子收集器。
+    pub fn child_with_labels(&self, labels: HashMap<String, String>) -> Arc<MetricsCollector> {
+        let child = self.detached_child(labels);
+        self.register_child(child.clone());
+        child
+    }
+
+    /// 创建尚未登记到本收集器的子收集器
+    ///
+    /// 用于子收集器的使用方可能创建失败的场景：成功后再通过 [`register_child`](Self::register_child) 登记，
+    /// 避免失败时在导出中留下无主的子收集器
+    pub(crate) fn detached_child(&self, labels: HashMap<String, String>) -> Arc<MetricsCollector> {
+        let mut merged = (*self.labels).clone();
+        merged.extend(labels);
+
+        let mut child = MetricsCollector::new().with_labels(merged);
+        child.slo_configs = self.slo_configs.clone();
+        child.config = self.config.clone();
+        Arc::new(child)
+    }
+
+    /// 登记子收集器，之后导出时合并其指标
+    pub(crate) fn register_child(&self, child: Arc<MetricsCollector>) {
+        self.children.write().push(child);
+    }
+
     /// 记录一次查询
     pub fn record_query(&self, query_type: &str, duration: Duration, success: bool, bytes: Option<u64>) {
         let latency_ns = duration.as_nanos() as u64;
         let duration_ms = duration.as_millis() as u64;
 
-        // 获取或创建指标
-        let metrics = {
-            let mut map = self.query_metrics.write();
-            if let Some(m) = map.get(query_type) {
-                m.clone()
-            } else {
-                let new_metrics = Arc::new(QueryMetricsInner {
-                    latency: RwLock::new(LatencyStorage::new()),
-                    histogram: LatencyHistogram::new(vec![1, 5, 10, 25, 50, 100, 250, 500, 1000, 5000]),
-                    throughput: ThroughputTrackerInner::new(),
-                    error_count: AtomicU64::new(0),
-                });
-                map.insert(query_type.to_string(), new_metrics.clone());
-                new_metrics
-            }
-        };
+        let metrics = self.query_metrics_for(query_type);
 
         // 记录延迟
         metrics.latency.write().record(latency_ns);
@@ -627,7 +1033,7 @@ impl MetricsCollector {
 
         // 记录吞吐量
         if success {
-            metrics.throughput.record_success(bytes);
+            metrics.throughput.record_success(bytes, self.start_time);
         } else {
             metrics.throughput.record_failure();
             metrics.error_count.fetch_add(1, Ordering::SeqCst);
@@ -649,6 +1055,40 @@ impl MetricsCollector {
         }
     }
 
+    /// 获取或创建指定查询类型的指标
+    fn query_metrics_for(&self, query_type: &str) -> Arc<QueryMetricsInner> {
+        let mut map = self.query_metrics.write();
+        if let Some(m) = map.get(query_type) {
+            m.clone()
+        } else {
+            let new_metrics = Arc::new(QueryMetricsInner {
+                latency: RwLock::new(LatencyStorage::new()),
+                histogram: LatencyHistogram::new(self.config.histogram_buckets_for(query_type).to_vec()),
+                throughput: ThroughputTrackerInner::new(),
+                error_count: AtomicU64::new(0),
+                error_kinds: Default::default(),
+            });
+            map.insert(query_type.to_string(), new_metrics.clone());
+            new_metrics
+        }
+    }
+
+    /// 按错误类别记录一次查询错误
+    ///
+    /// 只更新分类计数；失败查询的总数和延迟仍由 [`record_query`](Self::record_query) 记录
+    pub fn record_query_error(&self, query_type: &str, kind: QueryErrorKind) {
+        self.query_metrics_for(query_type).error_kinds[kind.index()].fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// 获取指定查询类型和错误类别的错误数
+    pub fn query_error_count(&self, query_type: &str, kind: QueryErrorKind) -> u64 {
+        self.query_metrics
+            .read()
+            .get(query_type)
+            .map(|m| m.error_kinds[kind.index()].load(Ordering::SeqCst))
+            .unwrap_or(0)
+    }
+
     /// 获取查询类型统计
     pub fn get_query_stats(&self, query_type: &str) -> Option<QueryStats> {
         let map = self.query_metrics.read();
@@ -703,6 +1143,8 @@ impl MetricsCollector {
             error_rate: 0.0,
             avg_qps: 0.0,
             window_qps: 0.0,
+            bytes_total: 0,
+            bytes_per_sec: 0.0,
         };
 
         for (_, m) in map.iter() {
@@ -711,6 +1153,8 @@ impl MetricsCollector {
             total.success_count += throughput.success_count;
             total.failure_count += throughput.failure_count;
             total.avg_qps += throughput.avg_qps;
+            total.bytes_total += throughput.bytes_total;
+            total.bytes_per_sec += throughput.bytes_per_sec;
         }
 
         if total.total_operations > 0 {
@@ -720,6 +1164,44 @@ impl MetricsCollector {
         total
     }
 
+    /// 为查询类型设置 SLO，`query_type` 与 [`record_query`](Self::record_query) 的参数一致
+    ///
+    /// 子收集器共享同一份 SLO 配置
+    pub fn set_slo(&self, query_type: &str, config: SloConfig) {
+        self.slo_configs.write().insert(query_type.to_string(), config);
+    }
+
+    /// 移除查询类型的 SLO
+    pub fn remove_slo(&self, query_type: &str) {
+        self.slo_configs.write().remove(query_type);
+    }
+
+    /// 所有已配置 SLO 的达标状态，按查询类型排序
+    pub fn slo_status(&self) -> Vec<SloStatus> {
+        let configs = self.slo_configs.read();
+        let mut statuses: Vec<SloStatus> = configs
+            .iter()
+            .map(|(query_type, config)| {
+                let (total, good, error_rate) = match self.get_query_stats(query_type) {
+                    Some(stats) => {
+                        let good = stats
+                            .histogram
+                            .buckets
+                            .iter()
+                            .filter(|bucket| bucket.boundary_ms <= config.latency_threshold_ms)
+                            .map(|bucket| bucket.count)
+                            .sum();
+                        (stats.histogram.total_samples, good, stats.throughput.error_rate)
+                    }
+                    None => (0, 0, 0.0),
+                };
+                slo_status(query_type, config, total, good, error_rate)
+            })
+            .collect();
+        statuses.sort_by(|a, b| a.query_type.cmp(&b.query_type));
+        statuses
+    }
+
     /// 获取慢查询记录
     pub fn slow_queries(&self) -> Vec<SlowQueryRecord> {
         self.slow_queries.read().clone()
@@ -742,6 +1224,27 @@ impl MetricsCollector {
         self.connection_errors.fetch_add(1, Ordering::SeqCst);
     }
 
+    /// 注册缓存统计，导出时以 `cache` 标签区分；同名缓存重复注册时替换旧的统计
+    #[cfg(feature = "cache")]
+    pub fn register_cache(&self, name: impl Into<String>, stats: crate::cache::CacheStats) {
+        let name = name.into();
+        let mut caches = self.caches.write();
+        match caches.iter_mut().find(|(existing, _)| *existing == name) {
+            Some(entry) => entry.1 = stats,
+            None => caches.push((name, stats)),
+        }
+    }
+
+    /// 记录一次查询重试
+    pub fn record_query_retry(&self) {
+        self.query_retries.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// 获取查询重试次数
+    pub fn query_retry_count(&self) -> u64 {
+        self.query_retries.load(Ordering::SeqCst)
+    }
+
     /// 更新连接池状态
     pub fn update_pool_status(&self, total: u32, active: u32, idle: u32) {
         self.pool_total.store(total as u64, Ordering::SeqCst);
@@ -773,6 +1276,11 @@ impl MetricsCollector {
         self.connection_acquire.write().record_failure();
     }
 
+    /// 记录连接获取耗时（无论成功与否）
+    pub fn record_connection_acquire_latency(&self, duration: Duration) {
+        self.connection_acquire.write().record_latency(duration);
+    }
+
     /// 获取连接获取统计
     pub fn connection_acquire_stats(&self) -> ConnectionAcquireStats {
         self.connection_acquire.read().stats()
@@ -810,6 +1318,7 @@ impl MetricsCollector {
         self.pool_idle.store(0, Ordering::SeqCst);
         self.connection_errors.store(0, Ordering::SeqCst);
         self.query_errors.store(0, Ordering::SeqCst);
+        self.query_retries.store(0, Ordering::SeqCst);
 
         let mut map = self.query_metrics.write();
         for metrics in map.values() {
@@ -829,136 +1338,582 @@ impl MetricsCollector {
     }
 
     /// 导出为 Prometheus 格式
+    ///
+    /// 通过 [`with_labels`](Self::with_labels) 设置的全局标签会附加到每一行指标上；
+    /// 本收集器与子收集器的样本按指标名合并，每个指标名的 `# HELP` / `# TYPE` 行只出现一次
     pub fn export_prometheus(&self) -> String {
+        self.prometheus_exposition().render()
+    }
+
+    /// 导出为 HTTP 响应所需的 `(Content-Type, body)`
+    ///
+    /// ```rust,ignore
+    /// // axum
+    /// async fn metrics(State(collector): State<Arc<MetricsCollector>>) -> impl IntoResponse {
+    ///     let (content_type, body) = collector.prometheus_response();
+    ///     ([(header::CONTENT_TYPE, content_type)], body)
+    /// }
+    ///
+    /// // warp
+    /// warp::path("metrics").map(move || {
+    ///     let (content_type, body) = collector.prometheus_response();
+    ///     warp::reply::with_header(body, "content-type", content_type)
+    /// });
+    /// ```
+    pub fn prometheus_response(&self) -> (&'static str, String) {
+        (PrometheusExposition::CONTENT_TYPE, self.export_prometheus())
+    }
+
+    /// 收集本收集器及所有子收集器的样本
+    fn prometheus_exposition(&self) -> PrometheusExposition {
+        let mut exposition = PrometheusExposition::new();
+        exposition.extend_text(&self.export_own_prometheus());
+        for child in self.children.read().iter() {
+            exposition.extend_text(&child.export_prometheus());
+        }
+        exposition
+    }
+
+    /// 导出本收集器自身的指标（不含子收集器）
+    ///
+    /// 每个指标名都有各自的 `# HELP` / `# TYPE` 头部，紧跟其全部样本
+    fn export_own_prometheus(&self) -> String {
         let mut output = String::new();
         let now = time::OffsetDateTime::now_utc();
+        let base = self.label_set(&[]);
 
-        let uptime_seconds = self.uptime().as_secs_f64();
-        output.push_str("# TYPE dbnexus_uptime gauge\n");
-        output.push_str(&format!("dbnexus_uptime_seconds {:.3}\n", uptime_seconds));
+        push_metric(
+            &mut output,
+            "dbnexus_uptime_seconds",
+            "gauge",
+            "Seconds since the metrics collector was created",
+            &base,
+            format!("{:.3}", self.uptime().as_secs_f64()),
+        );
 
         // 连接池指标
-        output.push_str("# TYPE dbnexus_pool_connections gauge\n");
-        output.push_str(&format!(
-            "dbnexus_pool_connections_total {}\n",
-            self.pool_total.load(Ordering::SeqCst)
-        ));
-        output.push_str(&format!(
-            "dbnexus_pool_connections_active {}\n",
-            self.pool_active.load(Ordering::SeqCst)
-        ));
-        output.push_str(&format!(
-            "dbnexus_pool_connections_idle {}\n",
-            self.pool_idle.load(Ordering::SeqCst)
-        ));
-        output.push_str(&format!(
-            "dbnexus_pool_connections_utilization {:.4}\n",
-            self.pool_status().utilization_rate()
-        ));
+        let pool: [(&str, &str, String); 4] = [
+            (
+                "dbnexus_pool_connections_total",
+                "Total connections in the pool",
+                self.pool_total.load(Ordering::SeqCst).to_string(),
+            ),
+            (
+                "dbnexus_pool_connections_active",
+                "Connections currently in use",
+                self.pool_active.load(Ordering::SeqCst).to_string(),
+            ),
+            (
+                "dbnexus_pool_connections_idle",
+                "Idle connections in the pool",
+                self.pool_idle.load(Ordering::SeqCst).to_string(),
+            ),
+            (
+                "dbnexus_pool_connections_utilization",
+                "Ratio of active to total connections",
+                format!("{:.4}", self.pool_status().utilization_rate()),
+            ),
+        ];
+        for (name, help, value) in pool {
+            push_metric(&mut output, name, "gauge", help, &base, value);
+        }
 
         // 错误指标
-        output.push_str("# TYPE dbnexus_errors counter\n");
-        output.push_str(&format!(
-            "dbnexus_connection_errors_total {}\n",
-            self.connection_errors.load(Ordering::SeqCst)
-        ));
-        output.push_str(&format!(
-            "dbnexus_query_errors_total {}\n",
-            self.query_errors.load(Ordering::SeqCst)
-        ));
+        push_metric(
+            &mut output,
+            "dbnexus_connection_errors_total",
+            "counter",
+            "Total connection errors",
+            &base,
+            self.connection_errors.load(Ordering::SeqCst),
+        );
+        push_metric(
+            &mut output,
+            "dbnexus_query_retries_total",
+            "counter",
+            "Total query retries",
+            &base,
+            self.query_retries.load(Ordering::SeqCst),
+        );
 
         // 连接获取指标
         let acquire_stats = self.connection_acquire_stats();
-        output.push_str("# TYPE dbnexus_connection_acquire counter\n");
-        output.push_str(&format!(
-            "dbnexus_connection_acquire_total {}\n",
-            acquire_stats.total_attempts
-        ));
-        output.push_str(&format!(
-            "dbnexus_connection_acquire_timeout_total {}\n",
-            acquire_stats.timeout_count
-        ));
-        output.push_str(&format!(
-            "dbnexus_connection_acquire_failure_total {}\n",
-            acquire_stats.failure_count
-        ));
+        let acquire: [(&str, &str, u64); 3] = [
+            (
+                "dbnexus_connection_acquire_total",
+                "Total connection acquire attempts",
+                acquire_stats.total_attempts,
+            ),
+            (
+                "dbnexus_connection_acquire_timeout_total",
+                "Connection acquire attempts that timed out",
+                acquire_stats.timeout_count,
+            ),
+            (
+                "dbnexus_connection_acquire_failure_total",
+                "Connection acquire attempts that failed",
+                acquire_stats.failure_count,
+            ),
+        ];
+        for (name, help, value) in acquire {
+            push_metric(&mut output, name, "counter", help, &base, value);
+        }
+
+        // 连接获取延迟
+        let acquire_latency = &acquire_stats.latency_percentiles;
+        let acquire_latency: [(&str, &str, Duration); 4] = [
+            (
+                "dbnexus_connection_acquire_latency_p50_seconds",
+                "Median connection acquire latency",
+                acquire_latency.p50(),
+            ),
+            (
+                "dbnexus_connection_acquire_latency_p90_seconds",
+                "90th percentile connection acquire latency",
+                acquire_latency.p90(),
+            ),
+            (
+                "dbnexus_connection_acquire_latency_p99_seconds",
+                "99th percentile connection acquire latency",
+                acquire_latency.p99(),
+            ),
+            (
+                "dbnexus_connection_acquire_latency_max_seconds",
+                "Maximum connection acquire latency",
+                acquire_latency.max(),
+            ),
+        ];
+        for (name, help, latency) in acquire_latency {
+            push_metric(
+                &mut output,
+                name,
+                "gauge",
+                help,
+                &base,
+                format!("{:.6}", latency.as_secs_f64()),
+            );
+        }
 
         // 事务指标
         let txn_stats = self.transaction_stats();
-        output.push_str("# TYPE dbnexus_transactions counter\n");
-        output.push_str(&format!(
-            "dbnexus_transactions_total {}\n",
-            txn_stats.total_transactions
-        ));
-        output.push_str(&format!(
-            "dbnexus_transactions_commit_total {}\n",
-            txn_stats.commit_count
-        ));
-        output.push_str(&format!(
-            "dbnexus_transactions_rollback_total {}\n",
-            txn_stats.rollback_count
-        ));
-        output.push_str(&format!(
-            "dbnexus_transactions_failure_total {}\n",
-            txn_stats.failure_count
-        ));
-        output.push_str(&format!(
-            "dbnexus_transactions_success_rate {:.2}\n",
-            txn_stats.success_rate
-        ));
-
-        // 查询指标
-        let stats = self.all_query_stats();
-        for (query_type, stat) in stats {
+        let transactions: [(&str, &str, u64); 4] = [
+            (
+                "dbnexus_transactions_total",
+                "Total transactions",
+                txn_stats.total_transactions,
+            ),
+            (
+                "dbnexus_transactions_commit_total",
+                "Committed transactions",
+                txn_stats.commit_count,
+            ),
+            (
+                "dbnexus_transactions_rollback_total",
+                "Rolled back transactions",
+                txn_stats.rollback_count,
+            ),
+            (
+                "dbnexus_transactions_failure_total",
+                "Failed transactions",
+                txn_stats.failure_count,
+            ),
+        ];
+        for (name, help, value) in transactions {
+            push_metric(&mut output, name, "counter", help, &base, value);
+        }
+        push_metric(
+            &mut output,
+            "dbnexus_transactions_success_rate",
+            "gauge",
+            "Ratio of committed to total transactions",
+            &base,
+            format!("{:.2}", txn_stats.success_rate),
+        );
+
+        // 缓存指标
+        #[cfg(feature = "cache")]
+        self.export_cache_metrics(&mut output);
+
+        // 查询指标，每个指标名下依次输出各查询类型的样本
+        let mut stats: Vec<(String, QueryStats)> = self.all_query_stats().into_iter().collect();
+        stats.sort_by(|a, b| a.0.cmp(&b.0));
+
+        // 总数单独成指标，避免与按类型、类别拆分的样本同名而在 sum() 时重复计数
+        push_metric(
+            &mut output,
+            "dbnexus_query_failures_total",
+            "counter",
+            "Total failed queries across all query types",
+            &base,
+            self.query_errors.load(Ordering::SeqCst),
+        );
+        push_header(
+            &mut output,
+            "dbnexus_query_errors_total",
+            "counter",
+            "Failed queries by query type and error kind",
+        );
+        for (query_type, _) in &stats {
             let type_label = query_type.to_lowercase();
+            for kind in QueryErrorKind::ALL {
+                output.push_str(&format!(
+                    "dbnexus_query_errors_total{} {}\n",
+                    self.label_set(&[("type", &type_label), ("kind", kind.as_label())]),
+                    self.query_error_count(query_type, kind)
+                ));
+            }
+        }
 
-            output.push_str(&format!(
-                "# TYPE dbnexus_queries_total counter\ndbnexus_queries_total{{type=\"{}\"}} {}\n",
-                type_label, stat.count
-            ));
+        type QuerySample = fn(&QueryStats) -> String;
+        let query_metrics: [(&str, &str, &str, QuerySample); 8] = [
+            ("dbnexus_queries_total", "counter", "Total executed queries", |stat| {
+                stat.count.to_string()
+            }),
+            (
+                "dbnexus_query_throughput_qps",
+                "gauge",
+                "Average queries per second",
+                |stat| format!("{:.2}", stat.throughput.avg_qps),
+            ),
+            (
+                "dbnexus_query_throughput_bytes_per_second",
+                "gauge",
+                "Average bytes transferred per second",
+                |stat| format!("{:.2}", stat.throughput.bytes_per_sec),
+            ),
+            (
+                "dbnexus_query_bytes_total",
+                "counter",
+                "Total bytes transferred",
+                |stat| stat.throughput.bytes_total.to_string(),
+            ),
+            (
+                "dbnexus_query_latency_p50_seconds",
+                "gauge",
+                "Median query latency",
+                |stat| format!("{:.6}", stat.latency_percentiles.p50().as_secs_f64()),
+            ),
+            (
+                "dbnexus_query_latency_p90_seconds",
+                "gauge",
+                "90th percentile query latency",
+                |stat| format!("{:.6}", stat.latency_percentiles.p90().as_secs_f64()),
+            ),
+            (
+                "dbnexus_query_latency_p95_seconds",
+                "gauge",
+                "95th percentile query latency",
+                |stat| format!("{:.6}", stat.latency_percentiles.p95().as_secs_f64()),
+            ),
+            (
+                "dbnexus_query_latency_p99_seconds",
+                "gauge",
+                "99th percentile query latency",
+                |stat| format!("{:.6}", stat.latency_percentiles.p99().as_secs_f64()),
+            ),
+        ];
+        if !stats.is_empty() {
+            for (name, kind, help, value) in query_metrics {
+                push_header(&mut output, name, kind, help);
+                for (query_type, stat) in &stats {
+                    output.push_str(&format!(
+                        "{}{} {}\n",
+                        name,
+                        self.label_set(&[("type", &query_type.to_lowercase())]),
+                        value(stat)
+                    ));
+                }
+            }
+        }
 
-            output.push_str("# TYPE dbnexus_query_throughput gauge\n");
-            output.push_str(&format!(
-                "dbnexus_query_throughput_qps{{type=\"{}\"}} {:.2}\n",
-                type_label, stat.throughput.avg_qps
-            ));
+        // SLO 指标
+        let slo_statuses = self.slo_status();
+        if !slo_statuses.is_empty() {
+            push_header(
+                &mut output,
+                "dbnexus_query_slo_compliance",
+                "gauge",
+                "Ratio of queries within the SLO latency threshold",
+            );
+            for status in &slo_statuses {
+                output.push_str(&format!(
+                    "dbnexus_query_slo_compliance{} {:.6}\n",
+                    self.label_set(&[("type", &status.query_type.to_lowercase())]),
+                    status.compliance
+                ));
+            }
+            push_header(
+                &mut output,
+                "dbnexus_query_slo_burn_rate",
+                "gauge",
+                "Rate at which the SLO error budget is consumed",
+            );
+            for status in &slo_statuses {
+                output.push_str(&format!(
+                    "dbnexus_query_slo_burn_rate{} {:.6}\n",
+                    self.label_set(&[("type", &status.query_type.to_lowercase())]),
+                    status.burn_rate
+                ));
+            }
+        }
 
-            // 延迟百分位
-            output.push_str("# TYPE dbnexus_query_latency_seconds gauge\n");
-            output.push_str(&format!(
-                "dbnexus_query_latency_p50_seconds{{type=\"{}\"}} {:.6}\n",
-                type_label,
-                stat.latency_percentiles.p50().as_secs_f64()
-            ));
-            output.push_str(&format!(
-                "dbnexus_query_latency_p90_seconds{{type=\"{}\"}} {:.6}\n",
-                type_label,
-                stat.latency_percentiles.p90().as_secs_f64()
-            ));
+        // 总吞吐量
+        let total = self.total_throughput();
+        push_metric(
+            &mut output,
+            "dbnexus_total_qps",
+            "gauge",
+            "Average queries per second across all query types",
+            &base,
+            format!("{:.2}", total.avg_qps),
+        );
+        push_metric(
+            &mut output,
+            "dbnexus_total_operations",
+            "counter",
+            "Total queries across all query types",
+            &base,
+            total.total_operations,
+        );
+        push_metric(
+            &mut output,
+            "dbnexus_error_rate",
+            "gauge",
+            "Ratio of failed to total queries",
+            &base,
+            format!("{:.4}", total.error_rate),
+        );
+
+        push_metric(
+            &mut output,
+            "dbnexus_metrics_timestamp",
+            "gauge",
+            "Unix timestamp of this export",
+            &base,
+            now.unix_timestamp(),
+        );
+
+        output
+    }
+
+    /// 导出已注册缓存的指标，每个指标名只输出一次头部
+    #[cfg(feature = "cache")]
+    fn export_cache_metrics(&self, output: &mut String) {
+        let caches = self.caches.read();
+        if caches.is_empty() {
+            return;
+        }
+
+        type Counter = fn(&crate::cache::CacheStats) -> u64;
+        let counters: [(&str, &str, Counter); 5] = [
+            ("dbnexus_cache_hits_total", "Total cache hits", |stats| {
+                stats.hits.load(Ordering::Relaxed)
+            }),
+            ("dbnexus_cache_misses_total", "Total cache misses", |stats| {
+                stats.misses.load(Ordering::Relaxed)
+            }),
+            ("dbnexus_cache_sets_total", "Total cache writes", |stats| {
+                stats.sets.load(Ordering::Relaxed)
+            }),
+            ("dbnexus_cache_deletes_total", "Total cache deletions", |stats| {
+                stats.deletes.load(Ordering::Relaxed)
+            }),
+            (
+                "dbnexus_cache_expirations_total",
+                "Total expired cache entries",
+                |stats| stats.expirations.load(Ordering::Relaxed),
+            ),
+        ];
+        for (metric, help, value) in counters {
+            push_header(output, metric, "counter", help);
+            for (name, stats) in caches.iter() {
+                output.push_str(&format!(
+                    "{}{} {}\n",
+                    metric,
+                    self.label_set(&[("cache", name)]),
+                    value(stats)
+                ));
+            }
+        }
+
+        push_header(output, "dbnexus_cache_size", "gauge", "Current number of cache entries");
+        for (name, stats) in caches.iter() {
             output.push_str(&format!(
-                "dbnexus_query_latency_p95_seconds{{type=\"{}\"}} {:.6}\n",
-                type_label,
-                stat.latency_percentiles.p95().as_secs_f64()
+                "dbnexus_cache_size{} {}\n",
+                self.label_set(&[("cache", name)]),
+                stats.size()
             ));
+        }
+
+        push_header(
+            output,
+            "dbnexus_cache_hit_rate",
+            "gauge",
+            "Ratio of cache hits to lookups",
+        );
+        for (name, stats) in caches.iter() {
             output.push_str(&format!(
-                "dbnexus_query_latency_p99_seconds{{type=\"{}\"}} {:.6}\n",
-                type_label,
-                stat.latency_percentiles.p99().as_secs_f64()
+                "dbnexus_cache_hit_rate{} {:.4}\n",
+                self.label_set(&[("cache", name)]),
+                stats.hit_rate()
             ));
         }
+    }
 
-        // 总吞吐量
-        let total = self.total_throughput();
-        output.push_str("# TYPE dbnexus_total_throughput gauge\n");
-        output.push_str(&format!("dbnexus_total_qps {:.2}\n", total.avg_qps));
-        output.push_str(&format!("dbnexus_total_operations {}\n", total.total_operations));
-        output.push_str(&format!("dbnexus_error_rate {:.4}\n", total.error_rate));
+    /// 生成 Prometheus 标签集合：指标自身的标签在前，全局标签按键名排序在后
+    ///
+    /// 没有任何标签时返回空字符串
+    fn label_set(&self, pairs: &[(&str, &str)]) -> String {
+        let mut global: Vec<(&String, &String)> = self.labels.iter().collect();
+        global.sort();
+
+        let rendered: Vec<String> = pairs
+            .iter()
+            .map(|(key, value)| format!("{}=\"{}\"", key, escape_label_value(value)))
+            .chain(
+                global
+                    .into_iter()
+                    .filter(|(key, _)| !pairs.iter().any(|(own, _)| *own == key.as_str()))
+                    .map(|(key, value)| format!("{}=\"{}\"", key, escape_label_value(value))),
+            )
+            .collect();
+
+        if rendered.is_empty() {
+            String::new()
+        } else {
+            format!("{{{}}}", rendered.join(","))
+        }
+    }
+}
 
-        output.push_str("# TYPE dbnexus_metrics_timestamp gauge\n");
-        output.push_str(&format!("dbnexus_metrics_timestamp {}\n", now.unix_timestamp()));
+/// 写入指标的 `# HELP` / `# TYPE` 头部
+fn push_header(output: &mut String, name: &str, kind: &str, help: &str) {
+    output.push_str(&format!("# HELP {} {}\n# TYPE {} {}\n", name, help, name, kind));
+}
 
+/// 写入只有一个样本的指标及其头部
+fn push_metric(output: &mut String, name: &str, kind: &str, help: &str, labels: &str, value: impl std::fmt::Display) {
+    push_header(output, name, kind, help);
+    output.push_str(&format!("{}{} {}\n", name, labels, value));
+}
+
+/// 由请求计数计算 SLO 状态
+fn slo_status(query_type: &str, config: &SloConfig, total: u64, good: u64, error_rate: f64) -> SloStatus {
+    let compliance = if total > 0 { good as f64 / total as f64 } else { 1.0 };
+    let latency_budget = (1.0 - config.target).max(f64::EPSILON);
+    let mut burn_rate = (1.0 - compliance) / latency_budget;
+    let mut met = compliance >= config.target;
+
+    if let Some(max_error_rate) = config.max_error_rate {
+        burn_rate = burn_rate.max(error_rate / max_error_rate.max(f64::EPSILON));
+        met &= error_rate <= max_error_rate;
+    }
+
+    SloStatus {
+        query_type: query_type.to_string(),
+        config: config.clone(),
+        total,
+        good,
+        compliance,
+        error_rate,
+        burn_rate,
+        met,
+    }
+}
+
+/// 一个指标族的头部与样本
+#[derive(Debug, Clone, Default)]
+struct MetricFamily {
+    /// `# HELP` 行
+    help: Option<String>,
+    /// `# TYPE` 行
+    kind: Option<String>,
+    /// 样本行
+    samples: Vec<String>,
+}
+
+/// Prometheus 文本格式（0.0.4）的指标集合
+///
+/// 样本按指标族归并：同名指标族的 `# HELP` / `# TYPE` 行只保留首次出现的一份，
+/// 其后依次输出所有样本，指标族按首次出现的顺序排列
+#[derive(Debug, Clone, Default)]
+pub struct PrometheusExposition {
+    /// 指标族，名称为空的族收集出现在任何 TYPE 行之前的样本
+    families: Vec<MetricFamily>,
+    /// 指标族名到 `families` 下标
+    positions: HashMap<String, usize>,
+}
+
+impl PrometheusExposition {
+    /// Prometheus 文本格式的 Content-Type
+    pub const CONTENT_TYPE: &'static str = "text/plain; version=0.0.4; charset=utf-8";
+
+    /// 创建空集合
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 追加一段 Prometheus 文本
+    pub fn extend_text(&mut self, text: &str) {
+        let mut current: Option<usize> = None;
+        for line in text.lines() {
+            let header = line
+                .strip_prefix("# HELP ")
+                .map(|rest| (true, rest))
+                .or_else(|| line.strip_prefix("# TYPE ").map(|rest| (false, rest)));
+
+            match header {
+                Some((is_help, rest)) => {
+                    let name = rest.split_whitespace().next().unwrap_or_default();
+                    let position = self.family(name);
+                    let family = &mut self.families[position];
+                    let slot = if is_help { &mut family.help } else { &mut family.kind };
+                    slot.get_or_insert_with(|| line.to_string());
+                    current = Some(position);
+                }
+                None if line.trim().is_empty() => {}
+                None => {
+                    let position = match current {
+                        Some(position) => position,
+                        None => self.family(""),
+                    };
+                    self.families[position].samples.push(line.to_string());
+                }
+            }
+        }
+    }
+
+    /// 渲染为 Prometheus 文本
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        for family in &self.families {
+            for line in family
+                .help
+                .iter()
+                .chain(family.kind.iter())
+                .chain(family.samples.iter())
+            {
+                output.push_str(line);
+                output.push('\n');
+            }
+        }
         output
     }
+
+    /// 获取或创建指标族
+    fn family(&mut self, name: &str) -> usize {
+        if let Some(position) = self.positions.get(name) {
+            return *position;
+        }
+        self.families.push(MetricFamily::default());
+        self.positions.insert(name.to_string(), self.families.len() - 1);
+        self.families.len() - 1
+    }
+}
+
+/// 按 Prometheus 文本格式转义标签值（反斜杠、双引号、换行）
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
 }
 
 #[cfg(test)]
@@ -999,6 +1954,27 @@ mod tests {
         assert_eq!(stats.histogram.total_samples, 4);
     }
 
+    /// TEST-U-093: 二分查找的桶分配与线性扫描一致，边界值落入该边界的桶
+    #[test]
+    fn test_latency_histogram_bucket_lookup() {
+        let boundaries: Vec<u64> = (1..=500).map(|i| i * 3).collect();
+        let linear = |latency_ms: u64| {
+            boundaries
+                .iter()
+                .position(|boundary| latency_ms <= *boundary)
+                .unwrap_or(boundaries.len())
+        };
+
+        for latency_ms in 0..=1600 {
+            let histogram = LatencyHistogram::new(boundaries.clone());
+            histogram.record(Duration::from_millis(latency_ms));
+
+            let stats = histogram.stats();
+            let bucket = stats.buckets.iter().position(|b| b.count == 1).unwrap();
+            assert_eq!(bucket, linear(latency_ms), "latency {}ms", latency_ms);
+        }
+    }
+
     /// TEST-U-042: 吞吐量测试
     #[test]
     fn test_throughput() {
@@ -1075,6 +2051,263 @@ mod tests {
         assert!(prometheus.contains("dbnexus_total_qps"));
     }
 
+    /// TEST-U-094: 传输字节数累计到吞吐量统计并导出
+    #[test]
+    fn test_query_bytes_total() {
+        let collector = MetricsCollector::new();
+
+        collector.record_query("SELECT", Duration::from_millis(1), true, Some(1024));
+        collector.record_query("SELECT", Duration::from_millis(1), true, Some(4096));
+        collector.record_query("SELECT", Duration::from_millis(1), true, None);
+        collector.record_query("INSERT", Duration::from_millis(1), true, Some(10));
+
+        let stats = collector.get_query_stats("SELECT").unwrap();
+        assert_eq!(stats.throughput.bytes_total, 5120);
+        assert!(stats.throughput.bytes_per_sec > 0.0);
+        assert_eq!(collector.total_throughput().bytes_total, 5130);
+
+        let prometheus = collector.export_prometheus();
+        assert!(prometheus.contains("dbnexus_query_bytes_total{type=\"select\"} 5120"));
+        assert!(prometheus.contains("dbnexus_query_bytes_total{type=\"insert\"} 10"));
+        assert!(prometheus.contains("dbnexus_query_throughput_bytes_per_second{type=\"select\"}"));
+    }
+
+    /// TEST-U-095: 最近记录时间基于收集器启动时刻单调递增
+    #[test]
+    fn test_throughput_last_record_time_advances() {
+        let collector = MetricsCollector::new();
+
+        collector.record_query("SELECT", Duration::from_millis(1), true, None);
+        let first = collector
+            .query_metrics_for("SELECT")
+            .throughput
+            .last_record_time
+            .load(Ordering::SeqCst);
+
+        std::thread::sleep(Duration::from_millis(20));
+        collector.record_query("SELECT", Duration::from_millis(1), true, None);
+        let second = collector
+            .query_metrics_for("SELECT")
+            .throughput
+            .last_record_time
+            .load(Ordering::SeqCst);
+
+        assert!(second >= first + 20, "first = {}, second = {}", first, second);
+    }
+
+    /// TEST-U-096: 按直方图计算 SLO 达标比例与错误预算消耗速度
+    #[test]
+    fn test_slo_status() {
+        let collector = MetricsCollector::new();
+        collector.set_slo("SELECT", SloConfig::new(50, 0.9));
+        collector.set_slo("INSERT", SloConfig::new(10, 0.99).with_max_error_rate(0.01));
+
+        for _ in 0..10 {
+            collector.record_query("SELECT", Duration::from_millis(10), true, None);
+        }
+        for _ in 0..5 {
+            collector.record_query("SELECT", Duration::from_millis(50), true, None);
+        }
+        for _ in 0..5 {
+            collector.record_query("SELECT", Duration::from_millis(100), true, None);
+        }
+
+        let statuses = collector.slo_status();
+        assert_eq!(statuses.len(), 2);
+
+        // 没有请求时视为达标
+        let insert = &statuses[0];
+        assert_eq!(insert.query_type, "INSERT");
+        assert_eq!(insert.total, 0);
+        assert_eq!(insert.compliance, 1.0);
+        assert!(insert.met);
+
+        let select = &statuses[1];
+        assert_eq!((select.total, select.good), (20, 15));
+        assert!((select.compliance - 0.75).abs() < 1e-9);
+        assert!((select.burn_rate - 2.5).abs() < 1e-6);
+        assert!(!select.met);
+
+        // 错误率超过目标同样不达标
+        collector.record_query("INSERT", Duration::from_millis(1), true, None);
+        collector.record_query("INSERT", Duration::from_millis(1), false, None);
+        let insert = collector.slo_status().remove(0);
+        assert_eq!(insert.compliance, 1.0);
+        assert!(!insert.met);
+        assert!((insert.burn_rate - 50.0).abs() < 1e-6);
+
+        let prometheus = collector.export_prometheus();
+        assert!(prometheus.contains("dbnexus_query_slo_compliance{type=\"select\"} 0.750000"));
+        assert!(prometheus.contains("dbnexus_query_slo_burn_rate{type=\"select\"} 2.500000"));
+    }
+
+    /// TEST-U-097: 按查询类型配置直方图桶边界
+    #[test]
+    fn test_custom_histogram_buckets() {
+        let config = MetricsConfig::default()
+            .with_histogram_buckets(vec![10, 100])
+            .with_query_histogram_buckets("CACHE_GET", vec![1, 2, 3]);
+        let collector = MetricsCollector::new().with_config(config);
+
+        collector.record_query("CACHE_GET", Duration::from_millis(2), true, None);
+        collector.record_query("SELECT", Duration::from_millis(50), true, None);
+
+        let boundaries = |query_type: &str| -> Vec<u64> {
+            let stats = collector.get_query_stats(query_type).unwrap();
+            stats.histogram.buckets.iter().map(|b| b.boundary_ms).collect()
+        };
+        assert_eq!(boundaries("CACHE_GET"), vec![1, 2, 3, u64::MAX]);
+        assert_eq!(boundaries("SELECT"), vec![10, 100, u64::MAX]);
+
+        let cache_get = collector.get_query_stats("CACHE_GET").unwrap();
+        assert_eq!(cache_get.histogram.buckets[1].count, 1);
+
+        // 子收集器沿用同一配置
+        let child = collector.child_with_labels(HashMap::from([("shard".to_string(), "0".to_string())]));
+        child.record_query("CACHE_GET", Duration::from_millis(1), true, None);
+        let child_stats = child.get_query_stats("CACHE_GET").unwrap();
+        assert_eq!(child_stats.histogram.buckets.len(), 4);
+    }
+
+    /// TEST-U-098: 每个指标名只有一组 HELP / TYPE 头部，样本紧跟在所属头部之后，并提供 Content-Type
+    #[test]
+    fn test_prometheus_exposition_dedupes_families() {
+        let collector = MetricsCollector::new();
+        collector.record_query("SELECT", Duration::from_millis(1), true, Some(10));
+        collector.record_query("INSERT", Duration::from_millis(1), false, None);
+        collector.record_query_error("INSERT", QueryErrorKind::Timeout);
+        collector.record_query("UPDATE", Duration::from_millis(1), true, None);
+        collector.set_slo("SELECT", SloConfig::new(10, 0.99));
+        let child = collector.child_with_labels(HashMap::from([("shard".to_string(), "0".to_string())]));
+        child.record_query("SELECT", Duration::from_millis(1), true, None);
+
+        let (content_type, body) = collector.prometheus_response();
+        assert_eq!(content_type, "text/plain; version=0.0.4; charset=utf-8");
+
+        let mut seen = std::collections::HashSet::new();
+        let mut current: Option<&str> = None;
+        let mut helped: Option<&str> = None;
+        for line in body.lines() {
+            if let Some(rest) = line.strip_prefix("# HELP ") {
+                helped = rest.split_whitespace().next();
+            } else if let Some(rest) = line.strip_prefix("# TYPE ") {
+                let name = rest.split_whitespace().next().unwrap();
+                assert_eq!(helped, Some(name), "HELP line should precede TYPE line of {}", name);
+                assert!(seen.insert(name), "Duplicate TYPE line for {}", name);
+                current = Some(name);
+            } else {
+                let name = line.split(['{', ' ']).next().unwrap();
+                assert_eq!(
+                    current,
+                    Some(name),
+                    "Sample {} does not match the preceding TYPE line",
+                    line
+                );
+            }
+        }
+        for name in [
+            "dbnexus_uptime_seconds",
+            "dbnexus_connection_errors_total",
+            "dbnexus_query_errors_total",
+            "dbnexus_query_throughput_bytes_per_second",
+            "dbnexus_query_latency_p99_seconds",
+            "dbnexus_query_slo_compliance",
+        ] {
+            assert!(seen.contains(name), "Missing TYPE line for {}", name);
+        }
+        assert!(body.contains("# TYPE dbnexus_query_throughput_bytes_per_second gauge"));
+        assert_eq!(body.matches("dbnexus_queries_total{").count(), 4);
+
+        let mut exposition = PrometheusExposition::new();
+        exposition.extend_text("# HELP up Up\n# TYPE up gauge\nup 1\n");
+        exposition.extend_text("# HELP up Up\n# TYPE up gauge\nup{shard=\"1\"} 1\n");
+        assert_eq!(
+            exposition.render(),
+            "# HELP up Up\n# TYPE up gauge\nup 1\nup{shard=\"1\"} 1\n"
+        );
+    }
+
+    /// TEST-U-071: 错误分类与按类别导出
+    #[test]
+    fn test_query_error_kinds() {
+        use sea_orm::{ConnAcquireErr, DbErr, RuntimeErr};
+
+        assert_eq!(
+            QueryErrorKind::from_db_err(&DbErr::ConnectionAcquire(ConnAcquireErr::Timeout)),
+            QueryErrorKind::Timeout
+        );
+        assert_eq!(
+            QueryErrorKind::from_db_err(&DbErr::ConnectionAcquire(ConnAcquireErr::ConnectionClosed)),
+            QueryErrorKind::Connection
+        );
+        assert_eq!(
+            QueryErrorKind::from_db_err(&DbErr::Exec(RuntimeErr::Internal(
+                "NOT NULL constraint failed: users.name".to_string()
+            ))),
+            QueryErrorKind::ConstraintViolation
+        );
+        assert_eq!(
+            QueryErrorKind::from_db_err(&DbErr::Custom("boom".to_string())),
+            QueryErrorKind::Other
+        );
+        assert_eq!(
+            QueryErrorKind::from_db_error(&crate::config::DbError::Transaction("query timeout".to_string())),
+            QueryErrorKind::Timeout
+        );
+
+        let collector = MetricsCollector::new();
+        collector.record_query("INSERT", Duration::from_millis(5), false, None);
+        collector.record_query_error("INSERT", QueryErrorKind::ConstraintViolation);
+        collector.record_query_error("INSERT", QueryErrorKind::ConstraintViolation);
+        collector.record_query_error("INSERT", QueryErrorKind::Timeout);
+
+        assert_eq!(
+            collector.query_error_count("INSERT", QueryErrorKind::ConstraintViolation),
+            2
+        );
+        assert_eq!(collector.query_error_count("SELECT", QueryErrorKind::Other), 0);
+
+        let prometheus = collector.export_prometheus();
+        assert!(prometheus.contains("dbnexus_query_errors_total{type=\"insert\",kind=\"constraint_violation\"} 2"));
+        assert!(prometheus.contains("dbnexus_query_errors_total{type=\"insert\",kind=\"timeout\"} 1"));
+        assert!(prometheus.contains("dbnexus_query_errors_total{type=\"insert\",kind=\"connection\"} 0"));
+        // 总数只出现在 dbnexus_query_failures_total 中，dbnexus_query_errors_total 的样本都带类型和类别标签
+        assert!(prometheus.contains("\ndbnexus_query_failures_total 1\n"));
+        assert!(
+            prometheus
+                .lines()
+                .filter(|line| line.starts_with("dbnexus_query_errors_total"))
+                .all(|line| line.starts_with("dbnexus_query_errors_total{type="))
+        );
+    }
+
+    /// TEST-U-072: 全局标签附加到所有指标族并正确转义
+    #[test]
+    fn test_prometheus_export_with_labels() {
+        let collector = MetricsCollector::new().with_labels(HashMap::from([
+            ("tenant".to_string(), "acme".to_string()),
+            ("db".to_string(), "orders \"eu\"\\1".to_string()),
+        ]));
+
+        collector.update_pool_status(10, 4, 6);
+        collector.record_query("SELECT", Duration::from_millis(10), true, None);
+        collector.record_transaction_commit();
+
+        let prometheus = collector.export_prometheus();
+        let global = "db=\"orders \\\"eu\\\"\\\\1\",tenant=\"acme\"";
+
+        assert!(prometheus.contains(&format!("dbnexus_pool_connections_active{{{}}} 4", global)));
+        assert!(prometheus.contains(&format!("dbnexus_queries_total{{type=\"select\",{}}} 1", global)));
+        assert!(prometheus.contains(&format!("dbnexus_transactions_commit_total{{{}}} 1", global)));
+        assert!(
+            prometheus
+                .lines()
+                .filter(|line| !line.starts_with('#'))
+                .all(|line| line.contains("tenant=\"acme\"")),
+            "{prometheus}"
+        );
+    }
+
     /// TEST-U-046: 慢查询记录测试
     #[test]
     fn test_slow_query_recording() {
@@ -1088,4 +2321,121 @@ mod tests {
         assert_eq!(slow[0].query_type, "SELECT");
         assert_eq!(slow[0].duration_ms, 100);
     }
+
+    /// TEST-U-047: 连接获取延迟指标测试
+    #[test]
+    fn test_connection_acquire_latency() {
+        let collector = MetricsCollector::new();
+
+        for i in 1..=10 {
+            collector.record_connection_acquire_latency(Duration::from_millis(i));
+            collector.record_connection_acquire_success();
+        }
+
+        let stats = collector.connection_acquire_stats();
+        assert_eq!(stats.latency_percentiles.sample_count, 10);
+        assert_eq!(stats.latency_percentiles.min(), Duration::from_millis(1));
+        assert_eq!(stats.latency_percentiles.max(), Duration::from_millis(10));
+        assert!(stats.latency_percentiles.p50() >= Duration::from_millis(5));
+        assert_eq!(stats.histogram.total_samples, 10);
+
+        let prometheus = collector.export_prometheus();
+        assert!(prometheus.contains("dbnexus_connection_acquire_latency_p50_seconds"));
+    }
+
+    /// TEST-U-082: 缓存统计注册后出现在 Prometheus 导出中
+    #[cfg(feature = "cache")]
+    #[tokio::test]
+    async fn test_cache_metrics_in_prometheus_export() {
+        use crate::cache::{CacheConfig, CacheKey, CacheManager};
+
+        let collector = MetricsCollector::new();
+        let cache: CacheManager<String> = CacheManager::new(CacheConfig::default());
+        cache.register_metrics(&collector, "users");
+
+        cache.set(CacheKey::new("users", "1"), "alice".to_string()).await;
+        cache.set(CacheKey::new("users", "2"), "bob".to_string()).await;
+        assert!(cache.get(&CacheKey::new("users", "1")).await.is_some());
+        assert!(cache.get(&CacheKey::new("users", "3")).await.is_none());
+        cache.delete(&CacheKey::new("users", "2")).await;
+
+        let prometheus = collector.export_prometheus();
+        assert!(prometheus.contains("# TYPE dbnexus_cache_hits_total counter"));
+        assert!(prometheus.contains("dbnexus_cache_hits_total{cache=\"users\"} 1"));
+        assert!(prometheus.contains("dbnexus_cache_misses_total{cache=\"users\"} 1"));
+        assert!(prometheus.contains("dbnexus_cache_sets_total{cache=\"users\"} 2"));
+        assert!(prometheus.contains("dbnexus_cache_deletes_total{cache=\"users\"} 1"));
+        assert!(prometheus.contains("dbnexus_cache_expirations_total{cache=\"users\"} 0"));
+        assert!(prometheus.contains("dbnexus_cache_size{cache=\"users\"} 1"));
+        assert!(prometheus.contains("# TYPE dbnexus_cache_hit_rate gauge"));
+        assert!(prometheus.contains("dbnexus_cache_hit_rate{cache=\"users\"} 0.5000"));
+    }
+
+    /// TEST-U-083: 子收集器独立计数并合并到父收集器的导出中
+    #[test]
+    fn test_child_collectors_merged_into_export() {
+        let parent = MetricsCollector::new().with_labels(HashMap::from([("app".to_string(), "orders".to_string())]));
+        let shard_0 = parent.child_with_labels(HashMap::from([("shard".to_string(), "orders_0".to_string())]));
+        let shard_1 = parent.child_with_labels(HashMap::from([("shard".to_string(), "orders_1".to_string())]));
+
+        shard_0.record_transaction_commit();
+        shard_1.record_transaction_commit();
+        shard_1.record_transaction_commit();
+
+        assert_eq!(parent.transaction_stats().commit_count, 0);
+        assert_eq!(shard_1.transaction_stats().commit_count, 2);
+
+        let prometheus = parent.export_prometheus();
+        assert!(prometheus.contains("dbnexus_transactions_commit_total{app=\"orders\"} 0"));
+        assert!(prometheus.contains("dbnexus_transactions_commit_total{app=\"orders\",shard=\"orders_0\"} 1"));
+        assert!(prometheus.contains("dbnexus_transactions_commit_total{app=\"orders\",shard=\"orders_1\"} 2"));
+        assert_eq!(
+            prometheus
+                .matches("# TYPE dbnexus_transactions_commit_total counter")
+                .count(),
+            1,
+            "Each family header should appear once"
+        );
+    }
+
+    /// TEST-U-085: 语句分类忽略注释、空白与大小写，CTE 按主语句分类
+    #[test]
+    fn test_classify_statement() {
+        let cases = [
+            ("SELECT * FROM users", QueryKind::Select),
+            ("  \n\tselect 1", QueryKind::Select),
+            ("/* c */ update users SET name = 'x'", QueryKind::Update),
+            ("-- audit\n/* a */ /* b */ DELETE FROM users", QueryKind::Delete),
+            ("Insert INTO users VALUES (1)", QueryKind::Insert),
+            ("REPLACE INTO users VALUES (1)", QueryKind::Insert),
+            ("VALUES (1), (2)", QueryKind::Select),
+            ("WITH x AS (SELECT 1) SELECT * FROM x", QueryKind::Select),
+            (
+                "with recursive t(n) as (select 1 union all select n + 1 from t) select n from t",
+                QueryKind::Select,
+            ),
+            (
+                "WITH stale AS (SELECT id FROM sessions WHERE expired) DELETE FROM sessions WHERE id IN (SELECT id FROM stale)",
+                QueryKind::Delete,
+            ),
+            (
+                "WITH \"update\" AS (SELECT 1) SELECT * FROM \"update\"",
+                QueryKind::Select,
+            ),
+            ("CREATE TABLE users (id INTEGER)", QueryKind::Ddl),
+            ("drop index idx_users", QueryKind::Ddl),
+            ("ALTER TABLE users ADD COLUMN age INTEGER", QueryKind::Ddl),
+            ("BEGIN", QueryKind::Other),
+            ("PRAGMA journal_mode", QueryKind::Other),
+            ("/* unterminated", QueryKind::Other),
+            ("", QueryKind::Other),
+        ];
+
+        for (sql, expected) in cases {
+            assert_eq!(classify_statement(sql), expected, "unexpected kind for {:?}", sql);
+        }
+
+        assert_eq!(QueryKind::Ddl.to_string(), "DDL");
+        assert_eq!(classify_statement("select 1").as_str(), "SELECT");
+    }
 }
@@ -95,6 +95,95 @@ impl ColumnType {
             ColumnType::Custom(name) => name.to_string(),
         }
     }
+
+    /// 从数据库类型名称解析列类型（[`to_sql`](Self::to_sql) 的逆操作，用于读取现有数据库的表结构）
+    ///
+    /// 忽略大小写和多余空白，支持 `VARCHAR(255)`、`TIMESTAMP(6) WITH TIME ZONE` 等带参数的写法，
+    /// 无法识别的类型映射为 [`ColumnType::Custom`]（保留原始写法）。
+    ///
+    /// `to_sql` 会把部分类型映射到同一个名称（如 SQLite 的 `TEXT`），此时无法还原原始变体，
+    /// 但保证 `ColumnType::from_sql(&t.to_sql(db), db).to_sql(db) == t.to_sql(db)`。
+    pub fn from_sql(s: &str, db_type: DatabaseType) -> ColumnType {
+        let upper = s.trim().to_uppercase();
+
+        // 拆出括号内的参数：TIMESTAMP(6) WITH TIME ZONE -> ("TIMESTAMP WITH TIME ZONE", "6")
+        let (name, params) = match (upper.find('('), upper.find(')')) {
+            (Some(open), Some(close)) if open < close => (
+                format!("{} {}", &upper[..open], &upper[close + 1..]),
+                Some(upper[open + 1..close].trim().to_string()),
+            ),
+            _ => (upper.clone(), None),
+        };
+        let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+        let length = params.as_deref().and_then(|p| p.parse::<u32>().ok());
+
+        match name.as_str() {
+            // MySQL 将 BOOLEAN 存储为 TINYINT(1)
+            "TINYINT" if db_type == DatabaseType::MySql && length == Some(1) => ColumnType::Boolean,
+            "INTEGER" | "INT" | "INT4" | "SMALLINT" | "INT2" | "TINYINT" | "MEDIUMINT" | "SERIAL" => {
+                ColumnType::Integer
+            }
+            "BIGINT" | "INT8" | "BIGSERIAL" => ColumnType::BigInteger,
+            "VARCHAR" | "CHARACTER VARYING" | "NVARCHAR" => ColumnType::String(length),
+            "TEXT" | "TINYTEXT" | "MEDIUMTEXT" | "LONGTEXT" | "CLOB" => ColumnType::Text,
+            "BOOLEAN" | "BOOL" => ColumnType::Boolean,
+            "FLOAT" | "REAL" | "FLOAT4" => ColumnType::Float,
+            "DOUBLE PRECISION" | "DOUBLE" | "FLOAT8" => ColumnType::Double,
+            "DATE" => ColumnType::Date,
+            "TIME" | "TIME WITHOUT TIME ZONE" => ColumnType::Time,
+            "DATETIME" | "TIMESTAMP WITHOUT TIME ZONE" => ColumnType::DateTime,
+            // PostgreSQL 的 TIMESTAMP 不带时区，与 DateTime 的 to_sql 一致
+            "TIMESTAMP" if db_type == DatabaseType::Postgres => ColumnType::DateTime,
+            "TIMESTAMP" | "TIMESTAMPTZ" | "TIMESTAMP WITH TIME ZONE" => ColumnType::Timestamp,
+            "JSON" | "JSONB" => ColumnType::Json,
+            "BLOB" | "TINYBLOB" | "MEDIUMBLOB" | "LONGBLOB" | "BYTEA" | "BINARY" | "VARBINARY" => ColumnType::Binary,
+            _ => ColumnType::Custom(s.trim().to_string()),
+        }
+    }
+
+    /// 从 Rust 字段类型映射列类型
+    ///
+    /// 传入字段类型的源码字符串，允许包含空白和路径前缀。`DbEntity` 派生宏在展开时按相同规则解析列类型。
+    /// `Option<T>`（包括 `std::option::Option<T>`）按 `T` 映射，`Decimal` 映射为 `DECIMAL` 定点数以保留精度，
+    /// 无法识别的类型映射为 [`ColumnType::Custom`]。
+    pub fn from_rust_type(rust_type: &str) -> ColumnType {
+        let normalized: String = rust_type.chars().filter(|c| !c.is_whitespace()).collect();
+        let inner = strip_option(&normalized).unwrap_or(&normalized);
+
+        // 去除路径前缀：chrono::DateTime<chrono::Utc> -> DateTime<Utc>
+        let simple: String = inner
+            .split_inclusive(['<', '>', ','])
+            .map(|segment| segment.rsplit("::").next().unwrap_or(segment))
+            .collect();
+
+        match simple.as_str() {
+            "i8" | "i16" | "i32" | "u8" | "u16" | "u32" => ColumnType::Integer,
+            "i64" | "u64" => ColumnType::BigInteger,
+            "String" | "&str" | "&'staticstr" => ColumnType::String(Some(255)),
+            "bool" => ColumnType::Boolean,
+            "f32" => ColumnType::Float,
+            "f64" => ColumnType::Double,
+            "Decimal" | "BigDecimal" => ColumnType::Custom("DECIMAL".to_string()),
+            "NaiveDate" | "Date" => ColumnType::Date,
+            "NaiveTime" | "Time" => ColumnType::Time,
+            "NaiveDateTime" | "DateTime" | "PrimitiveDateTime" => ColumnType::DateTime,
+            "DateTime<Utc>" | "DateTime<FixedOffset>" | "DateTimeUtc" | "DateTimeWithTimeZone" | "OffsetDateTime" => {
+                ColumnType::Timestamp
+            }
+            "Json" | "JsonValue" | "Value" => ColumnType::Json,
+            "Vec<u8>" => ColumnType::Binary,
+            _ => ColumnType::Custom(inner.to_string()),
+        }
+    }
+}
+
+/// 去除空白后的类型字符串为 `Option<T>`（允许 `std::option::` / `core::option::` 路径前缀）时返回 `T`
+fn strip_option(normalized: &str) -> Option<&str> {
+    let path = normalized.strip_prefix("::").unwrap_or(normalized);
+    ["Option<", "std::option::Option<", "core::option::Option<"]
+        .iter()
+        .find_map(|prefix| path.strip_prefix(prefix))
+        .and_then(|t| t.strip_suffix('>'))
 }
 
 /// 列定义
@@ -118,6 +207,34 @@ pub struct Column {
     pub comment: Option<String>,
 }
 
+impl Column {
+    /// 根据实体字段创建列定义
+    ///
+    /// `Option<T>` 字段可为空，其余字段和主键列不可为空
+    pub fn from_field(name: &str, rust_type: &str, is_primary_key: bool) -> Self {
+        let normalized: String = rust_type.chars().filter(|c| !c.is_whitespace()).collect();
+        let is_optional = strip_option(&normalized).is_some();
+
+        Self::typed(name, ColumnType::from_rust_type(rust_type), is_primary_key, is_optional)
+    }
+
+    /// 按已解析的列类型创建列定义
+    ///
+    /// `DbEntity` 派生宏在展开时确定列类型和字段是否为 `Option`；主键列始终不可为空
+    pub fn typed(name: &str, column_type: ColumnType, is_primary_key: bool, is_optional: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            column_type,
+            is_primary_key,
+            is_nullable: is_optional && !is_primary_key,
+            has_default: false,
+            default_value: None,
+            is_auto_increment: false,
+            comment: None,
+        }
+    }
+}
+
 /// 表定义
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Table {
@@ -135,6 +252,35 @@ pub struct Table {
     pub comment: Option<String>,
 }
 
+impl Table {
+    /// 由列定义创建表，主键列取自 `is_primary_key` 为真的列
+    pub fn new(name: &str, columns: Vec<Column>) -> Self {
+        let primary_key_columns = columns
+            .iter()
+            .filter(|c| c.is_primary_key)
+            .map(|c| c.name.clone())
+            .collect();
+
+        Self {
+            name: name.to_string(),
+            columns,
+            primary_key_columns,
+            indexes: Vec::new(),
+            foreign_keys: Vec::new(),
+            comment: None,
+        }
+    }
+}
+
+/// 可直接生成迁移表结构的实体
+///
+/// 可以由 `#[derive(DbEntity)]` 根据字段类型和 `#[primary_key]` / `#[table_name]`
+/// （或对应的 `#[sea_orm(...)]`）属性实现，无需像 [`RustEntityParser`] 那样重新解析源代码。
+pub trait ToMigrationTable {
+    /// 返回实体对应的表结构
+    fn migration_table() -> Table;
+}
+
 /// 索引定义
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Index {
@@ -148,6 +294,10 @@ pub struct Index {
     pub is_unique: bool,
     /// 是否是唯一约束
     pub is_constraint: bool,
+    /// 部分索引条件（如 `deleted_at IS NULL`，不含 `WHERE` 关键字）
+    pub where_clause: Option<String>,
+    /// 索引方法（如 PostgreSQL 的 `GIN`、`HASH`）
+    pub method: Option<String>,
 }
 
 /// 外键定义
@@ -331,13 +481,22 @@ pub enum ColumnChange {
         /// 新的默认值
         new_default: Option<String>,
     },
+    /// 列重命名
+    ///
+    /// 由重命名提示或启发式检测得出，生成 `RENAME COLUMN` 而不是删除后新增，保留列中的数据
+    Renamed {
+        /// 旧列名
+        old_name: String,
+        /// 新列名
+        new_name: String,
+    },
 }
 
 /// Migration 变更
 #[derive(Debug, Clone)]
 pub struct Migration {
     /// 版本号
-    pub version: u32,
+    pub version: u64,
     /// 变更描述
     pub description: String,
     /// 表变更
@@ -350,7 +509,7 @@ pub struct Migration {
 
 impl Migration {
     /// 创建新的 Migration
-    pub fn new(version: u32, description: String) -> Self {
+    pub fn new(version: u64, description: String) -> Self {
         Self {
             version,
             description,
@@ -370,7 +529,7 @@ impl Migration {
 #[derive(Debug, Clone)]
 pub struct MigrationVersion {
     /// 版本号
-    pub version: u32,
+    pub version: u64,
     /// 版本描述
     pub description: String,
     /// 应用时间
@@ -384,7 +543,7 @@ pub struct MigrationVersion {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct SerializableMigrationVersion {
     /// 版本号
-    pub version: u32,
+    pub version: u64,
     /// 版本描述
     pub description: String,
     /// 应用时间
@@ -398,7 +557,7 @@ impl From<MigrationVersion> for SerializableMigrationVersion {
         Self {
             version: mv.version,
             description: mv.description,
-            applied_at: mv.applied_at.to_string(),
+            applied_at: format_applied_at(mv.applied_at),
             file_path: mv.file_path,
         }
     }
@@ -409,8 +568,7 @@ impl From<SerializableMigrationVersion> for MigrationVersion {
         Self {
             version: sm.version,
             description: sm.description,
-            applied_at: time::OffsetDateTime::parse(&sm.applied_at, &time::format_description::well_known::Rfc3339)
-                .unwrap_or_else(|_| time::OffsetDateTime::now_utc()),
+            applied_at: parse_applied_at(&sm.applied_at).unwrap_or_else(time::OffsetDateTime::now_utc),
             file_path: sm.file_path,
         }
     }
@@ -445,12 +603,12 @@ impl MigrationHistory {
     }
 
     /// 检查版本是否已应用
-    pub fn is_version_applied(&self, version: u32) -> bool {
+    pub fn is_version_applied(&self, version: u64) -> bool {
         self.applied_migrations.iter().any(|m| m.version == version)
     }
 
     /// 获取最高已应用版本号
-    pub fn get_latest_version(&self) -> Option<u32> {
+    pub fn get_latest_version(&self) -> Option<u64> {
         self.applied_migrations.iter().map(|m| m.version).max()
     }
 
@@ -491,21 +649,56 @@ impl MigrationExecutor {
         // 确保迁移历史表存在
         self.ensure_migration_table_exists().await?;
 
-        // 查询已应用的迁移版本
-        let query_sql = "SELECT version FROM dbnexus_migrations ORDER BY version";
-
-        match self.connection.execute_unprepared(query_sql).await {
-            Ok(_) => {
-                // 表存在且查询成功
-                // 由于 Sea-ORM 的限制，我们使用一个简单的方法：
-                // 每次应用迁移时都会添加记录到历史中
-                // 这里我们假设历史已经被正确填充
+        // 查询已应用的迁移版本（统一转换为 BIGINT / TEXT 以便跨数据库解码）
+        let query_sql = match self.sql_generator.db_type {
+            DatabaseType::Postgres => {
+                "SELECT CAST(version AS BIGINT) AS version, description, \
+                 to_char(applied_at, 'YYYY-MM-DD\"T\"HH24:MI:SS.US\"Z\"') AS applied_at, \
+                 file_path FROM dbnexus_migrations ORDER BY version"
+            }
+            DatabaseType::MySql => {
+                "SELECT CAST(version AS SIGNED) AS version, description, \
+                 DATE_FORMAT(applied_at, '%Y-%m-%dT%H:%i:%s.%fZ') AS applied_at, \
+                 file_path FROM dbnexus_migrations ORDER BY version"
+            }
+            DatabaseType::Sqlite => {
+                "SELECT version, description, CAST(applied_at AS TEXT) AS applied_at, file_path \
+                 FROM dbnexus_migrations ORDER BY version"
+            }
+        };
+        let stmt = crate::orm::Statement::from_string(self.connection.get_database_backend(), query_sql);
+
+        let mut history = MigrationHistory::new();
+        match self.connection.query_all_raw(stmt).await {
+            Ok(rows) => {
+                for row in rows {
+                    let version: i64 = row.try_get("", "version").map_err(crate::config::DbError::Connection)?;
+                    let applied_at: Option<String> = row.try_get("", "applied_at").unwrap_or(None);
+                    history.add_migration(MigrationVersion {
+                        version: u64::try_from(version).map_err(|_| {
+                            crate::config::DbError::migration(
+                                0,
+                                "history",
+                                format!("Invalid migration version: {}", version),
+                            )
+                        })?,
+                        description: row.try_get("", "description").unwrap_or_default(),
+                        applied_at: applied_at
+                            .as_deref()
+                            .and_then(parse_applied_at)
+                            .unwrap_or_else(time::OffsetDateTime::now_utc),
+                        file_path: row
+                            .try_get::<Option<String>>("", "file_path")
+                            .unwrap_or(None)
+                            .unwrap_or_default(),
+                    });
+                }
             }
             Err(e) => {
                 tracing::warn!("Failed to load migration history: {}", e);
-                self.history = MigrationHistory::new();
             }
         }
+        self.history = history;
 
         Ok(())
     }
@@ -518,7 +711,7 @@ impl MigrationExecutor {
         let create_table_sql = match self.sql_generator.db_type {
             DatabaseType::Postgres => {
                 "CREATE TABLE IF NOT EXISTS dbnexus_migrations (
-                    version INTEGER PRIMARY KEY,
+                    version BIGINT PRIMARY KEY,
                     description TEXT NOT NULL,
                     applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
                     file_path TEXT
@@ -526,7 +719,7 @@ impl MigrationExecutor {
             }
             DatabaseType::MySql => {
                 "CREATE TABLE IF NOT EXISTS dbnexus_migrations (
-                    version INT PRIMARY KEY,
+                    version BIGINT PRIMARY KEY,
                     description TEXT NOT NULL,
                     applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
                     file_path TEXT
@@ -549,12 +742,57 @@ impl MigrationExecutor {
         Ok(())
     }
 
+    /// 写入一条迁移历史记录
+    ///
+    /// 使用参数化查询；`applied_at` 统一以 RFC3339（UTC、微秒精度）写入，
+    /// PostgreSQL / MySQL 转换为各自的时间戳类型。`conn` 可以是事务，以便与迁移 SQL 一起提交。
+    pub async fn record_applied<C: crate::orm::ConnectionTrait>(
+        &self,
+        conn: &C,
+        version: u64,
+        description: &str,
+        applied_at: time::OffsetDateTime,
+        file_path: &str,
+    ) -> Result<(), crate::config::DbError> {
+        let (backend, sql) = match self.sql_generator.db_type {
+            DatabaseType::Postgres => (
+                sea_orm::DatabaseBackend::Postgres,
+                "INSERT INTO dbnexus_migrations (version, description, applied_at, file_path) \
+                 VALUES ($1, $2, CAST($3 AS TIMESTAMPTZ) AT TIME ZONE 'UTC', $4)",
+            ),
+            DatabaseType::MySql => (
+                sea_orm::DatabaseBackend::MySql,
+                "INSERT INTO dbnexus_migrations (version, description, applied_at, file_path) \
+                 VALUES (?, ?, STR_TO_DATE(?, '%Y-%m-%dT%H:%i:%s.%fZ'), ?)",
+            ),
+            DatabaseType::Sqlite => (
+                sea_orm::DatabaseBackend::Sqlite,
+                "INSERT INTO dbnexus_migrations (version, description, applied_at, file_path) VALUES (?1, ?2, ?3, ?4)",
+            ),
+        };
+
+        let stmt = sea_orm::Statement::from_sql_and_values(
+            backend,
+            sql,
+            [
+                version_value(version),
+                description.into(),
+                format_applied_at(applied_at).into(),
+                file_path.into(),
+            ],
+        );
+
+        conn.execute_raw(stmt)
+            .await
+            .map_err(crate::config::DbError::Connection)?;
+        Ok(())
+    }
+
     /// 应用单个迁移
+    ///
+    /// `migration.sql` 非空时直接执行，否则由 `table_changes` 生成 SQL；迁移 SQL 与历史记录在同一事务中提交
     pub async fn apply_migration(&mut self, migration: &Migration) -> Result<(), crate::config::DbError> {
-        use crate::orm::{ConnectionTrait, TransactionTrait};
-
-        // 生成迁移 SQL
-        let sql = self.sql_generator.generate_migration_sql(migration);
+        use crate::orm::TransactionTrait;
 
         // 开始事务
         let txn = self
@@ -563,9 +801,29 @@ impl MigrationExecutor {
             .await
             .map_err(crate::config::DbError::Connection)?;
 
+        self.apply_migration_in(&txn, migration).await?;
+
+        // 提交事务
+        txn.commit().await.map_err(crate::config::DbError::Connection)?;
+
+        Ok(())
+    }
+
+    /// 在给定连接（通常是事务）上执行迁移 SQL 并写入历史记录
+    async fn apply_migration_in<C: crate::orm::ConnectionTrait>(
+        &self,
+        conn: &C,
+        migration: &Migration,
+    ) -> Result<MigrationVersion, crate::config::DbError> {
+        // 生成迁移 SQL
+        let sql = match &migration.sql {
+            Some(sql) => sql.clone(),
+            None => self.sql_generator.generate_migration_sql(migration),
+        };
+
         // 执行迁移 SQL
-        if !sql.is_empty() {
-            txn.execute_unprepared(&sql)
+        if !sql.trim().is_empty() {
+            conn.execute_unprepared(&sql)
                 .await
                 .map_err(crate::config::DbError::Connection)?;
         }
@@ -579,36 +837,115 @@ impl MigrationExecutor {
         };
 
         // 插入到迁移历史表
-        let insert_sql = match self.sql_generator.db_type {
-            DatabaseType::Postgres | DatabaseType::MySql => {
-                format!(
-                    "INSERT INTO dbnexus_migrations (version, description, applied_at, file_path) VALUES ({}, '{}', '{}', '{}');",
-                    migration.version,
-                    migration.description.replace('\'', "''"), // 转义单引号
-                    version_record.applied_at.to_string().replace('\'', "''"),
-                    version_record.file_path.replace('\'', "''")
-                )
-            }
-            DatabaseType::Sqlite => {
-                format!(
-                    "INSERT INTO dbnexus_migrations (version, description, applied_at, file_path) VALUES ({}, '{}', '{}', '{}');",
-                    migration.version,
-                    migration.description.replace('\'', "''"), // 转义单引号
-                    version_record.applied_at.to_string().replace('\'', "''"),
-                    version_record.file_path.replace('\'', "''")
-                )
-            }
-        };
+        self.record_applied(
+            conn,
+            version_record.version,
+            &version_record.description,
+            version_record.applied_at,
+            &version_record.file_path,
+        )
+        .await?;
 
-        txn.execute_unprepared(&insert_sql)
+        Ok(version_record)
+    }
+
+    /// 在给定连接上执行回滚 SQL（`migration.sql`，为空时跳过）并删除历史记录
+    async fn rollback_migration_in<C: crate::orm::ConnectionTrait>(
+        &self,
+        conn: &C,
+        migration: &Migration,
+    ) -> Result<(), crate::config::DbError> {
+        if let Some(sql) = migration.sql.as_deref().filter(|sql| !sql.trim().is_empty()) {
+            conn.execute_unprepared(sql)
+                .await
+                .map_err(crate::config::DbError::Connection)?;
+        }
+
+        let delete_sql = format!("DELETE FROM dbnexus_migrations WHERE version = {};", migration.version);
+        conn.execute_unprepared(&delete_sql)
             .await
             .map_err(crate::config::DbError::Connection)?;
-        // 提交事务
-        txn.commit().await.map_err(crate::config::DbError::Connection)?;
 
         Ok(())
     }
 
+    /// 在一个事务中按顺序执行整个迁移计划
+    ///
+    /// - `Up`: 逐个应用迁移，规则同 [`apply_migration`](Self::apply_migration)
+    /// - `Down`: 逐个执行 `migration.sql` 中的回滚 SQL 并删除历史记录
+    ///
+    /// 每完成一步调用一次 `progress`；所有步骤成功后统一提交，返回执行的步骤数。
+    /// 任一步骤失败时回滚整个计划，返回标明失败版本的 [`DbError::Migration`](crate::config::DbError::Migration)。
+    /// MySQL 的 DDL 会隐式提交，无法随计划一起回滚。
+    pub async fn execute_plan(
+        &mut self,
+        plan: &MigrationPlan,
+        mut progress: impl FnMut(MigrationProgress),
+    ) -> Result<usize, crate::config::DbError> {
+        use crate::orm::TransactionTrait;
+
+        if plan.migrations.is_empty() {
+            return Ok(0);
+        }
+
+        let txn = self
+            .connection
+            .begin()
+            .await
+            .map_err(crate::config::DbError::Connection)?;
+
+        let total = plan.migrations.len();
+        let mut applied = Vec::with_capacity(total);
+
+        for (index, migration) in plan.migrations.iter().enumerate() {
+            let (phase, result) = match plan.direction {
+                MigrationDirection::Up => ("apply", self.apply_migration_in(&txn, migration).await.map(Some)),
+                MigrationDirection::Down => (
+                    "rollback",
+                    self.rollback_migration_in(&txn, migration).await.map(|_| None),
+                ),
+            };
+
+            match result {
+                Ok(record) => applied.extend(record),
+                Err(e) => {
+                    tracing::error!("Migration plan failed at v{} ({}): {}", migration.version, phase, e);
+                    if let Err(rollback_err) = txn.rollback().await {
+                        tracing::warn!("Failed to roll back migration plan: {}", rollback_err);
+                    }
+                    return Err(crate::config::DbError::migration(
+                        migration.version,
+                        phase,
+                        e.to_string(),
+                    ));
+                }
+            }
+
+            progress(MigrationProgress {
+                step: index + 1,
+                total,
+                version: migration.version,
+                description: migration.description.clone(),
+                direction: plan.direction,
+            });
+        }
+
+        txn.commit().await.map_err(crate::config::DbError::Connection)?;
+
+        // 提交成功后同步内存中的迁移历史
+        match plan.direction {
+            MigrationDirection::Up => applied
+                .into_iter()
+                .for_each(|record| self.history.add_migration(record)),
+            MigrationDirection::Down => self
+                .history
+                .applied_migrations
+                .retain(|m| !plan.migrations.iter().any(|p| p.version == m.version)),
+        }
+
+        Ok(total)
+    }
+
     /// 获取待应用的迁移
     pub async fn get_pending_migrations<'a>(&'a mut self, all_migrations: &'a [Migration]) -> Vec<&'a Migration> {
         // 重新加载历史记录以获取最新状态
@@ -621,7 +958,7 @@ impl MigrationExecutor {
     }
 
     /// 获取所有迁移的版本号
-    pub fn get_all_versions(&self) -> Vec<u32> {
+    pub fn get_all_versions(&self) -> Vec<u64> {
         self.history.applied_migrations.iter().map(|m| m.version).collect()
     }
 
@@ -636,11 +973,53 @@ impl MigrationExecutor {
     }
 }
 
+/// 解析迁移文件名 `{version}_{description}.sql`
+///
+/// 版本号为 `u64`（可带前导零或 `v` 前缀），以容纳秒级时间戳；
+/// 超出 `i64` 范围的版本号无法写入迁移历史表，视为无效。描述中可包含下划线。
+pub fn parse_migration_filename(filename: &str) -> Option<(u64, String)> {
+    let stem = filename.strip_suffix(".sql").unwrap_or(filename);
+    let (version, description) = stem.split_once('_').unwrap_or((stem, ""));
+    let version = version.strip_prefix(['v', 'V']).unwrap_or(version);
+
+    if version.is_empty() || !version.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let version = version.parse::<u64>().ok().filter(|v| i64::try_from(*v).is_ok())?;
+    Some((version, description.to_string()))
+}
+
+/// 将应用时间格式化为迁移历史中存储的 RFC3339 字符串（UTC，固定微秒精度）
+pub fn format_applied_at(applied_at: time::OffsetDateTime) -> String {
+    let utc = applied_at.to_offset(time::UtcOffset::UTC);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z",
+        utc.year(),
+        u8::from(utc.month()),
+        utc.day(),
+        utc.hour(),
+        utc.minute(),
+        utc.second(),
+        utc.microsecond()
+    )
+}
+
+/// 解析迁移历史中存储的 RFC3339 应用时间
+pub fn parse_applied_at(value: &str) -> Option<time::OffsetDateTime> {
+    time::OffsetDateTime::parse(value.trim(), &time::format_description::well_known::Rfc3339).ok()
+}
+
+/// 迁移版本号的数据库参数值（历史表使用有符号 BIGINT 存储）
+fn version_value(version: u64) -> sea_orm::Value {
+    i64::try_from(version).unwrap_or(i64::MAX).into()
+}
+
 /// 迁移文件信息
 #[derive(Debug, Clone)]
 pub struct MigrationFile {
     /// 版本号
-    pub version: u32,
+    pub version: u64,
     /// 描述
     pub description: String,
     /// 文件路径
@@ -681,7 +1060,7 @@ impl MigrationExecutor {
 
             if path.is_file() && path.extension().map(|e| e == "sql").unwrap_or(false) {
                 if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-                    if let Some((version, description)) = Self::parse_filename(filename) {
+                    if let Some((version, description)) = parse_migration_filename(filename) {
                         let content = std::fs::read_to_string(&path).map_err(|e| {
                             crate::config::DbError::Config(format!("Failed to read migration file: {}", e))
                         })?;
@@ -705,19 +1084,6 @@ impl MigrationExecutor {
         Ok(migrations)
     }
 
-    /// 解析迁移文件名
-    pub(crate) fn parse_filename(filename: &str) -> Option<(u32, String)> {
-        let parts: Vec<&str> = filename.split('_').collect();
-        if parts.is_empty() {
-            return None;
-        }
-
-        let version = parts[0].parse::<u32>().ok()?;
-        let description = parts[1..].join("_").replace(".sql", "");
-
-        Some((version, description))
-    }
-
     /// 运行所有待应用的迁移
     ///
     /// # Arguments
@@ -785,7 +1151,7 @@ impl MigrationExecutor {
     }
 
     /// 检查迁移是否已应用（通过查询数据库）
-    async fn is_migration_applied(&self, version: u32) -> Result<bool, crate::config::DbError> {
+    async fn is_migration_applied(&self, version: u64) -> Result<bool, crate::config::DbError> {
         use crate::orm::{ConnectionTrait, Statement};
 
         // 先确保迁移历史表存在
@@ -796,22 +1162,22 @@ impl MigrationExecutor {
             DatabaseType::Postgres => Statement::from_sql_and_values(
                 sea_orm::DatabaseBackend::Postgres,
                 r#"SELECT 1 FROM dbnexus_migrations WHERE version = $1"#,
-                [version.into()],
+                [version_value(version)],
             ),
             DatabaseType::MySql => Statement::from_sql_and_values(
                 sea_orm::DatabaseBackend::MySql,
                 r#"SELECT 1 FROM dbnexus_migrations WHERE version = ?"#,
-                [version.into()],
+                [version_value(version)],
             ),
             DatabaseType::Sqlite => Statement::from_sql_and_values(
                 sea_orm::DatabaseBackend::Sqlite,
                 r#"SELECT 1 FROM dbnexus_migrations WHERE version = ?1"#,
-                [version.into()],
+                [version_value(version)],
             ),
         };
 
-        match self.connection.execute(check_sql).await {
-            Ok(result) => Ok(result.rows_affected() > 0),
+        match self.connection.query_one_raw(check_sql).await {
+            Ok(row) => Ok(row.is_some()),
             Err(_) => Ok(false),
         }
     }
@@ -840,43 +1206,14 @@ impl MigrationExecutor {
         // 记录迁移历史
         let applied_at = time::OffsetDateTime::now_utc();
 
-        // 使用参数化查询防止 SQL 注入
-        let insert_sql = match self.sql_generator.db_type {
-            DatabaseType::Postgres => sea_orm::Statement::from_sql_and_values(
-                sea_orm::DatabaseBackend::Postgres,
-                r#"INSERT INTO dbnexus_migrations (version, description, applied_at, file_path) VALUES ($1, $2, $3, $4)"#,
-                [
-                    migration_file.version.into(),
-                    migration_file.description.clone().into(),
-                    applied_at.to_string().into(),
-                    migration_file.file_path.to_string_lossy().to_string().into(),
-                ],
-            ),
-            DatabaseType::MySql => sea_orm::Statement::from_sql_and_values(
-                sea_orm::DatabaseBackend::MySql,
-                r#"INSERT INTO dbnexus_migrations (version, description, applied_at, file_path) VALUES (?, ?, ?, ?)"#,
-                [
-                    migration_file.version.into(),
-                    migration_file.description.clone().into(),
-                    applied_at.to_string().into(),
-                    migration_file.file_path.to_string_lossy().to_string().into(),
-                ],
-            ),
-            DatabaseType::Sqlite => sea_orm::Statement::from_sql_and_values(
-                sea_orm::DatabaseBackend::Sqlite,
-                r#"INSERT INTO dbnexus_migrations (version, description, applied_at, file_path) VALUES (?1, ?2, ?3, ?4)"#,
-                [
-                    migration_file.version.into(),
-                    migration_file.description.clone().into(),
-                    applied_at.to_string().into(),
-                    migration_file.file_path.to_string_lossy().to_string().into(),
-                ],
-            ),
-        };
-
-        txn.execute(insert_sql)
-            .await
-            .map_err(crate::config::DbError::Connection)?;
+        self.record_applied(
+            &txn,
+            migration_file.version,
+            &migration_file.description,
+            applied_at,
+            &migration_file.file_path.to_string_lossy(),
+        )
+        .await?;
 
         // 提交事务
         txn.commit().await.map_err(crate::config::DbError::Connection)?;
@@ -938,7 +1275,7 @@ impl MigrationExecutor {
 
         let mut rollback_count = 0;
         // 按版本号降序排序（先回滚最新的）
-        let mut versions: Vec<u32> = applied.iter().map(|m| m.version).collect();
+        let mut versions: Vec<u64> = applied.iter().map(|m| m.version).collect();
         versions.sort_by_key(|v| std::cmp::Reverse(*v));
 
         for version in versions {
@@ -960,7 +1297,7 @@ impl MigrationExecutor {
     }
 
     /// 回滚指定版本的迁移
-    pub async fn rollback_migration(&mut self, version: u32) -> Result<(), crate::config::DbError> {
+    pub async fn rollback_migration(&mut self, version: u64) -> Result<(), crate::config::DbError> {
         use crate::orm::{ConnectionTrait, TransactionTrait};
 
         let delete_sql = format!("DELETE FROM dbnexus_migrations WHERE version = {};", version);
@@ -984,10 +1321,176 @@ impl MigrationExecutor {
     }
 }
 
+/// 迁移锁（自动迁移与 CLI 共用）
+impl MigrationExecutor {
+    /// 获取迁移锁，防止多个迁移进程并发执行
+    ///
+    /// - PostgreSQL: `pg_try_advisory_lock`，轮询直到超时
+    /// - MySQL: `GET_LOCK`
+    /// - SQLite: 在 `dbnexus_migrations_lock` 中插入锁记录
+    ///
+    /// PostgreSQL / MySQL 的锁属于数据库会话，因此通过一个保持打开的事务固定连接；
+    /// 持有者崩溃时连接断开即自动释放。SQLite 锁记录需在异常退出后手动删除。
+    /// `lock_timeout` 为零时立即失败。
+    pub async fn acquire_lock(
+        &self,
+        lock_timeout: std::time::Duration,
+    ) -> Result<MigrationLock, crate::config::DbError> {
+        use crate::config::DbError;
+        use crate::orm::{ConnectionTrait, TransactionTrait};
+
+        let deadline = std::time::Instant::now() + lock_timeout;
+        let backend = self.connection.get_database_backend();
+
+        match self.sql_generator.db_type {
+            DatabaseType::Postgres | DatabaseType::MySql => {
+                let txn = self.connection.begin().await.map_err(DbError::Connection)?;
+                loop {
+                    let acquire_sql = match self.sql_generator.db_type {
+                        DatabaseType::Postgres => format!(
+                            "SELECT CAST(pg_try_advisory_lock({}) AS BIGINT) AS acquired",
+                            MIGRATION_LOCK_KEY
+                        ),
+                        _ => format!("SELECT GET_LOCK('{}', 0) AS acquired", MIGRATION_LOCK_NAME),
+                    };
+                    let row = txn
+                        .query_one_raw(crate::orm::Statement::from_string(backend, acquire_sql))
+                        .await
+                        .map_err(DbError::Connection)?;
+                    let acquired = row
+                        .and_then(|row| row.try_get::<Option<i64>>("", "acquired").ok().flatten())
+                        .unwrap_or(0);
+
+                    if acquired == 1 {
+                        return Ok(MigrationLock {
+                            db_type: self.sql_generator.db_type,
+                            connection: self.connection.clone(),
+                            session: Some(txn),
+                        });
+                    }
+                    if std::time::Instant::now() >= deadline {
+                        let _ = txn.rollback().await;
+                        return Err(lock_timeout_error(lock_timeout));
+                    }
+                    tokio::time::sleep(MIGRATION_LOCK_POLL_INTERVAL).await;
+                }
+            }
+            DatabaseType::Sqlite => {
+                self.connection
+                    .execute_unprepared(&format!(
+                        "CREATE TABLE IF NOT EXISTS {} (id INTEGER PRIMARY KEY, locked_at TEXT NOT NULL)",
+                        MIGRATION_LOCK_TABLE
+                    ))
+                    .await
+                    .map_err(DbError::Connection)?;
+
+                loop {
+                    let result = self
+                        .connection
+                        .execute_unprepared(&format!(
+                            "INSERT OR IGNORE INTO {} (id, locked_at) VALUES (1, datetime('now'))",
+                            MIGRATION_LOCK_TABLE
+                        ))
+                        .await
+                        .map_err(DbError::Connection)?;
+
+                    if result.rows_affected() == 1 {
+                        return Ok(MigrationLock {
+                            db_type: DatabaseType::Sqlite,
+                            connection: self.connection.clone(),
+                            session: None,
+                        });
+                    }
+                    if std::time::Instant::now() >= deadline {
+                        return Err(lock_timeout_error(lock_timeout));
+                    }
+                    tokio::time::sleep(MIGRATION_LOCK_POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+}
+
+/// PostgreSQL advisory lock 键（"dbnexus" 的 ASCII 编码）
+pub const MIGRATION_LOCK_KEY: i64 = 0x0064_626e_6578_7573;
+
+/// MySQL `GET_LOCK` 锁名
+pub const MIGRATION_LOCK_NAME: &str = "dbnexus_migrations";
+
+/// SQLite 迁移锁表名
+pub const MIGRATION_LOCK_TABLE: &str = "dbnexus_migrations_lock";
+
+/// 获取迁移锁的轮询间隔
+const MIGRATION_LOCK_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+fn lock_timeout_error(lock_timeout: std::time::Duration) -> crate::config::DbError {
+    crate::config::DbError::migration(
+        0,
+        "lock",
+        format!(
+            "Timed out after {}ms waiting for migration lock; another migration may be running",
+            lock_timeout.as_millis()
+        ),
+    )
+}
+
+/// 迁移锁
+///
+/// 通过 [`MigrationExecutor::acquire_lock`] 获取，迁移完成后调用 [`MigrationLock::release`] 释放
+#[derive(Debug)]
+pub struct MigrationLock {
+    /// 数据库类型
+    db_type: DatabaseType,
+    /// 数据库连接
+    connection: crate::orm::DatabaseConnection,
+    /// 固定持锁会话的事务（PostgreSQL / MySQL）
+    session: Option<crate::orm::DatabaseTransaction>,
+}
+
+impl MigrationLock {
+    /// 释放迁移锁
+    pub async fn release(self) -> Result<(), crate::config::DbError> {
+        use crate::config::DbError;
+        use crate::orm::ConnectionTrait;
+
+        match (self.db_type, self.session) {
+            (DatabaseType::Postgres, Some(txn)) => {
+                txn.execute_unprepared(&format!("SELECT pg_advisory_unlock({})", MIGRATION_LOCK_KEY))
+                    .await
+                    .map_err(DbError::Connection)?;
+                txn.commit().await.map_err(DbError::Connection)
+            }
+            (DatabaseType::MySql, Some(txn)) => {
+                txn.execute_unprepared(&format!("SELECT RELEASE_LOCK('{}')", MIGRATION_LOCK_NAME))
+                    .await
+                    .map_err(DbError::Connection)?;
+                txn.commit().await.map_err(DbError::Connection)
+            }
+            _ => {
+                self.connection
+                    .execute_unprepared(&format!("DELETE FROM {} WHERE id = 1", MIGRATION_LOCK_TABLE))
+                    .await
+                    .map_err(DbError::Connection)?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// 迁移文件指令：该迁移在事务外执行（如 `CREATE INDEX CONCURRENTLY`）
+pub const NO_TRANSACTION_DIRECTIVE: &str = "-- dbnexus:no-transaction";
+
 /// 迁移文件解析器
 pub struct MigrationFileParser;
 
 impl MigrationFileParser {
+    /// 判断迁移文件是否声明了 `-- dbnexus:no-transaction` 指令
+    pub fn requires_no_transaction(content: &str) -> bool {
+        content
+            .lines()
+            .any(|line| line.trim().eq_ignore_ascii_case(NO_TRANSACTION_DIRECTIVE))
+    }
+
     /// 解析迁移文件内容
     pub fn parse_migration_file(content: &str) -> Result<(String, String), String> {
         // 提取迁移描述
@@ -1046,7 +1549,7 @@ pub struct MigrationPlan {
 }
 
 /// 迁移方向
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MigrationDirection {
     /// 向上迁移（应用新版本）
     Up,
@@ -1054,6 +1557,21 @@ pub enum MigrationDirection {
     Down,
 }
 
+/// 迁移计划执行进度，每完成一步回调一次
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationProgress {
+    /// 已完成的步骤序号（从 1 开始）
+    pub step: usize,
+    /// 计划中的总步骤数
+    pub total: usize,
+    /// 当前步骤的迁移版本
+    pub version: u64,
+    /// 当前步骤的迁移描述
+    pub description: String,
+    /// 执行方向
+    pub direction: MigrationDirection,
+}
+
 /// 迁移工具 CLI 命令
 #[derive(Debug, Clone)]
 pub enum MigrationCommand {
@@ -1067,12 +1585,12 @@ pub enum MigrationCommand {
     /// 应用迁移
     Up {
         /// 目标版本号，None 表示应用所有迁移
-        target_version: Option<u32>,
+        target_version: Option<u64>,
     },
     /// 回滚迁移
     Down {
         /// 目标版本号，None 表示回滚到初始状态
-        target_version: Option<u32>,
+        target_version: Option<u64>,
     },
     /// 查看迁移状态
     Status,
@@ -1088,17 +1606,47 @@ pub enum MigrationCommand {
 }
 
 /// Schema 差异计算器
+///
+/// 默认按列名比较，改名的列会被视为删除旧列并新增新列（数据丢失）。
+/// 通过 [`with_column_rename`](Self::with_column_rename) 提供重命名提示，
+/// 或通过 [`with_rename_detection`](Self::with_rename_detection) 启用启发式检测，可生成 `RENAME COLUMN`。
 pub struct SchemaDiffer {
     /// 源 Schema
     old_schema: Schema,
     /// 目标 Schema
     new_schema: Schema,
+    /// 重命名提示：(表名, 新列名) -> 旧列名
+    rename_hints: HashMap<(String, String), String>,
+    /// 是否按类型与位置启发式检测重命名
+    detect_renames: bool,
 }
 
 impl SchemaDiffer {
     /// 创建新的 SchemaDiffer
     pub fn new(old_schema: Schema, new_schema: Schema) -> Self {
-        Self { old_schema, new_schema }
+        Self {
+            old_schema,
+            new_schema,
+            rename_hints: HashMap::new(),
+            detect_renames: false,
+        }
+    }
+
+    /// 添加重命名提示：`table` 表的 `old_name` 列在目标 Schema 中改名为 `new_name`
+    ///
+    /// 旧列在源 Schema、新列在目标 Schema 中都存在时生效，否则忽略该提示
+    pub fn with_column_rename(mut self, table: &str, old_name: &str, new_name: &str) -> Self {
+        self.rename_hints
+            .insert((table.to_string(), new_name.to_string()), old_name.to_string());
+        self
+    }
+
+    /// 启用启发式重命名检测
+    ///
+    /// 被删除的列与新增的列位于同一位置且类型、可空性相同时视为重命名
+    pub fn with_rename_detection(mut self, enabled: bool) -> Self {
+        self.detect_renames = enabled;
+        self
     }
 
     /// 计算差异并生成 Migration
@@ -1125,10 +1673,26 @@ impl SchemaDiffer {
         // 检测修改的表
         for new_table in &self.new_schema.tables {
             if let Some(old_table) = self.old_schema.get_table(&new_table.name) {
-                // 检测列变更
-                let column_changes = self.detect_column_changes(old_table, new_table);
-                let added_columns = self.detect_added_columns(old_table, new_table);
-                let removed_columns = self.detect_removed_columns(old_table, new_table);
+                // 检测列变更（重命名的列不再计入新增和删除）
+                let renames = self.detect_renamed_columns(old_table, new_table);
+                let mut column_changes: Vec<ColumnChange> = renames
+                    .iter()
+                    .map(|(old_name, new_name)| ColumnChange::Renamed {
+                        old_name: old_name.clone(),
+                        new_name: new_name.clone(),
+                    })
+                    .collect();
+                column_changes.extend(self.detect_column_changes(old_table, new_table, &renames));
+                let added_columns: Vec<Column> = self
+                    .detect_added_columns(old_table, new_table)
+                    .into_iter()
+                    .filter(|c| !renames.iter().any(|(_, new_name)| *new_name == c.name))
+                    .collect();
+                let removed_columns: Vec<String> = self
+                    .detect_removed_columns(old_table, new_table)
+                    .into_iter()
+                    .filter(|name| !renames.iter().any(|(old_name, _)| old_name == name))
+                    .collect();
                 let added_indexes = self.detect_added_indexes(old_table, new_table);
                 let removed_indexes = self.detect_removed_indexes(old_table, new_table);
                 let added_foreign_keys = self.detect_added_foreign_keys(old_table, new_table);
@@ -1163,12 +1727,62 @@ impl SchemaDiffer {
         migrations
     }
 
-    /// 检测列变更
-    fn detect_column_changes(&self, old_table: &Table, new_table: &Table) -> Vec<ColumnChange> {
+    /// 检测重命名的列，返回 (旧列名, 新列名)
+    ///
+    /// 只考虑仅存在于源表的列与仅存在于目标表的列；每个列最多参与一次重命名
+    fn detect_renamed_columns(&self, old_table: &Table, new_table: &Table) -> Vec<(String, String)> {
+        let removed = self.detect_removed_columns(old_table, new_table);
+        let added = self.detect_added_columns(old_table, new_table);
+        let mut renames: Vec<(String, String)> = Vec::new();
+
+        for new_column in &added {
+            let hinted = self
+                .rename_hints
+                .get(&(new_table.name.clone(), new_column.name.clone()))
+                .filter(|old_name| removed.contains(old_name) && !renames.iter().any(|(o, _)| o == *old_name));
+            if let Some(old_name) = hinted {
+                renames.push((old_name.clone(), new_column.name.clone()));
+            }
+        }
+
+        if self.detect_renames {
+            for new_column in &added {
+                if renames.iter().any(|(_, n)| *n == new_column.name) {
+                    continue;
+                }
+                let position = new_table.columns.iter().position(|c| c.name == new_column.name);
+                let candidate = position
+                    .and_then(|index| old_table.columns.get(index))
+                    .filter(|old_column| {
+                        removed.contains(&old_column.name)
+                            && !renames.iter().any(|(o, _)| *o == old_column.name)
+                            && old_column.column_type == new_column.column_type
+                            && old_column.is_nullable == new_column.is_nullable
+                    });
+                if let Some(old_column) = candidate {
+                    renames.push((old_column.name.clone(), new_column.name.clone()));
+                }
+            }
+        }
+
+        renames
+    }
+
+    /// 检测列变更（重命名的列与其旧列比较）
+    fn detect_column_changes(
+        &self,
+        old_table: &Table,
+        new_table: &Table,
+        renames: &[(String, String)],
+    ) -> Vec<ColumnChange> {
         let mut changes = Vec::new();
 
         for new_column in &new_table.columns {
-            if let Some(old_column) = old_table.columns.iter().find(|c| c.name == new_column.name) {
+            let old_name = renames
+                .iter()
+                .find(|(_, new_name)| *new_name == new_column.name)
+                .map_or(new_column.name.as_str(), |(old_name, _)| old_name.as_str());
+            if let Some(old_column) = old_table.columns.iter().find(|c| c.name == old_name) {
                 // 检测类型变更
                 if old_column.column_type != new_column.column_type {
                     changes.push(ColumnChange::TypeChanged {
@@ -1262,17 +1876,194 @@ impl SchemaDiffer {
     }
 }
 
+/// 数据库 Schema 内省器
+///
+/// 读取线上数据库的表和列定义，用于与迁移文件推导出的 Schema 对比。
+/// 列类型保留数据库声明的类型名（[`ColumnType::Custom`]），迁移历史表和锁表不计入结果。
+pub struct SchemaIntrospector;
+
+impl SchemaIntrospector {
+    /// 内省当前数据库的 Schema
+    pub async fn introspect<C: crate::orm::ConnectionTrait>(
+        conn: &C,
+        db_type: DatabaseType,
+    ) -> Result<Schema, crate::config::DbError> {
+        match db_type {
+            DatabaseType::Sqlite => Self::introspect_sqlite(conn).await,
+            DatabaseType::Postgres | DatabaseType::MySql => Self::introspect_information_schema(conn, db_type).await,
+        }
+    }
+
+    /// SQLite：`sqlite_master` + `PRAGMA table_info`
+    async fn introspect_sqlite<C: crate::orm::ConnectionTrait>(conn: &C) -> Result<Schema, crate::config::DbError> {
+        use crate::config::DbError;
+
+        let backend = conn.get_database_backend();
+        let tables = conn
+            .query_all_raw(crate::orm::Statement::from_string(
+                backend,
+                "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+            ))
+            .await
+            .map_err(DbError::Connection)?;
+
+        let mut schema = Schema::new(DatabaseType::Sqlite);
+        for row in tables {
+            let table_name: String = row.try_get("", "name").map_err(DbError::Connection)?;
+            if Self::is_internal_table(&table_name) {
+                continue;
+            }
+
+            let columns = conn
+                .query_all_raw(crate::orm::Statement::from_string(
+                    backend,
+                    format!("PRAGMA table_info(\"{}\")", table_name.replace('"', "\"\"")),
+                ))
+                .await
+                .map_err(DbError::Connection)?;
+
+            let mut table_columns = Vec::with_capacity(columns.len());
+            for column in columns {
+                let is_primary_key = column.try_get::<i64>("", "pk").map_err(DbError::Connection)? > 0;
+                let not_null = column.try_get::<i64>("", "notnull").map_err(DbError::Connection)? != 0;
+                let declared: String = column.try_get("", "type").unwrap_or_default();
+                let default_value: Option<String> = column.try_get("", "dflt_value").unwrap_or(None);
+                table_columns.push(Self::column(
+                    column.try_get("", "name").map_err(DbError::Connection)?,
+                    &declared,
+                    is_primary_key,
+                    !not_null && !is_primary_key,
+                    default_value,
+                ));
+            }
+            schema.add_table(Table::new(&table_name, table_columns));
+        }
+
+        Ok(schema)
+    }
+
+    /// PostgreSQL / MySQL：`information_schema.columns`
+    async fn introspect_information_schema<C: crate::orm::ConnectionTrait>(
+        conn: &C,
+        db_type: DatabaseType,
+    ) -> Result<Schema, crate::config::DbError> {
+        use crate::config::DbError;
+
+        let sql = match db_type {
+            DatabaseType::Postgres => {
+                "SELECT CAST(c.table_name AS TEXT) AS table_name, CAST(c.column_name AS TEXT) AS column_name, \
+                 CAST(c.data_type AS TEXT) AS data_type, CAST(c.is_nullable AS TEXT) AS is_nullable, \
+                 CAST(c.column_default AS TEXT) AS column_default, \
+                 CAST(CASE WHEN k.column_name IS NULL THEN 0 ELSE 1 END AS BIGINT) AS is_primary_key \
+                 FROM information_schema.columns c \
+                 LEFT JOIN information_schema.table_constraints t \
+                   ON t.table_schema = c.table_schema AND t.table_name = c.table_name AND t.constraint_type = 'PRIMARY KEY' \
+                 LEFT JOIN information_schema.key_column_usage k \
+                   ON k.constraint_name = t.constraint_name AND k.table_schema = c.table_schema \
+                   AND k.table_name = c.table_name AND k.column_name = c.column_name \
+                 WHERE c.table_schema = current_schema() \
+                 ORDER BY c.table_name, c.ordinal_position"
+            }
+            _ => {
+                "SELECT CAST(TABLE_NAME AS CHAR) AS table_name, CAST(COLUMN_NAME AS CHAR) AS column_name, \
+                 CAST(COLUMN_TYPE AS CHAR) AS data_type, CAST(IS_NULLABLE AS CHAR) AS is_nullable, \
+                 CAST(COLUMN_DEFAULT AS CHAR) AS column_default, \
+                 CAST(CASE WHEN COLUMN_KEY = 'PRI' THEN 1 ELSE 0 END AS SIGNED) AS is_primary_key \
+                 FROM information_schema.columns \
+                 WHERE TABLE_SCHEMA = DATABASE() \
+                 ORDER BY TABLE_NAME, ORDINAL_POSITION"
+            }
+        };
+
+        let rows = conn
+            .query_all_raw(crate::orm::Statement::from_string(conn.get_database_backend(), sql))
+            .await
+            .map_err(DbError::Connection)?;
+
+        let mut grouped: Vec<(String, Vec<Column>)> = Vec::new();
+        for row in rows {
+            let table_name: String = row.try_get("", "table_name").map_err(DbError::Connection)?;
+            if Self::is_internal_table(&table_name) {
+                continue;
+            }
+
+            let is_primary_key = row.try_get::<i64>("", "is_primary_key").map_err(DbError::Connection)? == 1;
+            let is_nullable: String = row.try_get("", "is_nullable").map_err(DbError::Connection)?;
+            let declared: String = row.try_get("", "data_type").map_err(DbError::Connection)?;
+            let column = Self::column(
+                row.try_get("", "column_name").map_err(DbError::Connection)?,
+                &declared,
+                is_primary_key,
+                is_nullable.eq_ignore_ascii_case("YES"),
+                row.try_get("", "column_default").unwrap_or(None),
+            );
+
+            match grouped.last_mut() {
+                Some((name, columns)) if *name == table_name => columns.push(column),
+                _ => grouped.push((table_name, vec![column])),
+            }
+        }
+
+        let mut schema = Schema::new(db_type);
+        for (table_name, columns) in grouped {
+            schema.add_table(Table::new(&table_name, columns));
+        }
+        Ok(schema)
+    }
+
+    /// 迁移工具自身使用的表
+    fn is_internal_table(table_name: &str) -> bool {
+        table_name == "dbnexus_migrations" || table_name == MIGRATION_LOCK_TABLE
+    }
+
+    fn column(
+        name: String,
+        declared_type: &str,
+        is_primary_key: bool,
+        is_nullable: bool,
+        default_value: Option<String>,
+    ) -> Column {
+        Column {
+            name,
+            column_type: ColumnType::Custom(declared_type.trim().to_uppercase()),
+            is_primary_key,
+            is_nullable,
+            has_default: default_value.is_some(),
+            default_value,
+            is_auto_increment: false,
+            comment: None,
+        }
+    }
+}
+
+/// SQLite 重建表时使用的临时表名后缀
+const SQLITE_REBUILD_SUFFIX: &str = "__dbnexus_rebuild";
+
 /// SQL 生成器
 #[derive(Debug, Clone)]
 pub struct SqlGenerator {
     /// 数据库类型
     pub db_type: DatabaseType,
+    /// SQLite 删除列时是否重建表（兼容 3.35 之前不支持 `DROP COLUMN` 的版本）
+    pub sqlite_rebuild_on_drop_column: bool,
 }
 
 impl SqlGenerator {
     /// 创建新的 SQLGenerator
     pub fn new(db_type: DatabaseType) -> Self {
-        Self { db_type }
+        Self {
+            db_type,
+            sqlite_rebuild_on_drop_column: false,
+        }
+    }
+
+    /// SQLite 删除列时改为重建表（新建表、复制数据、删除旧表、重命名）
+    ///
+    /// 需要通过 [`generate_migration_sql_with_schema`](Self::generate_migration_sql_with_schema)
+    /// 提供源 Schema 才能得到完整的表定义；对其他数据库无影响
+    pub fn with_sqlite_table_rebuild(mut self, enabled: bool) -> Self {
+        self.sqlite_rebuild_on_drop_column = enabled;
+        self
     }
 
     /// 生成列定义的 SQL（仅类型部分，用于测试）
@@ -1292,8 +2083,8 @@ impl SqlGenerator {
 
         sql.push_str(&column_defs.join(",\n"));
 
-        // 添加主键约束
-        if !table.primary_key_columns.is_empty() {
+        // 添加主键约束（SQLite 自增主键已在列级声明）
+        if !table.primary_key_columns.is_empty() && !self.sqlite_autoincrement_key(table) {
             sql.push_str(",\n");
             sql.push_str(&format!("    PRIMARY KEY ({})", table.primary_key_columns.join(", ")));
         }
@@ -1305,6 +2096,7 @@ impl SqlGenerator {
             if !index.is_constraint {
                 sql.push_str("\n\n");
                 sql.push_str(&self.generate_create_index_sql(index));
+                sql.push(';');
             }
         }
 
@@ -1317,12 +2109,38 @@ impl SqlGenerator {
         sql
     }
 
+    /// SQLite 单列自增主键需要写成列级 `INTEGER PRIMARY KEY AUTOINCREMENT`，不能再声明表级主键
+    fn sqlite_autoincrement_key(&self, table: &Table) -> bool {
+        self.db_type == DatabaseType::Sqlite
+            && table.primary_key_columns.len() == 1
+            && table
+                .columns
+                .iter()
+                .any(|c| c.name == table.primary_key_columns[0] && c.is_auto_increment)
+    }
+
     /// 生成列定义
-    fn generate_column_definition(&self, column: &Column, _pk_columns: &[String]) -> String {
-        let mut def = format!("    {} {}", column.name, column.column_type.to_sql(self.db_type));
+    ///
+    /// 自增只对单列主键生效；复合主键的列不输出列级 `PRIMARY KEY` / `AUTOINCREMENT`，只由表级约束声明
+    fn generate_column_definition(&self, column: &Column, pk_columns: &[String]) -> String {
+        let in_pk = pk_columns.contains(&column.name);
+        let auto_increment = column.is_auto_increment && in_pk && pk_columns.len() == 1;
+        if column.is_auto_increment && in_pk && !auto_increment {
+            tracing::warn!(
+                "Auto-increment ignored for column {} in composite primary key ({})",
+                column.name,
+                pk_columns.join(", ")
+            );
+        }
 
-        // 自增列不需要指定
-        if column.is_auto_increment && column.is_primary_key {
+        let column_type = match self.db_type {
+            // SQLite 的 AUTOINCREMENT 只能用于 INTEGER PRIMARY KEY
+            DatabaseType::Sqlite if auto_increment => "INTEGER".to_string(),
+            _ => column.column_type.to_sql(self.db_type),
+        };
+        let mut def = format!("    {} {}", column.name, column_type);
+
+        if auto_increment {
             match self.db_type {
                 DatabaseType::MySql => def.push_str(" AUTO_INCREMENT"),
                 DatabaseType::Sqlite => def.push_str(" PRIMARY KEY AUTOINCREMENT"),
@@ -1338,24 +2156,59 @@ impl SqlGenerator {
             def.push_str(&format!(" DEFAULT {}", default));
         }
 
-        // 主键列如果有自增，不需要单独 PRIMARY KEY
-        if column.is_primary_key && !column.is_auto_increment {
-            // 主键已在表级别处理
-        }
-
         def
     }
 
     /// 生成创建索引的 SQL
+    ///
+    /// 索引方法和部分索引条件按数据库能力渲染，不支持的部分忽略并记录警告：
+    /// - PostgreSQL: `USING method` 与 `WHERE` 都支持
+    /// - MySQL: 只支持 `BTREE` / `HASH` 方法，不支持部分索引
+    /// - SQLite: 支持部分索引，不支持索引方法
     pub fn generate_create_index_sql(&self, index: &Index) -> String {
         let unique = if index.is_unique { "UNIQUE " } else { "" };
-        format!(
-            "CREATE {}INDEX {} ON {} ({})",
-            unique,
-            index.name,
-            index.table_name,
-            index.columns.join(", ")
-        )
+        let method = index.method.as_deref().map(str::trim).filter(|m| !m.is_empty());
+        let where_clause = index.where_clause.as_deref().map(str::trim).filter(|w| !w.is_empty());
+
+        let method = match (self.db_type, method) {
+            (DatabaseType::Postgres, Some(method)) => Some(method),
+            (DatabaseType::MySql, Some(method))
+                if method.eq_ignore_ascii_case("BTREE") || method.eq_ignore_ascii_case("HASH") =>
+            {
+                Some(method)
+            }
+            (_, Some(method)) => {
+                tracing::warn!(
+                    "Index method '{}' is not supported on {:?}, ignored for index {}",
+                    method,
+                    self.db_type,
+                    index.name
+                );
+                None
+            }
+            (_, None) => None,
+        };
+        let where_clause = match (self.db_type, where_clause) {
+            (DatabaseType::MySql, Some(clause)) => {
+                tracing::warn!(
+                    "Partial indexes are not supported on MySQL, condition '{}' ignored for index {}",
+                    clause,
+                    index.name
+                );
+                None
+            }
+            (_, clause) => clause,
+        };
+
+        let mut sql = format!("CREATE {}INDEX {} ON {}", unique, index.name, index.table_name);
+        if let Some(method) = method {
+            sql.push_str(&format!(" USING {}", method.to_uppercase()));
+        }
+        sql.push_str(&format!(" ({})", index.columns.join(", ")));
+        if let Some(clause) = where_clause {
+            sql.push_str(&format!(" WHERE {}", clause));
+        }
+        sql
     }
 
     /// 生成添加外键的 SQL
@@ -1389,27 +2242,92 @@ impl SqlGenerator {
     }
 
     /// 生成删除列的 SQL
+    ///
+    /// SQLite 需要 3.35+ 才支持 `DROP COLUMN`，旧版本使用 [`Self::generate_rebuild_drop_columns_sql`]
     pub fn generate_drop_column_sql(&self, table_name: &str, column_name: &str) -> String {
         match self.db_type {
-            DatabaseType::MySql => {
+            DatabaseType::MySql | DatabaseType::Postgres | DatabaseType::Sqlite => {
                 format!("ALTER TABLE {} DROP COLUMN {};", table_name, column_name)
             }
-            DatabaseType::Postgres => {
-                format!("ALTER TABLE {} DROP COLUMN {};", table_name, column_name)
-            }
-            DatabaseType::Sqlite => {
-                // SQLite 不支持直接删除列，需要重建表
-                format!(
-                    "-- SQLite 不支持直接删除列，请手动重建表 {}
-ALTER TABLE {} DROP COLUMN {};",
-                    table_name, table_name, column_name
-                )
+        }
+    }
+
+    /// 通过重建表删除列（用于不支持 `DROP COLUMN` 的 SQLite 版本）
+    ///
+    /// 依次新建不含被删除列的临时表、复制数据、删除旧表、将临时表重命名为原表名，
+    /// 最后重建不涉及被删除列的索引和外键。
+    pub fn generate_rebuild_drop_columns_sql(&self, table: &Table, column_names: &[String]) -> String {
+        let kept = |name: &String| !column_names.contains(name);
+        let temp_name = format!("{}{}", table.name, SQLITE_REBUILD_SUFFIX);
+
+        let rebuilt = Table {
+            name: temp_name.clone(),
+            columns: table.columns.iter().filter(|c| kept(&c.name)).cloned().collect(),
+            primary_key_columns: table.primary_key_columns.iter().filter(|c| kept(c)).cloned().collect(),
+            indexes: Vec::new(),
+            foreign_keys: Vec::new(),
+            comment: table.comment.clone(),
+        };
+        let columns = rebuilt
+            .columns
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut statements = vec![
+            self.generate_create_table_sql(&rebuilt),
+            format!(
+                "INSERT INTO {} ({}) SELECT {} FROM {};",
+                temp_name, columns, columns, table.name
+            ),
+            self.generate_drop_table_sql(&table.name),
+            format!("ALTER TABLE {} RENAME TO {};", temp_name, table.name),
+        ];
+        statements.extend(
+            table
+                .indexes
+                .iter()
+                .filter(|index| !index.is_constraint && index.columns.iter().all(kept))
+                .map(|index| format!("{};", self.generate_create_index_sql(index))),
+        );
+        statements.extend(
+            table
+                .foreign_keys
+                .iter()
+                .filter(|fk| kept(&fk.column_name))
+                .map(|fk| self.generate_add_foreign_key_sql(fk)),
+        );
+
+        statements.join("\n")
+    }
+
+    /// 生成重命名列的 SQL
+    ///
+    /// 要求 PostgreSQL 任意版本、MySQL 8.0+ 或 SQLite 3.25+
+    pub fn generate_rename_column_sql(&self, table_name: &str, old_name: &str, new_name: &str) -> String {
+        match self.db_type {
+            DatabaseType::MySql | DatabaseType::Postgres | DatabaseType::Sqlite => {
+                format!("ALTER TABLE {} RENAME COLUMN {} TO {};", table_name, old_name, new_name)
             }
         }
     }
 
     /// 生成迁移的完整 SQL
     pub fn generate_migration_sql(&self, migration: &Migration) -> String {
+        self.render_migration_sql(migration, None)
+    }
+
+    /// 生成迁移的完整 SQL，从源 Schema 获取被修改表的完整定义
+    ///
+    /// 启用 [`with_sqlite_table_rebuild`](Self::with_sqlite_table_rebuild) 时，SQLite 的删除列
+    /// 在其他列变更之前以重建表的方式执行；源 Schema 中找不到表定义时仍生成 `DROP COLUMN`
+    pub fn generate_migration_sql_with_schema(&self, migration: &Migration, old_schema: &Schema) -> String {
+        self.render_migration_sql(migration, Some(old_schema))
+    }
+
+    /// 生成迁移 SQL
+    fn render_migration_sql(&self, migration: &Migration, old_schema: Option<&Schema>) -> String {
         let mut sql = String::new();
 
         for change in &migration.table_changes {
@@ -1426,23 +2344,42 @@ ALTER TABLE {} DROP COLUMN {};",
                 }
                 TableChange::AlterTable {
                     table_name,
+                    column_changes,
                     added_columns,
                     removed_columns,
                     added_indexes,
                     removed_indexes,
                     added_foreign_keys,
                     removed_foreign_keys,
-                    ..
                 } => {
                     sql.push_str(&format!("-- 修改表: {}\n", table_name));
 
+                    // 重建表基于源表定义，需在新增、重命名等列变更之前执行
+                    let rebuild_table = old_schema
+                        .filter(|_| self.db_type == DatabaseType::Sqlite && self.sqlite_rebuild_on_drop_column)
+                        .filter(|_| !removed_columns.is_empty())
+                        .and_then(|schema| schema.get_table(table_name));
+                    if let Some(table) = rebuild_table {
+                        sql.push_str(&format!("-- 重建表以删除列: {}\n", removed_columns.join(", ")));
+                        sql.push_str(&self.generate_rebuild_drop_columns_sql(table, removed_columns));
+                        sql.push('\n');
+                    }
+
+                    for change in column_changes {
+                        if let ColumnChange::Renamed { old_name, new_name } = change {
+                            sql.push_str(&format!("-- 重命名列: {} -> {}\n", old_name, new_name));
+                            sql.push_str(&self.generate_rename_column_sql(table_name, old_name, new_name));
+                            sql.push('\n');
+                        }
+                    }
+
                     for col in added_columns {
                         sql.push_str(&format!("-- 添加列: {}\n", col.name));
                         sql.push_str(&self.generate_add_column_sql(table_name, col));
                         sql.push('\n');
                     }
 
-                    for col_name in removed_columns {
+                    for col_name in removed_columns.iter().filter(|_| rebuild_table.is_none()) {
                         sql.push_str(&format!("-- 删除列: {}\n", col_name));
                         sql.push_str(&self.generate_drop_column_sql(table_name, col_name));
                         sql.push('\n');
@@ -1451,7 +2388,7 @@ ALTER TABLE {} DROP COLUMN {};",
                     for index in added_indexes {
                         sql.push_str(&format!("-- 添加索引: {}\n", index.name));
                         sql.push_str(&self.generate_create_index_sql(index));
-                        sql.push('\n');
+                        sql.push_str(";\n");
                     }
 
                     for index_name in removed_indexes {
@@ -1522,20 +2459,7 @@ impl RustEntityParser {
         // 实际实现需要完整的 Rust 解析器 (syn/quote)
         let columns = Self::extract_columns_from_code(entity_code)?;
 
-        let primary_key_columns = columns
-            .iter()
-            .filter(|c| c.is_primary_key)
-            .map(|c| c.name.clone())
-            .collect();
-
-        Ok(Table {
-            name: table_name.to_string(),
-            columns,
-            primary_key_columns,
-            indexes: Vec::new(),
-            foreign_keys: Vec::new(),
-            comment: None,
-        })
+        Ok(Table::new(table_name, columns))
     }
 
     /// 从代码中提取列信息
@@ -1549,9 +2473,9 @@ impl RustEntityParser {
         let mut current_field_type: Option<String> = None;
         let mut current_column_type: Option<ColumnType> = None;
         let mut field_column_type: Option<ColumnType> = None; // 当前字段开始前设置的 column_type
-        let mut is_primary_key = false;
-        let mut is_nullable = true;
-        let mut is_auto_increment = false;
+        // 属性行出现在字段行之前：先记在 pending 中，遇到字段行时归属于该字段
+        let (mut pending_primary_key, mut pending_nullable, mut pending_auto_increment) = (false, true, false);
+        let (mut is_primary_key, mut is_nullable, mut is_auto_increment) = (false, true, false);
 
         for line in &lines {
             let line = line.trim();
@@ -1578,15 +2502,13 @@ impl RustEntityParser {
                             });
                         }
                     }
-
-                    // 保存完后重置属性，为新字段做准备
-                    is_primary_key = false;
-                    is_nullable = true;
-                    is_auto_increment = false;
                 }
 
-                // 将当前属性行的 column_type 移到 field_column_type
+                // 将当前属性行的 column_type 与主键等标记移交给新字段
                 field_column_type = current_column_type.take();
+                is_primary_key = std::mem::take(&mut pending_primary_key);
+                is_nullable = std::mem::replace(&mut pending_nullable, true);
+                is_auto_increment = std::mem::take(&mut pending_auto_increment);
 
                 // 设置新字段
                 current_field_name = Some(field_name);
@@ -1599,19 +2521,21 @@ impl RustEntityParser {
                 current_column_type = Self::extract_column_type(line);
             }
 
-            // 检测主键
+            // 检测主键（可以有多个，组成复合主键）
             if line.contains("primary_key") {
-                is_primary_key = true;
+                pending_primary_key = true;
             }
 
             // 检测可空性
             if line.contains("NotNull") || line.contains("not_null") {
-                is_nullable = false;
+                pending_nullable = false;
             }
 
-            // 检测自增
-            if line.contains("AutoIncrement") || line.contains("auto_increment") {
-                is_auto_increment = true;
+            // 检测自增（复合主键实体会显式写 auto_increment = false）
+            if (line.contains("AutoIncrement") || line.contains("auto_increment"))
+                && !line.replace(' ', "").contains("auto_increment=false")
+            {
+                pending_auto_increment = true;
             }
 
             // 如果遇到新属性行，跳过
@@ -2017,4 +2941,438 @@ pub struct Model {
         );
         assert_eq!(RustEntityParser::parse_column_type_str("Json"), ColumnType::Json);
     }
+
+    /// TEST-U-065: Rust 类型到列类型的映射
+    #[test]
+    fn test_column_type_from_rust_type() {
+        assert_eq!(ColumnType::from_rust_type("i32"), ColumnType::Integer);
+        assert_eq!(ColumnType::from_rust_type("i64"), ColumnType::BigInteger);
+        assert_eq!(
+            ColumnType::from_rust_type("Option < String >"),
+            ColumnType::String(Some(255))
+        );
+        assert_eq!(ColumnType::from_rust_type("bool"), ColumnType::Boolean);
+        assert_eq!(
+            ColumnType::from_rust_type("chrono::DateTime<chrono::Utc>"),
+            ColumnType::Timestamp
+        );
+        assert_eq!(
+            ColumnType::from_rust_type("chrono::NaiveDateTime"),
+            ColumnType::DateTime
+        );
+        assert_eq!(ColumnType::from_rust_type("serde_json::Value"), ColumnType::Json);
+        assert_eq!(ColumnType::from_rust_type("Vec<u8>"), ColumnType::Binary);
+        assert_eq!(
+            ColumnType::from_rust_type("std::option::Option<i64>"),
+            ColumnType::BigInteger
+        );
+        assert!(Column::from_field("parent_id", "::core::option::Option<i64>", false).is_nullable);
+        assert_eq!(
+            ColumnType::from_rust_type("Uuid"),
+            ColumnType::Custom("Uuid".to_string())
+        );
+    }
+
+    /// TEST-U-066: 派生宏生成的 migration_table 与手写表结构一致
+    #[test]
+    fn test_migration_table_matches_hand_built() {
+        struct User;
+
+        // 与 #[derive(DbEntity)] 为下列实体生成的实现一致（派生宏本身见 TEST-M-032）：
+        // #[table_name = "users"] struct User { #[primary_key] id: i64, email: String, nickname: Option<String> }
+        impl ToMigrationTable for User {
+            fn migration_table() -> Table {
+                Table::new(
+                    "users",
+                    vec![
+                        Column::typed("id", ColumnType::BigInteger, true, false),
+                        Column::typed("email", ColumnType::String(Some(255)), false, false),
+                        Column::typed("nickname", ColumnType::String(Some(255)), false, true),
+                    ],
+                )
+            }
+        }
+
+        let column = |name: &str, column_type: ColumnType, is_primary_key: bool, is_nullable: bool| Column {
+            name: name.to_string(),
+            column_type,
+            is_primary_key,
+            is_nullable,
+            has_default: false,
+            default_value: None,
+            is_auto_increment: false,
+            comment: None,
+        };
+        let expected = Table {
+            name: "users".to_string(),
+            columns: vec![
+                column("id", ColumnType::BigInteger, true, false),
+                column("email", ColumnType::String(Some(255)), false, false),
+                column("nickname", ColumnType::String(Some(255)), false, true),
+            ],
+            primary_key_columns: vec!["id".to_string()],
+            indexes: Vec::new(),
+            foreign_keys: Vec::new(),
+            comment: None,
+        };
+
+        assert_eq!(User::migration_table(), expected);
+    }
+
+    /// TEST-U-109: Decimal 映射为 DECIMAL 定点数，不再按双精度浮点存储
+    #[test]
+    fn test_decimal_maps_to_fixed_point() {
+        for rust_type in [
+            "Decimal",
+            "rust_decimal::Decimal",
+            "Option<Decimal>",
+            "bigdecimal::BigDecimal",
+        ] {
+            let column_type = ColumnType::from_rust_type(rust_type);
+            assert_eq!(column_type, ColumnType::Custom("DECIMAL".to_string()), "{rust_type}");
+            for db_type in [DatabaseType::Postgres, DatabaseType::MySql, DatabaseType::Sqlite] {
+                assert_eq!(column_type.to_sql(db_type), "DECIMAL");
+                assert_eq!(ColumnType::from_sql(&column_type.to_sql(db_type), db_type), column_type);
+            }
+        }
+        assert_eq!(ColumnType::from_rust_type("f64"), ColumnType::Double);
+    }
+
+    /// TEST-U-069: 解析 no-transaction 指令
+    #[test]
+    fn test_requires_no_transaction() {
+        let directive =
+            "-- Migration: index\n-- dbnexus:no-transaction\n\n-- UP\nCREATE INDEX CONCURRENTLY idx ON t (c);";
+        assert!(MigrationFileParser::requires_no_transaction(directive));
+        assert!(MigrationFileParser::requires_no_transaction(
+            "  -- DBNEXUS:NO-TRANSACTION  \n-- UP"
+        ));
+
+        let plain = "-- Migration: table\n\n-- UP\nCREATE TABLE t (id INTEGER);";
+        assert!(!MigrationFileParser::requires_no_transaction(plain));
+    }
+
+    /// TEST-U-070: 迁移文件名解析支持大版本号与带下划线的描述
+    #[test]
+    fn test_parse_migration_filename() {
+        assert_eq!(
+            parse_migration_filename("0001_add_user_table.sql"),
+            Some((1, "add_user_table".to_string()))
+        );
+        assert_eq!(
+            parse_migration_filename("v1_0_init.sql"),
+            Some((1, "0_init".to_string()))
+        );
+
+        // 超出 u32 范围的秒级 / 毫秒级时间戳
+        assert_eq!(
+            parse_migration_filename("4294967296_create.sql"),
+            Some((4_294_967_296, "create".to_string()))
+        );
+        assert_eq!(
+            parse_migration_filename("1735689600000_add_orders.sql"),
+            Some((1_735_689_600_000, "add_orders".to_string()))
+        );
+
+        assert_eq!(parse_migration_filename("init.sql"), None);
+        assert_eq!(parse_migration_filename("18446744073709551615_overflow.sql"), None);
+    }
+
+    /// TEST-U-089: 从数据库类型名称解析列类型，并与 to_sql 往返一致
+    #[test]
+    fn test_column_type_from_sql() {
+        let variants = [
+            ColumnType::Integer,
+            ColumnType::BigInteger,
+            ColumnType::String(None),
+            ColumnType::String(Some(64)),
+            ColumnType::Text,
+            ColumnType::Boolean,
+            ColumnType::Float,
+            ColumnType::Double,
+            ColumnType::Date,
+            ColumnType::Time,
+            ColumnType::DateTime,
+            ColumnType::Timestamp,
+            ColumnType::Json,
+            ColumnType::Binary,
+            ColumnType::Custom("UUID".to_string()),
+        ];
+
+        for db_type in [DatabaseType::Postgres, DatabaseType::MySql, DatabaseType::Sqlite] {
+            for variant in &variants {
+                let sql = variant.to_sql(db_type);
+                let parsed = ColumnType::from_sql(&sql, db_type);
+                assert_eq!(
+                    parsed.to_sql(db_type),
+                    sql,
+                    "{:?} on {:?} parsed as {:?}",
+                    variant,
+                    db_type,
+                    parsed
+                );
+            }
+        }
+
+        // 名称不冲突时还原为原始变体
+        let pg = DatabaseType::Postgres;
+        assert_eq!(ColumnType::from_sql("varchar(255)", pg), ColumnType::String(Some(255)));
+        assert_eq!(ColumnType::from_sql("VARCHAR", pg), ColumnType::String(None));
+        assert_eq!(ColumnType::from_sql("bigint", pg), ColumnType::BigInteger);
+        assert_eq!(ColumnType::from_sql("JSONB", pg), ColumnType::Json);
+        assert_eq!(ColumnType::from_sql("double precision", pg), ColumnType::Double);
+        assert_eq!(ColumnType::from_sql("TIMESTAMP", pg), ColumnType::DateTime);
+        assert_eq!(
+            ColumnType::from_sql("timestamp(6)  with time zone", pg),
+            ColumnType::Timestamp
+        );
+        assert_eq!(
+            ColumnType::from_sql("character varying(32)", pg),
+            ColumnType::String(Some(32))
+        );
+        assert_eq!(ColumnType::from_sql("bytea", pg), ColumnType::Binary);
+
+        let mysql = DatabaseType::MySql;
+        assert_eq!(ColumnType::from_sql("tinyint(1)", mysql), ColumnType::Boolean);
+        assert_eq!(ColumnType::from_sql("TINYINT(4)", mysql), ColumnType::Integer);
+        assert_eq!(ColumnType::from_sql("TIMESTAMP", mysql), ColumnType::Timestamp);
+        assert_eq!(ColumnType::from_sql("datetime", mysql), ColumnType::DateTime);
+        assert_eq!(ColumnType::from_sql("json", mysql), ColumnType::Json);
+
+        let sqlite = DatabaseType::Sqlite;
+        assert_eq!(ColumnType::from_sql("text", sqlite), ColumnType::Text);
+        assert_eq!(ColumnType::from_sql("integer", sqlite), ColumnType::Integer);
+        assert_eq!(ColumnType::from_sql("TIMESTAMP", sqlite), ColumnType::Timestamp);
+
+        // 未知类型保留原始写法
+        assert_eq!(
+            ColumnType::from_sql(" numeric(10, 2) ", pg),
+            ColumnType::Custom("numeric(10, 2)".to_string())
+        );
+    }
+
+    /// TEST-U-090: 重命名提示与启发式检测生成 RENAME COLUMN，否则为删除加新增
+    #[test]
+    fn test_schema_diff_renamed_column() {
+        let schema_with = |email_column: &str| {
+            let mut schema = Schema::new(DatabaseType::Postgres);
+            schema.add_table(Table::new(
+                "users",
+                vec![
+                    Column::from_field("id", "i64", true),
+                    Column::from_field(email_column, "String", false),
+                    Column::from_field("age", "i32", false),
+                ],
+            ));
+            schema
+        };
+        let alter_changes = |migrations: Vec<Migration>| match migrations[0].table_changes[0].clone() {
+            TableChange::AlterTable {
+                column_changes,
+                added_columns,
+                removed_columns,
+                ..
+            } => (column_changes, added_columns, removed_columns),
+            other => panic!("Expected AlterTable change, got {:?}", other),
+        };
+        let renamed = ColumnChange::Renamed {
+            old_name: "email".to_string(),
+            new_name: "email_address".to_string(),
+        };
+        let pg = SqlGenerator::new(DatabaseType::Postgres);
+
+        // 未提供提示：删除加新增
+        let migrations = SchemaDiffer::new(schema_with("email"), schema_with("email_address")).diff();
+        let sql = pg.generate_migration_sql(&migrations[0]);
+        let (changes, added, removed) = alter_changes(migrations);
+        assert!(changes.is_empty());
+        assert_eq!(added.len(), 1);
+        assert_eq!(removed, vec!["email".to_string()]);
+        assert!(sql.contains("DROP COLUMN email;"));
+        assert!(!sql.contains("RENAME COLUMN"));
+
+        // 重命名提示
+        let migrations = SchemaDiffer::new(schema_with("email"), schema_with("email_address"))
+            .with_column_rename("users", "email", "email_address")
+            .diff();
+        let sql = pg.generate_migration_sql(&migrations[0]);
+        let (changes, added, removed) = alter_changes(migrations);
+        assert_eq!(changes, vec![renamed.clone()]);
+        assert!(added.is_empty());
+        assert!(removed.is_empty());
+        assert!(sql.contains("ALTER TABLE users RENAME COLUMN email TO email_address;"));
+        assert!(!sql.contains("DROP COLUMN"));
+
+        // 启发式检测：同位置、同类型
+        let migrations = SchemaDiffer::new(schema_with("email"), schema_with("email_address"))
+            .with_rename_detection(true)
+            .diff();
+        let (changes, _, removed) = alter_changes(migrations);
+        assert_eq!(changes, vec![renamed]);
+        assert!(removed.is_empty());
+
+        // 类型不同的同位置列不视为重命名
+        let mut changed_type = Schema::new(DatabaseType::Postgres);
+        changed_type.add_table(Table::new(
+            "users",
+            vec![
+                Column::from_field("id", "i64", true),
+                Column::from_field("email_verified", "bool", false),
+                Column::from_field("age", "i32", false),
+            ],
+        ));
+        let migrations = SchemaDiffer::new(schema_with("email"), changed_type)
+            .with_rename_detection(true)
+            .diff();
+        let (changes, added, removed) = alter_changes(migrations);
+        assert!(changes.is_empty());
+        assert_eq!(added.len(), 1);
+        assert_eq!(removed, vec!["email".to_string()]);
+
+        // 各后端均生成 RENAME COLUMN
+        for db_type in [DatabaseType::MySql, DatabaseType::Sqlite] {
+            assert_eq!(
+                SqlGenerator::new(db_type).generate_rename_column_sql("users", "email", "email_address"),
+                "ALTER TABLE users RENAME COLUMN email TO email_address;"
+            );
+        }
+    }
+
+    /// TEST-U-091: SQLite 删除列默认生成 DROP COLUMN，启用兼容模式时重建表
+    #[test]
+    fn test_sqlite_drop_column_sql() {
+        let sqlite = SqlGenerator::new(DatabaseType::Sqlite);
+        assert_eq!(
+            sqlite.generate_drop_column_sql("users", "email"),
+            "ALTER TABLE users DROP COLUMN email;"
+        );
+
+        let mut index = Index {
+            name: "idx_users_age".to_string(),
+            table_name: "users".to_string(),
+            columns: vec!["age".to_string()],
+            is_unique: false,
+            is_constraint: false,
+            where_clause: None,
+            method: None,
+        };
+        let mut old_table = Table::new(
+            "users",
+            vec![
+                Column::from_field("id", "i64", true),
+                Column::from_field("email", "String", false),
+                Column::from_field("age", "i32", false),
+            ],
+        );
+        old_table.indexes.push(index.clone());
+        index.name = "idx_users_email".to_string();
+        index.columns = vec!["email".to_string()];
+        old_table.indexes.push(index);
+
+        let mut old_schema = Schema::new(DatabaseType::Sqlite);
+        old_schema.add_table(old_table.clone());
+        let mut new_schema = Schema::new(DatabaseType::Sqlite);
+        let mut new_table = old_table.clone();
+        new_table.columns.retain(|c| c.name != "email");
+        new_table.indexes.retain(|i| i.name != "idx_users_email");
+        new_schema.add_table(new_table);
+
+        let migrations = SchemaDiffer::new(old_schema.clone(), new_schema).diff();
+
+        // 未启用兼容模式：只生成 DROP COLUMN，不再混入矛盾的注释
+        let plain = sqlite.generate_migration_sql_with_schema(&migrations[0], &old_schema);
+        assert!(plain.contains("ALTER TABLE users DROP COLUMN email;"));
+        assert!(!plain.contains("不支持"));
+        assert!(!plain.contains(SQLITE_REBUILD_SUFFIX));
+
+        let rebuild = sqlite
+            .clone()
+            .with_sqlite_table_rebuild(true)
+            .generate_migration_sql_with_schema(&migrations[0], &old_schema);
+        assert!(!rebuild.contains("DROP COLUMN"));
+        let expected_order = [
+            "CREATE TABLE users__dbnexus_rebuild (",
+            "INSERT INTO users__dbnexus_rebuild (id, age) SELECT id, age FROM users;",
+            "DROP TABLE users;",
+            "ALTER TABLE users__dbnexus_rebuild RENAME TO users;",
+            "CREATE INDEX idx_users_age ON users (age);",
+        ];
+        let positions: Vec<usize> = expected_order
+            .iter()
+            .map(|part| {
+                rebuild
+                    .find(part)
+                    .unwrap_or_else(|| panic!("Missing {:?} in:\n{}", part, rebuild))
+            })
+            .collect();
+        assert!(
+            positions.windows(2).all(|w| w[0] < w[1]),
+            "Unexpected order:\n{}",
+            rebuild
+        );
+        assert!(!rebuild.contains("CREATE INDEX idx_users_email"));
+
+        // 兼容模式对其他数据库无影响
+        let pg = SqlGenerator::new(DatabaseType::Postgres).with_sqlite_table_rebuild(true);
+        assert!(
+            pg.generate_migration_sql_with_schema(&migrations[0], &old_schema)
+                .contains("ALTER TABLE users DROP COLUMN email;")
+        );
+    }
+
+    /// TEST-U-092: 复合主键只生成表级 PRIMARY KEY，解析器收集多个主键字段
+    #[test]
+    fn test_composite_primary_key() {
+        let mut order_id = Column::from_field("order_id", "i64", true);
+        order_id.is_auto_increment = true;
+        let table = Table::new(
+            "order_items",
+            vec![
+                order_id,
+                Column::from_field("product_id", "i64", true),
+                Column::from_field("quantity", "i32", false),
+            ],
+        );
+
+        for db_type in [DatabaseType::Postgres, DatabaseType::MySql, DatabaseType::Sqlite] {
+            let sql = SqlGenerator::new(db_type).generate_create_table_sql(&table);
+            assert!(
+                sql.contains("PRIMARY KEY (order_id, product_id)"),
+                "{:?}:\n{}",
+                db_type,
+                sql
+            );
+            assert_eq!(sql.matches("PRIMARY KEY").count(), 1, "{:?}:\n{}", db_type, sql);
+            assert!(
+                !sql.contains("AUTO_INCREMENT") && !sql.contains("AUTOINCREMENT"),
+                "{:?}:\n{}",
+                db_type,
+                sql
+            );
+        }
+
+        // SQLite 单列自增主键在列级声明，不再重复表级主键
+        let mut id = Column::from_field("id", "i64", true);
+        id.is_auto_increment = true;
+        let users = Table::new("users", vec![id, Column::from_field("name", "String", false)]);
+        let sql = SqlGenerator::new(DatabaseType::Sqlite).generate_create_table_sql(&users);
+        assert!(sql.contains("id INTEGER PRIMARY KEY AUTOINCREMENT"));
+        assert_eq!(sql.matches("PRIMARY KEY").count(), 1);
+
+        let entity_code = r#"
+#[sea_orm(table_name = "order_items")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub order_id: i64,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub product_id: i64,
+    pub quantity: i32,
+}
+"#;
+        let parsed = RustEntityParser::parse_entity(entity_code, "order_items").unwrap();
+        assert_eq!(parsed.primary_key_columns, vec!["order_id", "product_id"]);
+        assert!(parsed.columns.iter().all(|c| !c.is_auto_increment));
+        let quantity = parsed.columns.iter().find(|c| c.name == "quantity").unwrap();
+        assert!(!quantity.is_primary_key);
+    }
 }
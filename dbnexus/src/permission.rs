@@ -5,13 +5,14 @@
 
 //! 权限控制模块
 //!
-//! 提供基于角色的表级权限控制功能
+//! 提供基于角色的表级权限控制功能，角色可通过 `extends` 继承其他角色的表权限
 
 use lru::LruCache;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::num::NonZeroUsize;
 use std::sync::{Arc, Mutex};
+use thiserror::Error;
 
 /// 权限操作类型
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -41,29 +42,114 @@ impl std::fmt::Display for PermissionAction {
 /// Operation 是 PermissionAction 的别名，用于简化使用
 pub type Operation = PermissionAction;
 
+/// 权限检查错误
+///
+/// 用于区分"策略未加载"与"权限被拒绝"等情况
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PermissionError {
+    /// 角色策略尚未加载到缓存
+    #[error("Permission policy for role '{role}' is not loaded")]
+    PolicyNotLoaded {
+        /// 角色名称
+        role: String,
+    },
+
+    /// 角色没有执行操作的权限
+    #[error("Role '{role}' does not have {operation} permission on table '{table}'")]
+    Denied {
+        /// 角色名称
+        role: String,
+        /// 表名
+        table: String,
+        /// 操作类型
+        operation: PermissionAction,
+    },
+
+    /// 角色无权查询受保护的列
+    #[error("Role '{role}' is not allowed to select column '{column}' on table '{table}'")]
+    ColumnDenied {
+        /// 角色名称
+        role: String,
+        /// 表名
+        table: String,
+        /// 列名
+        column: String,
+    },
+
+    /// 角色未在权限配置中定义
+    #[error("Role '{role}' not found in permission config")]
+    RoleNotFound {
+        /// 角色名称
+        role: String,
+    },
+
+    /// 角色继承关系存在环
+    #[error("Role inheritance cycle detected: {chain}")]
+    InheritanceCycle {
+        /// 形成环的继承链（如 `a -> b -> a`）
+        chain: String,
+    },
+
+    /// 策略缓存锁被破坏
+    #[error("Permission cache mutex poisoned")]
+    CachePoisoned,
+}
+
 /// 表权限配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TablePermission {
     /// 表名（支持通配符 *）
     pub name: String,
 
-    /// 允许的操作列表
+    /// 操作列表（用于 `deny` 时为空表示禁止全部操作）
+    #[serde(default)]
     pub operations: Vec<PermissionAction>,
 }
 
+impl TablePermission {
+    /// 是否匹配表名（支持通配符 *）
+    fn matches_table(&self, table: &str) -> bool {
+        self.name == "*" || self.name.eq_ignore_ascii_case(table)
+    }
+}
+
 /// 角色策略
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RolePolicy {
     /// 角色允许的表权限
+    #[serde(default)]
     pub tables: Vec<TablePermission>,
+
+    /// 继承的父角色，通过 [`PermissionConfig::resolve`] 合并
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extends: Vec<String>,
+
+    /// 禁止的表权限，优先于 `tables` 中的允许规则
+    ///
+    /// 例如 `tables: [{name: "*", ...}]` 搭配 `deny: [{name: secrets}]` 表示"除 secrets 外的所有表"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<TablePermission>,
 }
 
 impl RolePolicy {
+    /// 检查是否存在匹配的禁止规则
+    pub fn denies(&self, table: &str, operation: &PermissionAction) -> bool {
+        self.deny.iter().any(|perm| {
+            perm.matches_table(table) && (perm.operations.is_empty() || perm.operations.contains(operation))
+        })
+    }
+
     /// 检查角色是否有权限执行操作
+    ///
+    /// 匹配的禁止规则优先，其次才检查允许规则
     pub fn allows(&self, table: &str, operation: &PermissionAction) -> bool {
+        if self.denies(table, operation) {
+            return false;
+        }
+
         for perm in &self.tables {
             // 检查表名匹配（支持通配符）
-            if perm.name == "*" || perm.name == table {
+            if perm.matches_table(table) {
                 // 检查操作权限
                 if perm.operations.contains(operation) {
                     return true;
@@ -74,6 +160,78 @@ impl RolePolicy {
     }
 }
 
+/// 列级权限策略
+///
+/// 对应 `#[db_permission(deny_select = ["password_hash"])]`，由生成的 `Entity::column_policy()` 返回：
+/// 除豁免角色（默认 `admin`）外，其他角色查询时不返回受保护的列，显式查询这些列会被拒绝。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnPolicy {
+    /// 表名
+    pub table: String,
+
+    /// 禁止查询的列
+    pub deny_select: Vec<String>,
+
+    /// 不受限制的角色
+    #[serde(default = "default_exempt_roles")]
+    pub exempt_roles: Vec<String>,
+}
+
+fn default_exempt_roles() -> Vec<String> {
+    vec!["admin".to_string()]
+}
+
+impl ColumnPolicy {
+    /// 创建列级权限策略，`admin` 角色默认不受限制
+    pub fn new(table: &str, deny_select: &[&str]) -> Self {
+        Self {
+            table: table.to_string(),
+            deny_select: deny_select.iter().map(|column| column.to_string()).collect(),
+            exempt_roles: default_exempt_roles(),
+        }
+    }
+
+    /// 设置不受限制的角色
+    pub fn with_exempt_roles(mut self, roles: &[&str]) -> Self {
+        self.exempt_roles = roles.iter().map(|role| role.to_string()).collect();
+        self
+    }
+
+    /// 检查角色能否查询指定列
+    pub fn allows_column(&self, role: &str, column: &str) -> bool {
+        self.exempt_roles.iter().any(|exempt| exempt == role)
+            || !self
+                .deny_select
+                .iter()
+                .any(|denied| denied.eq_ignore_ascii_case(column))
+    }
+
+    /// 返回角色可见的列，用于生成默认查询的投影
+    pub fn visible_columns<'a>(&self, role: &str, columns: &[&'a str]) -> Vec<&'a str> {
+        columns
+            .iter()
+            .copied()
+            .filter(|column| self.allows_column(role, column))
+            .collect()
+    }
+
+    /// 检查显式请求的列，遇到受保护的列时返回错误
+    ///
+    /// # Errors
+    ///
+    /// 请求了角色无权查询的列时返回 [`PermissionError::ColumnDenied`]
+    pub fn require_columns(&self, role: &str, columns: &[&str]) -> Result<(), PermissionError> {
+        match columns.iter().find(|column| !self.allows_column(role, column)) {
+            Some(column) => Err(PermissionError::ColumnDenied {
+                role: role.to_string(),
+                table: self.table.clone(),
+                column: column.to_string(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
 /// 权限配置
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PermissionConfig {
@@ -93,13 +251,78 @@ impl PermissionConfig {
         self.roles.get(role)
     }
 
-    /// 检查角色是否有权限
-    pub fn check_access(&self, role: &str, table: &str, operation: PermissionAction) -> bool {
-        if let Some(policy) = self.get_role_policy(role) {
-            policy.allows(table, &operation)
-        } else {
-            false
+    /// 解析角色的最终策略（合并继承的表权限）
+    ///
+    /// 多个父角色的同名表权限取操作并集；角色自身声明的同名表权限覆盖继承值，其余表追加；
+    /// 禁止规则全部累加。
+    /// 返回的策略 `extends` 为空。
+    ///
+    /// # Errors
+    ///
+    /// 角色或其父角色未定义时返回 [`PermissionError::RoleNotFound`]，
+    /// 继承关系存在环时返回 [`PermissionError::InheritanceCycle`]
+    pub fn resolve(&self, role: &str) -> Result<RolePolicy, PermissionError> {
+        self.resolve_with_chain(role, &mut Vec::new())
+    }
+
+    fn resolve_with_chain(&self, role: &str, chain: &mut Vec<String>) -> Result<RolePolicy, PermissionError> {
+        if chain.iter().any(|visited| visited == role) {
+            chain.push(role.to_string());
+            return Err(PermissionError::InheritanceCycle {
+                chain: chain.join(" -> "),
+            });
         }
+
+        let policy = self
+            .get_role_policy(role)
+            .ok_or_else(|| PermissionError::RoleNotFound { role: role.to_string() })?;
+        if policy.extends.is_empty() {
+            return Ok(policy.clone());
+        }
+
+        chain.push(role.to_string());
+        let mut tables: Vec<TablePermission> = Vec::new();
+        let mut deny: Vec<TablePermission> = Vec::new();
+        for parent in &policy.extends {
+            let parent_policy = self.resolve_with_chain(parent, chain)?;
+            deny.extend(parent_policy.deny);
+            for inherited in parent_policy.tables {
+                match tables.iter_mut().find(|perm| perm.name == inherited.name) {
+                    Some(existing) => {
+                        for operation in inherited.operations {
+                            if !existing.operations.contains(&operation) {
+                                existing.operations.push(operation);
+                            }
+                        }
+                    }
+                    None => tables.push(inherited),
+                }
+            }
+        }
+        chain.pop();
+
+        for own in &policy.tables {
+            match tables.iter_mut().find(|perm| perm.name == own.name) {
+                Some(existing) => *existing = own.clone(),
+                None => tables.push(own.clone()),
+            }
+        }
+
+        // 禁止规则只会累加，子角色无法解除父角色的禁止
+        deny.extend(policy.deny.iter().cloned());
+
+        Ok(RolePolicy {
+            tables,
+            extends: Vec::new(),
+            deny,
+        })
+    }
+
+    /// 检查角色是否有权限（包含继承的权限）
+    pub fn check_access(&self, role: &str, table: &str, operation: PermissionAction) -> bool {
+        self.resolve(role)
+            .map(|policy| policy.allows(table, &operation))
+            .unwrap_or(false)
     }
 
     /// 验证配置完整性
@@ -117,11 +340,21 @@ impl PermissionConfig {
 
         // 检查每个角色的配置
         for (role_name, policy) in &self.roles {
-            // 检查角色是否有表权限配置
-            if policy.tables.is_empty() {
+            // 检查角色是否有表权限配置（仅继承父角色也视为有效）
+            if policy.tables.is_empty() && policy.extends.is_empty() {
                 errors.push(format!("Role '{}' has no table permissions defined", role_name));
             }
 
+            // 检查继承关系：父角色必须存在且不能形成环
+            for parent in &policy.extends {
+                if !self.roles.contains_key(parent) {
+                    errors.push(format!("Role '{}' extends undefined role '{}'", role_name, parent));
+                }
+            }
+            if let Err(e @ PermissionError::InheritanceCycle { .. }) = self.resolve(role_name) {
+                errors.push(e.to_string());
+            }
+
             // 检查每个表权限
             for table_perm in &policy.tables {
                 // 检查表名是否为空
@@ -137,6 +370,12 @@ impl PermissionConfig {
                     ));
                 }
             }
+
+            for deny_perm in &policy.deny {
+                if deny_perm.name.trim().is_empty() {
+                    errors.push(format!("Role '{}' has a deny rule with empty name", role_name));
+                }
+            }
         }
 
         if errors.is_empty() { Ok(()) } else { Err(errors) }
@@ -194,15 +433,17 @@ impl PermissionContext {
 
     /// 检查表访问权限
     ///
-    /// 此方法会先检查缓存，如果缓存未命中则加载权限策略到缓存
-    pub fn check_table_access(&self, table: &str, operation: &PermissionAction) -> bool {
-        let mut cache = match self.policy_cache.lock() {
-            Ok(guard) => guard,
-            Err(_) => {
-                tracing::error!("Permission cache mutex poisoned");
-                return false; // 如果锁被破坏，拒绝访问
-            }
-        };
+    /// 仅查询缓存，不会触发策略加载。返回 `Ok(true)` 表示允许，`Ok(false)` 表示拒绝。
+    ///
+    /// # Errors
+    ///
+    /// 如果角色策略未加载，返回 [`PermissionError::PolicyNotLoaded`]；
+    /// 如果缓存锁被破坏，返回 [`PermissionError::CachePoisoned`]
+    pub fn check_table_access(&self, table: &str, operation: &PermissionAction) -> Result<bool, PermissionError> {
+        let mut cache = self.policy_cache.lock().map_err(|_| {
+            tracing::error!("Permission cache mutex poisoned");
+            PermissionError::CachePoisoned
+        })?;
 
         // 尝试从缓存获取
         if let Some(policy) = cache.get(self.role.as_str()) {
@@ -214,38 +455,50 @@ impl PermissionContext {
                 operation,
                 allowed
             );
-            return allowed;
+            return Ok(allowed);
         }
 
-        // 缓存未命中，返回 false（实际使用时应该先调用 load_policy）
-        // 注意：权限策略加载需要 I/O 操作，应在外部异步加载
-        tracing::debug!(
-            "Permission cache miss for role '{}', consider loading policy first",
-            self.role
-        );
-        false
+        // 缓存未命中：与"拒绝"区分开，由调用方决定是否先调用 load_policy
+        tracing::debug!("Permission cache miss for role '{}'", self.role);
+        Err(PermissionError::PolicyNotLoaded {
+            role: self.role.clone(),
+        })
+    }
+
+    /// 检查表访问权限，拒绝时返回错误
+    ///
+    /// # Errors
+    ///
+    /// 权限被拒绝时返回 [`PermissionError::Denied`]，其余错误同 [`Self::check_table_access`]
+    pub fn require_table_access(&self, table: &str, operation: &PermissionAction) -> Result<(), PermissionError> {
+        if self.check_table_access(table, operation)? {
+            Ok(())
+        } else {
+            Err(PermissionError::Denied {
+                role: self.role.clone(),
+                table: table.to_string(),
+                operation: operation.clone(),
+            })
+        }
     }
 
     /// 加载权限策略到缓存
     ///
-    /// 从权限配置文件中加载指定角色的策略并缓存
+    /// 从权限配置中解析指定角色的策略（合并继承的父角色）并缓存
     ///
     /// # Errors
     ///
     /// 如果加载失败，返回错误信息
     pub fn load_policy(&self, config: &PermissionConfig) -> Result<(), String> {
+        let policy = config.resolve(&self.role).map_err(|e| e.to_string())?;
+
         let mut cache = match self.policy_cache.lock() {
             Ok(guard) => guard,
             Err(_) => return Err("Permission cache mutex poisoned".to_string()),
         };
-
-        if let Some(policy) = config.get_role_policy(&self.role) {
-            cache.put(self.role.clone(), policy.clone());
-            tracing::info!("Loaded permission policy for role '{}'", self.role);
-            Ok(())
-        } else {
-            Err(format!("Role '{}' not found in permission config", self.role))
-        }
+        cache.put(self.role.clone(), policy);
+        tracing::info!("Loaded permission policy for role '{}'", self.role);
+        Ok(())
     }
 
     /// 获取缓存统计信息
@@ -303,6 +556,7 @@ mod tests {
                     operations: vec![PermissionAction::Select],
                 },
             ],
+            ..Default::default()
         };
 
         // 精确表名匹配
@@ -373,6 +627,7 @@ roles:
                             name: "*".to_string(),
                             operations: vec![PermissionAction::Select, PermissionAction::Insert],
                         }],
+                        ..Default::default()
                     },
                 );
                 map
@@ -397,6 +652,7 @@ roles:
                             name: "users".to_string(),
                             operations: vec![PermissionAction::Select, PermissionAction::Insert],
                         }],
+                        ..Default::default()
                     },
                 );
                 map
@@ -428,6 +684,7 @@ roles:
                     "admin".to_string(),
                     RolePolicy {
                         tables: vec![], // 空表权限
+                        ..Default::default()
                     },
                 );
                 map
@@ -453,6 +710,7 @@ roles:
                             name: "users".to_string(),
                             operations: vec![], // 空操作列表
                         }],
+                        ..Default::default()
                     },
                 );
                 map
@@ -464,4 +722,215 @@ roles:
         let errors = result.unwrap_err();
         assert!(errors.iter().any(|e| e.contains("has no operations defined")));
     }
+
+    /// TEST-U-063: 列级权限 - 受限角色不可见受保护列
+    #[test]
+    fn test_column_policy_visible_columns() {
+        let policy = ColumnPolicy::new("users", &["password_hash"]);
+        let columns = ["id", "email", "password_hash"];
+
+        assert_eq!(policy.visible_columns("user", &columns), vec!["id", "email"]);
+        assert_eq!(policy.visible_columns("admin", &columns), columns.to_vec());
+        assert!(!policy.allows_column("user", "PASSWORD_HASH"));
+
+        let policy = policy.with_exempt_roles(&["auditor"]);
+        assert_eq!(policy.visible_columns("auditor", &columns), columns.to_vec());
+        assert_eq!(policy.visible_columns("admin", &columns), vec!["id", "email"]);
+    }
+
+    /// TEST-U-064: 列级权限 - 显式查询受保护列被拒绝
+    #[test]
+    fn test_column_policy_require_columns() {
+        let policy = ColumnPolicy::new("users", &["password_hash"]);
+
+        assert!(policy.require_columns("user", &["id", "email"]).is_ok());
+        assert!(policy.require_columns("admin", &["password_hash"]).is_ok());
+        assert_eq!(
+            policy.require_columns("user", &["id", "password_hash"]),
+            Err(PermissionError::ColumnDenied {
+                role: "user".to_string(),
+                table: "users".to_string(),
+                column: "password_hash".to_string(),
+            })
+        );
+    }
+
+    /// TEST-U-073: 角色继承 - manager 继承 viewer 的 SELECT 并追加 INSERT
+    #[test]
+    fn test_role_inheritance_resolve() {
+        let yaml = r#"
+roles:
+  viewer:
+    tables:
+      - name: "*"
+        operations: [select]
+  manager:
+    extends: [viewer]
+    tables:
+      - name: orders
+        operations: [select, insert]
+"#;
+        let config = PermissionConfig::from_yaml(yaml).unwrap();
+        assert!(config.validate().is_ok());
+
+        let manager = config.resolve("manager").unwrap();
+        assert!(manager.extends.is_empty());
+        assert!(manager.allows("users", &PermissionAction::Select));
+        assert!(!manager.allows("users", &PermissionAction::Insert));
+        assert!(manager.allows("orders", &PermissionAction::Insert));
+        assert!(!manager.allows("orders", &PermissionAction::Delete));
+
+        assert!(config.check_access("manager", "users", PermissionAction::Select));
+        assert!(!config.check_access("viewer", "orders", PermissionAction::Insert));
+
+        // 上下文加载的是解析后的策略
+        let cache = Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(16).unwrap())));
+        let ctx = PermissionContext::new("manager".to_string(), cache);
+        ctx.load_policy(&config).unwrap();
+        assert_eq!(ctx.check_table_access("users", &PermissionAction::Select), Ok(true));
+        assert_eq!(ctx.check_table_access("orders", &PermissionAction::Insert), Ok(true));
+    }
+
+    /// TEST-U-074: 角色继承 - 检测环与未定义的父角色
+    #[test]
+    fn test_role_inheritance_cycle_and_unknown_parent() {
+        let yaml = r#"
+roles:
+  a:
+    extends: [b]
+  b:
+    extends: [a]
+  orphan:
+    extends: [missing]
+"#;
+        let config = PermissionConfig::from_yaml(yaml).unwrap();
+
+        assert_eq!(
+            config.resolve("a").unwrap_err(),
+            PermissionError::InheritanceCycle {
+                chain: "a -> b -> a".to_string()
+            }
+        );
+        assert_eq!(
+            config.resolve("orphan").unwrap_err(),
+            PermissionError::RoleNotFound {
+                role: "missing".to_string()
+            }
+        );
+        assert!(!config.check_access("a", "users", PermissionAction::Select));
+
+        let errors = config.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.contains("inheritance cycle")));
+        assert!(errors.iter().any(|e| e.contains("extends undefined role 'missing'")));
+    }
+
+    /// TEST-U-075: 禁止规则 - 允许所有表但排除指定表
+    #[test]
+    fn test_role_policy_allow_all_except() {
+        let yaml = r#"
+roles:
+  analyst:
+    tables:
+      - name: "*"
+        operations: [select, insert]
+    deny:
+      - name: secrets
+      - name: audit_logs
+        operations: [insert]
+  auditor:
+    extends: [analyst]
+    tables:
+      - name: audit_logs
+        operations: [select, insert]
+"#;
+        let config = PermissionConfig::from_yaml(yaml).unwrap();
+        assert!(config.validate().is_ok());
+
+        let analyst = config.get_role_policy("analyst").unwrap();
+        assert!(analyst.allows("users", &PermissionAction::Select));
+        assert!(analyst.allows("users", &PermissionAction::Insert));
+        // 未列出操作的禁止规则禁止全部操作
+        assert!(!analyst.allows("secrets", &PermissionAction::Select));
+        assert!(!analyst.allows("secrets", &PermissionAction::Insert));
+        // 只禁止列出的操作
+        assert!(analyst.allows("audit_logs", &PermissionAction::Select));
+        assert!(!analyst.allows("audit_logs", &PermissionAction::Insert));
+
+        // 继承的禁止规则优先于子角色的显式允许
+        let auditor = config.resolve("auditor").unwrap();
+        assert!(!auditor.allows("audit_logs", &PermissionAction::Insert));
+        assert!(!auditor.allows("secrets", &PermissionAction::Select));
+        assert!(auditor.allows("orders", &PermissionAction::Select));
+    }
+
+    /// TEST-U-076: 禁止规则 - 通配符禁止优先于精确表名允许
+    #[test]
+    fn test_role_policy_wildcard_deny() {
+        let policy = RolePolicy {
+            tables: vec![TablePermission {
+                name: "users".to_string(),
+                operations: vec![PermissionAction::Select, PermissionAction::Delete],
+            }],
+            deny: vec![TablePermission {
+                name: "*".to_string(),
+                operations: vec![PermissionAction::Delete],
+            }],
+            ..Default::default()
+        };
+
+        assert!(policy.allows("users", &PermissionAction::Select));
+        assert!(policy.denies("users", &PermissionAction::Delete));
+        assert!(!policy.allows("users", &PermissionAction::Delete));
+        assert!(!policy.allows("orders", &PermissionAction::Select));
+
+        // 未配置 deny 的 YAML 保持兼容
+        let config = PermissionConfig::from_yaml(
+            r#"
+roles:
+  user:
+    tables:
+      - name: users
+        operations: [select]
+"#,
+        )
+        .unwrap();
+        assert!(config.get_role_policy("user").unwrap().deny.is_empty());
+        assert!(config.check_access("user", "users", PermissionAction::Select));
+    }
+
+    /// TEST-U-019: PermissionContext 策略未加载与拒绝的区分
+    #[test]
+    fn test_permission_context_not_loaded_vs_denied() {
+        let cache = Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(16).unwrap())));
+        let ctx = PermissionContext::new("reader".to_string(), cache);
+
+        // 未加载策略
+        assert_eq!(
+            ctx.check_table_access("users", &PermissionAction::Select),
+            Err(PermissionError::PolicyNotLoaded {
+                role: "reader".to_string()
+            })
+        );
+
+        let mut roles = HashMap::new();
+        roles.insert(
+            "reader".to_string(),
+            RolePolicy {
+                tables: vec![TablePermission {
+                    name: "users".to_string(),
+                    operations: vec![PermissionAction::Select],
+                }],
+                ..Default::default()
+            },
+        );
+        ctx.load_policy(&PermissionConfig { roles }).unwrap();
+
+        // 已加载：允许与拒绝
+        assert_eq!(ctx.check_table_access("users", &PermissionAction::Select), Ok(true));
+        assert_eq!(ctx.check_table_access("users", &PermissionAction::Delete), Ok(false));
+        assert!(matches!(
+            ctx.require_table_access("users", &PermissionAction::Delete),
+            Err(PermissionError::Denied { .. })
+        ));
+    }
 }
@@ -33,8 +33,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::Arc;
-use std::sync::RwLock;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
 
 /// 权限操作类型
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -146,6 +146,30 @@ pub enum PermissionDecision {
     Error(String),
 }
 
+/// 带决定规则的权限决策
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionEvaluation {
+    /// 决策结果
+    pub decision: PermissionDecision,
+    /// 决定该结果的规则名称，`None` 表示没有规则匹配（默认决策）
+    pub rule: Option<String>,
+}
+
+impl PermissionEvaluation {
+    /// 由规则决定的结果
+    pub fn by_rule(decision: PermissionDecision, rule: &str) -> Self {
+        Self {
+            decision,
+            rule: Some(rule.to_string()),
+        }
+    }
+
+    /// 没有规则匹配时的结果
+    pub fn by_default(decision: PermissionDecision) -> Self {
+        Self { decision, rule: None }
+    }
+}
+
 /// 权限上下文
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PermissionContext {
@@ -200,6 +224,9 @@ pub struct PermissionRule {
     pub subject: String,
     /// 目标资源（支持通配符 *）
     pub resource: String,
+    /// 目标资源类型（如 `table`、`column`、`schema`、`function`，支持通配符 *），`None` 表示匹配任意类型
+    #[serde(default)]
+    pub resource_type: Option<String>,
     /// 允许的操作
     pub allow: Vec<PermissionAction>,
     /// 拒绝的操作
@@ -217,6 +244,23 @@ fn default_enabled() -> bool {
     true
 }
 
+impl PermissionRule {
+    /// 规则的目标资源，未指定类型时按 `table` 处理
+    fn target_resource(&self) -> PermissionResource {
+        match &self.resource_type {
+            Some(resource_type) => PermissionResource::with_type(&self.resource, resource_type),
+            None => PermissionResource::new(&self.resource),
+        }
+    }
+
+    /// 规则是否适用于该资源类型
+    fn matches_resource_type(&self, resource: &PermissionResource) -> bool {
+        self.resource_type
+            .as_deref()
+            .is_none_or(|resource_type| resource_type == "*" || resource_type == resource.resource_type)
+    }
+}
+
 /// 权限提供者 trait
 /// 定义权限检查的标准接口
 #[async_trait]
@@ -232,6 +276,13 @@ pub trait PermissionProvider: Send + Sync + Debug {
     /// 权限决策结果
     async fn check_permission(&self, context: &PermissionContext) -> PermissionDecision;
 
+    /// 检查权限并返回决定结果的规则
+    ///
+    /// 默认实现调用 [`check_permission`](Self::check_permission)，不提供规则名称
+    async fn evaluate(&self, context: &PermissionContext) -> PermissionEvaluation {
+        PermissionEvaluation::by_default(self.check_permission(context).await)
+    }
+
     /// 获取主体可访问的资源列表
     async fn get_allowed_resources(&self, subject: &str) -> Vec<PermissionResource>;
 
@@ -245,19 +296,108 @@ pub trait PermissionProvider: Send + Sync + Debug {
     fn name(&self) -> &str;
 }
 
+/// 决策日志默认保留的条目数
+pub const DEFAULT_DECISION_LOG_CAPACITY: usize = 1000;
+
+/// 决策日志中没有规则匹配时记录的规则名称
+pub const DEFAULT_DECISION_RULE: &str = "default";
+
+/// 一次权限决策的记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecisionLogEntry {
+    /// 决策时间
+    pub timestamp: time::OffsetDateTime,
+    /// 主体
+    pub subject: String,
+    /// 资源
+    pub resource: String,
+    /// 操作
+    pub action: PermissionAction,
+    /// 决策结果
+    pub decision: PermissionDecision,
+    /// 决定结果的规则名称，没有规则匹配时为 [`DEFAULT_DECISION_RULE`]
+    pub rule: String,
+}
+
+/// 权限决策日志
+///
+/// `record` 在权限检查路径上同步调用，实现应尽快返回
+pub trait DecisionLog: Send + Sync + Debug {
+    /// 记录一次决策
+    fn record(&self, entry: DecisionLogEntry);
+
+    /// 最近的决策记录（按时间顺序），不保留记录的实现返回空列表
+    fn recent(&self) -> Vec<DecisionLogEntry> {
+        Vec::new()
+    }
+}
+
+/// 内存环形缓冲区决策日志，超过容量时丢弃最早的记录
+#[derive(Debug)]
+pub struct InMemoryDecisionLog {
+    capacity: usize,
+    entries: std::sync::Mutex<std::collections::VecDeque<DecisionLogEntry>>,
+}
+
+impl InMemoryDecisionLog {
+    /// 创建指定容量的决策日志
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: std::sync::Mutex::new(std::collections::VecDeque::new()),
+        }
+    }
+
+    /// 清空记录
+    pub fn clear(&self) {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+impl Default for InMemoryDecisionLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_DECISION_LOG_CAPACITY)
+    }
+}
+
+impl DecisionLog for InMemoryDecisionLog {
+    fn record(&self, entry: DecisionLogEntry) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    fn recent(&self) -> Vec<DecisionLogEntry> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .cloned()
+            .collect()
+    }
+}
+
 /// 策略决策点
 /// 统一处理权限决策，支持多种权限提供者
+///
+/// 内部状态使用 `tokio::sync::RwLock`，在异步上下文中等待锁不会阻塞运行时线程
 #[derive(Debug)]
 pub struct PolicyDecisionPoint {
     /// 权限提供者
     provider: Arc<dyn PermissionProvider>,
     /// 缓存
-    cache: RwLock<HashMap<String, PermissionDecision>>,
+    cache: RwLock<HashMap<String, PermissionEvaluation>>,
     /// 缓存配置
     #[allow(dead_code)]
     cache_ttl_seconds: u64,
     /// 是否启用缓存
     cache_enabled: bool,
+    /// 决策日志
+    decision_log: Arc<dyn DecisionLog>,
+    /// 是否记录非允许（拒绝、不适用、错误）的决策
+    log_denied: bool,
 }
 
 impl PolicyDecisionPoint {
@@ -268,6 +408,8 @@ impl PolicyDecisionPoint {
             cache: RwLock::new(HashMap::new()),
             cache_ttl_seconds: 300,
             cache_enabled: true,
+            decision_log: Arc::new(InMemoryDecisionLog::default()),
+            log_denied: true,
         }
     }
 
@@ -278,30 +420,75 @@ impl PolicyDecisionPoint {
             cache: RwLock::new(HashMap::new()),
             cache_ttl_seconds,
             cache_enabled: true,
+            decision_log: Arc::new(InMemoryDecisionLog::default()),
+            log_denied: true,
         }
     }
 
+    /// 替换决策日志（默认为 [`InMemoryDecisionLog`]）
+    pub fn with_decision_log(mut self, decision_log: Arc<dyn DecisionLog>) -> Self {
+        self.decision_log = decision_log;
+        self
+    }
+
+    /// 设置是否记录非允许的决策
+    pub fn with_log_denied(mut self, log_denied: bool) -> Self {
+        self.log_denied = log_denied;
+        self
+    }
+
+    /// 获取决策日志
+    pub fn decision_log(&self) -> &Arc<dyn DecisionLog> {
+        &self.decision_log
+    }
+
     /// 检查权限
     pub async fn check_permission(&self, context: &PermissionContext) -> PermissionDecision {
+        let evaluation = self.evaluate(context).await;
+        self.log_decision(context, &evaluation);
+        evaluation.decision
+    }
+
+    /// 获取权限决策（优先使用缓存）
+    async fn evaluate(&self, context: &PermissionContext) -> PermissionEvaluation {
         // 生成缓存键
         let cache_key = self.generate_cache_key(context);
 
         // 检查缓存
         if self.cache_enabled {
-            if let Some(decision) = self.get_cached_decision(&cache_key) {
-                return decision;
+            if let Some(evaluation) = self.get_cached_decision(&cache_key).await {
+                return evaluation;
             }
         }
 
         // 获取权限决策
-        let decision = self.provider.check_permission(context).await;
+        let evaluation = self.provider.evaluate(context).await;
 
         // 更新缓存
         if self.cache_enabled {
-            self.update_cache(&cache_key, decision.clone());
+            self.update_cache(&cache_key, evaluation.clone()).await;
         }
 
-        decision
+        evaluation
+    }
+
+    /// 写入决策日志
+    fn log_decision(&self, context: &PermissionContext, evaluation: &PermissionEvaluation) {
+        if evaluation.decision != PermissionDecision::Allow && !self.log_denied {
+            return;
+        }
+
+        self.decision_log.record(DecisionLogEntry {
+            timestamp: time::OffsetDateTime::now_utc(),
+            subject: context.subject.id.clone(),
+            resource: context.resource.name.clone(),
+            action: context.action.clone(),
+            decision: evaluation.decision.clone(),
+            rule: evaluation
+                .rule
+                .clone()
+                .unwrap_or_else(|| DEFAULT_DECISION_RULE.to_string()),
+        });
     }
 
     /// 检查用户是否有权限执行操作
@@ -344,26 +531,23 @@ impl PolicyDecisionPoint {
     /// 刷新缓存
     pub async fn refresh_cache(&self) {
         self.provider.refresh().await.ok();
-        if let Ok(mut cache) = self.cache.write() {
-            cache.clear();
-        }
+        self.cache.write().await.clear();
     }
 
     /// 启用/禁用缓存
     pub fn set_cache_enabled(&mut self, enabled: bool) {
         self.cache_enabled = enabled;
         if !enabled {
-            if let Ok(mut cache) = self.cache.write() {
-                cache.clear();
-            }
+            self.cache.get_mut().clear();
         }
     }
 
-    /// 生成缓存键
+    /// 生成缓存键，同名资源按资源类型区分
     fn generate_cache_key(&self, context: &PermissionContext) -> String {
         format!(
-            "{}:{}:{}:{}",
+            "{}:{}:{}:{}:{}",
             context.subject.id,
+            context.resource.resource_type,
             context.resource.name,
             context.action,
             context
@@ -374,31 +558,30 @@ impl PolicyDecisionPoint {
     }
 
     /// 获取缓存的决策
-    fn get_cached_decision(&self, key: &str) -> Option<PermissionDecision> {
-        if let Ok(cache) = self.cache.read() {
-            cache.get(key).cloned()
-        } else {
-            None
-        }
+    async fn get_cached_decision(&self, key: &str) -> Option<PermissionEvaluation> {
+        self.cache.read().await.get(key).cloned()
     }
 
     /// 更新缓存
-    fn update_cache(&self, key: &str, decision: PermissionDecision) {
-        if let Ok(mut cache) = self.cache.write() {
-            cache.insert(key.to_string(), decision);
-        }
+    async fn update_cache(&self, key: &str, evaluation: PermissionEvaluation) {
+        self.cache.write().await.insert(key.to_string(), evaluation);
     }
 }
 
 /// 基于 YAML 配置的权限提供者
+///
+/// 配置在首次检查时加载一次，之后权限检查不再读取文件；
+/// 通过 [`refresh`](PermissionProvider::refresh) 或 [`spawn_refresh_task`](Self::spawn_refresh_task) 重新加载
 #[derive(Debug)]
 pub struct YamlPermissionProvider {
     /// 配置文件路径
     config_path: String,
     /// 角色权限映射
     roles: RwLock<HashMap<String, Vec<PermissionRule>>>,
-    /// 缓存时间
-    last_refresh: RwLock<Instant>,
+    /// 最近一次加载时间，`None` 表示尚未加载
+    last_refresh: RwLock<Option<Instant>>,
+    /// 后台重新加载间隔，`None` 表示只在显式刷新时重新加载
+    refresh_interval: Option<Duration>,
     /// 提供者名称
     name: String,
 }
@@ -408,7 +591,8 @@ impl Default for YamlPermissionProvider {
         Self {
             config_path: String::new(),
             roles: RwLock::new(HashMap::new()),
-            last_refresh: RwLock::new(Instant::now()),
+            last_refresh: RwLock::new(None),
+            refresh_interval: None,
             name: "yaml".to_string(),
         }
     }
@@ -443,11 +627,51 @@ impl YamlPermissionProvider {
         Ok(Self {
             config_path: config_path.to_string(),
             roles: RwLock::new(HashMap::new()),
-            last_refresh: RwLock::new(Instant::now()),
+            last_refresh: RwLock::new(None),
+            refresh_interval: None,
             name: "yaml".to_string(),
         })
     }
 
+    /// 创建按固定间隔在后台重新加载配置的 YAML 权限提供者
+    ///
+    /// 需要调用 [`spawn_refresh_task`](Self::spawn_refresh_task) 启动后台任务
+    ///
+    /// # Errors
+    ///
+    /// 如果路径无效或不在允许的目录内，返回错误
+    pub fn new_with_refresh_interval(config_path: &str, refresh_interval: Duration) -> Result<Self, String> {
+        Ok(Self {
+            refresh_interval: Some(refresh_interval),
+            ..Self::new(config_path)?
+        })
+    }
+
+    /// 后台重新加载间隔
+    pub fn refresh_interval(&self) -> Option<Duration> {
+        self.refresh_interval
+    }
+
+    /// 启动后台重新加载任务，未设置重新加载间隔时返回 `None`
+    ///
+    /// 任务只持有提供者的弱引用，提供者被释放后自动退出；重新加载失败时保留原有配置
+    pub fn spawn_refresh_task(self: &Arc<Self>) -> Option<tokio::task::JoinHandle<()>> {
+        let interval = self.refresh_interval?;
+        let provider = Arc::downgrade(self);
+
+        Some(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(provider) = provider.upgrade() else {
+                    break;
+                };
+                if let Err(e) = provider.load_config().await {
+                    tracing::warn!("Failed to reload permission config {}: {}", provider.config_path, e);
+                }
+            }
+        }))
+    }
+
     /// 加载配置
     async fn load_config(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let content = tokio::fs::read_to_string(&self.config_path).await?;
@@ -460,14 +684,9 @@ impl YamlPermissionProvider {
 
         let config: YamlConfig = serde_yaml::from_str(&content)?;
 
-        // 更新角色权限
-        if let Ok(mut roles) = self.roles.write() {
-            *roles = config.roles;
-        }
-
-        if let Ok(mut last_refresh) = self.last_refresh.write() {
-            *last_refresh = Instant::now();
-        }
+        // 读取和解析在加锁前完成，写锁只用于替换内容
+        *self.roles.write().await = config.roles;
+        *self.last_refresh.write().await = Some(Instant::now());
 
         Ok(())
     }
@@ -484,6 +703,11 @@ impl YamlPermissionProvider {
             return false;
         }
 
+        // 检查资源类型匹配
+        if !rule.matches_resource_type(&context.resource) {
+            return false;
+        }
+
         // 检查操作匹配
         if !rule.allow.is_empty() && !rule.allow.contains(&context.action) && context.action != PermissionAction::All {
             return false;
@@ -496,18 +720,22 @@ impl YamlPermissionProvider {
 #[async_trait]
 impl PermissionProvider for YamlPermissionProvider {
     async fn check_permission(&self, context: &PermissionContext) -> PermissionDecision {
-        // 加载配置（如果需要）
-        let age = self.last_refresh.read().map(|r| r.elapsed()).unwrap_or_default();
-        if age.as_secs() > 60 {
+        self.evaluate(context).await.decision
+    }
+
+    async fn evaluate(&self, context: &PermissionContext) -> PermissionEvaluation {
+        // 只在首次检查时加载配置，之后的重新加载在检查路径之外进行
+        let loaded = self.last_refresh.read().await.is_some();
+        if !loaded {
             if let Err(e) = self.load_config().await {
-                return PermissionDecision::Error(format!("Failed to load config: {}", e));
+                return PermissionEvaluation::by_default(PermissionDecision::Error(format!(
+                    "Failed to load config: {}",
+                    e
+                )));
             }
         }
 
-        let roles = match self.roles.read() {
-            Ok(r) => r,
-            Err(_) => return PermissionDecision::Error("Lock error".to_string()),
-        };
+        let roles = self.roles.read().await;
         let subject_roles = self.get_subject_roles(&context.subject.id);
 
         // 按优先级排序规则
@@ -524,26 +752,23 @@ impl PermissionProvider for YamlPermissionProvider {
         }
 
         // 按优先级排序
-        matching_rules.sort_by(|a, b| b.priority.cmp(&a.priority));
+        matching_rules.sort_by_key(|rule| std::cmp::Reverse(rule.priority));
 
         // 评估规则
         for rule in matching_rules {
             if rule.allow.contains(&context.action) || rule.allow.contains(&PermissionAction::All) {
-                return PermissionDecision::Allow;
+                return PermissionEvaluation::by_rule(PermissionDecision::Allow, &rule.name);
             }
             if rule.deny.contains(&context.action) || rule.deny.contains(&PermissionAction::All) {
-                return PermissionDecision::Deny;
+                return PermissionEvaluation::by_rule(PermissionDecision::Deny, &rule.name);
             }
         }
 
-        PermissionDecision::NotApplicable
+        PermissionEvaluation::by_default(PermissionDecision::NotApplicable)
     }
 
     async fn get_allowed_resources(&self, subject: &str) -> Vec<PermissionResource> {
-        let roles = match self.roles.read() {
-            Ok(r) => r,
-            Err(_) => return Vec::new(),
-        };
+        let roles = self.roles.read().await;
         let subject_roles = self.get_subject_roles(subject);
         let mut resources = std::collections::HashSet::new();
 
@@ -551,7 +776,7 @@ impl PermissionProvider for YamlPermissionProvider {
             if let Some(rules) = roles.get(role_name) {
                 for rule in rules {
                     if rule.enabled && (rule.subject == "*" || rule.subject == subject) {
-                        resources.insert(PermissionResource::new(&rule.resource));
+                        resources.insert(rule.target_resource());
                     }
                 }
             }
@@ -561,10 +786,7 @@ impl PermissionProvider for YamlPermissionProvider {
     }
 
     async fn get_allowed_actions(&self, subject: &str, resource: &str) -> Vec<PermissionAction> {
-        let roles = match self.roles.read() {
-            Ok(r) => r,
-            Err(_) => return Vec::new(),
-        };
+        let roles = self.roles.read().await;
         let subject_roles = self.get_subject_roles(subject);
         let mut actions = std::collections::HashSet::new();
 
@@ -669,20 +891,19 @@ impl RbacPermissionProvider {
     }
 
     /// 添加角色
-    pub fn add_role(&self, role: Role) {
-        if let Ok(mut roles) = self.roles.write() {
-            roles.insert(role.name.clone(), role.clone());
-        }
-        if let Ok(mut hierarchy) = self.role_hierarchy.write() {
-            hierarchy.insert(role.name, role.extends);
-        }
+    pub async fn add_role(&self, role: Role) {
+        self.roles.write().await.insert(role.name.clone(), role.clone());
+        self.role_hierarchy.write().await.insert(role.name, role.extends);
     }
 
     /// 添加权限规则
-    pub fn add_permission(&self, role: &str, rule: PermissionRule) {
-        if let Ok(mut permissions) = self.permissions.write() {
-            permissions.entry(role.to_string()).or_default().push(rule);
-        }
+    pub async fn add_permission(&self, role: &str, rule: PermissionRule) {
+        self.permissions
+            .write()
+            .await
+            .entry(role.to_string())
+            .or_default()
+            .push(rule);
     }
 
     /// 获取角色的所有权限（包括继承的）
@@ -691,16 +912,8 @@ impl RbacPermissionProvider {
         let mut visited = std::collections::HashSet::new();
         let mut to_visit = vec![role.to_string()];
 
-        let permissions = if let Ok(p) = self.permissions.read() {
-            p
-        } else {
-            return Vec::new();
-        };
-        let hierarchy = if let Ok(h) = self.role_hierarchy.read() {
-            h
-        } else {
-            return Vec::new();
-        };
+        let permissions = self.permissions.read().await;
+        let hierarchy = self.role_hierarchy.read().await;
 
         while let Some(current_role) = to_visit.pop() {
             if visited.contains(&current_role) {
@@ -730,6 +943,10 @@ impl RbacPermissionProvider {
 #[async_trait]
 impl PermissionProvider for RbacPermissionProvider {
     async fn check_permission(&self, context: &PermissionContext) -> PermissionDecision {
+        self.evaluate(context).await.decision
+    }
+
+    async fn evaluate(&self, context: &PermissionContext) -> PermissionEvaluation {
         let subject_roles = self.get_subject_roles(&context.subject.id);
 
         // 获取所有角色的权限
@@ -740,21 +957,21 @@ impl PermissionProvider for RbacPermissionProvider {
         }
 
         // 按优先级排序
-        all_rules.sort_by(|a, b| b.priority.cmp(&a.priority));
+        all_rules.sort_by_key(|rule| std::cmp::Reverse(rule.priority));
 
         // 评估规则
         for rule in all_rules {
             if rule.enabled && self.matches_rule(&rule, context) {
                 if rule.allow.contains(&context.action) || rule.allow.contains(&PermissionAction::All) {
-                    return PermissionDecision::Allow;
+                    return PermissionEvaluation::by_rule(PermissionDecision::Allow, &rule.name);
                 }
                 if rule.deny.contains(&context.action) || rule.deny.contains(&PermissionAction::All) {
-                    return PermissionDecision::Deny;
+                    return PermissionEvaluation::by_rule(PermissionDecision::Deny, &rule.name);
                 }
             }
         }
 
-        PermissionDecision::NotApplicable
+        PermissionEvaluation::by_default(PermissionDecision::NotApplicable)
     }
 
     async fn get_allowed_resources(&self, subject: &str) -> Vec<PermissionResource> {
@@ -765,7 +982,7 @@ impl PermissionProvider for RbacPermissionProvider {
             let rules = self.get_role_permissions(role).await;
             for rule in rules {
                 if rule.enabled {
-                    resources.insert(PermissionResource::new(&rule.resource));
+                    resources.insert(rule.target_resource());
                 }
             }
         }
@@ -792,9 +1009,7 @@ impl PermissionProvider for RbacPermissionProvider {
     }
 
     async fn refresh(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        if let Ok(mut last_refresh) = self.last_refresh.write() {
-            *last_refresh = Instant::now();
-        }
+        *self.last_refresh.write().await = Instant::now();
         Ok(())
     }
 
@@ -818,7 +1033,7 @@ impl RbacPermissionProvider {
         if rule.resource != "*" && rule.resource != context.resource.name {
             return false;
         }
-        true
+        rule.matches_resource_type(&context.resource)
     }
 }
 
@@ -827,7 +1042,7 @@ impl RbacPermissionProvider {
 pub struct PermissionEngineConfig {
     /// 默认决策（当没有匹配规则时）
     pub default_decision: PermissionDecision,
-    /// 是否记录拒绝的决策
+    /// 是否把拒绝、不适用与错误的决策写入决策日志
     pub log_denied: bool,
     /// 缓存配置
     pub cache_ttl_seconds: u64,
@@ -869,11 +1084,22 @@ impl PermissionEngine {
     /// 创建带配置的权限引擎
     pub fn with_config(provider: Arc<dyn PermissionProvider>, config: PermissionEngineConfig) -> Self {
         Self {
-            pdp: PolicyDecisionPoint::with_cache(provider, config.cache_ttl_seconds),
+            pdp: PolicyDecisionPoint::with_cache(provider, config.cache_ttl_seconds).with_log_denied(config.log_denied),
             config,
         }
     }
 
+    /// 替换决策日志（默认为 [`InMemoryDecisionLog`]）
+    pub fn with_decision_log(mut self, decision_log: Arc<dyn DecisionLog>) -> Self {
+        self.pdp = self.pdp.with_decision_log(decision_log);
+        self
+    }
+
+    /// 获取决策日志
+    pub fn decision_log(&self) -> &Arc<dyn DecisionLog> {
+        self.pdp.decision_log()
+    }
+
     /// 检查权限
     pub async fn check(&self, subject: &str, resource: &str, action: &str) -> bool {
         let decision = self.pdp.check(subject, resource, action).await;
@@ -906,26 +1132,23 @@ mod tests {
         let provider = Arc::new(RbacPermissionProvider::new());
 
         // 添加角色和权限
-        provider.add_role(Role {
-            name: "admin".to_string(),
-            description: "管理员角色".to_string(),
-            enabled: true,
-            extends: vec![],
-        });
 
-        provider.add_permission(
-            "admin",
-            PermissionRule {
-                name: "admin_select".to_string(),
-                priority: 100,
-                subject: "*".to_string(),
-                resource: "users".to_string(),
-                allow: vec![PermissionAction::Select],
-                deny: vec![],
-                condition: None,
-                enabled: true,
-            },
-        );
+        provider
+            .add_permission(
+                "admin",
+                PermissionRule {
+                    name: "admin_select".to_string(),
+                    priority: 100,
+                    subject: "*".to_string(),
+                    resource: "users".to_string(),
+                    resource_type: None,
+                    allow: vec![PermissionAction::Select],
+                    deny: vec![],
+                    condition: None,
+                    enabled: true,
+                },
+            )
+            .await;
 
         let pdp = PolicyDecisionPoint::new(provider);
 
@@ -939,32 +1162,29 @@ mod tests {
         let provider = Arc::new(RbacPermissionProvider::new());
 
         // 添加角色
-        provider.add_role(Role {
-            name: "admin".to_string(),
-            description: "管理员角色".to_string(),
-            enabled: true,
-            extends: vec![],
-        });
 
         // 添加权限规则
-        provider.add_permission(
-            "admin",
-            PermissionRule {
-                name: "admin_all".to_string(),
-                priority: 100,
-                subject: "*".to_string(),
-                resource: "*".to_string(),
-                allow: vec![
-                    PermissionAction::Select,
-                    PermissionAction::Insert,
-                    PermissionAction::Update,
-                    PermissionAction::Delete,
-                ],
-                deny: vec![],
-                condition: None,
-                enabled: true,
-            },
-        );
+        provider
+            .add_permission(
+                "admin",
+                PermissionRule {
+                    name: "admin_all".to_string(),
+                    priority: 100,
+                    subject: "*".to_string(),
+                    resource: "*".to_string(),
+                    resource_type: None,
+                    allow: vec![
+                        PermissionAction::Select,
+                        PermissionAction::Insert,
+                        PermissionAction::Update,
+                        PermissionAction::Delete,
+                    ],
+                    deny: vec![],
+                    condition: None,
+                    enabled: true,
+                },
+            )
+            .await;
 
         let pdp = PolicyDecisionPoint::new(provider);
 
@@ -981,32 +1201,29 @@ mod tests {
         let provider = Arc::new(RbacPermissionProvider::new());
 
         // 添加角色
-        provider.add_role(Role {
-            name: "admin".to_string(),
-            description: "管理员角色".to_string(),
-            enabled: true,
-            extends: vec![],
-        });
 
         // 添加权限规则
-        provider.add_permission(
-            "admin",
-            PermissionRule {
-                name: "admin_all".to_string(),
-                priority: 100,
-                subject: "*".to_string(),
-                resource: "*".to_string(),
-                allow: vec![
-                    PermissionAction::Select,
-                    PermissionAction::Insert,
-                    PermissionAction::Update,
-                    PermissionAction::Delete,
-                ],
-                deny: vec![],
-                condition: None,
-                enabled: true,
-            },
-        );
+        provider
+            .add_permission(
+                "admin",
+                PermissionRule {
+                    name: "admin_all".to_string(),
+                    priority: 100,
+                    subject: "*".to_string(),
+                    resource: "*".to_string(),
+                    resource_type: None,
+                    allow: vec![
+                        PermissionAction::Select,
+                        PermissionAction::Insert,
+                        PermissionAction::Update,
+                        PermissionAction::Delete,
+                    ],
+                    deny: vec![],
+                    condition: None,
+                    enabled: true,
+                },
+            )
+            .await;
 
         let engine = PermissionEngine::new(provider);
 
@@ -1015,6 +1232,232 @@ mod tests {
         assert!(allowed);
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_checks_during_refresh() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = dir.path().join("permissions.yaml");
+        std::fs::write(
+            &path,
+            "roles:\n  admin:\n    - name: admin_select\n      subject: \"*\"\n      resource: users\n      allow: [select]\n",
+        )
+        .expect("Failed to write permission config");
+
+        let provider =
+            Arc::new(YamlPermissionProvider::new(path.to_str().unwrap()).expect("Failed to create provider"));
+        provider.refresh().await.expect("Failed to load permission config");
+
+        let mut pdp = PolicyDecisionPoint::new(provider.clone());
+        pdp.set_cache_enabled(false);
+        let pdp = Arc::new(pdp);
+
+        let mut tasks = Vec::new();
+        for _ in 0..8 {
+            let pdp = pdp.clone();
+            tasks.push(tokio::spawn(async move {
+                for _ in 0..50 {
+                    assert_eq!(pdp.check("admin", "users", "SELECT").await, PermissionDecision::Allow);
+                    tokio::task::yield_now().await;
+                }
+            }));
+        }
+        let refresher = pdp.clone();
+        tasks.push(tokio::spawn(async move {
+            for _ in 0..50 {
+                refresher.refresh_cache().await;
+                tokio::task::yield_now().await;
+            }
+        }));
+
+        tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            for task in tasks {
+                task.await.expect("Permission check task panicked");
+            }
+        })
+        .await
+        .expect("Concurrent checks and refreshes should not deadlock");
+    }
+
+    #[tokio::test]
+    async fn test_yaml_checks_do_not_read_file_after_load() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = dir.path().join("permissions.yaml");
+        let allow_select = "roles:\n  admin:\n    - name: admin_select\n      subject: \"*\"\n      resource: users\n      allow: [select]\n";
+        std::fs::write(&path, allow_select).expect("Failed to write permission config");
+
+        let provider = Arc::new(
+            YamlPermissionProvider::new_with_refresh_interval(path.to_str().unwrap(), Duration::from_millis(50))
+                .expect("Failed to create provider"),
+        );
+        let context = PermissionContext::new(
+            PermissionSubject::user("admin"),
+            PermissionResource::new("users"),
+            PermissionAction::Select,
+        );
+
+        // 首次检查加载配置；删除文件后检查仍然成功，说明检查路径不再读取文件
+        assert_eq!(provider.check_permission(&context).await, PermissionDecision::Allow);
+        std::fs::remove_file(&path).expect("Failed to remove permission config");
+        for _ in 0..10 {
+            assert_eq!(provider.check_permission(&context).await, PermissionDecision::Allow);
+        }
+        assert!(provider.refresh().await.is_err());
+        assert_eq!(provider.check_permission(&context).await, PermissionDecision::Allow);
+
+        // 后台任务按间隔重新加载
+        let task = provider.spawn_refresh_task().expect("Refresh interval should be set");
+        std::fs::write(&path, "roles: {}\n").expect("Failed to write permission config");
+        let reloaded = tokio::time::timeout(Duration::from_secs(5), async {
+            while provider.check_permission(&context).await == PermissionDecision::Allow {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await;
+        assert!(reloaded.is_ok(), "Background task should reload the config");
+        assert_eq!(
+            provider.check_permission(&context).await,
+            PermissionDecision::NotApplicable
+        );
+        task.abort();
+
+        assert!(
+            YamlPermissionProvider::new(path.to_str().unwrap())
+                .unwrap()
+                .refresh_interval()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_decision_log_records_deciding_rule() {
+        let provider = Arc::new(RbacPermissionProvider::new());
+        provider
+            .add_permission(
+                "admin",
+                PermissionRule {
+                    name: "admin_no_delete".to_string(),
+                    priority: 100,
+                    subject: "*".to_string(),
+                    resource: "users".to_string(),
+                    resource_type: None,
+                    allow: vec![],
+                    deny: vec![PermissionAction::Delete],
+                    condition: None,
+                    enabled: true,
+                },
+            )
+            .await;
+
+        let log = Arc::new(InMemoryDecisionLog::new(16));
+        let engine = PermissionEngine::new(provider.clone()).with_decision_log(log.clone());
+
+        assert!(!engine.check("admin", "users", "DELETE").await);
+        let entries = log.recent();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].subject, "admin");
+        assert_eq!(entries[0].resource, "users");
+        assert_eq!(entries[0].action, PermissionAction::Delete);
+        assert_eq!(entries[0].decision, PermissionDecision::Deny);
+        assert_eq!(entries[0].rule, "admin_no_delete");
+
+        // 没有规则匹配时记录为默认决策；缓存命中同样记录
+        assert!(!engine.check("admin", "orders", "SELECT").await);
+        assert!(!engine.check("admin", "orders", "SELECT").await);
+        let entries = engine.decision_log().recent();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[2].rule, DEFAULT_DECISION_RULE);
+        assert_eq!(entries[2].decision, PermissionDecision::NotApplicable);
+
+        // log_denied = false 时不记录拒绝的决策
+        let quiet = PermissionEngine::with_config(
+            provider,
+            PermissionEngineConfig {
+                log_denied: false,
+                ..Default::default()
+            },
+        );
+        assert!(!quiet.check("admin", "users", "DELETE").await);
+        assert!(quiet.decision_log().recent().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rule_resource_type_must_match() {
+        let provider = RbacPermissionProvider::new();
+        provider
+            .add_permission(
+                "analyst",
+                PermissionRule {
+                    name: "email_column".to_string(),
+                    priority: 100,
+                    subject: "*".to_string(),
+                    resource: "users".to_string(),
+                    resource_type: Some("column".to_string()),
+                    allow: vec![PermissionAction::Select],
+                    deny: vec![],
+                    condition: None,
+                    enabled: true,
+                },
+            )
+            .await;
+
+        let table = PermissionContext::new(
+            PermissionSubject::user("analyst"),
+            PermissionResource::new("users"),
+            PermissionAction::Select,
+        );
+        assert_eq!(
+            provider.check_permission(&table).await,
+            PermissionDecision::NotApplicable
+        );
+
+        let column = PermissionContext::new(
+            PermissionSubject::user("analyst"),
+            PermissionResource::with_type("users", "column"),
+            PermissionAction::Select,
+        );
+        assert_eq!(provider.check_permission(&column).await, PermissionDecision::Allow);
+
+        let resources = provider.get_allowed_resources("analyst").await;
+        assert_eq!(resources, vec![PermissionResource::with_type("users", "column")]);
+    }
+
+    #[tokio::test]
+    async fn test_cached_decision_is_scoped_to_resource_type() {
+        let provider = Arc::new(RbacPermissionProvider::new());
+        provider
+            .add_permission(
+                "analyst",
+                PermissionRule {
+                    name: "email_column".to_string(),
+                    priority: 100,
+                    subject: "*".to_string(),
+                    resource: "users".to_string(),
+                    resource_type: Some("column".to_string()),
+                    allow: vec![PermissionAction::Select],
+                    deny: vec![],
+                    condition: None,
+                    enabled: true,
+                },
+            )
+            .await;
+        let pdp = PolicyDecisionPoint::new(provider);
+
+        let column = PermissionContext::new(
+            PermissionSubject::user("analyst"),
+            PermissionResource::with_type("users", "column"),
+            PermissionAction::Select,
+        );
+        let table = PermissionContext::new(
+            PermissionSubject::user("analyst"),
+            PermissionResource::new("users"),
+            PermissionAction::Select,
+        );
+
+        // 先缓存列级决策，再查询同名表不能命中该缓存
+        assert_eq!(pdp.check_permission(&column).await, PermissionDecision::Allow);
+        assert_eq!(pdp.check_permission(&table).await, PermissionDecision::NotApplicable);
+        assert_eq!(pdp.check_permission(&column).await, PermissionDecision::Allow);
+    }
+
     #[tokio::test]
     async fn test_permission_context() {
         let context = PermissionContext::new(
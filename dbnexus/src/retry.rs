@@ -0,0 +1,346 @@
+// Copyright (c) 2025 Kirky.X
+//
+// Licensed under the MIT License
+// See LICENSE file in the project root for full license information.
+
+//! 瞬时错误重试模块
+//!
+//! 将 Sea-ORM 错误划分为可重试（连接中断、序列化失败、死锁、锁等待）与不可重试两类，
+//! 并对可重试错误按指数退避重试。
+//!
+//! # Example
+//!
+//! ```ignore
+//! use dbnexus::retry::RetryPolicy;
+//! use std::time::Duration;
+//!
+//! let policy = RetryPolicy::new(5).with_base_backoff(Duration::from_millis(20));
+//! let result = policy.run(|_attempt| session.execute_raw("UPDATE accounts SET ...")).await?;
+//! ```
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+
+use crate::config::{DbError, DbResult};
+
+/// 错误信息中表示瞬时故障的片段（小写）
+///
+/// 覆盖 PostgreSQL 的 SQLSTATE 40001 / 40P01、MySQL 的 1213 / 1205 以及 SQLite 的 BUSY / LOCKED
+const TRANSIENT_PATTERNS: &[&str] = &[
+    "40001",
+    "40p01",
+    "could not serialize access",
+    "serialization failure",
+    "deadlock",
+    "lock wait timeout",
+    "database is locked",
+    "database table is locked",
+    "connection reset",
+    "connection refused",
+    "connection closed",
+    "broken pipe",
+    "server has gone away",
+    "lost connection",
+];
+
+/// 错误分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// 瞬时错误，重新执行可能成功
+    Retryable,
+    /// 确定性错误（语法、约束、权限等），重试没有意义
+    Fatal,
+}
+
+impl ErrorClass {
+    /// 对 Sea-ORM 错误分类
+    pub fn of(err: &sea_orm::DbErr) -> Self {
+        use sea_orm::DbErr;
+
+        match err {
+            DbErr::ConnectionAcquire(_) | DbErr::Conn(_) => ErrorClass::Retryable,
+            // 唯一键、外键等约束冲突重试后结果不变
+            _ if err.sql_err().is_some() => ErrorClass::Fatal,
+            DbErr::Exec(_) | DbErr::Query(_) => {
+                let message = err.to_string().to_lowercase();
+                if TRANSIENT_PATTERNS.iter().any(|pattern| message.contains(pattern)) {
+                    ErrorClass::Retryable
+                } else {
+                    ErrorClass::Fatal
+                }
+            }
+            _ => ErrorClass::Fatal,
+        }
+    }
+
+    /// 对 DbNexus 错误分类，只有底层数据库错误可能被重试
+    pub fn of_db_error(err: &DbError) -> Self {
+        match err {
+            DbError::Connection(db_err) => Self::of(db_err),
+            _ => ErrorClass::Fatal,
+        }
+    }
+
+    /// 是否可重试
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ErrorClass::Retryable)
+    }
+}
+
+/// 重试策略
+///
+/// 第 n 次重试前等待 `base_backoff_ms * 2^(n-1)`，不超过 `max_backoff_ms`；
+/// 启用抖动时实际等待时间在该值的 50%~100% 之间随机取值，避免多个客户端同时重试
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// 最大尝试次数（包括首次执行），1 表示不重试
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+
+    /// 基础退避时间（毫秒）
+    #[serde(default = "default_base_backoff_ms")]
+    pub base_backoff_ms: u64,
+
+    /// 最大退避时间（毫秒）
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+
+    /// 是否启用随机抖动
+    #[serde(default = "default_jitter")]
+    pub jitter: bool,
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_base_backoff_ms() -> u64 {
+    50
+}
+
+fn default_max_backoff_ms() -> u64 {
+    2000
+}
+
+fn default_jitter() -> bool {
+    true
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            base_backoff_ms: default_base_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+            jitter: default_jitter(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 使用默认退避参数创建策略
+    ///
+    /// # Arguments
+    ///
+    /// * `max_attempts` - 最大尝试次数（包括首次执行），小于 1 时按 1 处理
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..Self::default()
+        }
+    }
+
+    /// 不重试的策略
+    pub fn no_retry() -> Self {
+        Self::new(1)
+    }
+
+    /// 设置基础退避时间
+    pub fn with_base_backoff(mut self, backoff: Duration) -> Self {
+        self.base_backoff_ms = backoff.as_millis() as u64;
+        self
+    }
+
+    /// 设置最大退避时间
+    pub fn with_max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff_ms = backoff.as_millis() as u64;
+        self
+    }
+
+    /// 启用或禁用随机抖动
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// 第 `retry` 次重试（从 1 开始）前的等待时间
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(31);
+        let delay = self
+            .base_backoff_ms
+            .saturating_mul(1u64 << exponent)
+            .min(self.max_backoff_ms);
+
+        if self.jitter && delay > 1 {
+            Duration::from_millis(rand::thread_rng().gen_range(delay.div_ceil(2)..=delay))
+        } else {
+            Duration::from_millis(delay)
+        }
+    }
+
+    /// 执行操作，可重试错误按策略重试
+    ///
+    /// `op` 的参数为当前尝试次数（从 1 开始）
+    ///
+    /// # Errors
+    ///
+    /// 遇到不可重试错误或尝试次数用尽时，返回最后一次的错误
+    pub async fn run<T, F, Fut>(&self, op: F) -> DbResult<T>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = DbResult<T>>,
+    {
+        self.run_with(op, |_, _| {}).await
+    }
+
+    /// 执行操作，每次重试前调用 `on_retry(下一次尝试次数, 错误)`
+    ///
+    /// # Errors
+    ///
+    /// 遇到不可重试错误或尝试次数用尽时，返回最后一次的错误
+    pub async fn run_with<T, F, Fut, R>(&self, mut op: F, mut on_retry: R) -> DbResult<T>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = DbResult<T>>,
+        R: FnMut(u32, &DbError),
+    {
+        let max_attempts = self.max_attempts.max(1);
+        let mut attempt = 1;
+
+        loop {
+            match op(attempt).await {
+                Ok(value) => return Ok(value),
+                Err(err) if attempt < max_attempts && ErrorClass::of_db_error(&err).is_retryable() => {
+                    tracing::warn!(attempt, max_attempts, error = %err, "Retryable database error, retrying");
+                    on_retry(attempt + 1, &err);
+                    tokio::time::sleep(self.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DbErr, RuntimeErr};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn exec_err(message: &str) -> DbError {
+        DbError::Connection(DbErr::Exec(RuntimeErr::Internal(message.to_string())))
+    }
+
+    /// TEST-U-078: 错误分类测试
+    #[test]
+    fn test_error_classification() {
+        let deadlock = DbErr::Exec(RuntimeErr::Internal(
+            "Deadlock found when trying to get lock; try restarting transaction".to_string(),
+        ));
+        assert_eq!(ErrorClass::of(&deadlock), ErrorClass::Retryable);
+
+        let serialization = DbErr::Query(RuntimeErr::Internal(
+            "error returned from database: could not serialize access due to concurrent update".to_string(),
+        ));
+        assert_eq!(ErrorClass::of(&serialization), ErrorClass::Retryable);
+
+        let conn = DbErr::Conn(RuntimeErr::Internal("connection reset by peer".to_string()));
+        assert_eq!(ErrorClass::of(&conn), ErrorClass::Retryable);
+
+        let syntax = DbErr::Exec(RuntimeErr::Internal("near \"SELEC\": syntax error".to_string()));
+        assert_eq!(ErrorClass::of(&syntax), ErrorClass::Fatal);
+
+        let permission = DbError::Permission {
+            role: "guest".to_string(),
+            table: "users".to_string(),
+            operation: crate::permission::PermissionAction::Delete,
+        };
+        assert_eq!(ErrorClass::of_db_error(&permission), ErrorClass::Fatal);
+    }
+
+    /// TEST-U-079: 退避时间计算测试
+    #[test]
+    fn test_backoff_growth() {
+        let policy = RetryPolicy::new(5)
+            .with_base_backoff(Duration::from_millis(10))
+            .with_max_backoff(Duration::from_millis(35))
+            .with_jitter(false);
+        assert_eq!(policy.backoff(1), Duration::from_millis(10));
+        assert_eq!(policy.backoff(2), Duration::from_millis(20));
+        assert_eq!(policy.backoff(3), Duration::from_millis(35));
+
+        let jittered = policy.with_jitter(true);
+        for retry in 1..=3 {
+            let delay = jittered.backoff(retry);
+            let full = policy.backoff(retry);
+            assert!(delay >= full / 2 && delay <= full);
+        }
+    }
+
+    /// TEST-U-080: 两次瞬时失败后成功，恰好尝试三次
+    #[tokio::test]
+    async fn test_retry_until_success() {
+        let policy = RetryPolicy::new(5).with_base_backoff(Duration::from_millis(1));
+        let attempts = AtomicU32::new(0);
+        let mut retries = Vec::new();
+
+        let result = policy
+            .run_with(
+                |attempt| {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        if attempt < 3 {
+                            Err(exec_err("deadlock detected"))
+                        } else {
+                            Ok(attempt)
+                        }
+                    }
+                },
+                |next, _| retries.push(next),
+            )
+            .await;
+
+        assert_eq!(result.expect("Operation should eventually succeed"), 3);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert_eq!(retries, vec![2, 3]);
+    }
+
+    /// TEST-U-081: 不可重试错误与尝试次数用尽
+    #[tokio::test]
+    async fn test_retry_stops_on_fatal_and_exhaustion() {
+        let policy = RetryPolicy::new(3).with_base_backoff(Duration::from_millis(1));
+
+        let attempts = AtomicU32::new(0);
+        let result: DbResult<()> = policy
+            .run(|_| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async { Err(exec_err("UNIQUE constraint failed: users.id")) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        let attempts = AtomicU32::new(0);
+        let result: DbResult<()> = policy
+            .run(|_| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async { Err(exec_err("database is locked")) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+}
@@ -10,18 +10,44 @@
 //! - `Monthly`: 按月分片
 //! - `Daily`: 按天分片
 //! - `Hash`: 哈希分片
+//! - `ConsistentHash`: 一致性哈希分片（虚拟节点，扩缩容时仅迁移少量数据）
+//! - `Range`: 按整数键区间分片（如租户 ID 区间）
 //!
 //! # Example
 //!
 //! ```ignore
 //! use dbnexus::sharding::{ShardRouter, ShardConfig};
 //!
-//! let config = ShardConfig::new("yearly", 12, "order", "postgresql://localhost/{shard}");
-//! let mut router = ShardRouter::with_config(&config);
+//! let config = ShardConfig::new("yearly", 12, "order", "postgresql://localhost/{shard}")?;
+//! let router = ShardRouter::with_config(&config)?;
+//!
+//! // 按需为每个分片创建连接池，并把 Session 路由到正确的分片
+//! let shards = ShardPool::new(router);
+//! let session = shards.get_session_for(Utc::now(), "user-42", "admin").await?;
 //! ```
 
+use crate::config::{DbError, DbResult};
+#[cfg(feature = "metrics")]
+use crate::metrics::MetricsCollector;
+use crate::pool::{DbPool, DbPoolBuilder, Session};
 use chrono::{DateTime, Datelike, Utc};
+use futures::future::join_all;
+use sea_orm::{ConnectionTrait, Database, DbBackend, QueryResult, Statement, Value};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use tokio::sync::{OnceCell, Semaphore};
+use twox_hash::XxHash64;
+
+/// 一致性哈希默认虚拟节点数（每个分片）
+pub const DEFAULT_VIRTUAL_NODES: u32 = 160;
+
+/// 跨分片查询默认最大并发数
+pub const DEFAULT_SHARD_QUERY_CONCURRENCY: usize = 8;
 
 /// 分片策略 trait
 pub trait ShardingStrategy: Send + Sync {
@@ -39,10 +65,41 @@ pub trait ShardingStrategy: Send + Sync {
 
     /// 克隆策略到 Box
     fn boxed_clone(&self) -> Box<dyn ShardingStrategy>;
+
+    /// 克隆一个针对 `total_shards` 个分片准备好的策略
+    ///
+    /// 默认同 [`boxed_clone`](Self::boxed_clone)；需要按分片数预计算状态的策略（如一致性哈希环）应重写
+    fn for_total_shards(&self, _total_shards: u32) -> Box<dyn ShardingStrategy> {
+        self.boxed_clone()
+    }
+
+    /// 根据时间和关键字计算分片 ID
+    ///
+    /// 默认组合时间与关键字的哈希后取模；关键字为空时退化为 [`calculate`](Self::calculate)
+    fn calculate_with_key(&self, timestamp: DateTime<Utc>, key: &str, total_shards: u32) -> u32 {
+        if key.is_empty() {
+            return self.calculate(timestamp, total_shards);
+        }
+
+        let mut hasher = XxHash64::default();
+        timestamp.to_rfc3339().as_bytes().hash(&mut hasher);
+        key.as_bytes().hash(&mut hasher);
+        (hasher.finish() % total_shards as u64) as u32
+    }
+
+    /// 根据整数键计算分片 ID
+    ///
+    /// 默认将键视为 Unix 时间戳（秒）并委托给 [`calculate`](Self::calculate)
+    fn calculate_by_key(&self, key: i64, total_shards: u32) -> u32 {
+        let timestamp = DateTime::from_timestamp(key, 0).unwrap_or_default();
+        self.calculate(timestamp, total_shards)
+    }
 }
 
 /// 年分片策略
-/// 按年份划分，如 2024 年对应 shard_id = 2024 % total_shards
+/// 按年份取模划分，如 2024 年对应 shard_id = 2024 % total_shards。
+/// 分片 ID 空间为 `0..total_shards`，与 [`ShardConfig::generate_all_connections`] 一致；
+/// 相差 `total_shards` 整数倍的年份（如 12 个分片下的 2024 与 2036）会落在同一分片。
 #[derive(Debug, Clone, Copy)]
 pub struct YearlyStrategy;
 
@@ -69,9 +126,8 @@ impl ShardingStrategy for YearlyStrategy {
         "yearly"
     }
 
-    fn is_valid_shard_id(&self, shard_id: u32, _total_shards: u32) -> bool {
-        // 年分片 ID 通常是年份本身，所以只需要基本验证
-        shard_id > 0
+    fn is_valid_shard_id(&self, shard_id: u32, total_shards: u32) -> bool {
+        shard_id < total_shards
     }
 
     fn current_shard(&self, total_shards: u32) -> u32 {
@@ -185,9 +241,6 @@ impl Default for HashStrategy {
 
 impl ShardingStrategy for HashStrategy {
     fn calculate(&self, timestamp: DateTime<Utc>, total_shards: u32) -> u32 {
-        use std::hash::{Hash, Hasher};
-        use twox_hash::XxHash64;
-
         let mut hasher = XxHash64::default();
         timestamp.to_rfc3339().as_bytes().hash(&mut hasher);
         let hash = hasher.finish();
@@ -211,17 +264,307 @@ impl ShardingStrategy for HashStrategy {
     }
 }
 
-/// 根据字符串创建分片策略
-pub fn create_strategy(name: &str) -> Box<dyn ShardingStrategy> {
-    match name.to_lowercase().as_str() {
+/// 一致性哈希分片策略
+///
+/// 每个分片在哈希环上放置若干虚拟节点，键按顺时针落到最近的虚拟节点。
+/// 分片数由 N 变为 N+1 时，仅约 1/(N+1) 的键需要迁移。
+#[derive(Debug, Clone)]
+pub struct ConsistentHashStrategy {
+    /// 每个分片的虚拟节点数
+    virtual_nodes: u32,
+    /// 预构建哈希环对应的分片数
+    ring_shards: u32,
+    /// 预构建的哈希环（按哈希值排序）
+    ring: Vec<(u64, u32)>,
+}
+
+impl ConsistentHashStrategy {
+    /// 创建一致性哈希策略，并为 `0..total_shards` 预构建哈希环
+    pub fn new(total_shards: u32, virtual_nodes: u32) -> Self {
+        let virtual_nodes = virtual_nodes.max(1);
+        Self {
+            virtual_nodes,
+            ring_shards: total_shards,
+            ring: Self::build_ring(total_shards, virtual_nodes),
+        }
+    }
+
+    /// 获取每个分片的虚拟节点数
+    pub fn virtual_nodes(&self) -> u32 {
+        self.virtual_nodes
+    }
+
+    /// 根据关键字在哈希环上定位分片 ID
+    pub fn locate(&self, key: &str, total_shards: u32) -> u32 {
+        if total_shards == 0 {
+            return 0;
+        }
+
+        let ring = self.ring_for(total_shards);
+        let hash = Self::hash_key(key.as_bytes());
+        let index = ring.partition_point(|(point, _)| *point < hash);
+        ring.get(index)
+            .or_else(|| ring.first())
+            .map(|(_, shard)| *shard)
+            .unwrap_or(0)
+    }
+
+    fn ring_for(&self, total_shards: u32) -> Cow<'_, [(u64, u32)]> {
+        if total_shards == self.ring_shards {
+            Cow::Borrowed(&self.ring)
+        } else {
+            Cow::Owned(Self::build_ring(total_shards, self.virtual_nodes))
+        }
+    }
+
+    fn build_ring(total_shards: u32, virtual_nodes: u32) -> Vec<(u64, u32)> {
+        #[cfg(test)]
+        tests::RING_BUILDS.with(|builds| builds.set(builds.get() + 1));
+
+        let mut ring = Vec::with_capacity(total_shards as usize * virtual_nodes as usize);
+        for shard_id in 0..total_shards {
+            for node in 0..virtual_nodes {
+                let point = Self::hash_key(format!("shard-{}#{}", shard_id, node).as_bytes());
+                ring.push((point, shard_id));
+            }
+        }
+        ring.sort_unstable();
+        ring
+    }
+
+    fn hash_key(bytes: &[u8]) -> u64 {
+        let mut hasher = XxHash64::default();
+        bytes.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl Default for ConsistentHashStrategy {
+    fn default() -> Self {
+        Self::new(0, DEFAULT_VIRTUAL_NODES)
+    }
+}
+
+impl ShardingStrategy for ConsistentHashStrategy {
+    fn calculate(&self, timestamp: DateTime<Utc>, total_shards: u32) -> u32 {
+        self.locate(&timestamp.to_rfc3339(), total_shards)
+    }
+
+    fn name(&self) -> &'static str {
+        "consistent_hash"
+    }
+
+    fn is_valid_shard_id(&self, shard_id: u32, total_shards: u32) -> bool {
+        shard_id < total_shards
+    }
+
+    fn current_shard(&self, total_shards: u32) -> u32 {
+        self.calculate(Utc::now(), total_shards)
+    }
+
+    fn boxed_clone(&self) -> Box<dyn ShardingStrategy> {
+        Box::new(self.clone())
+    }
+
+    fn for_total_shards(&self, total_shards: u32) -> Box<dyn ShardingStrategy> {
+        if total_shards == self.ring_shards {
+            Box::new(self.clone())
+        } else {
+            Box::new(Self::new(total_shards, self.virtual_nodes))
+        }
+    }
+
+    fn calculate_with_key(&self, timestamp: DateTime<Utc>, key: &str, total_shards: u32) -> u32 {
+        // 仅按关键字定位，保证同一关键字在扩缩容前后尽量落在同一分片
+        if key.is_empty() {
+            self.calculate(timestamp, total_shards)
+        } else {
+            self.locate(key, total_shards)
+        }
+    }
+}
+
+/// 区间分片策略
+///
+/// 按整数键所在的区间映射到指定分片，未命中任何区间时使用默认分片。
+/// 区间按声明顺序匹配，重叠时以先声明者为准。
+/// 按时间路由时以 Unix 秒级时间戳作为区间键，时间区间可通过 [`from_time_ranges`](Self::from_time_ranges) 声明。
+/// 区间或默认分片超出 `0..total_shards` 时路由到最后一个分片，可用 [`validate`](Self::validate) 提前检查。
+#[derive(Debug, Clone)]
+pub struct RangeStrategy {
+    /// 区间到分片 ID 的映射
+    ranges: Vec<(RangeInclusive<i64>, u32)>,
+    /// 默认分片 ID
+    default_shard: u32,
+}
+
+impl RangeStrategy {
+    /// 创建区间分片策略
+    pub fn new(ranges: Vec<(RangeInclusive<i64>, u32)>, default_shard: u32) -> Self {
+        Self { ranges, default_shard }
+    }
+
+    /// 按时间区间创建策略，区间端点换算为 Unix 秒级时间戳
+    pub fn from_time_ranges(ranges: Vec<(RangeInclusive<DateTime<Utc>>, u32)>, default_shard: u32) -> Self {
+        let ranges = ranges
+            .into_iter()
+            .map(|(range, shard_id)| (range.start().timestamp()..=range.end().timestamp(), shard_id))
+            .collect();
+        Self::new(ranges, default_shard)
+    }
+
+    /// 获取默认分片 ID
+    pub fn default_shard(&self) -> u32 {
+        self.default_shard
+    }
+
+    /// 检查所有区间和默认分片都指向 `0..total_shards` 内的分片
+    ///
+    /// # Errors
+    ///
+    /// 存在超出范围的分片 ID 时返回配置错误
+    pub fn validate(&self, total_shards: u32) -> DbResult<()> {
+        let out_of_range = self
+            .ranges
+            .iter()
+            .map(|(_, shard_id)| *shard_id)
+            .chain(std::iter::once(self.default_shard))
+            .find(|shard_id| *shard_id >= total_shards);
+        match out_of_range {
+            Some(shard_id) => Err(DbError::Config(format!(
+                "Range sharding targets shard {} but only {} shards are configured",
+                shard_id, total_shards
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+impl ShardingStrategy for RangeStrategy {
+    fn calculate(&self, timestamp: DateTime<Utc>, total_shards: u32) -> u32 {
+        // 以 Unix 秒级时间戳作为区间键
+        self.calculate_by_key(timestamp.timestamp(), total_shards)
+    }
+
+    fn name(&self) -> &'static str {
+        "range"
+    }
+
+    fn is_valid_shard_id(&self, shard_id: u32, total_shards: u32) -> bool {
+        shard_id < total_shards
+    }
+
+    fn current_shard(&self, total_shards: u32) -> u32 {
+        self.calculate(Utc::now(), total_shards)
+    }
+
+    fn boxed_clone(&self) -> Box<dyn ShardingStrategy> {
+        Box::new(self.clone())
+    }
+
+    fn calculate_by_key(&self, key: i64, total_shards: u32) -> u32 {
+        let shard_id = self
+            .ranges
+            .iter()
+            .find(|(range, _)| range.contains(&key))
+            .map(|(_, shard_id)| *shard_id)
+            .unwrap_or(self.default_shard);
+
+        let last_shard = total_shards.saturating_sub(1);
+        if shard_id > last_shard {
+            tracing::warn!(
+                key,
+                shard_id,
+                total_shards,
+                "Range sharding target is out of range, routing to the last shard"
+            );
+            return last_shard;
+        }
+        shard_id
+    }
+}
+
+/// 可通过名称创建的分片策略（规范名称，即 [`ShardingStrategy::name`] 的返回值）
+///
+/// `range` 策略需要区间配置，不能仅凭名称创建
+pub const STRATEGY_NAMES: &[&str] = &["yearly", "monthly", "daily", "hash", "consistent_hash"];
+
+impl dyn ShardingStrategy {
+    /// 根据名称创建分片策略，名称不区分大小写并支持别名（如 `year`、`consistent`）
+    ///
+    /// 未知名称返回 `None`，与 [`ShardingStrategy::name`] 互为逆操作
+    pub fn from_name(name: &str) -> Option<Box<dyn ShardingStrategy>> {
+        strategy_from_name(name, None)
+    }
+}
+
+/// 根据名称创建分片策略，`total_shards` 存在时一致性哈希策略按其预构建哈希环
+fn strategy_from_name(name: &str, total_shards: Option<u32>) -> Option<Box<dyn ShardingStrategy>> {
+    let strategy: Box<dyn ShardingStrategy> = match name.to_lowercase().as_str() {
         "yearly" | "year" => Box::new(YearlyStrategy),
         "monthly" | "month" => Box::new(MonthlyStrategy),
         "daily" | "day" => Box::new(DailyStrategy),
         "hash" => Box::new(HashStrategy),
-        _ => Box::new(YearlyStrategy), // 默认使用年分片
+        "consistent_hash" | "consistent" => match total_shards {
+            Some(total_shards) => Box::new(ConsistentHashStrategy::new(total_shards, DEFAULT_VIRTUAL_NODES)),
+            None => Box::new(ConsistentHashStrategy::default()),
+        },
+        _ => return None,
+    };
+    Some(strategy)
+}
+
+/// 校验策略名称，未知名称返回配置错误
+fn validate_strategy_name(name: &str) -> DbResult<()> {
+    match strategy_from_name(name, None) {
+        Some(_) => Ok(()),
+        None => Err(unknown_strategy(name)),
     }
 }
 
+fn unknown_strategy(name: &str) -> DbError {
+    DbError::Config(format!(
+        "Unknown sharding strategy '{}', expected one of: {}",
+        name,
+        STRATEGY_NAMES.join(", ")
+    ))
+}
+
+/// 根据字符串创建分片策略
+///
+/// 未知名称回退为年分片；需要校验名称时使用 [`from_name`](trait.ShardingStrategy.html#method.from_name)
+pub fn create_strategy(name: &str) -> Box<dyn ShardingStrategy> {
+    <dyn ShardingStrategy>::from_name(name).unwrap_or_else(|| Box::new(YearlyStrategy))
+}
+
+/// 根据字符串和总分片数创建分片策略
+///
+/// 一致性哈希策略会按 `total_shards` 预构建哈希环，其他策略同 [`create_strategy`]
+fn create_strategy_for(name: &str, total_shards: u32) -> Box<dyn ShardingStrategy> {
+    strategy_from_name(name, Some(total_shards)).unwrap_or_else(|| Box::new(YearlyStrategy))
+}
+
+/// 跨分片查询中单个分片失败时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShardFailureMode {
+    /// 任一分片失败即返回包含所有失败分片信息的错误
+    #[default]
+    FailFast,
+    /// 跳过失败的分片（记录警告），只合并成功分片的结果；所有分片都失败时仍返回错误
+    Partial,
+}
+
+/// 重新分片时需要迁移的一条记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardMove {
+    /// 记录的路由关键字
+    pub key: String,
+    /// 原分片 ID
+    pub old_shard: u32,
+    /// 新分片 ID
+    pub new_shard: u32,
+}
+
 /// 分片信息
 #[derive(Debug, Clone)]
 pub struct ShardInfo {
@@ -241,6 +584,10 @@ pub struct ShardRouter {
     strategy: Box<dyn ShardingStrategy>,
     /// 分片配置映射
     shards: HashMap<u32, ShardInfo>,
+    /// 跨分片查询最大并发数
+    max_concurrency: usize,
+    /// 跨分片查询的失败处理方式
+    failure_mode: ShardFailureMode,
 }
 
 impl Clone for ShardRouter {
@@ -249,17 +596,23 @@ impl Clone for ShardRouter {
             total_shards: self.total_shards,
             strategy: self.strategy.boxed_clone(),
             shards: self.shards.clone(),
+            max_concurrency: self.max_concurrency,
+            failure_mode: self.failure_mode,
         }
     }
 }
 
 impl ShardRouter {
     /// 创建新的分片路由器
+    ///
+    /// 策略通过 [`ShardingStrategy::for_total_shards`] 按 `total_shards` 准备，如一致性哈希环只在此构建一次
     pub fn new<S: ShardingStrategy + 'static>(strategy: S, total_shards: u32) -> Self {
         Self {
             total_shards,
-            strategy: Box::new(strategy),
+            strategy: strategy.for_total_shards(total_shards),
             shards: HashMap::new(),
+            max_concurrency: DEFAULT_SHARD_QUERY_CONCURRENCY,
+            failure_mode: ShardFailureMode::default(),
         }
     }
 
@@ -267,20 +620,34 @@ impl ShardRouter {
     pub fn with_strategy(strategy: &str, total_shards: u32) -> Self {
         Self {
             total_shards,
-            strategy: create_strategy(strategy),
+            strategy: create_strategy_for(strategy, total_shards),
             shards: HashMap::new(),
+            max_concurrency: DEFAULT_SHARD_QUERY_CONCURRENCY,
+            failure_mode: ShardFailureMode::default(),
         }
     }
 
     /// 使用配置创建路由器
-    pub fn with_config(config: &ShardConfig) -> Self {
-        let mut router = Self::with_strategy(&config.strategy, config.total_shards);
+    ///
+    /// # Errors
+    ///
+    /// 配置中的策略名称未知时返回错误
+    pub fn with_config(config: &ShardConfig) -> DbResult<Self> {
+        let strategy = strategy_from_name(&config.strategy, Some(config.total_shards))
+            .ok_or_else(|| unknown_strategy(&config.strategy))?;
+        let mut router = Self {
+            total_shards: config.total_shards,
+            strategy,
+            shards: HashMap::new(),
+            max_concurrency: DEFAULT_SHARD_QUERY_CONCURRENCY,
+            failure_mode: ShardFailureMode::default(),
+        };
 
         for (shard_id, connection_string) in config.generate_all_connections() {
             router.register_shard(shard_id, format!("{}_{}", config.prefix, shard_id), connection_string);
         }
 
-        router
+        Ok(router)
     }
 
     /// 注册分片
@@ -307,21 +674,15 @@ impl ShardRouter {
         self.shards.get(&shard_id)
     }
 
+    /// 根据整数键路由到分片
+    pub fn route_by_key(&self, key: i64) -> Option<&ShardInfo> {
+        let shard_id = self.strategy.calculate_by_key(key, self.total_shards);
+        self.shards.get(&shard_id)
+    }
+
     /// 计算分片 ID（不依赖注册的分片）
     pub fn calculate_shard(&self, timestamp: DateTime<Utc>, key: &str) -> u32 {
-        if key.is_empty() {
-            self.strategy.calculate(timestamp, self.total_shards)
-        } else {
-            // 组合时间和关键字的哈希
-            use std::hash::{Hash, Hasher};
-            use twox_hash::XxHash64;
-
-            let mut hasher = XxHash64::default();
-            timestamp.to_rfc3339().as_bytes().hash(&mut hasher);
-            key.as_bytes().hash(&mut hasher);
-            let hash = hasher.finish();
-            (hash % self.total_shards as u64) as u32
-        }
+        self.strategy.calculate_with_key(timestamp, key, self.total_shards)
     }
 
     /// 获取所有分片
@@ -338,12 +699,418 @@ impl ShardRouter {
     pub fn total_shards(&self) -> u32 {
         self.total_shards
     }
+
+    /// 设置跨分片查询最大并发数（最小为 1）
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    /// 获取跨分片查询最大并发数
+    pub fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    /// 生成分片数由 `old_total` 变为 `new_total` 时的迁移计划
+    ///
+    /// 按路由器的策略分别计算每个 `(时间戳, 关键字)` 在新旧分片数下的分片 ID，
+    /// 只返回分片发生变化的记录，顺序与输入一致。一致性哈希策略下迁移量最小，
+    /// 取模哈希策略下大部分记录都会迁移。
+    ///
+    /// # Errors
+    ///
+    /// `old_total` 或 `new_total` 为 0 时返回错误
+    pub fn reshard_plan(
+        &self,
+        old_total: u32,
+        new_total: u32,
+        keys: &[(DateTime<Utc>, String)],
+    ) -> DbResult<Vec<ShardMove>> {
+        if old_total == 0 || new_total == 0 {
+            return Err(DbError::Config(format!(
+                "Cannot plan resharding from {} to {} shards: shard count must be positive",
+                old_total, new_total
+            )));
+        }
+
+        let old_strategy = self.strategy.for_total_shards(old_total);
+        let new_strategy = self.strategy.for_total_shards(new_total);
+        let plan = keys
+            .iter()
+            .filter_map(|(timestamp, key)| {
+                let old_shard = old_strategy.calculate_with_key(*timestamp, key, old_total);
+                let new_shard = new_strategy.calculate_with_key(*timestamp, key, new_total);
+                (old_shard != new_shard).then(|| ShardMove {
+                    key: key.clone(),
+                    old_shard,
+                    new_shard,
+                })
+            })
+            .collect();
+
+        Ok(plan)
+    }
+
+    /// 设置跨分片查询的失败处理方式
+    pub fn with_failure_mode(mut self, failure_mode: ShardFailureMode) -> Self {
+        self.failure_mode = failure_mode;
+        self
+    }
+
+    /// 获取跨分片查询的失败处理方式
+    pub fn failure_mode(&self) -> ShardFailureMode {
+        self.failure_mode
+    }
+
+    /// 在所有已注册分片上并行执行查询并合并结果
+    ///
+    /// 每个分片按 `connection_string` 建立连接，结果按分片 ID 顺序拼接。
+    /// 分片失败时按 [`failure_mode`](Self::failure_mode) 处理。
+    pub async fn query_all_shards_merged<T, F>(&self, sql: &str, params: Vec<Value>, map_row: F) -> DbResult<Vec<T>>
+    where
+        F: Fn(&QueryResult) -> DbResult<T>,
+    {
+        let map_row = &map_row;
+        let results = self
+            .scatter(|shard| {
+                let params = params.clone();
+                async move { Self::query_shard(shard, sql, params, map_row).await }
+            })
+            .await?;
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    /// 统计所有分片上满足条件的行数之和
+    ///
+    /// `where_clause` 为不含 `WHERE` 关键字的原始 SQL 条件，会原样拼接到每个分片的查询中，
+    /// 不要传入未经校验的用户输入。空分片计为 0。
+    ///
+    /// # Errors
+    ///
+    /// 表名不合法，或分片失败且 [`failure_mode`](Self::failure_mode) 不允许跳过时返回错误
+    pub async fn count_all_shards(&self, table: &str, where_clause: Option<&str>) -> DbResult<u64> {
+        validate_identifier(table)?;
+        let sql = with_where(format!("SELECT COUNT(*) AS total FROM {}", table), where_clause);
+
+        let counts = self
+            .aggregate(|_| sql.clone(), |row| Ok(row.try_get::<i64>("", "total")?))
+            .await?;
+        Ok(counts.into_iter().map(|count| count.max(0) as u64).sum())
+    }
+
+    /// 对所有分片上指定列求和（浮点）
+    ///
+    /// 空分片或列全为 NULL 时计为 0，其他约定同 [`count_all_shards`](Self::count_all_shards)
+    ///
+    /// # Errors
+    ///
+    /// 表名或列名不合法，或分片失败且 [`failure_mode`](Self::failure_mode) 不允许跳过时返回错误
+    pub async fn sum_all_shards(&self, table: &str, column: &str, where_clause: Option<&str>) -> DbResult<f64> {
+        let sums: Vec<f64> = self
+            .sum_shards(table, column, where_clause, |backend| match backend {
+                DbBackend::Postgres => "DOUBLE PRECISION",
+                DbBackend::MySql => "DOUBLE",
+                _ => "REAL",
+            })
+            .await?;
+        Ok(sums.into_iter().sum())
+    }
+
+    /// 对所有分片上指定整数列求和
+    ///
+    /// 约定同 [`sum_all_shards`](Self::sum_all_shards)，合计溢出 `i64` 时返回错误
+    ///
+    /// # Errors
+    ///
+    /// 表名或列名不合法、合计溢出，或分片失败且 [`failure_mode`](Self::failure_mode) 不允许跳过时返回错误
+    pub async fn sum_all_shards_i64(&self, table: &str, column: &str, where_clause: Option<&str>) -> DbResult<i64> {
+        let sums: Vec<i64> = self
+            .sum_shards(table, column, where_clause, |backend| match backend {
+                DbBackend::MySql => "SIGNED",
+                DbBackend::Postgres => "BIGINT",
+                _ => "INTEGER",
+            })
+            .await?;
+        sums.into_iter().try_fold(0i64, |total, sum| {
+            total
+                .checked_add(sum)
+                .ok_or_else(|| DbError::Config(format!("Sum of '{}.{}' across shards overflows i64", table, column)))
+        })
+    }
+
+    /// 在每个分片上执行 `SUM`，结果按 `cast_type` 转换以统一各数据库的返回类型
+    async fn sum_shards<T>(
+        &self,
+        table: &str,
+        column: &str,
+        where_clause: Option<&str>,
+        cast_type: fn(DbBackend) -> &'static str,
+    ) -> DbResult<Vec<T>>
+    where
+        T: sea_orm::TryGetable,
+    {
+        validate_identifier(table)?;
+        validate_identifier(column)?;
+
+        self.aggregate(
+            |backend| {
+                with_where(
+                    format!(
+                        "SELECT CAST(COALESCE(SUM({}), 0) AS {}) AS total FROM {}",
+                        column,
+                        cast_type(backend),
+                        table
+                    ),
+                    where_clause,
+                )
+            },
+            |row| Ok(row.try_get::<T>("", "total")?),
+        )
+        .await
+    }
+
+    /// 在每个分片上执行返回单行的聚合查询，按分片 ID 顺序返回各分片的结果
+    async fn aggregate<T, S, F>(&self, build_sql: S, read: F) -> DbResult<Vec<T>>
+    where
+        S: Fn(DbBackend) -> String,
+        F: Fn(&QueryResult) -> DbResult<T>,
+    {
+        let build_sql = &build_sql;
+        let read = &read;
+        self.scatter(|shard| async move {
+            let conn = Database::connect(&shard.connection_string).await?;
+            let backend = conn.get_database_backend();
+            let row = conn
+                .query_one_raw(Statement::from_string(backend, build_sql(backend)))
+                .await;
+            let _ = conn.close().await;
+
+            let row = row?.ok_or_else(|| DbError::Config("Aggregate query returned no rows".to_string()))?;
+            read(&row)
+        })
+        .await
+    }
+
+    /// 在所有已注册分片上并行执行 `op`，按分片 ID 顺序返回成功的结果
+    ///
+    /// 并发数受 [`max_concurrency`](Self::max_concurrency) 限制，失败分片按 [`failure_mode`](Self::failure_mode) 处理
+    async fn scatter<'s, T, Op, Fut>(&'s self, op: Op) -> DbResult<Vec<T>>
+    where
+        Op: Fn(&'s ShardInfo) -> Fut,
+        Fut: Future<Output = DbResult<T>>,
+    {
+        let mut shards: Vec<&ShardInfo> = self.shards.values().collect();
+        shards.sort_by_key(|shard| shard.shard_id);
+        let total = shards.len();
+
+        let semaphore = Arc::new(Semaphore::new(self.max_concurrency));
+        let op = &op;
+        let tasks = shards.into_iter().map(|shard| {
+            let semaphore = semaphore.clone();
+            async move {
+                let result = match semaphore.acquire().await {
+                    Ok(_permit) => op(shard).await,
+                    Err(e) => Err(DbError::Config(format!("Shard query semaphore closed: {}", e))),
+                };
+                result.map_err(|e| format!("shard '{}' ({}): {}", shard.name, shard.shard_id, e))
+            }
+        });
+
+        let mut succeeded = Vec::with_capacity(total);
+        let mut failures = Vec::new();
+        for result in join_all(tasks).await {
+            match result {
+                Ok(value) => succeeded.push(value),
+                Err(e) => failures.push(e),
+            }
+        }
+
+        let skip_failures = self.failure_mode == ShardFailureMode::Partial && failures.len() < total;
+        if failures.is_empty() || skip_failures {
+            if !failures.is_empty() {
+                tracing::warn!(
+                    "Skipping {} failed shard(s) of {}: {}",
+                    failures.len(),
+                    total,
+                    failures.join("; ")
+                );
+            }
+            Ok(succeeded)
+        } else {
+            Err(DbError::Connection(sea_orm::DbErr::Custom(format!(
+                "Query failed on {} shard(s): {}",
+                failures.len(),
+                failures.join("; ")
+            ))))
+        }
+    }
+
+    async fn query_shard<T, F>(shard: &ShardInfo, sql: &str, params: Vec<Value>, map_row: &F) -> DbResult<Vec<T>>
+    where
+        F: Fn(&QueryResult) -> DbResult<T>,
+    {
+        let conn = Database::connect(&shard.connection_string).await?;
+        let statement = Statement::from_sql_and_values(conn.get_database_backend(), sql, params);
+        let rows = conn.query_all_raw(statement).await;
+        let _ = conn.close().await;
+
+        rows?.iter().map(map_row).collect()
+    }
+}
+
+/// 配置单个分片连接池构建器的回调
+type ShardBuilderFn = dyn Fn(&ShardInfo, DbPoolBuilder) -> DbPoolBuilder + Send + Sync;
+
+/// 分片连接池
+///
+/// 持有一个 [`ShardRouter`]，首次访问某个分片时才用 [`DbPoolBuilder`] 创建该分片的 [`DbPool`] 并缓存。
+/// 每个分片使用独立的初始化单元：不同分片可并行创建，同一分片的并发请求只创建一次，创建失败时下次访问会重试。
+pub struct ShardPool {
+    /// 分片路由器
+    router: ShardRouter,
+    /// 分片 ID 到连接池的映射
+    pools: std::sync::Mutex<HashMap<u32, Arc<OnceCell<DbPool>>>>,
+    /// 自定义每个分片的连接池构建器（URL 始终取自分片的连接字符串）
+    configure: Option<Box<ShardBuilderFn>>,
+    /// 各分片共享的指标收集器，每个分片以 `shard` 标签区分
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<MetricsCollector>>,
+}
+
+impl ShardPool {
+    /// 创建分片连接池，不会立即连接任何分片
+    pub fn new(router: ShardRouter) -> Self {
+        Self {
+            router,
+            pools: std::sync::Mutex::new(HashMap::new()),
+            configure: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+        }
+    }
+
+    /// 自定义每个分片的连接池构建器（如连接数、权限配置）
+    ///
+    /// 回调返回后仍会以分片的连接字符串覆盖 URL
+    pub fn with_builder<F>(mut self, configure: F) -> Self
+    where
+        F: Fn(&ShardInfo, DbPoolBuilder) -> DbPoolBuilder + Send + Sync + 'static,
+    {
+        self.configure = Some(Box::new(configure));
+        self
+    }
+
+    /// 使用共享的指标收集器
+    ///
+    /// 每个分片的连接池获得一个 [子收集器](MetricsCollector::child_with_labels)，带 `shard="<分片名>"` 标签，
+    /// 创建成功后才登记到 `metrics`，导出 `metrics` 即可得到所有分片的指标
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// 获取分片路由器
+    pub fn router(&self) -> &ShardRouter {
+        &self.router
+    }
+
+    /// 已创建连接池的分片 ID（升序）
+    pub fn initialized_shards(&self) -> Vec<u32> {
+        let pools = self.pools.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut ids: Vec<u32> = pools
+            .iter()
+            .filter(|(_, cell)| cell.initialized())
+            .map(|(shard_id, _)| *shard_id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// 获取分片的连接池，首次访问时创建
+    ///
+    /// # Errors
+    ///
+    /// 分片未注册或连接池创建失败时返回错误
+    pub async fn pool_for_shard(&self, shard_id: u32) -> DbResult<DbPool> {
+        let shard = self
+            .router
+            .shards
+            .get(&shard_id)
+            .ok_or_else(|| DbError::Config(format!("Shard {} is not registered", shard_id)))?;
+
+        let cell = self
+            .pools
+            .lock()
+            .map_err(|_| DbError::Config("Shard pool mutex poisoned".to_string()))?
+            .entry(shard_id)
+            .or_insert_with(|| Arc::new(OnceCell::new()))
+            .clone();
+
+        cell.get_or_try_init(|| self.create_pool(shard)).await.cloned()
+    }
+
+    /// 为分片创建连接池
+    async fn create_pool(&self, shard: &ShardInfo) -> DbResult<DbPool> {
+        let mut builder = DbPool::builder();
+        if let Some(configure) = &self.configure {
+            builder = configure(shard, builder);
+        }
+
+        // 子收集器在连接池创建成功后才登记，失败的初始化尝试不在导出中留下分片样本
+        #[cfg(feature = "metrics")]
+        let child = self.metrics.as_ref().map(|metrics| {
+            let labels = HashMap::from([("shard".to_string(), shard.name.clone())]);
+            metrics.detached_child(labels)
+        });
+        #[cfg(feature = "metrics")]
+        if let Some(child) = &child {
+            builder = builder.metrics(child.clone());
+        }
+
+        tracing::info!(
+            "Creating connection pool for shard '{}' ({})",
+            shard.name,
+            shard.shard_id
+        );
+        let pool = builder.url(shard.connection_string.clone()).build().await?;
+
+        #[cfg(feature = "metrics")]
+        if let (Some(metrics), Some(child)) = (&self.metrics, child) {
+            metrics.register_child(child);
+        }
+        Ok(pool)
+    }
+
+    /// 按时间戳和关键字路由到分片并获取 Session
+    ///
+    /// # Errors
+    ///
+    /// 目标分片未注册、连接池创建失败或获取 Session 失败时返回错误
+    pub async fn get_session_for(&self, timestamp: DateTime<Utc>, key: &str, role: &str) -> DbResult<Session> {
+        let shard_id = self.router.calculate_shard(timestamp, key);
+        self.get_session_for_shard_id(shard_id, role).await
+    }
+
+    /// 获取指定分片的 Session
+    ///
+    /// # Errors
+    ///
+    /// 分片未注册、连接池创建失败或获取 Session 失败时返回错误
+    pub async fn get_session_for_shard_id(&self, shard_id: u32, role: &str) -> DbResult<Session> {
+        self.pool_for_shard(shard_id).await?.get_session(role).await
+    }
 }
 
 /// 分片配置
-#[derive(Debug, Clone)]
+///
+/// 反序列化时校验策略名称，未知名称（如拼写错误的 `montly`）会报错而不是回退为默认策略
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ShardConfig {
     /// 策略名称
+    #[serde(deserialize_with = "deserialize_strategy_name")]
     pub strategy: String,
     /// 总分片数
     pub total_shards: u32,
@@ -364,20 +1131,37 @@ impl Default for ShardConfig {
     }
 }
 
+/// 反序列化并校验策略名称
+fn deserialize_strategy_name<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let name = String::deserialize(deserializer)?;
+    validate_strategy_name(&name).map_err(serde::de::Error::custom)?;
+    Ok(name)
+}
+
 impl ShardConfig {
     /// 创建分片配置
-    pub fn new(strategy: &str, total_shards: u32, prefix: &str, connection_template: &str) -> Self {
-        Self {
+    ///
+    /// # Errors
+    ///
+    /// 策略名称未知时返回错误，可选名称见 [`STRATEGY_NAMES`]
+    pub fn new(strategy: &str, total_shards: u32, prefix: &str, connection_template: &str) -> DbResult<Self> {
+        validate_strategy_name(strategy)?;
+        Ok(Self {
             strategy: strategy.to_string(),
             total_shards,
             prefix: prefix.to_string(),
             connection_template: connection_template.to_string(),
-        }
+        })
     }
 
     /// 生成连接字符串
+    ///
+    /// 支持 `{shard}`、`{prefix}`、`{id}` 以及补零占位符 `{id:04}`（宽度为冒号后的数字）
     pub fn generate_connection_string(&self, shard_id: u32) -> String {
-        self.connection_template
+        replace_padded_id(&self.connection_template, shard_id)
             .replace("{shard}", &format!("{}_{}", self.prefix, shard_id))
             .replace("{prefix}", &self.prefix)
             .replace("{id}", &shard_id.to_string())
@@ -391,11 +1175,97 @@ impl ShardConfig {
     }
 }
 
+/// 校验表名、列名只包含字母、数字、下划线和 `.`（用于 `schema.table`），避免拼接 SQL 时注入
+fn validate_identifier(name: &str) -> DbResult<()> {
+    let valid = !name.is_empty()
+        && name
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(DbError::Config(format!(
+            "Invalid identifier for shard aggregate: '{}'",
+            name
+        )))
+    }
+}
+
+/// 为聚合查询追加 `WHERE` 条件
+fn with_where(sql: String, where_clause: Option<&str>) -> String {
+    match where_clause.map(str::trim).filter(|clause| !clause.is_empty()) {
+        Some(clause) => format!("{} WHERE {}", sql, clause),
+        None => sql,
+    }
+}
+
+/// 替换模板中的 `{id:0N}` / `{id:N}` 占位符为补零后的分片 ID
+///
+/// 无法解析宽度的占位符保持原样
+fn replace_padded_id(template: &str, shard_id: u32) -> String {
+    const PREFIX: &str = "{id:";
+
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find(PREFIX) {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + PREFIX.len()..];
+        let width = after
+            .find('}')
+            .and_then(|end| after[..end].parse::<usize>().ok().map(|width| (end, width)));
+
+        match width {
+            Some((end, width)) => {
+                result.push_str(&format!("{:0width$}", shard_id, width = width));
+                rest = &after[end + 1..];
+            }
+            None => {
+                result.push_str(PREFIX);
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use chrono::{TimeZone, Utc};
 
+    thread_local! {
+        /// 当前线程构建一致性哈希环的次数
+        pub(super) static RING_BUILDS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    /// TEST-U-116: 路由器只为分片数构建一次哈希环，路由时不再重建
+    #[test]
+    fn test_consistent_hash_ring_built_once() {
+        let builds = || RING_BUILDS.with(std::cell::Cell::get);
+        let mut router = ShardRouter::new(ConsistentHashStrategy::default(), 8);
+        let before = builds();
+        for shard_id in 0..8 {
+            router.register_shard(
+                shard_id,
+                format!("shard_{}", shard_id),
+                format!("sqlite:shard_{}", shard_id),
+            );
+        }
+
+        let timestamp = Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap();
+        for _ in 0..100 {
+            assert!(router.route(timestamp).is_some());
+        }
+        assert_eq!(builds(), before, "Routing must reuse the prebuilt ring");
+
+        // 分片数一致时不重建
+        let strategy = ConsistentHashStrategy::new(8, DEFAULT_VIRTUAL_NODES);
+        let before = builds();
+        let _router = ShardRouter::new(strategy, 8);
+        assert_eq!(builds(), before);
+    }
+
     #[test]
     fn test_yearly_strategy() {
         let strategy = YearlyStrategy;
@@ -448,7 +1318,8 @@ mod tests {
 
     #[test]
     fn test_shard_config() {
-        let config = ShardConfig::new("yearly", 12, "order", "postgresql://localhost/{shard}");
+        let config = ShardConfig::new("yearly", 12, "order", "postgresql://localhost/{shard}")
+            .expect("Failed to create shard config");
 
         assert_eq!(config.generate_connection_string(4), "postgresql://localhost/order_4");
         assert_eq!(config.strategy, "yearly");
@@ -456,11 +1327,184 @@ mod tests {
 
     #[test]
     fn test_router_with_config() {
-        let config = ShardConfig::new("yearly", 4, "data", "postgresql://localhost/{shard}");
-        let router = ShardRouter::with_config(&config);
+        let config = ShardConfig::new("yearly", 4, "data", "postgresql://localhost/{shard}")
+            .expect("Failed to create shard config");
+        let router = ShardRouter::with_config(&config).expect("Failed to create router");
 
         assert_eq!(router.total_shards(), 4);
         assert_eq!(router.all_shards().len(), 4);
         assert_eq!(router.strategy_name(), "yearly");
     }
+
+    /// TEST-U-048: 一致性哈希扩容时仅迁移少量键
+    #[test]
+    fn test_consistent_hash_rebalance() {
+        let before = ConsistentHashStrategy::new(4, DEFAULT_VIRTUAL_NODES);
+        let after = ConsistentHashStrategy::new(5, DEFAULT_VIRTUAL_NODES);
+        let dt = Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap();
+
+        let total_keys = 10_000;
+        let mut moved = 0;
+        for i in 0..total_keys {
+            let key = format!("user_{}", i);
+            let old_shard = before.calculate_with_key(dt, &key, 4);
+            let new_shard = after.calculate_with_key(dt, &key, 5);
+            if old_shard != new_shard {
+                // 迁移的键只能落到新增的分片上
+                assert_eq!(new_shard, 4);
+                moved += 1;
+            }
+        }
+
+        let fraction = moved as f64 / total_keys as f64;
+        assert!(
+            fraction > 0.1 && fraction < 0.3,
+            "moved fraction {:.3} should be close to 1/5",
+            fraction
+        );
+    }
+
+    /// TEST-U-049: 路由器使用一致性哈希策略路由关键字
+    #[test]
+    fn test_router_consistent_hash_route_with_key() {
+        let config = ShardConfig::new("consistent_hash", 4, "user", "sqlite:./data/{shard}.db")
+            .expect("Failed to create shard config");
+        let router = ShardRouter::with_config(&config).expect("Failed to create router");
+        let strategy = ConsistentHashStrategy::new(4, DEFAULT_VIRTUAL_NODES);
+        let dt = Utc::now();
+
+        assert_eq!(router.strategy_name(), "consistent_hash");
+        for i in 0..100 {
+            let key = format!("user_{}", i);
+            let shard = router.route_with_key(dt, &key).expect("shard should be registered");
+            assert_eq!(shard.shard_id, strategy.locate(&key, 4));
+        }
+    }
+
+    /// TEST-U-050: 年分片 ID 与配置生成的分片 ID 空间一致
+    #[test]
+    fn test_yearly_routes_to_generated_shard() {
+        let config =
+            ShardConfig::new("yearly", 12, "order", "sqlite:./data/{shard}.db").expect("Failed to create shard config");
+        let router = ShardRouter::with_config(&config).expect("Failed to create router");
+        let strategy = YearlyStrategy;
+
+        let dt = Utc.with_ymd_and_hms(2024, 6, 15, 0, 0, 0).unwrap();
+        let shard = router.route(dt).expect("2024 should route to a registered shard");
+        assert_eq!(shard.shard_id, 2024 % 12);
+        assert_eq!(shard.name, "order_8");
+
+        for year in 2000..2100 {
+            let dt = Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).unwrap();
+            let shard_id = strategy.calculate(dt, 12);
+            assert!(strategy.is_valid_shard_id(shard_id, 12));
+            assert!(
+                router.route(dt).is_some(),
+                "year {} should route to a registered shard",
+                year
+            );
+        }
+        assert!(strategy.is_valid_shard_id(0, 12));
+        assert!(!strategy.is_valid_shard_id(12, 12));
+    }
+
+    /// TEST-U-051: 区间分片策略路由测试
+    #[test]
+    fn test_range_strategy() {
+        let strategy = RangeStrategy::new(vec![(1..=1000, 0), (1001..=5000, 1), (5001..=10000, 2)], 3);
+
+        assert_eq!(strategy.name(), "range");
+        assert_eq!(strategy.calculate_by_key(1, 4), 0);
+        assert_eq!(strategy.calculate_by_key(1000, 4), 0);
+        assert_eq!(strategy.calculate_by_key(1001, 4), 1);
+        assert_eq!(strategy.calculate_by_key(7777, 4), 2);
+        // 未命中任何区间时路由到默认分片
+        assert_eq!(strategy.calculate_by_key(0, 4), 3);
+        assert_eq!(strategy.calculate_by_key(10001, 4), 3);
+        assert_eq!(strategy.calculate_by_key(-5, 4), 3);
+
+        let mut router = ShardRouter::new(strategy, 4);
+        for shard_id in 0..4 {
+            router.register_shard(
+                shard_id,
+                format!("tenant_{}", shard_id),
+                format!("sqlite:./data/tenant_{}.db", shard_id),
+            );
+        }
+        assert_eq!(router.route_by_key(42).map(|s| s.shard_id), Some(0));
+        assert_eq!(router.route_by_key(20000).map(|s| s.shard_id), Some(3));
+    }
+
+    /// TEST-U-110: 区间分片超出分片数时路由到最后一个分片，时间区间按秒级时间戳匹配
+    #[test]
+    fn test_range_strategy_respects_total_shards() {
+        let strategy = RangeStrategy::new(vec![(1..=1000, 0), (1001..=5000, 5)], 7);
+        assert_eq!(strategy.calculate_by_key(3000, 4), 3);
+        assert_eq!(strategy.calculate_by_key(9999, 4), 3);
+        assert_eq!(strategy.calculate_by_key(3000, 8), 5);
+        assert!(strategy.validate(8).is_ok());
+        let err = strategy.validate(4).unwrap_err();
+        assert!(err.to_string().contains("shard 5"));
+
+        let mut router = ShardRouter::new(strategy, 4);
+        for shard_id in 0..4 {
+            router.register_shard(
+                shard_id,
+                format!("tenant_{}", shard_id),
+                format!("sqlite:./data/tenant_{}.db", shard_id),
+            );
+        }
+        assert_eq!(router.route_by_key(3000).map(|s| s.shard_id), Some(3));
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2024, 12, 31, 23, 59, 59).unwrap();
+        let strategy = RangeStrategy::from_time_ranges(vec![(start..=end, 1)], 0);
+        assert_eq!(
+            strategy.calculate(Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap(), 2),
+            1
+        );
+        assert_eq!(
+            strategy.calculate(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(), 2),
+            0
+        );
+    }
+
+    /// TEST-U-052: 时间策略的整数键默认按时间戳计算
+    #[test]
+    fn test_calculate_by_key_delegates_to_timestamp() {
+        let strategy = YearlyStrategy;
+        let dt = Utc.with_ymd_and_hms(2024, 6, 15, 0, 0, 0).unwrap();
+
+        assert_eq!(
+            strategy.calculate_by_key(dt.timestamp(), 12),
+            strategy.calculate(dt, 12)
+        );
+    }
+
+    /// TEST-U-053: 连接模板补零占位符测试
+    #[test]
+    fn test_shard_config_padded_id() {
+        let config = ShardConfig::new("hash", 16, "db", "sqlite:./data/{prefix}_{id:04}.db")
+            .expect("Failed to create shard config");
+        assert_eq!(config.generate_connection_string(7), "sqlite:./data/db_0007.db");
+        assert_eq!(config.generate_connection_string(12345), "sqlite:./data/db_12345.db");
+
+        let config = ShardConfig::new("hash", 16, "db", "sqlite:./data/{prefix}_{id:02}_{id}.db")
+            .expect("Failed to create shard config");
+        assert_eq!(config.generate_connection_string(3), "sqlite:./data/db_03_3.db");
+        assert_eq!(config.generate_connection_string(10), "sqlite:./data/db_10_10.db");
+
+        // 原有占位符保持不变
+        let config = ShardConfig::new("hash", 16, "order", "postgresql://localhost/{shard}?id={id}")
+            .expect("Failed to create shard config");
+        assert_eq!(
+            config.generate_connection_string(7),
+            "postgresql://localhost/order_7?id=7"
+        );
+
+        // 无法解析的宽度保持原样
+        let config =
+            ShardConfig::new("hash", 16, "db", "sqlite:./data/{id:xx}.db").expect("Failed to create shard config");
+        assert_eq!(config.generate_connection_string(7), "sqlite:./data/{id:xx}.db");
+    }
 }
@@ -0,0 +1,118 @@
+// Copyright (c) 2025 Kirky.X
+//
+// Licensed under the MIT License
+// See LICENSE file in the project root for full license information.
+
+//! 测试辅助模块
+//!
+//! 为依赖 dbnexus 的 crate 提供无需真实数据库的 [`DbPool`]，需要同时启用 `test-util` 与 `sqlite` 特性。
+//!
+//! `sqlite::memory:` 的每个连接都是一个全新的数据库，因此内存连接池固定只持有一个连接：
+//! 所有 Session 依次借用同一个连接，前一个 Session 创建的表和数据对后一个 Session 可见。
+//! 由于只有一个连接，同一时刻只能持有一个 Session，第二个 Session 会等待前一个被释放。
+//!
+//! 内存连接池默认授予 `admin` 角色对所有表的全部权限，可通过
+//! [`DbPool::in_memory_builder`] 再调用 `permission_config` 替换。
+//!
+//! # Example
+//!
+//! ```ignore
+//! use dbnexus::DbPool;
+//! use dbnexus::test_util::seed_table;
+//!
+//! let pool = DbPool::in_memory().await?;
+//! let session = pool.get_session("admin").await?;
+//! seed_table(
+//!     &session,
+//!     "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)",
+//!     &["INSERT INTO users (id, name) VALUES (1, 'alice')"],
+//! )
+//! .await?;
+//! ```
+
+use crate::config::{DbConfig, DbResult};
+use crate::permission::{PermissionAction, PermissionConfig, RolePolicy, TablePermission};
+use crate::pool::{DbPool, DbPoolBuilder, Session};
+
+/// 内存数据库 URL
+pub const IN_MEMORY_URL: &str = "sqlite::memory:";
+
+/// 单连接内存数据库配置
+pub fn in_memory_config() -> DbConfig {
+    DbConfig {
+        url: IN_MEMORY_URL.to_string(),
+        max_connections: 1,
+        min_connections: 1,
+        ..DbConfig::default()
+    }
+}
+
+/// 授予 `admin` 角色对所有表全部权限的权限配置
+pub fn admin_permission_config() -> PermissionConfig {
+    let admin = RolePolicy {
+        tables: vec![TablePermission {
+            name: "*".to_string(),
+            operations: vec![
+                PermissionAction::Select,
+                PermissionAction::Insert,
+                PermissionAction::Update,
+                PermissionAction::Delete,
+            ],
+        }],
+        ..Default::default()
+    };
+
+    PermissionConfig {
+        roles: [("admin".to_string(), admin)].into_iter().collect(),
+    }
+}
+
+impl DbPool {
+    /// 创建单连接的 SQLite 内存连接池
+    ///
+    /// 所有 Session 共享同一个内存数据库，详见 [模块文档](crate::test_util)
+    ///
+    /// # Errors
+    ///
+    /// 打开内存数据库失败时返回错误
+    pub async fn in_memory() -> DbResult<Self> {
+        Self::in_memory_builder().build().await
+    }
+
+    /// 预先配置为单连接内存数据库的构建器，可继续设置权限配置、指标等组件
+    pub fn in_memory_builder() -> DbPoolBuilder {
+        Self::builder()
+            .config(in_memory_config())
+            .permission_config(admin_permission_config())
+    }
+}
+
+/// 建表并写入种子数据
+///
+/// 语句通过 Session 执行，仍遵循权限检查：建表需要 `admin` 角色的 Session
+///
+/// # Arguments
+///
+/// * `session` - 执行语句的 Session
+/// * `create_sql` - 建表语句
+/// * `rows` - 插入数据的语句
+///
+/// # Errors
+///
+/// 任一语句执行失败时返回错误，之前已执行的语句不会回滚
+pub async fn seed_table(session: &Session, create_sql: &str, rows: &[&str]) -> DbResult<()> {
+    session.execute_raw(create_sql).await?;
+    seed(session, rows).await
+}
+
+/// 依次执行种子数据语句
+///
+/// # Errors
+///
+/// 任一语句执行失败时返回错误，之前已执行的语句不会回滚
+pub async fn seed(session: &Session, statements: &[&str]) -> DbResult<()> {
+    for statement in statements {
+        session.execute_raw(statement).await?;
+    }
+    Ok(())
+}
@@ -7,9 +7,14 @@
 //!
 //! 提供基于 OpenTelemetry 的分布式追踪功能。
 //! 支持 OTLP 和标准输出导出器。
+//!
+//! 启用后，连接获取和 `Session` 查询会创建 `db.acquire_connection` / `db.query` span，
+//! 父上下文取自当前 OpenTelemetry `Context`（例如 [`extract`] 返回并 attach 的上下文）。
 
-use opentelemetry::KeyValue;
-use opentelemetry::global;
+use crate::config::DatabaseType;
+use opentelemetry::global::{self, BoxedSpan};
+use opentelemetry::trace::{Span, SpanKind, Status, Tracer};
+use opentelemetry::{Context, KeyValue};
 use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::Resource;
 use opentelemetry_sdk::propagation::TraceContextPropagator;
@@ -60,17 +65,17 @@ async fn init_otlp(endpoint: &str) -> Result<TracerProvider, String> {
 }
 
 /// 使用标准输出初始化追踪
+///
+/// 使用 `opentelemetry_stdout` 导出器将 span 打印到标准输出，不建立任何网络连接
 fn init_stdout() -> Result<TracerProvider, String> {
     let resource = Resource::new(vec![KeyValue::new("service.name", "dbnexus")]);
 
     let config = Config::default().with_resource(resource);
 
-    let provider = opentelemetry_otlp::new_pipeline()
-        .tracing()
-        .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint("stdout"))
-        .with_trace_config(config)
-        .install_simple()
-        .map_err(|e| e.to_string())?;
+    let provider = TracerProvider::builder()
+        .with_simple_exporter(opentelemetry_stdout::SpanExporter::default())
+        .with_config(config)
+        .build();
 
     Ok(provider)
 }
@@ -83,8 +88,84 @@ pub fn inject(headers: &mut HashMap<String, String>) {
 }
 
 /// 从 HashMap 提取追踪上下文
-pub fn extract(headers: &HashMap<String, String>) {
-    global::get_text_map_propagator(|propagator| {
-        let _ = propagator.extract(headers);
-    });
+///
+/// 将返回的上下文 `attach` 后，库创建的数据库 span 会以其为父 span
+pub fn extract(headers: &HashMap<String, String>) -> Context {
+    global::get_text_map_propagator(|propagator| propagator.extract(headers))
+}
+
+/// 获取连接的 span 名称
+pub const SPAN_ACQUIRE_CONNECTION: &str = "db.acquire_connection";
+
+/// 执行查询的 span 名称
+pub const SPAN_QUERY: &str = "db.query";
+
+/// 语句摘要的最大长度（字符）
+const STATEMENT_SUMMARY_LEN: usize = 100;
+
+/// 以当前上下文为父上下文创建数据库 span
+pub(crate) fn start_span(name: &'static str, attributes: Vec<KeyValue>) -> BoxedSpan {
+    let tracer = global::tracer("dbnexus");
+    tracer
+        .span_builder(name)
+        .with_kind(SpanKind::Client)
+        .with_attributes(attributes)
+        .start_with_context(&tracer, &Context::current())
+}
+
+/// 结束 span，失败时记录错误状态
+pub(crate) fn finish_span<T, E: std::fmt::Display>(mut span: BoxedSpan, result: &Result<T, E>) {
+    if let Err(e) = result {
+        span.set_status(Status::error(e.to_string()));
+    }
+    span.end();
+}
+
+/// 根据数据库类型返回 `db.system` 属性值
+pub(crate) fn db_system(db_type: DatabaseType) -> &'static str {
+    match db_type {
+        DatabaseType::Postgres => "postgresql",
+        DatabaseType::MySql => "mysql",
+        DatabaseType::Sqlite => "sqlite",
+    }
+}
+
+/// 生成语句摘要：合并空白并截断，避免 span 中出现过长的 SQL
+pub(crate) fn statement_summary(sql: &str) -> String {
+    sql.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(STATEMENT_SUMMARY_LEN)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// TEST-U-067: stdout 导出器初始化不依赖网络
+    #[tokio::test]
+    async fn test_init_stdout_exporter() {
+        let started = std::time::Instant::now();
+        let guard = init("stdout", "").await;
+
+        assert!(guard.is_ok());
+        // 未尝试连接名为 "stdout" 的主机
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+    }
+
+    /// TEST-U-068: 语句摘要合并空白并截断
+    #[test]
+    fn test_statement_summary() {
+        assert_eq!(
+            statement_summary("SELECT id,\n       name\n  FROM users"),
+            "SELECT id, name FROM users"
+        );
+
+        let long_sql = format!("SELECT {} FROM users", "column_name, ".repeat(20));
+        assert_eq!(statement_summary(&long_sql).chars().count(), STATEMENT_SUMMARY_LEN);
+        assert_eq!(db_system(DatabaseType::Postgres), "postgresql");
+        assert_eq!(db_system(DatabaseType::Sqlite), "sqlite");
+    }
 }
@@ -14,6 +14,28 @@ use dbnexus::audit::{
 use std::sync::Arc;
 use std::time::Duration;
 
+/// 开启 `db_audit` 的实体
+#[cfg(feature = "sqlite")]
+mod account {
+    use sea_orm::entity::prelude::*;
+
+    #[dbnexus::db_crud]
+    #[dbnexus::db_audit]
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+    #[sea_orm(table_name = "accounts")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub id: i32,
+        pub name: String,
+        pub email: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
 /// TEST-AUDIT-001: 多条件组合查询测试
 #[tokio::test]
 async fn test_audit_query_multiple_conditions() {
@@ -344,3 +366,359 @@ async fn test_audit_event_json_roundtrip() {
     assert_eq!(original.after_value, restored.after_value);
     assert_eq!(original.operation, restored.operation);
 }
+
+/// TEST-AUDIT-014: 数据库审计存储记录更新的列级变更
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_db_audit_storage_records_update_diff() {
+    use dbnexus::audit::{AUDIT_LOG_TABLE, DbAuditStorage};
+    use sea_orm::{ConnectionTrait, Database, Statement};
+
+    let conn = Database::connect("sqlite::memory:")
+        .await
+        .expect("Failed to connect to SQLite");
+    let storage = DbAuditStorage::new(conn.clone());
+    storage.ensure_table().await.expect("Failed to create audit table");
+
+    let logger = AuditLogger::new(AuditConfig::default(), Arc::new(storage));
+    let event = AuditEvent::update(
+        "users",
+        "42",
+        "user-7",
+        Some(r#"{"id": 42, "name": "Old", "email": "a@example.com"}"#.to_string()),
+        Some(r#"{"id": 42, "name": "New", "email": "a@example.com"}"#.to_string()),
+    )
+    .with_user("editor", "10.0.0.1");
+    logger.log(event).await.expect("Failed to log audit event");
+
+    let row = conn
+        .query_one_raw(Statement::from_string(
+            conn.get_database_backend(),
+            format!("SELECT operation, table_name, record_id, actor, changes FROM {AUDIT_LOG_TABLE}"),
+        ))
+        .await
+        .unwrap()
+        .expect("Audit row should exist");
+
+    assert_eq!(row.try_get::<String>("", "operation").unwrap(), "UPDATE");
+    assert_eq!(row.try_get::<String>("", "table_name").unwrap(), "users");
+    assert_eq!(row.try_get::<String>("", "record_id").unwrap(), "42");
+    assert_eq!(row.try_get::<String>("", "actor").unwrap(), "editor");
+
+    let changes: serde_json::Value = serde_json::from_str(&row.try_get::<String>("", "changes").unwrap()).unwrap();
+    assert_eq!(changes, serde_json::json!({ "name": { "old": "Old", "new": "New" } }));
+
+    // 通过存储查询还原事件
+    let filters = AuditQueryFilters {
+        operation: Some(AuditOperation::Update),
+        ..Default::default()
+    };
+    let events = logger.query(&filters).await.expect("Query should succeed");
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].user_role, "editor");
+    assert_eq!(events[0].user_id, "user-7");
+}
+
+/// TEST-AUDIT-015: 安装审计输出端后，UPDATE 语句生成一条带角色和受影响行数的审计记录
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_statement_audit_records_update() {
+    use dbnexus::audit::{AuditResult, InMemoryAuditSink};
+    use dbnexus::{DbConfig, DbPool, PermissionConfig};
+
+    let permission_config = PermissionConfig::from_yaml(
+        r#"
+roles:
+  admin:
+    tables:
+      - name: "*"
+        operations: [select, insert, update, delete]
+"#,
+    )
+    .expect("Failed to parse permission config");
+
+    // 内存数据库每个连接独立，固定单连接保证建表与后续语句在同一个库上执行
+    let config = DbConfig {
+        url: "sqlite::memory:".to_string(),
+        max_connections: 1,
+        min_connections: 1,
+        ..DbConfig::default()
+    };
+    let sink = Arc::new(InMemoryAuditSink::new());
+    let pool = DbPool::builder()
+        .config(config)
+        .permission_config(permission_config)
+        .audit_sink(sink.clone())
+        .build()
+        .await
+        .expect("Failed to build pool");
+
+    let session = pool.get_session("admin").await.expect("Failed to get session");
+    session
+        .execute_raw("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)")
+        .await
+        .expect("Failed to create table");
+    session
+        .execute_raw("INSERT INTO users (id, name) VALUES (1, 'a'), (2, 'b'), (3, 'c')")
+        .await
+        .expect("Failed to insert rows");
+    sink.clear().await;
+
+    session
+        .execute_raw("UPDATE users SET name = 'z' WHERE id < 3")
+        .await
+        .expect("Failed to update rows");
+    // 查询语句不产生审计记录
+    session
+        .query_all("SELECT * FROM users")
+        .await
+        .expect("Failed to query rows");
+
+    let entries = sink.entries().await;
+    assert_eq!(entries.len(), 1, "Expected exactly one audit entry: {:?}", entries);
+    let entry = &entries[0];
+    assert_eq!(entry.role, "admin");
+    assert_eq!(entry.table.as_deref(), Some("users"));
+    assert_eq!(entry.operation, AuditOperation::Update);
+    assert_eq!(entry.rows_affected, Some(2));
+    assert_eq!(entry.result, AuditResult::Success);
+    assert!(entry.statement.starts_with("UPDATE users"));
+
+    // 执行失败同样记录
+    let result = session.execute_raw("DELETE FROM missing_table").await;
+    assert!(result.is_err());
+    let entries = sink.entries().await;
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].operation, AuditOperation::Delete);
+    assert_eq!(entries[1].result, AuditResult::Failure);
+    assert!(entries[1].rows_affected.is_none());
+    assert!(entries[1].error.is_some());
+}
+
+/// TEST-AUDIT-016: 文件审计输出端按 JSON Lines 追加写入
+#[tokio::test]
+async fn test_file_audit_sink_appends_json_lines() {
+    use dbnexus::audit::{AuditSink, FileAuditSink, StatementAuditEntry};
+
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let path = dir.path().join("audit.jsonl");
+
+    let first = StatementAuditEntry::new(
+        "admin",
+        Some("users"),
+        AuditOperation::Create,
+        "INSERT INTO users VALUES (1)",
+    )
+    .succeeded(1);
+    let second = StatementAuditEntry::new(
+        "admin",
+        None,
+        AuditOperation::Other("DDL".to_string()),
+        "DROP TABLE users",
+    )
+    .failed("no such table: users");
+
+    {
+        let sink = FileAuditSink::open(&path).expect("Failed to open audit file");
+        sink.record(&first).await.expect("Failed to record entry");
+    }
+    // 重新打开后继续追加
+    let sink = FileAuditSink::open(&path).expect("Failed to reopen audit file");
+    sink.record(&second).await.expect("Failed to record entry");
+
+    let content = std::fs::read_to_string(&path).expect("Failed to read audit file");
+    let entries: Vec<StatementAuditEntry> = content
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("Each line should be a JSON entry"))
+        .collect();
+    assert_eq!(entries, vec![first, second]);
+}
+
+/// TEST-AUDIT-017: db_audit 生成的增删改方法写入 audit_log，更新记录变更列的新旧值
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_db_audit_entity_writes_audit_log() {
+    use dbnexus::audit::AUDIT_LOG_TABLE;
+    use dbnexus::{DbConfig, DbPool, PermissionConfig};
+    use sea_orm::Set;
+
+    let permission_config = PermissionConfig::from_yaml(
+        r#"
+roles:
+  admin:
+    tables:
+      - name: "*"
+        operations: [select, insert, update, delete]
+"#,
+    )
+    .expect("Failed to parse permission config");
+    let config = DbConfig {
+        url: "sqlite::memory:".to_string(),
+        max_connections: 1,
+        min_connections: 1,
+        ..DbConfig::default()
+    };
+    let pool = DbPool::builder()
+        .config(config)
+        .permission_config(permission_config)
+        .build()
+        .await
+        .expect("Failed to build pool");
+
+    let mut session = pool.get_session("admin").await.expect("Failed to get session");
+    session
+        .execute_raw("CREATE TABLE accounts (id INTEGER PRIMARY KEY, name TEXT NOT NULL, email TEXT NOT NULL)")
+        .await
+        .expect("Failed to create table");
+    session
+        .execute_raw(&format!(
+            "CREATE TABLE {AUDIT_LOG_TABLE} (id VARCHAR(36) PRIMARY KEY, operation VARCHAR(64) NOT NULL, \
+             table_name VARCHAR(255) NOT NULL, record_id VARCHAR(255) NOT NULL, actor VARCHAR(255) NOT NULL, \
+             user_id VARCHAR(255) NOT NULL, created_at VARCHAR(64) NOT NULL, changes TEXT NULL)"
+        ))
+        .await
+        .expect("Failed to create audit table");
+
+    account::Entity::create(
+        &mut session,
+        account::ActiveModel {
+            id: Set(7),
+            name: Set("Old".to_string()),
+            email: Set("a@example.com".to_string()),
+        },
+    )
+    .await
+    .expect("Failed to create account");
+    account::Entity::modify(
+        &mut session,
+        7,
+        account::ActiveModel {
+            name: Set("New".to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Failed to modify account")
+    .expect("Account should exist");
+    // 没有匹配的行时不写入审计记录
+    assert!(
+        account::Entity::modify(&mut session, 99, Default::default())
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert_eq!(account::Entity::remove(&mut session, 7).await.unwrap(), 1);
+
+    let rows = session
+        .query_all(&format!(
+            "SELECT operation, table_name, record_id, actor, changes FROM {AUDIT_LOG_TABLE} ORDER BY rowid"
+        ))
+        .await
+        .expect("Failed to query audit rows");
+    let rows: Vec<(String, String, String, String, serde_json::Value)> = rows
+        .iter()
+        .map(|row| {
+            let changes: String = row.try_get("", "changes").unwrap();
+            (
+                row.try_get("", "operation").unwrap(),
+                row.try_get("", "table_name").unwrap(),
+                row.try_get("", "record_id").unwrap(),
+                row.try_get("", "actor").unwrap(),
+                serde_json::from_str(&changes).unwrap(),
+            )
+        })
+        .collect();
+
+    let row = |operation: &str, changes: serde_json::Value| {
+        (
+            operation.to_string(),
+            "accounts".to_string(),
+            "7".to_string(),
+            "admin".to_string(),
+            changes,
+        )
+    };
+    assert_eq!(
+        rows,
+        vec![
+            row(
+                "CREATE",
+                serde_json::json!({
+                    "email": { "old": null, "new": "a@example.com" },
+                    "id": { "old": null, "new": 7 },
+                    "name": { "old": null, "new": "Old" },
+                })
+            ),
+            row("UPDATE", serde_json::json!({ "name": { "old": "Old", "new": "New" } })),
+            row(
+                "DELETE",
+                serde_json::json!({
+                    "email": { "old": "a@example.com", "new": null },
+                    "id": { "old": 7, "new": null },
+                    "name": { "old": "New", "new": null },
+                })
+            ),
+        ]
+    );
+}
+
+/// TEST-AUDIT-018: 权限检查拒绝的写语句同样生成失败的审计记录
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_statement_audit_records_denied_write() {
+    use dbnexus::audit::{AuditResult, InMemoryAuditSink};
+    use dbnexus::{DbConfig, DbPool, PermissionConfig};
+
+    let permission_config = PermissionConfig::from_yaml(
+        r#"
+roles:
+  admin:
+    tables:
+      - name: "*"
+        operations: [select, insert, update, delete]
+  reader:
+    tables:
+      - name: "users"
+        operations: [select]
+"#,
+    )
+    .expect("Failed to parse permission config");
+
+    let config = DbConfig {
+        url: "sqlite::memory:".to_string(),
+        max_connections: 1,
+        min_connections: 1,
+        ..DbConfig::default()
+    };
+    let sink = Arc::new(InMemoryAuditSink::new());
+    let pool = DbPool::builder()
+        .config(config)
+        .permission_config(permission_config)
+        .audit_sink(sink.clone())
+        .build()
+        .await
+        .expect("Failed to build pool");
+
+    {
+        let admin = pool.get_session("admin").await.expect("Failed to get session");
+        admin
+            .execute_raw("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)")
+            .await
+            .expect("Failed to create table");
+    }
+    sink.clear().await;
+
+    let reader = pool.get_session("reader").await.expect("Failed to get session");
+    let result = reader.execute_raw("DELETE FROM users WHERE id = 1").await;
+    assert!(result.is_err(), "Reader should not be allowed to delete");
+
+    let entries = sink.entries().await;
+    assert_eq!(entries.len(), 1, "Expected exactly one audit entry: {:?}", entries);
+    let entry = &entries[0];
+    assert_eq!(entry.role, "reader");
+    assert_eq!(entry.table.as_deref(), Some("users"));
+    assert_eq!(entry.operation, AuditOperation::Delete);
+    assert_eq!(entry.result, AuditResult::Failure);
+    assert!(entry.rows_affected.is_none());
+    assert!(entry.error.is_some());
+}
@@ -82,6 +82,9 @@ DROP TABLE orders;
         migrations_dir: None,
         auto_migrate: false,
         migration_timeout: 60,
+        replica_urls: Vec::new(),
+        statement_timeout_ms: None,
+        password_file: None,
     };
 
     let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
@@ -123,6 +126,9 @@ async fn test_empty_migrations_directory() {
         migrations_dir: None,
         auto_migrate: false,
         migration_timeout: 60,
+        replica_urls: Vec::new(),
+        statement_timeout_ms: None,
+        password_file: None,
     };
 
     let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
@@ -146,6 +152,9 @@ async fn test_nonexistent_migrations_directory() {
         migrations_dir: None,
         auto_migrate: false,
         migration_timeout: 60,
+        replica_urls: Vec::new(),
+        statement_timeout_ms: None,
+        password_file: None,
     };
 
     let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
@@ -231,6 +240,9 @@ DROP TABLE table_2;
         migrations_dir: None,
         auto_migrate: false,
         migration_timeout: 60,
+        replica_urls: Vec::new(),
+        statement_timeout_ms: None,
+        password_file: None,
     };
 
     let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
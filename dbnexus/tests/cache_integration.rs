@@ -20,6 +20,8 @@ async fn test_cache_zero_capacity() {
         default_ttl: 300,
         cleanup_interval: 60,
         enable_stats: true,
+        negative_ttl: 30,
+        ttl_jitter_fraction: 0.0,
     };
 
     let cache = CacheManager::with_strategy(config, Box::new(LruStrategy::new(300)));
@@ -39,6 +41,8 @@ async fn test_cache_zero_ttl() {
         default_ttl: 0,
         cleanup_interval: 60,
         enable_stats: true,
+        negative_ttl: 30,
+        ttl_jitter_fraction: 0.0,
     };
 
     let cache = CacheManager::with_strategy(config, Box::new(LruStrategy::new(0)));
@@ -59,6 +63,8 @@ async fn test_cache_zero_cleanup_interval() {
         default_ttl: 1,
         cleanup_interval: 0,
         enable_stats: true,
+        negative_ttl: 30,
+        ttl_jitter_fraction: 0.0,
     };
 
     let cache = CacheManager::with_strategy(config, Box::new(LruStrategy::new(1)));
@@ -79,6 +85,8 @@ async fn test_cache_strategy_combo_operations() {
         default_ttl: 120,
         cleanup_interval: 30,
         enable_stats: true,
+        negative_ttl: 30,
+        ttl_jitter_fraction: 0.0,
     };
 
     let cache = CacheManager::with_strategy(config, Box::new(lru));
@@ -110,6 +118,8 @@ async fn test_cache_concurrent_reads() {
         default_ttl: 300,
         cleanup_interval: 60,
         enable_stats: true,
+        negative_ttl: 30,
+        ttl_jitter_fraction: 0.0,
     };
     let cache = CacheManager::new(config);
     let cache = Arc::new(cache);
@@ -142,6 +152,8 @@ async fn test_cache_concurrent_writes() {
         default_ttl: 300,
         cleanup_interval: 60,
         enable_stats: true,
+        negative_ttl: 30,
+        ttl_jitter_fraction: 0.0,
     };
     let cache = CacheManager::new(config);
     let cache = Arc::new(cache);
@@ -175,6 +187,8 @@ async fn test_cache_concurrent_read_write() {
         default_ttl: 300,
         cleanup_interval: 60,
         enable_stats: true,
+        negative_ttl: 30,
+        ttl_jitter_fraction: 0.0,
     };
     let cache = CacheManager::new(config);
     let cache = Arc::new(cache);
@@ -228,6 +242,8 @@ async fn test_cache_concurrent_eviction() {
         default_ttl: 300,
         cleanup_interval: 60,
         enable_stats: true,
+        negative_ttl: 30,
+        ttl_jitter_fraction: 0.0,
     };
     let cache = CacheManager::new(config);
     let cache = Arc::new(cache);
@@ -272,6 +288,8 @@ async fn test_cache_large_dataset_performance() {
         default_ttl: 300,
         cleanup_interval: 60,
         enable_stats: true,
+        negative_ttl: 30,
+        ttl_jitter_fraction: 0.0,
     };
     let cache = CacheManager::new(config);
 
@@ -312,6 +330,8 @@ async fn test_cache_throughput_benchmark() {
         default_ttl: 60,
         cleanup_interval: 30,
         enable_stats: true,
+        negative_ttl: 30,
+        ttl_jitter_fraction: 0.0,
     };
     let cache = CacheManager::new(config);
 
@@ -354,6 +374,8 @@ async fn test_cache_cleanup_manual() {
         default_ttl: 1,
         cleanup_interval: 3600,
         enable_stats: true,
+        negative_ttl: 30,
+        ttl_jitter_fraction: 0.0,
     };
     let cache = CacheManager::new(config);
 
@@ -434,6 +456,8 @@ async fn test_cache_stats_verification() {
         default_ttl: 300,
         cleanup_interval: 60,
         enable_stats: true,
+        negative_ttl: 30,
+        ttl_jitter_fraction: 0.0,
     };
     let cache = CacheManager::new(config);
 
@@ -481,6 +505,8 @@ async fn test_cache_hit_rate() {
         default_ttl: 300,
         cleanup_interval: 60,
         enable_stats: true,
+        negative_ttl: 30,
+        ttl_jitter_fraction: 0.0,
     };
     let cache = CacheManager::new(config);
 
@@ -500,3 +526,452 @@ async fn test_cache_hit_rate() {
     assert!(hit_rate > 0.0, "Hit rate should be greater than 0");
     assert!(hit_rate <= 1.0, "Hit rate should not exceed 1.0");
 }
+
+/// TEST-CACHE-016: 读取会更新 LRU 顺序，淘汰真正最久未使用的条目
+#[tokio::test]
+async fn test_cache_get_updates_lru_order() {
+    let config = CacheConfig {
+        max_capacity: 3,
+        ..Default::default()
+    };
+    let cache = CacheManager::<String>::new(config);
+
+    let key_a = CacheKey::new("users", "a");
+    let key_b = CacheKey::new("users", "b");
+    let key_c = CacheKey::new("users", "c");
+    let key_d = CacheKey::new("users", "d");
+
+    cache.set(key_a.clone(), "A".to_string()).await;
+    cache.set(key_b.clone(), "B".to_string()).await;
+    cache.set(key_c.clone(), "C".to_string()).await;
+
+    assert_eq!(cache.get(&key_a).await, Some("A".to_string()));
+
+    cache.set(key_d.clone(), "D".to_string()).await;
+
+    assert_eq!(cache.len().await, 3);
+    assert!(
+        cache.get(&key_b).await.is_none(),
+        "B should be evicted as least recently used"
+    );
+    assert_eq!(cache.get(&key_a).await, Some("A".to_string()));
+    assert_eq!(cache.get(&key_c).await, Some("C".to_string()));
+    assert_eq!(cache.get(&key_d).await, Some("D".to_string()));
+}
+
+/// TEST-CACHE-017: 并发未命中时加载函数只执行一次
+#[tokio::test]
+async fn test_cache_get_or_compute_single_flight() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let cache = Arc::new(CacheManager::<String>::new(CacheConfig::default()));
+    let loads = Arc::new(AtomicUsize::new(0));
+    let key = CacheKey::new("users", "hot");
+
+    let mut handles = Vec::new();
+    for _ in 0..20 {
+        let cache = cache.clone();
+        let loads = loads.clone();
+        let key = key.clone();
+        handles.push(tokio::spawn(async move {
+            cache
+                .get_or_compute(key, Duration::from_secs(60), async {
+                    loads.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    "loaded".to_string()
+                })
+                .await
+        }));
+    }
+
+    for handle in handles {
+        assert_eq!(handle.await.unwrap(), "loaded");
+    }
+
+    assert_eq!(loads.load(Ordering::SeqCst), 1, "Loader should run exactly once");
+    assert_eq!(cache.pending_loads(), 0, "In-flight entries should be cleaned up");
+    assert_eq!(cache.get(&key).await, Some("loaded".to_string()));
+}
+
+/// TEST-CACHE-018: 空值缓存在 negative_ttl 内只调用一次加载函数
+#[tokio::test]
+async fn test_cache_negative_caching() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let config = CacheConfig {
+        negative_ttl: 1,
+        ..Default::default()
+    };
+    let cache = CacheManager::<String>::new(config);
+    let loads = AtomicUsize::new(0);
+    let key = CacheKey::new("users", "missing");
+
+    for _ in 0..5 {
+        let value = cache
+            .get_or_compute_optional(key.clone(), Duration::from_secs(60), async {
+                loads.fetch_add(1, Ordering::SeqCst);
+                None
+            })
+            .await;
+        assert!(value.is_none());
+    }
+    assert_eq!(loads.load(Ordering::SeqCst), 1, "Cached miss should not reload");
+    assert!(
+        cache.get(&key).await.is_none(),
+        "Tombstone should not be returned by get"
+    );
+
+    // 空值占位过期后重新加载
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    let value = cache
+        .get_or_compute_optional(key.clone(), Duration::from_secs(60), async {
+            loads.fetch_add(1, Ordering::SeqCst);
+            Some("found".to_string())
+        })
+        .await;
+    assert_eq!(value, Some("found".to_string()));
+    assert_eq!(loads.load(Ordering::SeqCst), 2);
+    assert_eq!(cache.get(&key).await, Some("found".to_string()));
+}
+
+/// TEST-CACHE-019: 后台清理任务移除未访问的过期条目
+#[tokio::test]
+async fn test_cache_cleanup_task() {
+    use std::sync::atomic::Ordering;
+
+    let config = CacheConfig {
+        cleanup_interval: 1,
+        ..Default::default()
+    };
+    let cache = Arc::new(CacheManager::<String>::new(config));
+
+    cache
+        .set_with_ttl(
+            CacheKey::new("users", "stale"),
+            "stale".to_string(),
+            Duration::from_millis(100),
+        )
+        .await;
+    cache.set(CacheKey::new("users", "fresh"), "fresh".to_string()).await;
+
+    let handle = cache.clone().spawn_cleanup_task();
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+
+    // 过期条目未被访问，也应被后台任务清理
+    assert_eq!(cache.len().await, 1);
+    assert_eq!(cache.stats().expirations.load(Ordering::Relaxed), 1);
+
+    handle.shutdown().await;
+}
+
+/// TEST-CACHE-020: 批量读写只返回存在的键并正确统计
+#[tokio::test]
+async fn test_cache_get_many_set_many() {
+    use std::sync::atomic::Ordering;
+
+    let cache = CacheManager::<String>::new(CacheConfig::default());
+    let items: Vec<(CacheKey, String)> = (0..5)
+        .map(|i| (CacheKey::new("users", &i.to_string()), format!("user_{}", i)))
+        .collect();
+    cache.set_many(items).await;
+
+    assert_eq!(cache.len().await, 5);
+    assert_eq!(cache.stats().sets.load(Ordering::Relaxed), 5);
+
+    let keys: Vec<CacheKey> = (3..8).map(|i| CacheKey::new("users", &i.to_string())).collect();
+    let found = cache.get_many(&keys).await;
+
+    assert_eq!(found.len(), 2);
+    assert_eq!(found.get(&CacheKey::new("users", "3")), Some(&"user_3".to_string()));
+    assert_eq!(found.get(&CacheKey::new("users", "4")), Some(&"user_4".to_string()));
+    assert!(!found.contains_key(&CacheKey::new("users", "5")));
+    assert_eq!(cache.stats().hits.load(Ordering::Relaxed), 2);
+    assert_eq!(cache.stats().misses.load(Ordering::Relaxed), 3);
+}
+
+/// TEST-CACHE-021: 自定义存储后端接收缓存管理器的所有操作
+#[tokio::test]
+async fn test_cache_manager_delegates_to_backend() {
+    use async_trait::async_trait;
+    use dbnexus::cache::{CacheBackend, CacheLookup};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct MockBackend {
+        entries: Mutex<HashMap<CacheKey, Option<String>>>,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl CacheBackend<String> for MockBackend {
+        async fn get(&self, key: &CacheKey) -> CacheLookup<String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            match self.entries.lock().await.get(key) {
+                Some(value) => CacheLookup::Hit(value.clone()),
+                None => CacheLookup::Miss,
+            }
+        }
+
+        async fn set(&self, key: CacheKey, value: Option<String>, _ttl: Duration) {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.entries.lock().await.insert(key, value);
+        }
+
+        async fn delete(&self, key: &CacheKey) -> bool {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.entries.lock().await.remove(key).is_some()
+        }
+
+        async fn clear(&self) {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.entries.lock().await.clear();
+        }
+
+        async fn len(&self) -> usize {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.entries.lock().await.len()
+        }
+    }
+
+    let mut cache = CacheManager::with_backend(
+        CacheConfig::default(),
+        Box::new(LruStrategy::new(60)),
+        MockBackend::default(),
+    );
+    let key = CacheKey::new("users", "1");
+
+    cache.set(key.clone(), "alice".to_string()).await;
+    assert_eq!(cache.get(&key).await, Some("alice".to_string()));
+    assert_eq!(cache.len().await, 1);
+    assert_eq!(
+        cache.backend().entries.lock().await.get(&key),
+        Some(&Some("alice".to_string()))
+    );
+
+    cache.delete(&key).await;
+    assert!(cache.get(&key).await.is_none());
+
+    cache.set(key.clone(), "bob".to_string()).await;
+    cache.clear().await;
+    assert!(cache.is_empty().await);
+
+    // set+len, get, len, delete+len, get, set+len, clear, len（写入和删除后同步 size 统计）
+    assert_eq!(cache.backend().calls.load(Ordering::SeqCst), 11);
+    assert_eq!(
+        cache.stats().sets.load(Ordering::Relaxed),
+        0,
+        "Stats are reset by clear"
+    );
+}
+
+/// TEST-CACHE-022: 实体删除或主键变更后缓存失效
+#[tokio::test]
+async fn test_entity_invalidation_after_mutation() {
+    let cache = CacheManager::<String>::new(CacheConfig::default());
+
+    cache.set(CacheKey::new("users", "1"), "alice".to_string()).await;
+    cache.set(CacheKey::new("users", "2"), "bob".to_string()).await;
+
+    // delete 后清除对应实体
+    cache.invalidate_entity("users", "1").await;
+    assert!(cache.get(&CacheKey::new("users", "1")).await.is_none());
+    assert_eq!(cache.get(&CacheKey::new("users", "2")).await, Some("bob".to_string()));
+
+    // 主键 2 -> 3 的更新同时清除新旧键
+    cache.set(CacheKey::new("users", "3"), "stale".to_string()).await;
+    cache.invalidate_entity_update("users", "2", "3").await;
+    assert!(cache.get(&CacheKey::new("users", "2")).await.is_none());
+    assert!(cache.get(&CacheKey::new("users", "3")).await.is_none());
+    assert_eq!(cache.stats().deletes.load(std::sync::atomic::Ordering::Relaxed), 3);
+}
+
+/// TEST-CACHE-023: 版本化写入拒绝过期版本，接受当前版本
+#[tokio::test]
+async fn test_set_if_version_rejects_stale_writer() {
+    use dbnexus::cache::CasError;
+
+    let cache = CacheManager::<String>::new(CacheConfig::default());
+    let key = CacheKey::new("accounts", "1");
+
+    // 不存在的条目版本为 0，只有期望版本为 0 的写入成功
+    assert_eq!(cache.get_versioned(&key).await, (None, 0));
+    assert!(matches!(
+        cache.set_if_version(key.clone(), "balance=999".to_string(), 7).await,
+        Err(CasError::Conflict {
+            expected: 7,
+            current: 0
+        })
+    ));
+    let v1 = cache
+        .set_if_version(key.clone(), "balance=100".to_string(), 0)
+        .await
+        .expect("Initial write should succeed");
+
+    // 两个写入方读到同一版本
+    let (value, writer_a) = cache.get_versioned(&key).await;
+    let (_, writer_b) = cache.get_versioned(&key).await;
+    assert_eq!(value.as_deref(), Some("balance=100"));
+    assert_eq!(writer_a, v1);
+    assert_eq!(writer_b, v1);
+
+    let v2 = cache
+        .set_if_version(key.clone(), "balance=80".to_string(), writer_a)
+        .await
+        .expect("Write with the current version should succeed");
+    assert!(v2 > v1);
+
+    let stale = cache
+        .set_if_version(key.clone(), "balance=130".to_string(), writer_b)
+        .await;
+    assert_eq!(
+        stale,
+        Err(CasError::Conflict {
+            expected: v1,
+            current: v2
+        })
+    );
+    assert_eq!(cache.get_versioned(&key).await, (Some("balance=80".to_string()), v2));
+
+    // 普通写入同样使版本递增；删除后重新写入不会复用旧版本
+    cache.set(key.clone(), "balance=75".to_string()).await;
+    let (_, v3) = cache.get_versioned(&key).await;
+    assert!(v3 > v2);
+    assert!(
+        cache
+            .set_if_version(key.clone(), "balance=0".to_string(), v2)
+            .await
+            .is_err()
+    );
+
+    cache.delete(&key).await;
+    let v4 = cache
+        .set_if_version(key.clone(), "balance=10".to_string(), 0)
+        .await
+        .unwrap();
+    assert!(v4 > v3);
+}
+
+/// 记录持久化调用的批次
+type Batches = Arc<std::sync::Mutex<Vec<Vec<(CacheKey, String)>>>>;
+
+/// TEST-CACHE-024: 写穿策略在 set 返回前持久化，失败时不写入缓存
+#[tokio::test]
+async fn test_write_through_persists_immediately() {
+    use dbnexus::cache::WritePolicy;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let batches: Batches = Arc::default();
+    let fail = Arc::new(AtomicBool::new(false));
+    let policy = {
+        let batches = batches.clone();
+        let fail = fail.clone();
+        WritePolicy::write_through(move |batch| {
+            let batches = batches.clone();
+            let fail = fail.load(Ordering::SeqCst);
+            async move {
+                if fail {
+                    return Err("store unavailable".into());
+                }
+                batches.lock().unwrap().push(batch);
+                Ok(())
+            }
+        })
+    };
+    let cache = CacheManager::<String>::new(CacheConfig::default()).with_write_policy(policy);
+    let key = CacheKey::new("users", "1");
+
+    cache.set(key.clone(), "alice".to_string()).await;
+    assert_eq!(*batches.lock().unwrap(), vec![vec![(key.clone(), "alice".to_string())]]);
+    assert_eq!(cache.get(&key).await, Some("alice".to_string()));
+
+    // 持久化失败：不缓存新值，旧值也被移除
+    fail.store(true, Ordering::SeqCst);
+    cache.set(key.clone(), "bob".to_string()).await;
+    assert!(cache.get(&key).await.is_none());
+    assert_eq!(batches.lock().unwrap().len(), 1);
+}
+
+/// TEST-CACHE-025: 写回策略按数量阈值、定时任务和 clear 批量持久化
+#[tokio::test]
+async fn test_write_behind_batches_and_flushes() {
+    use dbnexus::cache::{PersistError, WritePolicy};
+
+    let batches: Batches = Arc::default();
+    let persister = {
+        let batches = batches.clone();
+        move |batch| {
+            let batches = batches.clone();
+            async move {
+                batches.lock().unwrap().push(batch);
+                Ok::<(), PersistError>(())
+            }
+        }
+    };
+    let mut cache = CacheManager::<String>::new(CacheConfig::default()).with_write_policy(WritePolicy::write_behind(
+        persister,
+        3,
+        Duration::from_secs(3600),
+    ));
+    let key = |id: &str| CacheKey::new("users", id);
+
+    // 缓存立即可读，未达到阈值前不持久化；同一键只保留最新值
+    cache.set(key("1"), "alice".to_string()).await;
+    cache.set(key("2"), "bob".to_string()).await;
+    cache.set(key("1"), "alice-v2".to_string()).await;
+    assert_eq!(cache.get(&key("1")).await, Some("alice-v2".to_string()));
+    assert!(batches.lock().unwrap().is_empty());
+    assert_eq!(cache.pending_writes(), 2);
+
+    cache.set(key("3"), "carol".to_string()).await;
+    assert_eq!(
+        *batches.lock().unwrap(),
+        vec![vec![
+            (key("1"), "alice-v2".to_string()),
+            (key("2"), "bob".to_string()),
+            (key("3"), "carol".to_string()),
+        ]]
+    );
+    assert_eq!(cache.pending_writes(), 0);
+
+    // clear 前刷新剩余写入
+    cache.set(key("4"), "dave".to_string()).await;
+    assert_eq!(batches.lock().unwrap().len(), 1);
+    cache.clear().await;
+    assert_eq!(batches.lock().unwrap()[1], vec![(key("4"), "dave".to_string())]);
+    assert_eq!(cache.pending_writes(), 0);
+
+    // 定时刷新任务
+    let batches: Batches = Arc::default();
+    let persister = {
+        let batches = batches.clone();
+        move |batch| {
+            let batches = batches.clone();
+            async move {
+                batches.lock().unwrap().push(batch);
+                Ok::<(), PersistError>(())
+            }
+        }
+    };
+    let cache = Arc::new(
+        CacheManager::<String>::new(CacheConfig::default()).with_write_policy(WritePolicy::write_behind(
+            persister,
+            100,
+            Duration::from_millis(20),
+        )),
+    );
+    let handle = cache
+        .clone()
+        .spawn_write_behind_task()
+        .expect("Write-behind task should start");
+    cache.set(key("5"), "erin".to_string()).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(*batches.lock().unwrap(), vec![vec![(key("5"), "erin".to_string())]]);
+    handle.shutdown().await;
+
+    assert!(
+        Arc::new(CacheManager::<String>::new(CacheConfig::default()))
+            .spawn_write_behind_task()
+            .is_none()
+    );
+}
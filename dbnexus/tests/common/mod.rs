@@ -14,6 +14,7 @@ use tempfile::TempDir;
 /// 获取测试数据库配置
 ///
 /// 根据环境变量或默认值返回数据库配置
+#[allow(dead_code)]
 pub fn get_test_config() -> DbConfig {
     // 使用统一的配置管理
     let pool_config = PoolConfig {
@@ -34,6 +35,9 @@ pub fn get_test_config() -> DbConfig {
         migrations_dir: None,
         auto_migrate: false,
         migration_timeout: 60,
+        replica_urls: Vec::new(),
+        statement_timeout_ms: None,
+        password_file: None,
     });
 
     // 应用池配置
@@ -81,6 +85,9 @@ pub fn get_sqlite_memory_config() -> DbConfig {
         migrations_dir: None,
         auto_migrate: false,
         migration_timeout: 60,
+        replica_urls: Vec::new(),
+        statement_timeout_ms: None,
+        password_file: None,
     }
 }
 
@@ -93,7 +100,7 @@ pub fn get_sqlite_file_config() -> (DbConfig, TempDir) {
     let db_path = temp_dir.path().join("test.db");
 
     let config = DbConfig {
-        url: format!("sqlite:///{}", db_path.display()),
+        url: format!("sqlite://{}?mode=rwc", db_path.display()),
         max_connections: 5,
         min_connections: 1,
         idle_timeout: 300,
@@ -102,6 +109,9 @@ pub fn get_sqlite_file_config() -> (DbConfig, TempDir) {
         migrations_dir: None,
         auto_migrate: false,
         migration_timeout: 60,
+        replica_urls: Vec::new(),
+        statement_timeout_ms: None,
+        password_file: None,
     };
 
     (config, temp_dir)
@@ -120,6 +130,9 @@ pub fn get_small_pool_config() -> DbConfig {
         migrations_dir: None,
         auto_migrate: false,
         migration_timeout: 60,
+        replica_urls: Vec::new(),
+        statement_timeout_ms: None,
+        password_file: None,
     }
 }
 
@@ -136,6 +149,9 @@ pub fn get_large_pool_config() -> DbConfig {
         migrations_dir: None,
         auto_migrate: false,
         migration_timeout: 60,
+        replica_urls: Vec::new(),
+        statement_timeout_ms: None,
+        password_file: None,
     }
 }
 
@@ -229,3 +245,32 @@ where
 {
     futures::future::join_all(tasks).await
 }
+
+/// 创建测试用的权限配置文件
+///
+/// 返回配置文件路径和临时目录清理句柄
+#[allow(dead_code)]
+pub fn create_permissions_file(yaml: &str) -> (String, TempDir) {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let path = temp_dir.path().join("permissions.yaml");
+    std::fs::write(&path, yaml).expect("Failed to write permissions file");
+    (path.display().to_string(), temp_dir)
+}
+
+/// 为配置授予 admin 角色对所有表的全部权限
+///
+/// 返回权限配置文件的临时目录清理句柄
+#[allow(dead_code)]
+pub fn grant_admin_all_tables(config: &mut DbConfig) -> TempDir {
+    let (permissions_path, temp_dir) = create_permissions_file(
+        r#"
+roles:
+  admin:
+    tables:
+      - name: "*"
+        operations: [select, insert, update, delete]
+"#,
+    );
+    config.permissions_path = Some(permissions_path);
+    temp_dir
+}
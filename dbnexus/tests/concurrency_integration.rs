@@ -113,7 +113,8 @@ async fn test_concurrent_health_checks() {
 /// TEST-CONC-004: 并发数据库操作测试
 #[tokio::test]
 async fn test_concurrent_database_operations() {
-    let config = common::get_test_config();
+    let mut config = common::get_test_config();
+    let _perm_dir = common::grant_admin_all_tables(&mut config);
     let pool = DbPool::with_config(config).await.expect("Failed to create pool");
     let pool = Arc::new(pool);
 
@@ -281,7 +282,8 @@ async fn test_concurrent_role_sessions() {
 /// TEST-CONC-008: 并发事务测试
 #[tokio::test]
 async fn test_concurrent_transactions() {
-    let config = common::get_test_config();
+    let mut config = common::get_test_config();
+    let _perm_dir = common::grant_admin_all_tables(&mut config);
     let pool = DbPool::with_config(config).await.expect("Failed to create pool");
     let pool = Arc::new(pool);
 
@@ -352,6 +354,9 @@ async fn test_pool_capacity_boundary() {
         migrations_dir: None,
         auto_migrate: false,
         migration_timeout: 60,
+        replica_urls: Vec::new(),
+        statement_timeout_ms: None,
+        password_file: None,
     };
 
     let pool = DbPool::with_config(pool_config).await.expect("Failed to create pool");
@@ -491,3 +496,83 @@ async fn test_large_scale_concurrent_stress() {
         "Should complete at least some operations under concurrent stress"
     );
 }
+
+/// TEST-CONC-013: 连接获取公平性测试（FIFO）
+#[tokio::test]
+async fn test_fair_fifo_connection_acquire() {
+    let config = common::get_small_pool_config();
+    let max_connections = config.max_connections as usize;
+    let pool = Arc::new(DbPool::with_config(config).await.expect("Failed to create pool"));
+
+    // 占满连接池
+    let mut held = Vec::new();
+    for _ in 0..max_connections {
+        held.push(pool.get_session("admin").await.expect("Failed to get session"));
+    }
+
+    let num_waiters = 16;
+    let order = Arc::new(tokio::sync::Mutex::new(Vec::new()));
+    let mut handles = Vec::new();
+
+    for i in 0..num_waiters {
+        let pool = pool.clone();
+        let order = order.clone();
+        handles.push(tokio::spawn(async move {
+            let session = pool.get_session("admin").await?;
+            order.lock().await.push(i);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(session);
+            Ok::<_, dbnexus::DbError>(())
+        }));
+        // 错开入队时间以确定等待顺序
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+
+    drop(held);
+
+    for (i, result) in futures::future::join_all(handles).await.into_iter().enumerate() {
+        let result = result.expect("Task should not panic");
+        assert!(result.is_ok(), "Waiter {} should not time out: {:?}", i, result.err());
+    }
+
+    let order = order.lock().await;
+    assert_eq!(order.len(), num_waiters);
+    for (position, waiter) in order.iter().enumerate() {
+        assert!(
+            position.abs_diff(*waiter) <= max_connections,
+            "Waiter {} acquired at position {}, expected roughly FIFO order",
+            waiter,
+            position
+        );
+    }
+}
+
+/// TEST-CONC-014: 连接池按需扩容测试
+#[tokio::test]
+async fn test_pool_grows_on_demand_to_max() {
+    let mut config = common::get_test_config();
+    config.min_connections = 1;
+    config.max_connections = 5;
+    config.acquire_timeout = 1000;
+    let pool = Arc::new(DbPool::with_config(config).await.expect("Failed to create pool"));
+
+    let mut handles = Vec::new();
+    for i in 0..5 {
+        let pool = pool.clone();
+        handles.push(tokio::spawn(async move {
+            let session = pool.get_session(&format!("user{}", i)).await?;
+            // 同时持有会话，迫使连接池扩容
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            drop(session);
+            Ok::<_, dbnexus::DbError>(())
+        }));
+    }
+
+    for (i, result) in futures::future::join_all(handles).await.into_iter().enumerate() {
+        let result = result.expect("Task should not panic");
+        assert!(result.is_ok(), "Session {} should be acquired without timeout", i);
+    }
+
+    let status = pool.status();
+    assert!(status.total <= 5, "Pool should not exceed max_connections");
+}
@@ -0,0 +1,616 @@
+// Copyright (c) 2025 Kirky.X
+//
+// Licensed under the MIT License
+// See LICENSE file in the project root for full license information.
+
+//! 实体查询辅助函数集成测试
+//!
+//! 测试 `db_crud` 生成的方法及其依赖的分页、计数等通用查询，以及 DTO 校验后插入
+
+#![cfg(feature = "sqlite")]
+
+mod common;
+
+use dbnexus::entity::{self, Validate, ValidationErrors, Validator};
+use dbnexus::{DbError, DbPool};
+use sea_orm::{ActiveModelTrait, ConnectionTrait, Database, DatabaseConnection, IntoActiveModel, NotSet, Set};
+
+mod article {
+    use sea_orm::entity::prelude::*;
+
+    #[dbnexus::db_crud]
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+    #[sea_orm(table_name = "articles")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        pub title: String,
+        #[sea_orm(unique)]
+        pub slug: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// 与 `article` 同表、开启缓存失效的实体
+#[cfg(feature = "cache")]
+mod cached_article {
+    use sea_orm::entity::prelude::*;
+
+    #[dbnexus::db_cache]
+    #[dbnexus::db_crud]
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+    #[sea_orm(table_name = "articles")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        pub title: String,
+        pub slug: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// dbnexus 0.1.0 写法的普通结构体实体
+#[derive(Debug, PartialEq, dbnexus::DbEntity)]
+#[db_entity]
+#[table_name = "users"]
+#[dbnexus::db_crud]
+struct User {
+    #[primary_key]
+    id: i64,
+    name: String,
+    #[sea_orm(unique)]
+    email: String,
+}
+
+/// 与 `User` 同表、只允许 admin 查询的实体
+#[derive(Debug, dbnexus::DbEntity)]
+#[table_name = "users"]
+#[dbnexus::db_crud]
+#[dbnexus::db_permission(roles = ["admin"], operations = ["SELECT"])]
+struct ReadOnlyUser {
+    #[primary_key]
+    id: i64,
+    name: String,
+    email: String,
+}
+
+/// 入站 DTO：新建文章
+struct NewArticle {
+    title: String,
+    slug: String,
+}
+
+impl Validate for NewArticle {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        Validator::new()
+            .non_empty("title", &self.title)
+            .length("slug", &self.slug, 3, 64)
+            .finish()
+    }
+}
+
+impl IntoActiveModel<article::ActiveModel> for NewArticle {
+    fn into_active_model(self) -> article::ActiveModel {
+        article::ActiveModel {
+            id: NotSet,
+            title: Set(self.title),
+            slug: Set(self.slug),
+        }
+    }
+}
+
+/// 创建内存数据库并写入 `count` 篇文章
+async fn seeded_db(count: i32) -> DatabaseConnection {
+    let conn = Database::connect("sqlite::memory:")
+        .await
+        .expect("Failed to connect to SQLite");
+    conn.execute_unprepared(
+        "CREATE TABLE articles (id INTEGER PRIMARY KEY, title TEXT NOT NULL, slug TEXT NOT NULL UNIQUE)",
+    )
+    .await
+    .expect("Failed to create articles table");
+
+    for id in 1..=count {
+        conn.execute_unprepared(&format!(
+            "INSERT INTO articles (id, title, slug) VALUES ({id}, 'Article {id}', 'article-{id}')"
+        ))
+        .await
+        .expect("Failed to seed articles");
+    }
+
+    conn
+}
+
+/// 使用 SQLite 文件数据库，并授予 admin 角色对所有表的全部权限
+fn admin_file_config() -> (dbnexus::DbConfig, tempfile::TempDir, tempfile::TempDir) {
+    let (mut config, temp_dir) = common::get_sqlite_file_config();
+    let (permissions_path, perm_dir) = common::create_permissions_file(
+        r#"
+roles:
+  admin:
+    tables:
+      - name: "*"
+        operations: [select, insert, update, delete]
+"#,
+    );
+    config.permissions_path = Some(permissions_path);
+    (config, temp_dir, perm_dir)
+}
+
+/// TEST-ENT-001: 分页查询返回当前页和总数
+#[tokio::test]
+async fn test_list_paginated() {
+    let conn = seeded_db(7).await;
+
+    assert_eq!(entity::count::<article::Entity, _>(&conn).await.unwrap(), 7);
+
+    let (first, total) = entity::list_paginated::<article::Entity, _>(&conn, 0, 3).await.unwrap();
+    assert_eq!(total, 7);
+    assert_eq!(first.iter().map(|a| a.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+
+    let (last, total) = entity::list_paginated::<article::Entity, _>(&conn, 2, 3).await.unwrap();
+    assert_eq!(total, 7);
+    assert_eq!(last.iter().map(|a| a.id).collect::<Vec<_>>(), vec![7]);
+
+    let (beyond, _) = entity::list_paginated::<article::Entity, _>(&conn, 5, 3).await.unwrap();
+    assert!(beyond.is_empty());
+
+    assert!(entity::list_paginated::<article::Entity, _>(&conn, 0, 0).await.is_err());
+}
+
+/// TEST-ENT-002: 按唯一列查找实体
+#[tokio::test]
+async fn test_find_by_unique_column() {
+    let conn = seeded_db(2).await;
+
+    let inserted = article::ActiveModel {
+        id: Set(10),
+        title: Set("Hello".to_string()),
+        slug: Set("hello-world".to_string()),
+    }
+    .insert(&conn)
+    .await
+    .expect("Failed to insert article");
+
+    let found = entity::find_by::<article::Entity, _, _>(&conn, article::Column::Slug, "hello-world")
+        .await
+        .unwrap();
+    assert_eq!(found, Some(inserted));
+
+    let missing = entity::find_by::<article::Entity, _, _>(&conn, article::Column::Slug, "no-such-slug")
+        .await
+        .unwrap();
+    assert!(missing.is_none());
+}
+
+/// TEST-ENT-003: 校验失败时返回字段错误且不写入数据
+#[tokio::test]
+async fn test_insert_validated_rejects_invalid_dto() {
+    let (config, _temp_dir, _perm_dir) = admin_file_config();
+    let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
+    let mut session = pool.get_session("admin").await.expect("Failed to get session");
+    session
+        .execute_raw("CREATE TABLE articles (id INTEGER PRIMARY KEY, title TEXT NOT NULL, slug TEXT NOT NULL UNIQUE)")
+        .await
+        .expect("Failed to create articles table");
+
+    let invalid = NewArticle {
+        title: "  ".to_string(),
+        slug: "ab".to_string(),
+    };
+    match session.insert_validated(invalid).await {
+        Err(DbError::Validation(errors)) => {
+            let fields: Vec<&str> = errors.errors.iter().map(|e| e.field.as_str()).collect();
+            assert_eq!(fields, vec!["title", "slug"]);
+            let message = DbError::Validation(errors).to_string();
+            assert!(message.contains("title: must not be empty"), "{message}");
+            assert!(message.contains("slug: length must be between 3 and 64"), "{message}");
+        }
+        other => panic!("Expected validation error, got {other:?}"),
+    }
+
+    let conn = session.connection().expect("Failed to get connection").clone();
+    assert_eq!(entity::count::<article::Entity, _>(&conn).await.unwrap(), 0);
+
+    let inserted = session
+        .insert_validated(NewArticle {
+            title: "Hello".to_string(),
+            slug: "hello-world".to_string(),
+        })
+        .await
+        .expect("Valid DTO should be inserted");
+    assert_eq!(inserted.slug, "hello-world");
+    assert_eq!(entity::count::<article::Entity, _>(&conn).await.unwrap(), 1);
+}
+
+/// TEST-ENT-004: 并发读取同一实体只加载一次数据库，之后命中缓存
+#[cfg(feature = "cache")]
+#[tokio::test]
+async fn test_find_cached_single_flight() {
+    use dbnexus::cache::{CacheConfig, CacheManager};
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    let (config, _temp_dir, _perm_dir) = admin_file_config();
+    let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
+    let session = pool.get_session("admin").await.expect("Failed to get session");
+    #[cfg(feature = "metrics")]
+    let (session, metrics) = {
+        let mut session = session;
+        let metrics = std::sync::Arc::new(dbnexus::metrics::MetricsCollector::new());
+        session.set_metrics(metrics.clone());
+        (session, metrics)
+    };
+    session
+        .execute_raw("CREATE TABLE articles (id INTEGER PRIMARY KEY, title TEXT NOT NULL, slug TEXT NOT NULL UNIQUE)")
+        .await
+        .expect("Failed to create articles table");
+    session
+        .execute_raw("INSERT INTO articles (id, title, slug) VALUES (1, 'Cached', 'cached')")
+        .await
+        .expect("Failed to seed article");
+
+    let cache: CacheManager<article::Model> = CacheManager::new(CacheConfig::default());
+    let ttl = Duration::from_secs(60);
+    let concurrency = 16;
+
+    let results =
+        futures::future::join_all((0..concurrency).map(|_| session.find_cached::<article::Entity>(1, &cache, ttl)))
+            .await;
+    for result in results {
+        let model = result
+            .expect("find_cached should succeed")
+            .expect("Article should exist");
+        assert_eq!(model.slug, "cached");
+    }
+
+    // 单飞加载：只有一次加载写入了缓存
+    assert_eq!(cache.stats().sets.load(Ordering::Relaxed), 1);
+    #[cfg(feature = "metrics")]
+    assert_eq!(metrics.get_query_stats("SELECT").map(|stats| stats.count), Some(1));
+
+    // 再次读取直接命中缓存
+    let hits_before = cache.stats().hits.load(Ordering::Relaxed);
+    session
+        .find_cached::<article::Entity>(1, &cache, ttl)
+        .await
+        .expect("find_cached should succeed");
+    assert_eq!(cache.stats().hits.load(Ordering::Relaxed), hits_before + 1);
+
+    // 不存在的记录写入空值占位
+    assert!(
+        session
+            .find_cached::<article::Entity>(42, &cache, ttl)
+            .await
+            .expect("find_cached should succeed")
+            .is_none()
+    );
+}
+
+/// TEST-ENT-005: 分块批量插入 2500 行，语句数与分块一致
+#[tokio::test]
+async fn test_bulk_insert_chunks_rows() {
+    let (config, _temp_dir, _perm_dir) = admin_file_config();
+    let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
+    let mut session = pool.get_session("admin").await.expect("Failed to get session");
+    session
+        .execute_raw("CREATE TABLE articles (id INTEGER PRIMARY KEY, title TEXT NOT NULL, slug TEXT NOT NULL UNIQUE)")
+        .await
+        .expect("Failed to create articles table");
+
+    let models = |range: std::ops::RangeInclusive<i32>| -> Vec<article::ActiveModel> {
+        range
+            .map(|id| article::ActiveModel {
+                id: Set(id),
+                title: Set(format!("Article {id}")),
+                slug: Set(format!("article-{id}")),
+            })
+            .collect()
+    };
+
+    // SQLite 参数上限 999，3 列每块最多 333 行
+    let result = session
+        .bulk_insert(models(1..=2500), None)
+        .await
+        .expect("Failed to bulk insert");
+    assert_eq!(result.rows_inserted, 2500);
+    assert_eq!(result.statements, 2500_usize.div_ceil(333));
+
+    let result = session
+        .bulk_insert(models(2501..=2750), Some(100))
+        .await
+        .expect("Failed to bulk insert");
+    assert_eq!(result.rows_inserted, 250);
+    assert_eq!(result.statements, 3);
+
+    let conn = session.connection().expect("Failed to get connection").clone();
+    assert_eq!(entity::count::<article::Entity, _>(&conn).await.unwrap(), 2750);
+
+    // 原子模式下任一块失败（主键冲突）时全部回滚
+    let mut conflicting = models(3001..=3400);
+    conflicting.extend(models(1..=1));
+    assert!(session.bulk_insert_atomic(conflicting, Some(200)).await.is_err());
+    assert_eq!(entity::count::<article::Entity, _>(&conn).await.unwrap(), 2750);
+}
+
+/// TEST-ENT-006: db_crud 生成的分页和计数方法遍历已写入的表
+#[tokio::test]
+async fn test_generated_list_paginated() {
+    let conn = seeded_db(5).await;
+
+    assert_eq!(article::Entity::count(&conn).await.unwrap(), 5);
+
+    let mut seen = Vec::new();
+    for page in 0..3 {
+        let (items, total) = article::Entity::list_paginated(&conn, page, 2).await.unwrap();
+        assert_eq!(total, 5);
+        seen.extend(items.into_iter().map(|a| a.slug));
+    }
+    assert_eq!(seen, (1..=5).map(|id| format!("article-{id}")).collect::<Vec<_>>());
+    assert!(article::Entity::list_paginated(&conn, 0, 0).await.is_err());
+}
+
+/// TEST-ENT-007: db_crud 生成的增删改查方法通过 Session 执行并检查权限
+#[tokio::test]
+async fn test_generated_crud_through_session() {
+    let (config, _temp_dir, _perm_dir) = admin_file_config();
+    let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
+    let mut session = pool.get_session("admin").await.expect("Failed to get session");
+    session
+        .execute_raw("CREATE TABLE articles (id INTEGER PRIMARY KEY, title TEXT NOT NULL, slug TEXT NOT NULL UNIQUE)")
+        .await
+        .expect("Failed to create articles table");
+
+    let created = article::Entity::create(
+        &mut session,
+        article::ActiveModel {
+            id: Set(1),
+            title: Set("Draft".to_string()),
+            slug: Set("draft".to_string()),
+        },
+    )
+    .await
+    .expect("Failed to create article");
+    assert_eq!(created.title, "Draft");
+
+    // 主键随更新一起修改时按新主键返回
+    let updated = article::Entity::modify(
+        &mut session,
+        1,
+        article::ActiveModel {
+            id: Set(2),
+            title: Set("Published".to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Failed to modify article")
+    .expect("Article should exist");
+    assert_eq!(
+        (updated.id, updated.title.as_str(), updated.slug.as_str()),
+        (2, "Published", "draft")
+    );
+    assert!(article::Entity::fetch(&session, 1).await.unwrap().is_none());
+    assert_eq!(article::Entity::fetch(&session, 2).await.unwrap(), Some(updated));
+
+    assert!(
+        article::Entity::modify(&mut session, 99, Default::default())
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert_eq!(article::Entity::remove(&mut session, 2).await.unwrap(), 1);
+    assert_eq!(article::Entity::remove(&mut session, 2).await.unwrap(), 0);
+
+    // 只有查询权限的角色不能写入
+    let mut reader = pool.get_session("reader").await.expect("Failed to get session");
+    let err = article::Entity::create(&mut reader, Default::default())
+        .await
+        .expect_err("Role without policy must not insert");
+    assert!(
+        matches!(err, DbError::Forbidden { .. } | DbError::Permission { .. }),
+        "{err:?}"
+    );
+}
+
+/// TEST-ENT-008: db_crud 为唯一列生成的 find_by_slug 查找刚插入的行
+#[tokio::test]
+async fn test_generated_find_by_indexed_column() {
+    let conn = seeded_db(2).await;
+
+    let inserted = article::ActiveModel {
+        id: Set(10),
+        title: Set("Hello".to_string()),
+        slug: Set("hello-world".to_string()),
+    }
+    .insert(&conn)
+    .await
+    .expect("Failed to insert article");
+
+    assert_eq!(
+        article::Entity::find_by_slug(&conn, "hello-world").await.unwrap(),
+        Some(inserted)
+    );
+    assert!(
+        article::Entity::find_by_slug(&conn, "no-such-slug".to_string())
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+/// TEST-ENT-009: db_cache 生成的增删改方法在成功后使实体缓存失效，主键变化时新旧键都失效
+#[cfg(feature = "cache")]
+#[tokio::test]
+async fn test_generated_cache_invalidation() {
+    use dbnexus::cache::{CacheConfig, CacheKey, CacheManager};
+    use std::time::Duration;
+
+    let (config, _temp_dir, _perm_dir) = admin_file_config();
+    let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
+    let mut session = pool.get_session("admin").await.expect("Failed to get session");
+    session
+        .execute_raw("CREATE TABLE articles (id INTEGER PRIMARY KEY, title TEXT NOT NULL, slug TEXT NOT NULL UNIQUE)")
+        .await
+        .expect("Failed to create articles table");
+
+    let cache: CacheManager<cached_article::Model> = CacheManager::new(CacheConfig::default());
+    let ttl = Duration::from_secs(60);
+    let key = |id: i32| CacheKey::new("articles", &id.to_string());
+
+    // 插入前读取会缓存空值占位，插入后必须清除
+    assert!(
+        cached_article::Entity::fetch_cached(&session, &cache, 1, ttl)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let created = cached_article::Entity::create(
+        &mut session,
+        &cache,
+        cached_article::ActiveModel {
+            id: Set(1),
+            title: Set("Draft".to_string()),
+            slug: Set("draft".to_string()),
+        },
+    )
+    .await
+    .expect("Failed to create article");
+    assert_eq!(
+        cached_article::Entity::fetch_cached(&session, &cache, 1, ttl)
+            .await
+            .unwrap(),
+        Some(created)
+    );
+    assert!(cache.get(&key(1)).await.is_some());
+
+    // 主键从 1 改为 2：新键上预先缓存的空值占位和旧键都被清除
+    assert!(
+        cached_article::Entity::fetch_cached(&session, &cache, 2, ttl)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let updated = cached_article::Entity::modify(
+        &mut session,
+        &cache,
+        1,
+        cached_article::ActiveModel {
+            id: Set(2),
+            title: Set("Published".to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Failed to modify article")
+    .expect("Article should exist");
+    assert!(cache.get(&key(1)).await.is_none());
+    assert_eq!(
+        cached_article::Entity::fetch_cached(&session, &cache, 2, ttl)
+            .await
+            .unwrap(),
+        Some(updated)
+    );
+    assert!(cache.get(&key(2)).await.is_some());
+
+    assert_eq!(
+        cached_article::Entity::remove(&mut session, &cache, 2).await.unwrap(),
+        1
+    );
+    assert!(cache.get(&key(2)).await.is_none());
+    assert!(
+        cached_article::Entity::fetch_cached(&session, &cache, 2, ttl)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+/// TEST-ENT-010: 普通结构体上的 db_crud 生成 0.1.0 的增删改查方法，db_permission 限制角色和操作
+#[tokio::test]
+async fn test_plain_struct_crud_and_role_permission() {
+    use dbnexus::orm::ColumnTrait;
+    use dbnexus::{Condition, PermissionAction, PermissionContext};
+
+    let (config, _temp_dir, _perm_dir) = admin_file_config();
+    let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
+    let mut session = pool.get_session("admin").await.expect("Failed to get session");
+    session
+        .execute_raw("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, email TEXT NOT NULL UNIQUE)")
+        .await
+        .expect("Failed to create users table");
+
+    let user = |id: i64, name: &str| User {
+        id,
+        name: name.to_string(),
+        email: format!("{}@example.com", name.to_lowercase()),
+    };
+    let inserted = User::insert(&mut session, user(1, "Alice")).await.unwrap();
+    assert_eq!(inserted, user(1, "Alice"));
+    User::insert(&mut session, user(2, "Bob")).await.unwrap();
+    User::insert(&mut session, user(3, "Carol")).await.unwrap();
+
+    assert_eq!(User::find_by_id(&session, 1).await.unwrap(), Some(user(1, "Alice")));
+    assert!(User::find_by_id(&session, 42).await.unwrap().is_none());
+    assert_eq!(User::count(&session).await.unwrap(), 3);
+
+    let mut renamed = user(1, "Alice");
+    renamed.name = "Alicia".to_string();
+    assert_eq!(User::update(&mut session, renamed).await.unwrap().name, "Alicia");
+    assert!(User::update(&mut session, user(42, "Nobody")).await.is_err());
+
+    let conn = session.connection().expect("Failed to get connection").clone();
+    assert_eq!(
+        User::find_by_email(&conn, "bob@example.com").await.unwrap(),
+        Some(user(2, "Bob"))
+    );
+
+    let filter = Condition::any()
+        .add(user_entity::Column::Name.eq("Bob"))
+        .add(user_entity::Column::Name.eq("Carol"));
+    assert_eq!(User::delete_many(&mut session, filter).await.unwrap(), 2);
+    let remaining = User::find_all(&session).await.unwrap();
+    assert_eq!(
+        remaining.iter().map(|u| u.name.as_str()).collect::<Vec<_>>(),
+        ["Alicia"]
+    );
+    assert_eq!(User::delete(&mut session, 1).await.unwrap(), 1);
+    assert_eq!(User::count(&session).await.unwrap(), 0);
+
+    // db_permission：只允许 admin 查询，写操作和其他角色都被拒绝
+    assert_eq!(ReadOnlyUser::ALLOWED_ROLES, ["admin"]);
+    assert_eq!(ReadOnlyUser::ALLOWED_OPERATIONS, [PermissionAction::Select]);
+    let row = ReadOnlyUser {
+        id: 7,
+        name: "Dave".to_string(),
+        email: "dave@example.com".to_string(),
+    };
+    let err = ReadOnlyUser::insert(&mut session, row).await.unwrap_err();
+    assert!(
+        matches!(
+            &err,
+            DbError::Permission {
+                operation: PermissionAction::Insert,
+                ..
+            }
+        ),
+        "{err:?}"
+    );
+    assert_eq!(User::count(&session).await.unwrap(), 0);
+    assert!(ReadOnlyUser::find_all(&session).await.unwrap().is_empty());
+
+    let guest = PermissionContext::with_cache_size("guest".to_string(), 4);
+    assert!(matches!(
+        ReadOnlyUser::check_permission(&guest),
+        Err(DbError::Forbidden { .. })
+    ));
+    assert!(ReadOnlyUser::check_operation(session.permission_ctx(), &PermissionAction::Select).is_ok());
+}
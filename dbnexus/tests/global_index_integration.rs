@@ -0,0 +1,542 @@
+// Copyright (c) 2025 Kirky.X
+//
+// Licensed under the MIT License
+// See LICENSE file in the project root for full license information.
+
+//! GlobalIndex 集成测试
+//!
+//! 测试全局索引的注册、缓存一致性、查询路径等功能
+
+#![cfg(all(feature = "global-index", feature = "sqlite"))]
+
+use dbnexus::global_index::{
+    ChangeCapture, ChangeCaptureConfig, GlobalIndex, IndexEntry, PollingChangeCapture, SyncEvent,
+};
+
+fn entry(record_id: &str, shard_id: u32, index_value: &str) -> IndexEntry {
+    IndexEntry::new("orders", record_id, shard_id, "user_id", index_value)
+}
+
+/// 去掉数据库写入的时间戳，便于与 [`entry`] 构造的条目比较
+fn untimed(entries: Vec<IndexEntry>) -> Vec<IndexEntry> {
+    entries
+        .into_iter()
+        .map(|e| IndexEntry {
+            created_at: None,
+            updated_at: None,
+            ..e
+        })
+        .collect()
+}
+
+/// TEST-GIDX-001: 两种查询路径共用缓存且结果一致
+#[tokio::test]
+async fn test_query_paths_share_cache() {
+    let index = GlobalIndex::new("sqlite::memory:")
+        .await
+        .expect("Failed to create global index");
+
+    index.register_entry(entry("order_1", 0, "user_a")).await.unwrap();
+    index.register_entry(entry("order_2", 1, "user_a")).await.unwrap();
+    index.register_entry(entry("order_3", 2, "user_b")).await.unwrap();
+
+    let all_from_db = index.query_all_shards("orders", "user_id").await.unwrap();
+    let all_from_cache = index.query_all_shards("orders", "user_id").await.unwrap();
+    assert_eq!(all_from_db, all_from_cache);
+    assert_eq!(all_from_db.len(), 3);
+
+    let mut by_index = index.query_by_index("orders", "user_id", "user_a").await.unwrap();
+    by_index.extend(index.query_by_index("orders", "user_id", "user_b").await.unwrap());
+    by_index.sort_by(|a, b| a.record_id.cmp(&b.record_id));
+    assert_eq!(by_index, all_from_cache);
+
+    // 注册后缓存保持一致
+    index.register_entry(entry("order_4", 3, "user_c")).await.unwrap();
+    let all = index.query_all_shards("orders", "user_id").await.unwrap();
+    assert_eq!(all.len(), 4);
+    assert_eq!(
+        untimed(index.query_by_index("orders", "user_id", "user_c").await.unwrap()),
+        vec![entry("order_4", 3, "user_c")]
+    );
+
+    // 删除后两种查询路径都不再返回该记录
+    index
+        .process_sync_event(SyncEvent::Delete {
+            table_name: "orders".to_string(),
+            record_id: "order_1".to_string(),
+            shard_id: 0,
+            index_key: "user_id".to_string(),
+            index_value: "user_a".to_string(),
+        })
+        .await
+        .unwrap();
+    let all = index.query_all_shards("orders", "user_id").await.unwrap();
+    assert!(all.iter().all(|e| e.record_id != "order_1"));
+    assert_eq!(
+        untimed(index.query_by_index("orders", "user_id", "user_a").await.unwrap()),
+        vec![entry("order_2", 1, "user_a")]
+    );
+}
+
+/// TEST-GIDX-002: 缓存数量受上限约束，数据库仍返回全部记录
+#[tokio::test]
+async fn test_cache_is_bounded() {
+    let config = ChangeCaptureConfig {
+        cache_max_entries: 5,
+        ..Default::default()
+    };
+    let index = GlobalIndex::with_config("sqlite::memory:", config)
+        .await
+        .expect("Failed to create global index");
+
+    for i in 0..20 {
+        index
+            .register_entry(entry(&format!("order_{:02}", i), i % 4, &format!("user_{}", i)))
+            .await
+            .unwrap();
+        assert!(index.cached_entry_count().await <= 5);
+    }
+
+    let all = index.query_all_shards("orders", "user_id").await.unwrap();
+    assert_eq!(all.len(), 20);
+    assert!(index.cached_entry_count().await <= 5);
+
+    // 被淘汰的条目仍可从数据库查询
+    let first = index.query_by_index("orders", "user_id", "user_0").await.unwrap();
+    assert_eq!(untimed(first), vec![entry("order_00", 0, "user_0")]);
+    assert!(index.cached_entry_count().await <= 5);
+}
+
+/// TEST-GIDX-003: 缓存过期后重新从数据库加载
+#[tokio::test]
+async fn test_cache_ttl_expiry() {
+    let config = ChangeCaptureConfig {
+        cache_ttl_ms: 50,
+        ..Default::default()
+    };
+    let index = GlobalIndex::with_config("sqlite::memory:", config)
+        .await
+        .expect("Failed to create global index");
+
+    index.register_entry(entry("order_1", 0, "user_a")).await.unwrap();
+    assert_eq!(index.query_all_shards("orders", "user_id").await.unwrap().len(), 1);
+    assert_eq!(index.cached_entry_count().await, 1);
+
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let entries = index.query_by_index("orders", "user_id", "user_a").await.unwrap();
+    assert_eq!(untimed(entries), vec![entry("order_1", 0, "user_a")]);
+    assert_eq!(index.query_all_shards("orders", "user_id").await.unwrap().len(), 1);
+}
+
+/// TEST-GIDX-004: 轮询变更表生成同步事件
+#[tokio::test]
+async fn test_polling_change_capture_outbox() {
+    use chrono::{SecondsFormat, Utc};
+    use sea_orm::{ConnectionTrait, Database};
+
+    let conn = Database::connect("sqlite::memory:").await.expect("Failed to connect");
+    conn.execute_unprepared(
+        "CREATE TABLE global_index_outbox (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            table_name TEXT NOT NULL,
+            record_id TEXT NOT NULL,
+            shard_id INTEGER NOT NULL,
+            op TEXT NOT NULL,
+            index_key TEXT NOT NULL,
+            index_value TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            processed INTEGER NOT NULL DEFAULT 0
+        )",
+    )
+    .await
+    .expect("Failed to create outbox table");
+
+    let mut capture = PollingChangeCapture::new(conn.clone(), 100);
+    assert!(
+        capture.next_event().await.is_none(),
+        "Stopped capture should not emit events"
+    );
+    capture.start().await.unwrap();
+
+    let now = Utc::now().to_rfc3339_opts(SecondsFormat::Micros, true);
+    conn.execute_unprepared(&format!(
+        "INSERT INTO global_index_outbox (table_name, record_id, shard_id, op, index_key, index_value, created_at) VALUES \
+         ('orders', 'order_1', 2, 'insert', 'user_id', 'user_a', '{now}'), \
+         ('orders', 'order_0', 1, 'delete', 'user_id', 'user_b', '{now}')"
+    ))
+    .await
+    .expect("Failed to insert outbox rows");
+
+    match capture.next_event().await {
+        Some(SyncEvent::Insert {
+            record_id,
+            shard_id,
+            index_value,
+            ..
+        }) => {
+            assert_eq!(record_id, "order_1");
+            assert_eq!(shard_id, 2);
+            assert_eq!(index_value, "user_a");
+        }
+        other => panic!("Expected Insert event, got {:?}", other),
+    }
+    match capture.next_event().await {
+        Some(SyncEvent::Delete { record_id, .. }) => assert_eq!(record_id, "order_0"),
+        other => panic!("Expected Delete event, got {:?}", other),
+    }
+    assert!(
+        capture.next_event().await.is_none(),
+        "Processed rows should not be emitted again"
+    );
+
+    let unprocessed = conn
+        .query_one_raw(sea_orm::Statement::from_string(
+            conn.get_database_backend(),
+            "SELECT COUNT(*) AS cnt FROM global_index_outbox WHERE processed = 0",
+        ))
+        .await
+        .unwrap()
+        .expect("Count query should return a row");
+    assert_eq!(unprocessed.try_get::<i64>("", "cnt").unwrap(), 0);
+}
+
+/// TEST-GIDX-005: 重复注册同一记录时更新索引值
+#[tokio::test]
+async fn test_register_entry_upsert() {
+    let index = GlobalIndex::new("sqlite::memory:")
+        .await
+        .expect("Failed to create global index");
+
+    index.register_entry(entry("order_1", 0, "user_a")).await.unwrap();
+    index
+        .register_entry(entry("order_1", 3, "user_b"))
+        .await
+        .expect("Re-registering the same record should update instead of failing");
+
+    assert!(
+        index
+            .query_by_index("orders", "user_id", "user_a")
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        untimed(index.query_by_index("orders", "user_id", "user_b").await.unwrap()),
+        vec![entry("order_1", 3, "user_b")]
+    );
+    assert_eq!(
+        untimed(index.query_all_shards("orders", "user_id").await.unwrap()),
+        vec![entry("order_1", 3, "user_b")]
+    );
+
+    // 批量注册同样支持更新
+    index
+        .register_entries(vec![entry("order_1", 1, "user_c"), entry("order_2", 2, "user_c")])
+        .await
+        .unwrap();
+    let all = index.query_all_shards("orders", "user_id").await.unwrap();
+    assert_eq!(
+        untimed(all),
+        vec![entry("order_1", 1, "user_c"), entry("order_2", 2, "user_c")]
+    );
+}
+
+/// TEST-GIDX-006: 同步失败的条目标记为 failed，重试后变为 synced
+#[tokio::test]
+async fn test_failed_sync_is_retried() {
+    use dbnexus::global_index::{SYNC_STATUS_FAILED, SYNC_STATUS_PENDING, SYNC_STATUS_SYNCED};
+    use sea_orm::ConnectionTrait;
+
+    let index = GlobalIndex::new("sqlite::memory:")
+        .await
+        .expect("Failed to create global index");
+
+    // 通过触发器模拟最终同步写入失败（pending 写入可以成功）
+    index
+        .get_connection()
+        .execute_unprepared(
+            "CREATE TRIGGER fail_sync BEFORE UPDATE ON global_index WHEN NEW.sync_status = 'synced' \
+             BEGIN SELECT RAISE(ABORT, 'simulated sync failure'); END;",
+        )
+        .await
+        .expect("Failed to create trigger");
+
+    index
+        .process_sync_event(SyncEvent::Insert {
+            table_name: "orders".to_string(),
+            record_id: "order_1".to_string(),
+            shard_id: 2,
+            index_key: "user_id".to_string(),
+            index_value: "user_a".to_string(),
+        })
+        .await
+        .expect("Sync failure should not be propagated");
+
+    assert_eq!(
+        untimed(index.query_by_status(SYNC_STATUS_FAILED).await.unwrap()),
+        vec![entry("order_1", 2, "user_a")]
+    );
+    assert!(index.query_by_status(SYNC_STATUS_PENDING).await.unwrap().is_empty());
+
+    // 故障未恢复时重试仍失败
+    assert_eq!(index.retry_failed(10).await.unwrap(), 0);
+    assert_eq!(index.query_by_status(SYNC_STATUS_FAILED).await.unwrap().len(), 1);
+
+    index
+        .get_connection()
+        .execute_unprepared("DROP TRIGGER fail_sync")
+        .await
+        .expect("Failed to drop trigger");
+
+    assert_eq!(index.retry_failed(10).await.unwrap(), 1);
+    assert!(index.query_by_status(SYNC_STATUS_FAILED).await.unwrap().is_empty());
+    assert_eq!(
+        untimed(index.query_by_status(SYNC_STATUS_SYNCED).await.unwrap()),
+        vec![entry("order_1", 2, "user_a")]
+    );
+}
+
+/// TEST-GIDX-007: 分页与流式查询遍历 2500 条索引条目
+#[tokio::test]
+async fn test_paged_and_streamed_queries() {
+    use futures::TryStreamExt;
+    use sea_orm::ConnectionTrait;
+
+    let index = GlobalIndex::new("sqlite::memory:")
+        .await
+        .expect("Failed to create global index");
+
+    let entries: Vec<IndexEntry> = (0..2500)
+        .map(|i| entry(&format!("order_{:04}", i), i % 8, &format!("user_{}", i % 100)))
+        .collect();
+    for chunk in entries.chunks(500) {
+        index.register_entries(chunk.to_vec()).await.unwrap();
+    }
+
+    let mut paged = Vec::new();
+    for page in 0..3 {
+        let rows = index
+            .query_all_shards_paged("orders", "user_id", page, 1000)
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), if page < 2 { 1000 } else { 500 });
+        paged.extend(rows);
+    }
+    assert!(
+        index
+            .query_all_shards_paged("orders", "user_id", 3, 1000)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(untimed(paged), entries);
+
+    let streamed: Vec<IndexEntry> = index
+        .query_all_shards_stream("orders", "user_id")
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(untimed(streamed), entries);
+
+    // 预热缓存后，绕过 GlobalIndex 删除的数据仍从缓存返回
+    let warmed: Vec<IndexEntry> = index
+        .query_all_shards_stream_cached("orders", "user_id")
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(untimed(warmed), entries);
+    index
+        .get_connection()
+        .execute_unprepared("DELETE FROM global_index WHERE record_id >= 'order_2000'")
+        .await
+        .unwrap();
+    assert_eq!(index.query_all_shards("orders", "user_id").await.unwrap().len(), 2500);
+
+    let streamed: Vec<IndexEntry> = index
+        .query_all_shards_stream("orders", "user_id")
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(streamed.len(), 2000);
+}
+
+/// TEST-GIDX-008: 按 SQLite 类型建表，表已存在时不再执行 DDL
+#[tokio::test]
+async fn test_schema_created_once_with_backend_types() {
+    use dbnexus::migration::{DatabaseType, SqlGenerator};
+    use sea_orm::{ConnectionTrait, Statement};
+
+    let sql = SqlGenerator::new(DatabaseType::Sqlite).generate_create_table_sql(&GlobalIndex::schema_table());
+    assert!(!sql.contains("WITH TIME ZONE"));
+    assert!(!sql.contains("VARCHAR"));
+    assert!(sql.contains("CREATE UNIQUE INDEX uk_table_record ON global_index (table_name, record_id);"));
+
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let url = format!("sqlite://{}?mode=rwc", dir.path().join("index.db").display());
+
+    let index = GlobalIndex::new(&url).await.expect("Failed to create global index");
+    index.register_entry(entry("order_1", 0, "user_a")).await.unwrap();
+    drop(index);
+
+    // 表已存在：不重复建表，数据保留
+    let index = GlobalIndex::new(&url).await.expect("Failed to reopen global index");
+    assert_eq!(
+        untimed(index.query_by_index("orders", "user_id", "user_a").await.unwrap()),
+        vec![entry("order_1", 0, "user_a")]
+    );
+
+    let columns = index
+        .get_connection()
+        .query_all_raw(Statement::from_string(
+            index.get_connection().get_database_backend(),
+            "SELECT name, type FROM pragma_table_info('global_index') ORDER BY cid",
+        ))
+        .await
+        .unwrap();
+    let types: Vec<String> = columns.iter().map(|row| row.try_get("", "type").unwrap()).collect();
+    assert_eq!(types[..3], ["TEXT", "TEXT", "TEXT"]);
+    assert_eq!(types[3], "INTEGER");
+}
+
+/// TEST-GIDX-009: 条目携带时间戳，可按更新时间排序并按数值范围查询
+#[tokio::test]
+async fn test_timestamps_and_numeric_range() {
+    use dbnexus::global_index::SYNC_STATUS_SYNCED;
+    use std::time::Duration;
+
+    let index = GlobalIndex::new("sqlite::memory:")
+        .await
+        .expect("Failed to create global index");
+    let amount = |record_id: &str, value: &str| IndexEntry::new("orders", record_id, 0, "amount", value);
+
+    index.register_entry(amount("order_1", "15")).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    index.register_entry(amount("order_2", "250.5")).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    // 更新 order_1：更新时间变为最新，创建时间保持不变
+    index.register_entry(amount("order_1", "99")).await.unwrap();
+    // 注册后写入缓存的条目与数据库中的行一致，包括保留下来的创建时间
+    let registered = index.query_by_index("orders", "amount", "99").await.unwrap();
+    let stored = index.query_by_status(SYNC_STATUS_SYNCED).await.unwrap();
+    assert_eq!(registered.len(), 1);
+    assert_eq!(stored.iter().find(|e| e.record_id == "order_1"), Some(&registered[0]));
+    assert!(registered[0].created_at.is_some() && registered[0].created_at < registered[0].updated_at);
+    index.register_entry(amount("order_3", "-3")).await.unwrap();
+    index.register_entry(amount("order_4", "n/a")).await.unwrap();
+
+    let mut all = index.query_all_shards("orders", "amount").await.unwrap();
+    assert!(all.iter().all(|e| e.created_at.is_some() && e.updated_at.is_some()));
+    let order_1 = all.iter().find(|e| e.record_id == "order_1").cloned().unwrap();
+    let order_2 = all.iter().find(|e| e.record_id == "order_2").cloned().unwrap();
+    assert!(order_1.created_at < order_2.created_at);
+    assert!(order_1.updated_at > order_2.updated_at);
+
+    all.sort_by_key(|e| e.updated_at);
+    assert_eq!(all[0].record_id, "order_2");
+
+    // 缓存命中与数据库读取的时间戳一致
+    let cached = index.query_by_index("orders", "amount", "99").await.unwrap();
+    assert_eq!(cached, vec![order_1]);
+
+    let in_range: Vec<String> = index
+        .query_by_index_range("orders", "amount", 0.0..=100.0)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|e| e.index_value)
+        .collect();
+    assert_eq!(in_range, vec!["99"]);
+
+    let at_least: Vec<String> = index
+        .query_by_index_range("orders", "amount", -5.0..)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|e| e.record_id)
+        .collect();
+    assert_eq!(at_least, vec!["order_3", "order_1", "order_2"]);
+}
+
+/// TEST-GIDX-010: 失败的删除事件按记录的操作重试；pending 写入失败时加入重试队列而不返回错误
+#[tokio::test]
+async fn test_failed_delete_is_retried_as_delete() {
+    use dbnexus::global_index::{SYNC_OP_DELETE, SYNC_STATUS_FAILED};
+    use sea_orm::{ConnectionTrait, EntityTrait};
+
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let url = format!("sqlite://{}?mode=rwc", dir.path().join("index.db").display());
+
+    let index = GlobalIndex::new(&url).await.expect("Failed to create global index");
+    index.register_entry(entry("order_1", 0, "user_a")).await.unwrap();
+    index
+        .get_connection()
+        .execute_unprepared(
+            "CREATE TRIGGER fail_delete BEFORE DELETE ON global_index \
+             BEGIN SELECT RAISE(ABORT, 'simulated delete failure'); END;",
+        )
+        .await
+        .expect("Failed to create trigger");
+    index
+        .process_sync_event(SyncEvent::Delete {
+            table_name: "orders".to_string(),
+            record_id: "order_1".to_string(),
+            shard_id: 0,
+            index_key: "user_id".to_string(),
+            index_value: "user_a".to_string(),
+        })
+        .await
+        .expect("Sync failure should not be propagated");
+    drop(index);
+
+    // 重新打开后重试队列为空，按条目记录的 sync_op 重建删除事件
+    let index = GlobalIndex::new(&url).await.expect("Failed to reopen global index");
+    let failed = index.query_by_status(SYNC_STATUS_FAILED).await.unwrap();
+    assert_eq!(untimed(failed), vec![entry("order_1", 0, "user_a")]);
+    let row = dbnexus::global_index::Entity::find()
+        .one(index.get_connection())
+        .await
+        .unwrap()
+        .expect("Index row should exist");
+    assert_eq!(row.sync_op, SYNC_OP_DELETE);
+
+    index
+        .get_connection()
+        .execute_unprepared("DROP TRIGGER fail_delete")
+        .await
+        .expect("Failed to drop trigger");
+    assert_eq!(index.retry_failed(10).await.unwrap(), 1);
+    assert!(index.query_all_shards("orders", "user_id").await.unwrap().is_empty());
+
+    // pending 写入失败：事件进入重试队列，恢复后重试成功
+    index
+        .get_connection()
+        .execute_unprepared(
+            "CREATE TRIGGER fail_insert BEFORE INSERT ON global_index \
+             BEGIN SELECT RAISE(ABORT, 'simulated insert failure'); END;",
+        )
+        .await
+        .expect("Failed to create trigger");
+    index
+        .process_sync_event(SyncEvent::Insert {
+            table_name: "orders".to_string(),
+            record_id: "order_2".to_string(),
+            shard_id: 1,
+            index_key: "user_id".to_string(),
+            index_value: "user_b".to_string(),
+        })
+        .await
+        .expect("Pending write failure should not be propagated");
+    assert!(index.query_all_shards("orders", "user_id").await.unwrap().is_empty());
+
+    index
+        .get_connection()
+        .execute_unprepared("DROP TRIGGER fail_insert")
+        .await
+        .expect("Failed to drop trigger");
+    assert_eq!(index.retry_failed(10).await.unwrap(), 1);
+    assert_eq!(
+        untimed(index.query_all_shards("orders", "user_id").await.unwrap()),
+        vec![entry("order_2", 1, "user_b")]
+    );
+}
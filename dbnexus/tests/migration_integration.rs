@@ -9,8 +9,8 @@
 
 use dbnexus::DbPool;
 use dbnexus::migration::{
-    Column, ColumnType, DatabaseType, Index, Migration, MigrationExecutor, MigrationFileParser, MigrationHistory,
-    Schema, SchemaDiffer, SqlGenerator, Table, TableChange,
+    Column, ColumnType, DatabaseType, Index, Migration, MigrationDirection, MigrationExecutor, MigrationFileParser,
+    MigrationHistory, MigrationPlan, Schema, SchemaDiffer, SqlGenerator, Table, TableChange, ToMigrationTable,
 };
 mod common;
 
@@ -263,6 +263,8 @@ fn test_create_index_sql_generation() {
         columns: vec!["email".to_string()],
         is_unique: false,
         is_constraint: false,
+        where_clause: None,
+        method: None,
     };
 
     let sql = generator.generate_create_index_sql(&index);
@@ -667,3 +669,394 @@ fn test_migration_history_pending() {
     assert_eq!(pending[0].version, 2);
     assert_eq!(pending[1].version, 3);
 }
+
+/// TEST-M-026: 迁移锁被持有时第二个执行器超时
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_migration_lock_times_out_when_held() {
+    let (config, _temp_dir) = common::get_sqlite_file_config();
+    let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
+
+    let mut first_session = pool.get_session("admin").await.expect("Failed to get session");
+    let first = MigrationExecutor::new(
+        first_session.connection().expect("Failed to get connection").clone(),
+        DatabaseType::Sqlite,
+    );
+    let mut second_session = pool.get_session("admin").await.expect("Failed to get session");
+    let second = MigrationExecutor::new(
+        second_session.connection().expect("Failed to get connection").clone(),
+        DatabaseType::Sqlite,
+    );
+
+    let lock = first
+        .acquire_lock(std::time::Duration::from_secs(1))
+        .await
+        .expect("First runner should acquire the lock");
+
+    let started = std::time::Instant::now();
+    let result = second.acquire_lock(std::time::Duration::from_millis(300)).await;
+    assert!(
+        matches!(result, Err(dbnexus::DbError::Migration { version: 0, ref phase, ref message }) if phase == "lock" && message.contains("migration lock")),
+        "Second runner should time out while the lock is held"
+    );
+    assert!(started.elapsed() >= std::time::Duration::from_millis(300));
+
+    lock.release().await.expect("Failed to release lock");
+
+    let lock = second
+        .acquire_lock(std::time::Duration::ZERO)
+        .await
+        .expect("Lock should be available after release");
+    lock.release().await.expect("Failed to release lock");
+}
+
+/// TEST-M-027: apply_migration 写入的 applied_at 可按 RFC3339 读回
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_apply_migration_applied_at_round_trip() {
+    let (config, _temp_dir) = common::get_sqlite_file_config();
+    let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
+    let mut session = pool.get_session("admin").await.expect("Failed to get session");
+    let connection = session.connection().expect("Failed to get connection").clone();
+
+    let mut executor = MigrationExecutor::new(connection, DatabaseType::Sqlite);
+    executor.load_history().await.expect("Failed to load history");
+
+    let applied_at =
+        time::OffsetDateTime::from_unix_timestamp_nanos(1_700_000_000_123_456_789).expect("Invalid timestamp");
+    let mut migration = Migration::new(1_700_000_000, "it's a \"quoted\" 描述".to_string());
+    migration.timestamp = Some(applied_at);
+    migration.add_table_change(TableChange::CreateTable(Table::new(
+        "round_trip_items",
+        vec![Column::from_field("id", "i64", true)],
+    )));
+
+    executor
+        .apply_migration(&migration)
+        .await
+        .expect("Failed to apply migration");
+    executor.load_history().await.expect("Failed to reload history");
+
+    let record = executor
+        .history
+        .applied_migrations
+        .iter()
+        .find(|m| m.version == 1_700_000_000)
+        .expect("Applied migration should be in history");
+    assert_eq!(record.description, "it's a \"quoted\" 描述");
+    // 存储精度为微秒
+    assert_eq!(
+        record.applied_at,
+        applied_at.replace_nanosecond(123_456_000).expect("Invalid nanosecond")
+    );
+    assert_eq!(
+        dbnexus::migration::format_applied_at(record.applied_at),
+        "2023-11-14T22:13:20.123456Z"
+    );
+}
+
+/// TEST-M-028: SQLite 重建表删除列后保留其余列的数据和索引
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_rebuild_drop_column_preserves_data() {
+    use sea_orm::{ConnectionTrait, Database, Statement};
+
+    let conn = Database::connect("sqlite::memory:")
+        .await
+        .expect("Failed to connect to SQLite");
+
+    let mut table = Table::new(
+        "users",
+        vec![
+            Column::from_field("id", "i64", true),
+            Column::from_field("email", "String", false),
+            Column::from_field("age", "i32", false),
+        ],
+    );
+    table.indexes.push(Index {
+        name: "idx_users_age".to_string(),
+        table_name: "users".to_string(),
+        columns: vec!["age".to_string()],
+        is_unique: false,
+        is_constraint: false,
+        where_clause: None,
+        method: None,
+    });
+
+    let generator = SqlGenerator::new(DatabaseType::Sqlite).with_sqlite_table_rebuild(true);
+    conn.execute_unprepared(&generator.generate_create_table_sql(&table))
+        .await
+        .expect("Failed to create table");
+    conn.execute_unprepared(
+        "INSERT INTO users (id, email, age) VALUES (1, 'a@example.com', 30), (2, 'b@example.com', 40)",
+    )
+    .await
+    .expect("Failed to insert rows");
+
+    let sql = generator.generate_rebuild_drop_columns_sql(&table, &["email".to_string()]);
+    conn.execute_unprepared(&sql).await.expect("Failed to rebuild table");
+
+    let rows = conn
+        .query_all_raw(Statement::from_string(
+            conn.get_database_backend(),
+            "SELECT id, age FROM users ORDER BY id",
+        ))
+        .await
+        .expect("Failed to query rebuilt table");
+    let values: Vec<(i64, i32)> = rows
+        .iter()
+        .map(|row| {
+            (
+                row.try_get::<i64>("", "id").expect("Failed to read id"),
+                row.try_get::<i32>("", "age").expect("Failed to read age"),
+            )
+        })
+        .collect();
+    assert_eq!(values, vec![(1, 30), (2, 40)]);
+
+    assert!(conn.execute_unprepared("SELECT email FROM users").await.is_err());
+
+    let index = conn
+        .query_one_raw(Statement::from_string(
+            conn.get_database_backend(),
+            "SELECT name FROM sqlite_master WHERE type = 'index' AND name = 'idx_users_age'",
+        ))
+        .await
+        .expect("Failed to query sqlite_master");
+    assert!(index.is_some(), "Index should be recreated after rebuild");
+}
+
+/// TEST-M-029: PostgreSQL 部分唯一索引与索引方法，MySQL / SQLite 忽略不支持的部分
+#[test]
+fn test_create_partial_index_sql() {
+    let partial_unique = Index {
+        name: "uq_users_email_active".to_string(),
+        table_name: "users".to_string(),
+        columns: vec!["email".to_string()],
+        is_unique: true,
+        is_constraint: false,
+        where_clause: Some("deleted_at IS NULL".to_string()),
+        method: Some("btree".to_string()),
+    };
+    let gin = Index {
+        name: "idx_docs_tags".to_string(),
+        table_name: "docs".to_string(),
+        columns: vec!["tags".to_string()],
+        is_unique: false,
+        is_constraint: false,
+        where_clause: None,
+        method: Some("gin".to_string()),
+    };
+
+    let pg = SqlGenerator::new(DatabaseType::Postgres);
+    assert_eq!(
+        pg.generate_create_index_sql(&partial_unique),
+        "CREATE UNIQUE INDEX uq_users_email_active ON users USING BTREE (email) WHERE deleted_at IS NULL"
+    );
+    assert_eq!(
+        pg.generate_create_index_sql(&gin),
+        "CREATE INDEX idx_docs_tags ON docs USING GIN (tags)"
+    );
+
+    // MySQL 保留 BTREE / HASH，忽略部分索引条件和其他方法
+    let mysql = SqlGenerator::new(DatabaseType::MySql);
+    assert_eq!(
+        mysql.generate_create_index_sql(&partial_unique),
+        "CREATE UNIQUE INDEX uq_users_email_active ON users USING BTREE (email)"
+    );
+    assert_eq!(
+        mysql.generate_create_index_sql(&gin),
+        "CREATE INDEX idx_docs_tags ON docs (tags)"
+    );
+
+    // SQLite 支持部分索引，忽略索引方法
+    let sqlite = SqlGenerator::new(DatabaseType::Sqlite);
+    assert_eq!(
+        sqlite.generate_create_index_sql(&partial_unique),
+        "CREATE UNIQUE INDEX uq_users_email_active ON users (email) WHERE deleted_at IS NULL"
+    );
+    assert_eq!(
+        sqlite.generate_create_index_sql(&gin),
+        "CREATE INDEX idx_docs_tags ON docs (tags)"
+    );
+}
+
+/// TEST-M-030: SQLite 执行复合主键建表语句，主键约束作用于列组合
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_composite_primary_key() {
+    use sea_orm::{ConnectionTrait, Database};
+
+    let conn = Database::connect("sqlite::memory:")
+        .await
+        .expect("Failed to connect to SQLite");
+
+    let table = Table::new(
+        "order_items",
+        vec![
+            Column::from_field("order_id", "i64", true),
+            Column::from_field("product_id", "i64", true),
+            Column::from_field("quantity", "i32", false),
+        ],
+    );
+    let sql = SqlGenerator::new(DatabaseType::Sqlite).generate_create_table_sql(&table);
+    conn.execute_unprepared(&sql).await.expect("Failed to create table");
+
+    conn.execute_unprepared("INSERT INTO order_items (order_id, product_id, quantity) VALUES (1, 1, 2), (1, 2, 3)")
+        .await
+        .expect("Failed to insert rows");
+    assert!(
+        conn.execute_unprepared("INSERT INTO order_items (order_id, product_id, quantity) VALUES (1, 2, 5)")
+            .await
+            .is_err()
+    );
+}
+
+/// TEST-M-031: 迁移计划逐步回调进度，失败时整个计划回滚并标明失败版本
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_execute_migration_plan() {
+    use sea_orm::ConnectionTrait;
+
+    let (config, _temp_dir) = common::get_sqlite_file_config();
+    let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
+    let mut session = pool.get_session("admin").await.expect("Failed to get session");
+    let connection = session.connection().expect("Failed to get connection").clone();
+
+    let mut executor = MigrationExecutor::new(connection, DatabaseType::Sqlite);
+    executor.load_history().await.expect("Failed to load history");
+
+    let mut create_items = Migration::new(1, "create_items".to_string());
+    create_items.add_table_change(TableChange::CreateTable(Table::new(
+        "plan_items",
+        vec![Column::from_field("id", "i64", true)],
+    )));
+    let mut add_tags = Migration::new(2, "create_tags".to_string());
+    add_tags.sql = Some("CREATE TABLE plan_tags (id INTEGER PRIMARY KEY);".to_string());
+
+    let plan = MigrationPlan {
+        migrations: vec![create_items, add_tags],
+        direction: MigrationDirection::Up,
+    };
+    let mut steps = Vec::new();
+    let executed = executor
+        .execute_plan(&plan, |progress| {
+            steps.push((progress.step, progress.total, progress.version))
+        })
+        .await
+        .expect("Failed to execute plan");
+    assert_eq!(executed, 2);
+    assert_eq!(steps, vec![(1, 2, 1), (2, 2, 2)]);
+    assert_eq!(executor.get_all_versions(), vec![1, 2]);
+
+    // 第二步失败：第一步的表和历史记录也不会保留
+    let mut create_notes = Migration::new(3, "create_notes".to_string());
+    create_notes.sql = Some("CREATE TABLE plan_notes (id INTEGER PRIMARY KEY);".to_string());
+    let mut broken = Migration::new(4, "broken".to_string());
+    broken.sql = Some("CREATE TABLE plan_items (id INTEGER PRIMARY KEY);".to_string());
+    let plan = MigrationPlan {
+        migrations: vec![create_notes, broken],
+        direction: MigrationDirection::Up,
+    };
+    let mut callbacks = 0;
+    let err = executor
+        .execute_plan(&plan, |_| callbacks += 1)
+        .await
+        .expect_err("Plan with a failing step should fail");
+    assert!(
+        matches!(err, dbnexus::DbError::Migration { version: 4, .. }),
+        "{:?}",
+        err
+    );
+    assert_eq!(callbacks, 1);
+
+    executor.load_history().await.expect("Failed to reload history");
+    assert_eq!(executor.get_all_versions(), vec![1, 2]);
+    assert!(
+        executor
+            .connection
+            .execute_unprepared("SELECT id FROM plan_notes")
+            .await
+            .is_err()
+    );
+
+    // 向下执行回滚 SQL 并删除历史记录
+    let mut drop_tags = Migration::new(2, "create_tags".to_string());
+    drop_tags.sql = Some("DROP TABLE plan_tags;".to_string());
+    let plan = MigrationPlan {
+        migrations: vec![drop_tags],
+        direction: MigrationDirection::Down,
+    };
+    executor
+        .execute_plan(&plan, |_| {})
+        .await
+        .expect("Failed to roll back plan");
+    assert_eq!(executor.get_all_versions(), vec![1]);
+    assert!(
+        executor
+            .connection
+            .execute_unprepared("SELECT id FROM plan_tags")
+            .await
+            .is_err()
+    );
+}
+
+/// TEST-M-032: #[derive(DbEntity)] 按字段类型和属性生成迁移表结构，Decimal 保留为定点数
+#[test]
+fn test_derive_db_entity_migration_table() {
+    /// 仅用于类型名映射
+    struct Decimal;
+
+    #[allow(dead_code)]
+    #[derive(dbnexus::DbEntity)]
+    #[db_entity(migration_only)]
+    #[table_name = "invoices"]
+    struct Invoice {
+        #[primary_key]
+        id: i64,
+        customer: String,
+        total: Decimal,
+        note: Option<String>,
+    }
+
+    let table = Invoice::migration_table();
+    assert_eq!(table.name, "invoices");
+    assert_eq!(table.primary_key_columns, vec!["id".to_string()]);
+    assert_eq!(
+        table.columns,
+        vec![
+            Column::from_field("id", "i64", true),
+            Column::from_field("customer", "String", false),
+            Column::from_field("total", "Decimal", false),
+            Column::from_field("note", "Option<String>", false),
+        ]
+    );
+    assert_eq!(table.columns[2].column_type, ColumnType::Custom("DECIMAL".to_string()));
+    assert!(table.columns[3].is_nullable);
+}
+
+/// TEST-M-033: #[derive(DbEntity)] 将带完整路径的 Option 字段生成可空列，按内部类型映射
+#[test]
+fn test_derive_db_entity_qualified_option() {
+    #[allow(dead_code)]
+    #[derive(dbnexus::DbEntity)]
+    #[db_entity(migration_only)]
+    #[table_name = "categories"]
+    struct Category {
+        #[primary_key]
+        id: i64,
+        parent_id: std::option::Option<i64>,
+        archived_at: ::core::option::Option<chrono::DateTime<chrono::Utc>>,
+    }
+
+    let table = Category::migration_table();
+    assert_eq!(
+        table.columns,
+        vec![
+            Column::typed("id", ColumnType::BigInteger, true, false),
+            Column::typed("parent_id", ColumnType::BigInteger, false, true),
+            Column::typed("archived_at", ColumnType::Timestamp, false, true),
+        ]
+    );
+    assert!(table.columns[1].is_nullable && table.columns[2].is_nullable);
+}
@@ -9,8 +9,9 @@
 //! 包括数据库特定SQL生成、连接健康检查、Schema差异等
 
 use dbnexus::DbPool;
-use dbnexus::config::DbConfig;
+use dbnexus::config::{ConfigCorrector, DbConfig};
 use dbnexus::migration::{ColumnType, DatabaseType, SqlGenerator};
+use sea_orm::{ConnectionTrait, Statement};
 mod common;
 
 /// TEST-MDB-001: SQLite连接测试
@@ -281,6 +282,9 @@ fn test_multi_database_config() {
         migrations_dir: None,
         auto_migrate: false,
         migration_timeout: 60,
+        replica_urls: Vec::new(),
+        statement_timeout_ms: None,
+        password_file: None,
     };
 
     let postgres_config = DbConfig {
@@ -293,6 +297,9 @@ fn test_multi_database_config() {
         migrations_dir: None,
         auto_migrate: false,
         migration_timeout: 60,
+        replica_urls: Vec::new(),
+        statement_timeout_ms: None,
+        password_file: None,
     };
 
     let mysql_config = DbConfig {
@@ -305,6 +312,9 @@ fn test_multi_database_config() {
         migrations_dir: None,
         auto_migrate: false,
         migration_timeout: 60,
+        replica_urls: Vec::new(),
+        statement_timeout_ms: None,
+        password_file: None,
     };
 
     // 验证配置有效
@@ -336,7 +346,8 @@ async fn test_pool_status_across_databases() {
 /// TEST-MDB-014: 数据库特定功能测试
 #[tokio::test]
 async fn test_database_specific_features() {
-    let config = common::get_test_config();
+    let mut config = common::get_test_config();
+    let _perm_dir = common::grant_admin_all_tables(&mut config);
     let pool = DbPool::with_config(config).await.expect("Failed to create pool");
     let session = pool.get_session("admin").await.expect("Failed to get session");
 
@@ -522,3 +533,166 @@ fn is_valid_database_url(url: &str) -> bool {
     let prefixes = ["sqlite:", "postgres:", "postgresql:", "mysql:"];
     prefixes.iter().any(|prefix| url.starts_with(prefix))
 }
+
+/// TEST-MDB-019: PostgreSQL max_connections 实际值查询
+#[tokio::test]
+async fn test_postgres_query_max_connections() {
+    let config = common::get_test_config();
+
+    // 仅在配置了真实 PostgreSQL 时运行
+    if detect_db_type(&config.url) != DatabaseType::Postgres {
+        return;
+    }
+
+    let connection = sea_orm::Database::connect(&config.url)
+        .await
+        .expect("Failed to connect to PostgreSQL");
+    let max_connections = ConfigCorrector::query_database_max_connections(&connection, DatabaseType::Postgres).await;
+
+    let expected: String = connection
+        .query_one_raw(Statement::from_string(
+            connection.get_database_backend(),
+            "SHOW max_connections",
+        ))
+        .await
+        .expect("SHOW max_connections failed")
+        .expect("SHOW max_connections returned no row")
+        .try_get("", "max_connections")
+        .expect("max_connections column missing");
+
+    assert_eq!(max_connections.to_string(), expected.trim());
+}
+
+/// TEST-MDB-020: MySQL max_connections 实际值查询
+#[tokio::test]
+async fn test_mysql_query_max_connections() {
+    let config = common::get_test_config();
+
+    // 仅在配置了真实 MySQL 时运行
+    if detect_db_type(&config.url) != DatabaseType::MySql {
+        return;
+    }
+
+    let connection = sea_orm::Database::connect(&config.url)
+        .await
+        .expect("Failed to connect to MySQL");
+    let max_connections = ConfigCorrector::query_database_max_connections(&connection, DatabaseType::MySql).await;
+
+    let expected: u64 = connection
+        .query_one_raw(Statement::from_string(
+            connection.get_database_backend(),
+            "SELECT @@max_connections AS value",
+        ))
+        .await
+        .expect("SELECT @@max_connections failed")
+        .expect("SELECT @@max_connections returned no row")
+        .try_get("", "value")
+        .expect("value column missing");
+
+    assert_eq!(u64::from(max_connections), expected);
+}
+
+/// TEST-MDB-021: PostgreSQL 上创建全局索引表
+#[cfg(feature = "global-index")]
+#[tokio::test]
+async fn test_postgres_global_index_schema() {
+    use dbnexus::global_index::{GlobalIndex, IndexEntry};
+
+    let config = common::get_test_config();
+
+    // 仅在配置了真实 PostgreSQL 时运行
+    if detect_db_type(&config.url) != DatabaseType::Postgres {
+        return;
+    }
+
+    let connection = sea_orm::Database::connect(&config.url)
+        .await
+        .expect("Failed to connect to PostgreSQL");
+    connection
+        .execute_unprepared("DROP TABLE IF EXISTS global_index")
+        .await
+        .expect("Failed to drop global_index");
+
+    let index = GlobalIndex::new(&config.url)
+        .await
+        .expect("Failed to create global index on PostgreSQL");
+    let entry = IndexEntry::new("orders", "order_1", 1, "user_id", "user_a");
+    index
+        .register_entry(entry.clone())
+        .await
+        .expect("Failed to register entry");
+    // 重复创建不再执行 DDL
+    let index = GlobalIndex::new(&config.url)
+        .await
+        .expect("Failed to reopen global index");
+    let found = index.query_by_index("orders", "user_id", "user_a").await.unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].record_id, entry.record_id);
+    assert!(found[0].updated_at.is_some());
+
+    connection
+        .execute_unprepared("DROP TABLE global_index")
+        .await
+        .expect("Failed to drop global_index");
+}
+
+/// TEST-MDB-022: PostgreSQL pg_notify 发出的变更通知转换为同步事件
+#[cfg(all(feature = "global-index", feature = "postgres"))]
+#[tokio::test]
+async fn test_postgres_notify_change_capture() {
+    use dbnexus::global_index::{ChangeCapture, PostgresNotifyChangeCapture, SyncEvent};
+    use std::time::Duration;
+
+    let config = common::get_test_config();
+
+    // 仅在配置了真实 PostgreSQL 时运行
+    if detect_db_type(&config.url) != DatabaseType::Postgres {
+        return;
+    }
+
+    let mut capture = PostgresNotifyChangeCapture::new(&config.url)
+        .with_channel("dbnexus_test_changes")
+        .with_wait_timeout(Duration::from_secs(5));
+    capture
+        .start()
+        .await
+        .expect("Failed to subscribe to notification channel");
+    assert!(capture.is_running());
+
+    let connection = sea_orm::Database::connect(&config.url)
+        .await
+        .expect("Failed to connect to PostgreSQL");
+    connection
+        .execute_unprepared(
+            r#"SELECT pg_notify('dbnexus_test_changes', 'not json'),
+                      pg_notify('dbnexus_test_changes', '{"table":"orders","record_id":"order_7","shard_id":2,"op":"update","index_key":"user_id","index_value":"user_b","old_index_value":"user_a"}')"#,
+        )
+        .await
+        .expect("Failed to send notification");
+
+    // 无效负载被跳过，返回随后的有效事件
+    match capture.next_event().await {
+        Some(SyncEvent::Update {
+            table_name,
+            record_id,
+            shard_id,
+            old_index_key,
+            old_index_value,
+            new_index_key,
+            new_index_value,
+        }) => {
+            assert_eq!(table_name, "orders");
+            assert_eq!(record_id, "order_7");
+            assert_eq!(shard_id, 2);
+            assert_eq!(old_index_key, "user_id");
+            assert_eq!(old_index_value, "user_a");
+            assert_eq!(new_index_key, "user_id");
+            assert_eq!(new_index_value, "user_b");
+        }
+        other => panic!("Expected update event, got {:?}", other),
+    }
+
+    capture.stop().await.expect("Failed to stop change capture");
+    assert!(!capture.is_running());
+    assert!(capture.next_event().await.is_none());
+}
@@ -9,6 +9,27 @@ use dbnexus::DbPool;
 use dbnexus::permission::{PermissionAction as Operation, PermissionConfig, RolePolicy, TablePermission};
 mod common;
 
+#[cfg(feature = "sqlite")]
+mod user {
+    use sea_orm::entity::prelude::*;
+
+    #[dbnexus::db_crud]
+    #[dbnexus::db_permission(deny_select = ["password_hash"])]
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+    #[sea_orm(table_name = "users")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub id: i32,
+        pub email: String,
+        pub password_hash: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
 #[tokio::test]
 async fn test_permission_context_role() {
     let config = common::get_test_config();
@@ -44,6 +65,7 @@ fn test_role_policy_allows() {
             name: "users".to_string(),
             operations: vec![Operation::Select, Operation::Insert],
         }],
+        ..Default::default()
     };
 
     assert!(policy.allows("users", &Operation::Select));
@@ -67,6 +89,7 @@ fn test_permission_config_check_access() {
                         Operation::Delete,
                     ],
                 }],
+                ..Default::default()
             },
         )]
         .into_iter()
@@ -77,3 +100,207 @@ fn test_permission_config_check_access() {
     assert!(config.check_access("admin", "orders", Operation::Delete));
     assert!(!config.check_access("user", "users", Operation::Delete)); // user role not defined
 }
+
+#[tokio::test]
+async fn test_session_permission_denied_vs_not_loaded() {
+    let (permissions_path, _dir) = common::create_permissions_file(
+        r#"
+roles:
+  reader:
+    tables:
+      - name: users
+        operations:
+          - select
+"#,
+    );
+
+    let mut config = common::get_test_config();
+    config.permissions_path = Some(permissions_path);
+    let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
+
+    let reader = pool.get_session("reader").await.expect("Failed to get session");
+    assert!(reader.check_permission("users", &Operation::Select).is_ok());
+    let denied = reader.check_permission("users", &Operation::Delete).unwrap_err();
+    assert!(denied.to_string().contains("does not have DELETE permission"));
+
+    let guest = pool.get_session("guest").await.expect("Failed to get session");
+    let not_loaded = guest.check_permission("users", &Operation::Select).unwrap_err();
+    assert!(not_loaded.to_string().contains("is not loaded"));
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_column_policy_hides_protected_column() {
+    use dbnexus::permission::ColumnPolicy;
+
+    let (permissions_path, _perm_dir) = common::create_permissions_file(
+        r#"
+roles:
+  admin:
+    tables:
+      - name: "*"
+        operations: [select, insert, update, delete]
+  user:
+    tables:
+      - name: "*"
+        operations: [select]
+"#,
+    );
+    let (mut config, _db_dir) = common::get_sqlite_file_config();
+    config.permissions_path = Some(permissions_path);
+    let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
+
+    let admin = pool.get_session("admin").await.expect("Failed to get session");
+    admin
+        .execute_raw("CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT NOT NULL, password_hash TEXT NOT NULL)")
+        .await
+        .expect("Failed to create users table");
+    admin
+        .execute_raw("INSERT INTO users (id, email, password_hash) VALUES (1, 'a@example.com', 'argon2-hash')")
+        .await
+        .expect("Failed to insert user");
+    drop(admin);
+
+    let policy = ColumnPolicy::new("users", &["password_hash"]);
+    let all_columns = ["id", "email", "password_hash"];
+    let user = pool.get_session("user").await.expect("Failed to get session");
+
+    // 默认投影省略受保护列
+    let projection = policy.visible_columns(user.role(), &all_columns);
+    let row = user
+        .query_one(&format!("SELECT {} FROM users WHERE id = 1", projection.join(", ")))
+        .await
+        .expect("Query should succeed")
+        .expect("User row should exist");
+    assert_eq!(row.try_get::<String>("", "email").unwrap(), "a@example.com");
+    assert!(row.try_get::<String>("", "password_hash").is_err());
+
+    // 显式查询受保护列被拒绝
+    let denied = user.check_column_access(&policy, &["id", "password_hash"]).unwrap_err();
+    assert!(denied.to_string().contains("password_hash"));
+
+    let admin = pool.get_session("admin").await.expect("Failed to get session");
+    assert!(admin.check_column_access(&policy, &all_columns).is_ok());
+}
+
+#[tokio::test]
+async fn test_permission_error_carries_structured_context() {
+    use dbnexus::DbError;
+
+    let (permissions_path, _dir) = common::create_permissions_file(
+        r#"
+roles:
+  reader:
+    tables:
+      - name: orders
+        operations:
+          - select
+"#,
+    );
+
+    let mut config = common::get_test_config();
+    config.permissions_path = Some(permissions_path);
+    let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
+    let reader = pool.get_session("reader").await.expect("Failed to get session");
+
+    let err = reader
+        .execute_raw("DELETE FROM orders WHERE id = 1")
+        .await
+        .expect_err("reader must not delete orders");
+    match err {
+        DbError::Permission { role, table, operation } => {
+            assert_eq!(role, "reader");
+            assert_eq!(table, "orders");
+            assert_eq!(operation, Operation::Delete);
+        }
+        other => panic!("Expected structured permission error, got {:?}", other),
+    }
+
+    // DDL 等无法归结到单表操作的拒绝使用 Forbidden
+    let err = reader
+        .execute_raw("DROP TABLE orders")
+        .await
+        .expect_err("reader must not run DDL");
+    assert!(matches!(err, DbError::Forbidden { ref role, .. } if role == "reader"));
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_db_permission_entity_omits_protected_column() {
+    use sea_orm::Set;
+
+    let (permissions_path, _perm_dir) = common::create_permissions_file(
+        r#"
+roles:
+  admin:
+    tables:
+      - name: "*"
+        operations: [select, insert, update, delete]
+  user:
+    tables:
+      - name: "*"
+        operations: [select]
+"#,
+    );
+    let (mut config, _db_dir) = common::get_sqlite_file_config();
+    config.permissions_path = Some(permissions_path);
+    let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
+
+    let mut admin = pool.get_session("admin").await.expect("Failed to get session");
+    admin
+        .execute_raw("CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT NOT NULL, password_hash TEXT NOT NULL)")
+        .await
+        .expect("Failed to create users table");
+    for id in 1..=3 {
+        user::Entity::create(
+            &mut admin,
+            user::ActiveModel {
+                id: Set(id),
+                email: Set(format!("u{id}@example.com")),
+                password_hash: Set(format!("hash-{id}")),
+            },
+        )
+        .await
+        .expect("Failed to insert user");
+    }
+
+    let reader = pool.get_session("user").await.expect("Failed to get session");
+
+    // 受限角色的默认查询省略受保护列
+    let visible = user::Entity::fetch_visible(&reader, 1)
+        .await
+        .expect("Query should succeed")
+        .expect("User row should exist");
+    assert_eq!(visible, serde_json::json!({ "id": 1, "email": "u1@example.com" }));
+
+    let (page, total) = user::Entity::list_visible(&reader, 0, 2)
+        .await
+        .expect("Query should succeed");
+    assert_eq!(total, 3);
+    assert_eq!(page.len(), 2);
+    assert!(
+        page.iter()
+            .all(|row| row.get("password_hash").is_none() && row.get("email").is_some())
+    );
+
+    // 完整行或显式请求受保护列时报错
+    let err = user::Entity::fetch(&reader, 1).await.unwrap_err();
+    assert!(err.to_string().contains("password_hash"), "{err}");
+    let err = user::Entity::fetch_columns(&reader, 1, &[user::Column::Id, user::Column::PasswordHash])
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("password_hash"), "{err}");
+    let email = user::Entity::fetch_columns(&reader, 2, &[user::Column::Email])
+        .await
+        .unwrap();
+    assert_eq!(email, Some(serde_json::json!({ "email": "u2@example.com" })));
+
+    // 豁免角色不受限制
+    let full = user::Entity::fetch(&admin, 1)
+        .await
+        .unwrap()
+        .expect("User row should exist");
+    assert_eq!(full.password_hash, "hash-1");
+    let visible = user::Entity::fetch_visible(&admin, 1).await.unwrap().unwrap();
+    assert_eq!(visible["password_hash"], "hash-1");
+}
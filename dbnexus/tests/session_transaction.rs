@@ -84,3 +84,211 @@ async fn test_transaction_commit_without_begin_error() {
     let result = session.commit().await;
     assert!(result.is_err());
 }
+
+#[tokio::test]
+async fn test_session_query_wrappers() {
+    let (permissions_path, _dir) = common::create_permissions_file(
+        r#"
+roles:
+  admin:
+    tables:
+      - name: "*"
+        operations: [select, insert, update, delete]
+"#,
+    );
+    let mut config = common::get_test_config();
+    config.permissions_path = Some(permissions_path);
+    let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
+    let mut session = pool.get_session("admin").await.expect("Failed to get session");
+
+    let table = common::generate_test_table_name("query_wrapper");
+    common::create_test_table(&mut session, &table).await;
+    session
+        .execute_raw(&format!("INSERT INTO {} (id, data) VALUES (1, 'a'), (2, 'b')", table))
+        .await
+        .expect("Failed to insert rows");
+
+    let rows = session
+        .query_all(&format!("SELECT id, data FROM {} ORDER BY id", table))
+        .await
+        .expect("Failed to query rows");
+    assert_eq!(rows.len(), 2);
+
+    let row = session
+        .query_one(&format!("SELECT data FROM {} WHERE id = 2", table))
+        .await
+        .expect("Failed to query row")
+        .expect("Row should exist");
+    let data: String = row.try_get("", "data").expect("Failed to read column");
+    assert_eq!(data, "b");
+
+    common::cleanup_test_table(&mut session, &table).await;
+}
+
+#[tokio::test]
+async fn test_raw_statements_run_inside_session_transaction() {
+    let (permissions_path, _dir) = common::create_permissions_file(
+        r#"
+roles:
+  admin:
+    tables:
+      - name: "*"
+        operations: [select, insert, update, delete]
+"#,
+    );
+    let mut config = common::get_test_config();
+    config.permissions_path = Some(permissions_path);
+    let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
+    let mut session = pool.get_session("admin").await.expect("Failed to get session");
+
+    let table = common::generate_test_table_name("raw_txn");
+    common::create_test_table(&mut session, &table).await;
+
+    session.begin_transaction().await.expect("Failed to begin transaction");
+    session
+        .execute_raw(&format!("INSERT INTO {} (id, data) VALUES (1, 'a')", table))
+        .await
+        .expect("Failed to insert row");
+    session
+        .execute(&format!("INSERT INTO {} (id, data) VALUES (2, 'b')", table))
+        .await
+        .expect("Failed to insert row");
+    let row = session
+        .query_one(&format!("SELECT data FROM {} WHERE id = 2", table))
+        .await
+        .expect("Failed to query row");
+    assert!(
+        row.is_some(),
+        "Uncommitted row should be visible inside the transaction"
+    );
+    session.rollback().await.expect("Failed to rollback transaction");
+
+    let rows = session
+        .query_all(&format!("SELECT id FROM {}", table))
+        .await
+        .expect("Failed to query rows");
+    assert!(rows.is_empty(), "Rolled back rows should not persist");
+
+    common::cleanup_test_table(&mut session, &table).await;
+}
+
+#[cfg(feature = "metrics")]
+#[tokio::test]
+async fn test_transaction_guard_records_metrics() {
+    use dbnexus::metrics::MetricsCollector;
+    use std::sync::Arc;
+
+    let config = common::get_test_config();
+    let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
+    let mut session = pool.get_session("admin").await.expect("Failed to get session");
+    let metrics = Arc::new(MetricsCollector::new());
+    session.set_metrics(metrics.clone());
+
+    // 显式提交
+    let txn = session.begin().await.expect("Failed to begin transaction");
+    txn.commit().await.expect("Failed to commit transaction");
+
+    // 显式回滚
+    let txn = session.begin().await.expect("Failed to begin transaction");
+    txn.rollback().await.expect("Failed to rollback transaction");
+
+    // 未提交直接 drop
+    let txn = session.begin().await.expect("Failed to begin transaction");
+    drop(txn);
+
+    let stats = metrics.transaction_stats();
+    assert_eq!(stats.commit_count, 1);
+    assert_eq!(stats.rollback_count, 2);
+    assert_eq!(stats.failure_count, 0);
+}
+
+#[tokio::test]
+async fn test_nested_transaction_savepoint_rollback() {
+    let (permissions_path, _dir) = common::create_permissions_file(
+        r#"
+roles:
+  admin:
+    tables:
+      - name: "*"
+        operations: [select, insert, update, delete]
+"#,
+    );
+    let mut config = common::get_test_config();
+    config.permissions_path = Some(permissions_path);
+    let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
+    let mut session = pool.get_session("admin").await.expect("Failed to get session");
+    #[cfg(feature = "metrics")]
+    let metrics = {
+        let metrics = std::sync::Arc::new(dbnexus::metrics::MetricsCollector::new());
+        session.set_metrics(metrics.clone());
+        metrics
+    };
+
+    let table = common::generate_test_table_name("savepoint");
+    common::create_test_table(&mut session, &table).await;
+
+    {
+        let outer = session.begin().await.expect("Failed to begin transaction");
+        assert_eq!(outer.depth(), 1);
+        assert!(!outer.is_savepoint());
+        outer
+            .execute(&format!("INSERT INTO {} (id, data) VALUES (1, 'outer')", table))
+            .await
+            .expect("Failed to insert outer row");
+
+        let inner = outer.begin().await.expect("Failed to create savepoint");
+        assert_eq!(inner.depth(), 2);
+        assert!(inner.is_savepoint());
+        inner
+            .execute(&format!("INSERT INTO {} (id, data) VALUES (2, 'inner')", table))
+            .await
+            .expect("Failed to insert inner row");
+        inner.rollback().await.expect("Failed to rollback savepoint");
+
+        // savepoint 回滚后外层事务仍可继续使用
+        outer
+            .execute(&format!("INSERT INTO {} (id, data) VALUES (3, 'outer')", table))
+            .await
+            .expect("Failed to insert outer row after savepoint rollback");
+        outer.commit().await.expect("Failed to commit transaction");
+    }
+    assert_eq!(session.transaction_depth(), 0);
+
+    let rows = session
+        .query_all(&format!("SELECT id FROM {} ORDER BY id", table))
+        .await
+        .expect("Failed to query rows");
+    let ids: Vec<i64> = rows
+        .iter()
+        .map(|row| row.try_get("", "id").expect("Failed to read id"))
+        .collect();
+    assert_eq!(ids, vec![1, 3]);
+
+    #[cfg(feature = "metrics")]
+    {
+        let stats = metrics.transaction_stats();
+        assert_eq!(stats.commit_count, 1);
+        assert_eq!(stats.rollback_count, 0);
+    }
+
+    common::cleanup_test_table(&mut session, &table).await;
+}
+
+#[tokio::test]
+async fn test_begin_inside_session_transaction_opens_savepoint() {
+    let config = common::get_test_config();
+    let pool = DbPool::with_config(config).await.expect("Failed to create test pool");
+    let mut session = pool.get_session("admin").await.expect("Failed to get session");
+    session.begin_transaction().await.expect("Failed to begin transaction");
+    assert_eq!(session.transaction_depth(), 1);
+
+    {
+        let savepoint = session.begin().await.expect("Failed to create savepoint");
+        assert!(savepoint.is_savepoint());
+        savepoint.commit().await.expect("Failed to release savepoint");
+    }
+
+    assert_eq!(session.transaction_depth(), 1);
+    session.commit().await.expect("Failed to commit transaction");
+    assert_eq!(session.transaction_depth(), 0);
+}
@@ -282,7 +282,8 @@ fn test_strategy_factory_case_insensitive() {
 /// TEST-SHARD-013: ShardConfig 连接字符串模板测试
 #[test]
 fn test_shard_config_template_parsing() {
-    let config = ShardConfig::new("yearly", 12, "orders", "postgresql://localhost/{shard}/{prefix}_{id}");
+    let config = ShardConfig::new("yearly", 12, "orders", "postgresql://localhost/{shard}/{prefix}_{id}")
+        .expect("Failed to create shard config");
 
     let shard_0 = config.generate_connection_string(0);
     let shard_5 = config.generate_connection_string(5);
@@ -298,9 +299,10 @@ fn test_shard_config_template_parsing() {
 /// TEST-SHARD-014: 路由器配置集成测试
 #[test]
 fn test_router_with_config_integration() {
-    let config = ShardConfig::new("monthly", 6, "products", "postgresql://localhost/{shard}/products.db");
+    let config = ShardConfig::new("monthly", 6, "products", "postgresql://localhost/{shard}/products.db")
+        .expect("Failed to create shard config");
 
-    let router = ShardRouter::with_config(&config);
+    let router = ShardRouter::with_config(&config).expect("Failed to create router");
 
     let total = router.total_shards();
     let strategy = router.strategy_name();
@@ -335,3 +337,390 @@ fn test_sharding_strategy_boundaries() {
     let shard_365 = daily.calculate(dt, 365);
     assert!(shard_365 < 365, "Daily shard should be less than 365");
 }
+
+/// TEST-SHARD-016: 跨分片查询合并测试
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_query_all_shards_merged() {
+    use sea_orm::{ConnectionTrait, Database};
+
+    let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+    let mut router = ShardRouter::with_strategy("yearly", 2).with_max_concurrency(1);
+
+    for shard_id in 0..2u32 {
+        let url = format!(
+            "sqlite:{}?mode=rwc",
+            temp_dir.path().join(format!("users_{}.db", shard_id)).display()
+        );
+        let conn = Database::connect(&url).await.expect("Failed to connect shard");
+        conn.execute_unprepared("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+            .await
+            .expect("Failed to create table");
+        conn.execute_unprepared(&format!(
+            "INSERT INTO users (id, name) VALUES ({}, 'user_{}')",
+            shard_id + 1,
+            shard_id
+        ))
+        .await
+        .expect("Failed to insert row");
+        conn.close().await.expect("Failed to close shard connection");
+
+        router.register_shard(shard_id, format!("users_{}", shard_id), url);
+    }
+
+    let names = router
+        .query_all_shards_merged("SELECT name FROM users WHERE id > ?", vec![0i32.into()], |row| {
+            Ok(row.try_get::<String>("", "name")?)
+        })
+        .await
+        .expect("Merged query should succeed");
+    assert_eq!(names, vec!["user_0".to_string(), "user_1".to_string()]);
+
+    // 注册一个不存在表的分片，错误中应包含该分片名
+    let broken_url = format!("sqlite:{}?mode=rwc", temp_dir.path().join("broken.db").display());
+    router.register_shard(2, "users_broken".to_string(), broken_url);
+    let err = router
+        .query_all_shards_merged("SELECT name FROM users", Vec::new(), |row| {
+            Ok(row.try_get::<String>("", "name")?)
+        })
+        .await
+        .expect_err("Query on broken shard should fail");
+    assert!(err.to_string().contains("users_broken"), "unexpected error: {}", err);
+}
+
+/// TEST-SHARD-017: ShardPool 按需为分片创建连接池并路由 Session
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_shard_pool_routes_sessions_to_lazy_pools() {
+    use dbnexus::DbConfig;
+    use dbnexus::permission::PermissionConfig;
+    use dbnexus::sharding::ShardPool;
+
+    let mut router = ShardRouter::with_strategy("hash", 2);
+    for shard_id in 0..2u32 {
+        router.register_shard(shard_id, format!("users_{}", shard_id), "sqlite::memory:".to_string());
+    }
+
+    // 找到落在不同分片上的两个关键字
+    let timestamp = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+    let keys: Vec<String> = {
+        let first = "user-0".to_string();
+        let first_shard = router.calculate_shard(timestamp, &first);
+        let second = (1..)
+            .map(|i| format!("user-{}", i))
+            .find(|key| router.calculate_shard(timestamp, key) != first_shard)
+            .expect("Two shards should both receive keys");
+        vec![first, second]
+    };
+
+    let permissions = PermissionConfig::from_yaml(
+        r#"
+roles:
+  admin:
+    tables:
+      - name: "*"
+        operations: [select, insert, update, delete]
+"#,
+    )
+    .expect("Failed to parse permissions");
+    // 内存数据库每个连接都是独立的库，分片连接池固定为单连接
+    let shards = ShardPool::new(router).with_builder(move |_shard, builder| {
+        builder
+            .config(DbConfig {
+                max_connections: 1,
+                min_connections: 1,
+                ..DbConfig::default()
+            })
+            .permission_config(permissions.clone())
+    });
+    assert!(shards.initialized_shards().is_empty(), "Pools should be created lazily");
+
+    for key in &keys {
+        let session = shards
+            .get_session_for(timestamp, key, "admin")
+            .await
+            .expect("Failed to get routed session");
+        session
+            .execute_raw("CREATE TABLE users (name TEXT NOT NULL)")
+            .await
+            .expect("Failed to create table");
+        session
+            .execute_raw(&format!("INSERT INTO users (name) VALUES ('{}')", key))
+            .await
+            .expect("Failed to insert row");
+    }
+    assert_eq!(shards.initialized_shards(), vec![0, 1]);
+
+    // 每个分片只包含路由到它的那一行
+    for key in &keys {
+        let shard_id = shards.router().calculate_shard(timestamp, key);
+        let session = shards
+            .get_session_for_shard_id(shard_id, "admin")
+            .await
+            .expect("Failed to get shard session");
+        let rows = session
+            .query_all("SELECT name FROM users")
+            .await
+            .expect("Failed to query shard");
+        let names: Vec<String> = rows
+            .iter()
+            .map(|row| row.try_get("", "name").expect("Failed to read name"))
+            .collect();
+        assert_eq!(names, vec![key.clone()]);
+    }
+
+    assert!(shards.get_session_for_shard_id(7, "admin").await.is_err());
+}
+
+/// TEST-SHARD-018: 共享指标收集器按分片标签导出
+#[cfg(all(feature = "sqlite", feature = "metrics"))]
+#[tokio::test]
+async fn test_shard_pool_shares_metrics_with_shard_label() {
+    use dbnexus::metrics::MetricsCollector;
+    use dbnexus::sharding::ShardPool;
+    use std::sync::Arc;
+
+    let mut router = ShardRouter::with_strategy("hash", 2);
+    for shard_id in 0..2u32 {
+        router.register_shard(shard_id, format!("orders_{}", shard_id), "sqlite::memory:".to_string());
+    }
+
+    let metrics = Arc::new(MetricsCollector::new());
+    let shards = ShardPool::new(router).with_metrics(metrics.clone());
+    for shard_id in 0..2u32 {
+        let _session = shards
+            .get_session_for_shard_id(shard_id, "admin")
+            .await
+            .expect("Failed to get shard session");
+    }
+
+    let prometheus = metrics.export_prometheus();
+    assert!(prometheus.contains("dbnexus_pool_connections_total{shard=\"orders_0\"}"));
+    assert!(prometheus.contains("dbnexus_pool_connections_total{shard=\"orders_1\"}"));
+}
+
+/// TEST-SHARD-023: 分片连接池创建失败时不登记该分片的子收集器，重试成功后只登记一次
+#[cfg(all(feature = "sqlite", feature = "metrics"))]
+#[tokio::test]
+async fn test_failed_shard_init_leaves_no_metrics() {
+    use dbnexus::metrics::MetricsCollector;
+    use dbnexus::sharding::ShardPool;
+    use std::sync::Arc;
+
+    let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+    let database = temp_dir.path().join("missing").join("orders_0.db");
+    let mut router = ShardRouter::with_strategy("hash", 1);
+    router.register_shard(
+        0,
+        "orders_0".to_string(),
+        format!("sqlite://{}?mode=rwc", database.display()),
+    );
+
+    let metrics = Arc::new(MetricsCollector::new());
+    let shards = ShardPool::new(router).with_metrics(metrics.clone());
+    for _ in 0..2 {
+        assert!(shards.get_session_for_shard_id(0, "admin").await.is_err());
+    }
+    assert!(!metrics.export_prometheus().contains("shard=\"orders_0\""));
+
+    // 目录创建后重试成功，分片样本只出现一次
+    std::fs::create_dir_all(database.parent().unwrap()).expect("Failed to create shard dir");
+    let _session = shards
+        .get_session_for_shard_id(0, "admin")
+        .await
+        .expect("Failed to get shard session");
+    let prometheus = metrics.export_prometheus();
+    assert_eq!(
+        prometheus
+            .matches("dbnexus_pool_connections_total{shard=\"orders_0\"}")
+            .count(),
+        1
+    );
+}
+
+/// TEST-SHARD-019: 跨分片 COUNT / SUM 聚合与部分失败模式
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_aggregate_all_shards() {
+    use dbnexus::sharding::ShardFailureMode;
+    use sea_orm::{ConnectionTrait, Database};
+
+    let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+    let mut router = ShardRouter::with_strategy("hash", 3);
+
+    // 分片 0 有 3 行，分片 1 有 2 行，分片 2 为空表
+    let shard_amounts: [&[i64]; 3] = [&[10, 20, 30], &[5, 7], &[]];
+    for (shard_id, amounts) in shard_amounts.iter().enumerate() {
+        let url = format!(
+            "sqlite:{}?mode=rwc",
+            temp_dir.path().join(format!("orders_{}.db", shard_id)).display()
+        );
+        let conn = Database::connect(&url).await.expect("Failed to connect shard");
+        conn.execute_unprepared("CREATE TABLE orders (id INTEGER PRIMARY KEY, amount INTEGER NOT NULL)")
+            .await
+            .expect("Failed to create table");
+        for amount in amounts.iter() {
+            conn.execute_unprepared(&format!("INSERT INTO orders (amount) VALUES ({})", amount))
+                .await
+                .expect("Failed to insert row");
+        }
+        conn.close().await.expect("Failed to close shard connection");
+
+        router.register_shard(shard_id as u32, format!("orders_{}", shard_id), url);
+    }
+
+    assert_eq!(router.count_all_shards("orders", None).await.expect("Count failed"), 5);
+    assert_eq!(
+        router
+            .count_all_shards("orders", Some("amount > 6"))
+            .await
+            .expect("Filtered count failed"),
+        4
+    );
+    assert_eq!(
+        router
+            .sum_all_shards_i64("orders", "amount", None)
+            .await
+            .expect("Integer sum failed"),
+        72
+    );
+    let sum = router
+        .sum_all_shards("orders", "amount", Some("amount < 25"))
+        .await
+        .expect("Float sum failed");
+    assert!((sum - 42.0).abs() < f64::EPSILON, "unexpected sum: {}", sum);
+
+    // 非法标识符在访问分片前即被拒绝
+    assert!(
+        router
+            .count_all_shards("orders; DROP TABLE orders", None)
+            .await
+            .is_err()
+    );
+    assert!(router.sum_all_shards("orders", "amount)", None).await.is_err());
+
+    // 注册一个缺少表的分片：默认模式报错，部分失败模式跳过该分片
+    let broken_url = format!("sqlite:{}?mode=rwc", temp_dir.path().join("broken.db").display());
+    router.register_shard(3, "orders_broken".to_string(), broken_url);
+    let err = router
+        .count_all_shards("orders", None)
+        .await
+        .expect_err("Count on broken shard should fail");
+    assert!(err.to_string().contains("orders_broken"), "unexpected error: {}", err);
+
+    let router = router.with_failure_mode(ShardFailureMode::Partial);
+    assert_eq!(
+        router
+            .count_all_shards("orders", None)
+            .await
+            .expect("Partial count should skip broken shard"),
+        5
+    );
+    assert_eq!(
+        router
+            .sum_all_shards_i64("orders", "amount", None)
+            .await
+            .expect("Partial sum should skip broken shard"),
+        72
+    );
+}
+
+/// TEST-SHARD-020: 按名称创建策略与名称往返
+#[test]
+fn test_strategy_from_name_round_trip() {
+    use dbnexus::sharding::{HashStrategy, STRATEGY_NAMES};
+
+    for name in STRATEGY_NAMES {
+        let strategy = <dyn ShardingStrategy>::from_name(name).expect("Known strategy name should resolve");
+        assert_eq!(strategy.name(), *name);
+
+        let again = <dyn ShardingStrategy>::from_name(strategy.name()).expect("Strategy name should round-trip");
+        assert_eq!(again.name(), strategy.name());
+    }
+
+    // 别名与大小写
+    assert_eq!(
+        <dyn ShardingStrategy>::from_name("Month").map(|s| s.name()),
+        Some("monthly")
+    );
+    assert_eq!(
+        <dyn ShardingStrategy>::from_name("consistent").map(|s| s.name()),
+        Some("consistent_hash")
+    );
+    assert_eq!(
+        <dyn ShardingStrategy>::from_name(HashStrategy.name()).map(|s| s.name()),
+        Some("hash")
+    );
+
+    // 未知名称不回退为默认策略
+    assert!(<dyn ShardingStrategy>::from_name("montly").is_none());
+    assert!(<dyn ShardingStrategy>::from_name("").is_none());
+    assert!(<dyn ShardingStrategy>::from_name("range").is_none());
+}
+
+/// TEST-SHARD-021: ShardConfig 创建、反序列化与路由器构建校验策略名称
+#[test]
+fn test_shard_config_rejects_unknown_strategy() {
+    let err = ShardConfig::new("montly", 12, "orders", "sqlite:./data/{shard}.db")
+        .expect_err("Unknown strategy should be rejected");
+    assert!(err.to_string().contains("montly"), "unexpected error: {}", err);
+
+    let config: ShardConfig = serde_json::from_str(
+        r#"{"strategy": "Monthly", "total_shards": 6, "prefix": "orders", "connection_template": "sqlite:./{shard}.db"}"#,
+    )
+    .expect("Failed to deserialize shard config");
+    assert_eq!(config.total_shards, 6);
+    let router = ShardRouter::with_config(&config).expect("Failed to create router");
+    assert_eq!(router.strategy_name(), "monthly");
+
+    // 缺省字段使用默认值
+    let config: ShardConfig = serde_yaml::from_str("strategy: hash\n").expect("Failed to deserialize shard config");
+    assert_eq!(config.total_shards, ShardConfig::default().total_shards);
+
+    let err = serde_yaml::from_str::<ShardConfig>("strategy: montly\ntotal_shards: 12\n")
+        .expect_err("Unknown strategy should fail to deserialize");
+    assert!(err.to_string().contains("montly"), "unexpected error: {}", err);
+
+    // 直接修改字段绕过校验时由 with_config 报错
+    let config = ShardConfig {
+        strategy: "montly".to_string(),
+        ..ShardConfig::default()
+    };
+    assert!(ShardRouter::with_config(&config).is_err());
+}
+
+/// TEST-SHARD-022: 重新分片迁移计划
+#[test]
+fn test_reshard_plan_moves_minimal_keys() {
+    let timestamp = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+    let keys: Vec<_> = (0..2000).map(|i| (timestamp, format!("user_{}", i))).collect();
+
+    // 一致性哈希：4 -> 5 只有约 1/5 的键迁移，且都迁移到新增分片
+    let router = ShardRouter::with_strategy("consistent_hash", 4);
+    let plan = router.reshard_plan(4, 5, &keys).expect("Failed to plan resharding");
+    let moved = plan.len() as f64 / keys.len() as f64;
+    assert!(moved < 0.3, "moved fraction {:.3} should be close to 1/5", moved);
+    for entry in &plan {
+        assert_eq!(
+            entry.new_shard, 4,
+            "key '{}' should only move to the new shard",
+            entry.key
+        );
+        assert_eq!(entry.old_shard, router.calculate_shard(timestamp, &entry.key));
+    }
+
+    // 取模哈希：大部分键都需要迁移
+    let router = ShardRouter::with_strategy("hash", 4);
+    let plan = router.reshard_plan(4, 5, &keys).expect("Failed to plan resharding");
+    assert!(plan.len() > keys.len() / 2, "modulo hashing should move most keys");
+    assert!(plan.iter().all(|entry| entry.old_shard != entry.new_shard));
+
+    // 分片数不变时无需迁移
+    assert!(
+        router
+            .reshard_plan(4, 4, &keys)
+            .expect("Failed to plan resharding")
+            .is_empty()
+    );
+    assert!(router.reshard_plan(0, 4, &keys).is_err());
+}
@@ -0,0 +1,62 @@
+// Copyright (c) 2025 Kirky.X
+//
+// Licensed under the MIT License
+// See LICENSE file in the project root for full license information.
+
+//! 测试辅助集成测试
+//!
+//! 测试单连接内存连接池在多个 Session 之间共享同一个内存数据库
+
+#![cfg(all(feature = "test-util", feature = "sqlite"))]
+
+use dbnexus::DbPool;
+use dbnexus::test_util::{seed, seed_table};
+
+/// TEST-TU-001: 一个 Session 建表写入，另一个 Session 读取
+#[tokio::test]
+async fn test_in_memory_pool_shares_database_across_sessions() {
+    let pool = DbPool::in_memory().await.expect("Failed to create in-memory pool");
+
+    {
+        let session = pool.get_session("admin").await.expect("Failed to get session");
+        seed_table(
+            &session,
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)",
+            &["INSERT INTO users (id, name) VALUES (1, 'alice')"],
+        )
+        .await
+        .expect("Failed to seed table");
+    }
+
+    let session = pool.get_session("admin").await.expect("Failed to get session");
+    seed(&session, &["INSERT INTO users (id, name) VALUES (2, 'bob')"])
+        .await
+        .expect("Failed to seed rows");
+    let rows = session
+        .query_all("SELECT name FROM users ORDER BY id")
+        .await
+        .expect("Table created by the first session should be visible");
+    let names: Vec<String> = rows
+        .iter()
+        .map(|row| row.try_get("", "name").expect("Failed to read name"))
+        .collect();
+    assert_eq!(names, vec!["alice", "bob"]);
+
+    let status = pool.status();
+    assert_eq!(status.total, 1, "In-memory pool must hold exactly one connection");
+}
+
+/// TEST-TU-002: 内存连接池之间互相隔离
+#[tokio::test]
+async fn test_in_memory_pools_are_isolated() {
+    let first = DbPool::in_memory().await.expect("Failed to create in-memory pool");
+    let second = DbPool::in_memory().await.expect("Failed to create in-memory pool");
+
+    let session = first.get_session("admin").await.expect("Failed to get session");
+    seed_table(&session, "CREATE TABLE items (id INTEGER PRIMARY KEY)", &[])
+        .await
+        .expect("Failed to seed table");
+
+    let other = second.get_session("admin").await.expect("Failed to get session");
+    assert!(other.query_all("SELECT id FROM items").await.is_err());
+}
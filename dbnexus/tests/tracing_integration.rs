@@ -0,0 +1,148 @@
+// Copyright (c) 2025 Kirky.X
+//
+// Licensed under the MIT License
+// See LICENSE file in the project root for full license information.
+
+//! 追踪集成测试
+//!
+//! 使用内存导出器验证连接获取与查询的 span
+
+#![cfg(all(feature = "tracing", feature = "sqlite"))]
+
+use dbnexus::DbPool;
+use opentelemetry::trace::{TraceContextExt, Tracer};
+use opentelemetry::{Context, Value, global};
+use opentelemetry_sdk::export::trace::SpanData;
+use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+use opentelemetry_sdk::trace::TracerProvider;
+use std::sync::OnceLock;
+mod common;
+
+/// 安装全局内存导出器（每个测试进程只安装一次）
+fn exporter() -> &'static InMemorySpanExporter {
+    static EXPORTER: OnceLock<InMemorySpanExporter> = OnceLock::new();
+    EXPORTER.get_or_init(|| {
+        let exporter = InMemorySpanExporter::default();
+        let provider = TracerProvider::builder().with_simple_exporter(exporter.clone()).build();
+        global::set_tracer_provider(provider);
+        exporter
+    })
+}
+
+/// 获取指定角色产生的 span
+fn spans_for_role(role: &str) -> Vec<SpanData> {
+    exporter()
+        .get_finished_spans()
+        .expect("Failed to read finished spans")
+        .into_iter()
+        .filter(|span| attribute(span, "db.role") == Some(Value::from(role.to_string())))
+        .collect()
+}
+
+fn attribute(span: &SpanData, key: &str) -> Option<Value> {
+    span.attributes
+        .iter()
+        .find(|kv| kv.key.as_str() == key)
+        .map(|kv| kv.value.clone())
+}
+
+/// TEST-TRACE-001: 获取连接与查询均产生 span 并携带数据库属性
+#[tokio::test]
+async fn test_pool_and_query_spans() {
+    exporter();
+    let pool = DbPool::with_config(common::get_sqlite_memory_config())
+        .await
+        .expect("Failed to create pool");
+
+    let session = pool.get_session("trace_basic").await.expect("Failed to get session");
+    session
+        .query_all("SELECT name FROM sqlite_master   WHERE type = 'table'")
+        .await
+        .expect("Query should succeed");
+
+    let spans = spans_for_role("trace_basic");
+    let acquire = spans
+        .iter()
+        .find(|span| span.name == dbnexus::tracing::SPAN_ACQUIRE_CONNECTION)
+        .expect("Missing acquire span");
+    assert_eq!(attribute(acquire, "db.system"), Some(Value::from("sqlite")));
+
+    let query = spans
+        .iter()
+        .find(|span| span.name == dbnexus::tracing::SPAN_QUERY)
+        .expect("Missing query span");
+    assert_eq!(attribute(query, "db.system"), Some(Value::from("sqlite")));
+    assert_eq!(
+        attribute(query, "db.statement"),
+        Some(Value::from(
+            "SELECT name FROM sqlite_master WHERE type = 'table'".to_string()
+        ))
+    );
+    assert_eq!(
+        attribute(query, "db.operation"),
+        Some(Value::from("SELECT".to_string()))
+    );
+    assert_eq!(
+        attribute(query, "db.sql.table"),
+        Some(Value::from("sqlite_master".to_string()))
+    );
+}
+
+/// TEST-TRACE-002: 查询 span 以当前注入的上下文为父 span
+#[tokio::test]
+async fn test_query_span_respects_parent_context() {
+    exporter();
+    let pool = DbPool::with_config(common::get_sqlite_memory_config())
+        .await
+        .expect("Failed to create pool");
+    let session = pool.get_session("trace_parent").await.expect("Failed to get session");
+
+    let parent = global::tracer("dbnexus-test").start("http.request");
+    let cx = Context::current_with_span(parent);
+    let parent_context = cx.span().span_context().clone();
+    let guard = cx.attach();
+
+    session
+        .query_one("SELECT name FROM sqlite_master")
+        .await
+        .expect("Query should succeed");
+    drop(guard);
+
+    let query = spans_for_role("trace_parent")
+        .into_iter()
+        .find(|span| span.name == dbnexus::tracing::SPAN_QUERY)
+        .expect("Missing query span");
+    assert_eq!(query.parent_span_id, parent_context.span_id());
+    assert_eq!(query.span_context.trace_id(), parent_context.trace_id());
+}
+
+/// TEST-TRACE-003: Session 自定义属性出现在后续查询 span 上
+#[tokio::test]
+async fn test_session_span_attributes() {
+    exporter();
+    let pool = DbPool::with_config(common::get_sqlite_memory_config())
+        .await
+        .expect("Failed to create pool");
+    let mut session = pool.get_session("trace_attrs").await.expect("Failed to get session");
+
+    session.with_span_attributes(vec![
+        ("http.request_id".to_string(), "req-42".to_string()),
+        ("tenant".to_string(), "acme".to_string()),
+    ]);
+    session.with_span_attributes(vec![("tenant".to_string(), "globex".to_string())]);
+
+    session
+        .query_all("SELECT name FROM sqlite_master")
+        .await
+        .expect("Query should succeed");
+
+    let query = spans_for_role("trace_attrs")
+        .into_iter()
+        .find(|span| span.name == dbnexus::tracing::SPAN_QUERY)
+        .expect("Missing query span");
+    assert_eq!(
+        attribute(&query, "http.request_id"),
+        Some(Value::from("req-42".to_string()))
+    );
+    assert_eq!(attribute(&query, "tenant"), Some(Value::from("globex".to_string())));
+}